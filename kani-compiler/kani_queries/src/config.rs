@@ -0,0 +1,307 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module defines the verification config of a crate, which gathers the unwind bounds, stub
+//! mappings and reachability settings in one json file instead of spreading them over attributes
+//! and command line options. For example:
+//!
+//! ```json
+//! {
+//!     "version": 2,
+//!     "reachability": { "cache_dir": "target/kani-reachability" },
+//!     "functions": { "parser::parse_header": { "unwind": 4 } },
+//!     "harnesses": {
+//!         "check_parse": {
+//!             "unwind": 10,
+//!             "stubs": {
+//!                 "io::read_input": "mocks::read_input",
+//!                 "io::read_config": ["mocks::read_config", "legacy::mocks::read_config"]
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The replacement of a stub may be a list of paths, in which case the first one that resolves is
+//! used. This keeps a config working while a mock moves from one module to another.
+//!
+//! Every section is optional. The paths are resolved relative to the crate root, and the harness
+//! names may be partially qualified, as with `--harness`.
+//!
+//! The `version` key is the version of the format, so a file written for another version of Kani
+//! is not silently misread. A file of an older version is migrated to [CONFIG_VERSION] when it is
+//! loaded, and a file of a newer version is rejected. A file without a version is a version 1 file,
+//! since the key did not exist in that version.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The version of the config format read by this version of Kani. It must be bumped whenever a
+/// file of the previous version would be misread, together with a step in
+/// [VerificationConfig::migrate].
+pub const CONFIG_VERSION: u32 = 2;
+
+/// The verification config of a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerificationConfig {
+    /// The version of the format of the config, which is 1 if the key is missing.
+    #[serde(default = "unversioned")]
+    pub version: u32,
+    #[serde(default)]
+    pub reachability: ReachabilityConfig,
+    /// The settings of the functions that are not harnesses, keyed by the function path.
+    #[serde(default)]
+    pub functions: BTreeMap<String, FunctionConfig>,
+    /// The settings of each harness, keyed by the harness name.
+    #[serde(default)]
+    pub harnesses: BTreeMap<String, HarnessConfig>,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        VerificationConfig {
+            version: CONFIG_VERSION,
+            reachability: ReachabilityConfig::default(),
+            functions: BTreeMap::new(),
+            harnesses: BTreeMap::new(),
+        }
+    }
+}
+
+/// The version of the files that do not have a `version` key.
+fn unversioned() -> u32 {
+    1
+}
+
+/// The version of a config, which is read before the rest of the config so that a file of an
+/// unsupported version is rejected before its keys are interpreted.
+#[derive(Deserialize)]
+struct ConfigVersion {
+    version: Option<u64>,
+}
+
+/// The settings of the reachability analysis. The command line options take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReachabilityConfig {
+    /// Directory where the result of the reachability analysis is cached. A relative path is
+    /// relative to the directory of the config file.
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// The settings of a function that is not a harness.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FunctionConfig {
+    /// The unwind bound of the function, like `#[kani::unwind]`.
+    pub unwind: u32,
+}
+
+/// The settings of a harness.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HarnessConfig {
+    /// The unwind bound of the harness, like `#[kani::unwind]`.
+    pub unwind: Option<u32>,
+    /// The stubs of the harness, which map the path of each original function/method to the
+    /// paths of its replacement, like `#[kani::stub]`. The paths are tried in order, and the
+    /// first one that resolves is used. A single path may be given as a string.
+    #[serde(default, deserialize_with = "deserialize_stubs")]
+    pub stubs: BTreeMap<String, Vec<String>>,
+}
+
+/// The replacement of a stub in the config, which is either one path or a list of paths.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Replacements {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Deserialize the stubs of a harness, turning each single replacement into a list.
+fn deserialize_stubs<'de, D>(deserializer: D) -> Result<BTreeMap<String, Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let stubs = BTreeMap::<String, Replacements>::deserialize(deserializer)?;
+    Ok(stubs
+        .into_iter()
+        .map(|(original, replacements)| match replacements {
+            Replacements::One(path) => (original, vec![path]),
+            Replacements::Many(paths) => (original, paths),
+        })
+        .collect())
+}
+
+impl VerificationConfig {
+    /// Parse a config from a json string, and migrate it to [CONFIG_VERSION] if it is of an older
+    /// version. The error reports the line and column of the first problem, e.g. a misspelled key
+    /// or a bound that is not a `u32`, or the version if it is not supported.
+    pub fn from_json(data: &str) -> Result<VerificationConfig, String> {
+        if let Ok(ConfigVersion { version: Some(version) }) = serde_json::from_str(data) {
+            if version == 0 || version > u64::from(CONFIG_VERSION) {
+                return Err(format!(
+                    "unsupported config version {version}: this version of Kani reads versions 1 \
+                    to {CONFIG_VERSION}, so the config may require a newer version of Kani"
+                ));
+            }
+        }
+        let config: VerificationConfig =
+            serde_json::from_str(data).map_err(|err| err.to_string())?;
+        Ok(config.migrate())
+    }
+
+    /// Migrate a config of an older version to [CONFIG_VERSION], one version at a time.
+    fn migrate(mut self) -> VerificationConfig {
+        while self.version < CONFIG_VERSION {
+            match self.version {
+                // Version 1 files only lack the `version` key, so they read the same.
+                1 => {}
+                version => unreachable!("unsupported config version {version}"),
+            }
+            self.version += 1;
+        }
+        self
+    }
+
+    /// Load the config from the given file. The error names the file.
+    pub fn from_file(path: &Path) -> Result<VerificationConfig, String> {
+        let mut config = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| VerificationConfig::from_json(&data))
+            .map_err(|err| {
+                format!("Failed to load the verification config from `{}`: {err}", path.display())
+            })?;
+        if let (Some(dir), Some(parent)) = (&mut config.reachability.cache_dir, path.parent()) {
+            *dir = parent.join(&dir);
+        }
+        Ok(config)
+    }
+
+    /// Return the settings of the harness with the given fully qualified name, if any. An exact
+    /// match of the name is preferred; otherwise, the longest key that matches is picked.
+    pub fn harness(&self, name: &str) -> Option<&HarnessConfig> {
+        self.harnesses
+            .iter()
+            .filter(|(key, _)| matches_harness(key, name))
+            .max_by_key(|(key, _)| (*key == name, key.len()))
+            .map(|(_, harness)| harness)
+    }
+}
+
+/// Whether a harness key of the config refers to the harness with the given fully qualified
+/// name, i.e., the key is the name itself or a suffix of it that starts with a path segment.
+pub fn matches_harness(key: &str, name: &str) -> bool {
+    name == key || name.strip_suffix(key).map_or(false, |prefix| prefix.ends_with("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_formed_config() {
+        let config = VerificationConfig::from_json(
+            r#"{
+                "reachability": { "cache_dir": "target/cache" },
+                "functions": { "parser::parse_header": { "unwind": 4 } },
+                "harnesses": {
+                    "check_parse": {
+                        "unwind": 10,
+                        "stubs": {
+                            "io::read_input": "mocks::read_input",
+                            "io::read_config": ["mocks::read_config", "old::read_config"]
+                        }
+                    },
+                    "proofs::check_parse": {},
+                    "check_empty": { "unwind": 1 }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.reachability.cache_dir, Some(PathBuf::from("target/cache")));
+        assert_eq!(config.functions["parser::parse_header"], FunctionConfig { unwind: 4 });
+        let check_parse = config.harness("check_parse").unwrap();
+        assert_eq!(check_parse.unwind, Some(10));
+        assert_eq!(check_parse.stubs["io::read_input"], ["mocks::read_input"]);
+        assert_eq!(
+            check_parse.stubs["io::read_config"],
+            ["mocks::read_config", "old::read_config"]
+        );
+        // The longest key that matches wins, and partial segments do not match.
+        assert_eq!(config.harness("proofs::check_parse"), Some(&HarnessConfig::default()));
+        assert_eq!(config.harness("other::check_parse").unwrap().unwind, Some(10));
+        assert_eq!(config.harness("check_empty").unwrap().stubs.len(), 0);
+        assert_eq!(config.harness("recheck_empty"), None);
+        assert_eq!(VerificationConfig::from_json("{}").unwrap(), VerificationConfig::default());
+    }
+
+    #[test]
+    fn malformed_config() {
+        let unknown_key = VerificationConfig::from_json(
+            r#"{
+                "harnesses": {
+                    "check_parse": { "unwnd": 10 }
+                }
+            }"#,
+        );
+        assert_eq!(
+            unknown_key.unwrap_err(),
+            "unknown field `unwnd`, expected `unwind` or `stubs` at line 3 column 44"
+        );
+        let wrong_type =
+            VerificationConfig::from_json(r#"{ "functions": { "f": { "unwind": -1 } } }"#);
+        assert_eq!(
+            wrong_type.unwrap_err(),
+            "invalid value: integer `-1`, expected u32 at line 1 column 36"
+        );
+        let missing_bound = VerificationConfig::from_json(r#"{ "functions": { "f": {} } }"#);
+        assert_eq!(missing_bound.unwrap_err(), "missing field `unwind` at line 1 column 24");
+        let not_json = VerificationConfig::from_json("harnesses = {}");
+        assert_eq!(not_json.unwrap_err(), "expected value at line 1 column 1");
+        let wrong_version = VerificationConfig::from_json(r#"{ "version": "2" }"#);
+        assert!(wrong_version.unwrap_err().starts_with("invalid type: string \"2\", expected u32"));
+    }
+
+    #[test]
+    fn current_version_config() {
+        let config = VerificationConfig::from_json(
+            r#"{ "version": 2, "harnesses": { "check_parse": { "unwind": 10 } } }"#,
+        )
+        .unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.harness("check_parse").unwrap().unwind, Some(10));
+    }
+
+    #[test]
+    fn old_version_config_is_migrated() {
+        let unversioned = VerificationConfig::from_json(
+            r#"{ "harnesses": { "check_parse": { "stubs": { "io::read": "mocks::read" } } } }"#,
+        )
+        .unwrap();
+        assert_eq!(unversioned.version, CONFIG_VERSION);
+        assert_eq!(unversioned.harness("check_parse").unwrap().stubs["io::read"], ["mocks::read"]);
+        let version_1 = VerificationConfig::from_json(
+            r#"{ "version": 1, "harnesses": { "check_parse": { "stubs": { "io::read": "mocks::read" } } } }"#,
+        )
+        .unwrap();
+        assert_eq!(version_1, unversioned);
+    }
+
+    #[test]
+    fn future_version_config_is_rejected() {
+        // The keys of a newer version are not interpreted, so the error is about the version.
+        let future = VerificationConfig::from_json(
+            r#"{ "version": 3, "harnesses": { "check_parse": { "contracts": [] } } }"#,
+        );
+        assert_eq!(
+            future.unwrap_err(),
+            "unsupported config version 3: this version of Kani reads versions 1 to 2, so the \
+            config may require a newer version of Kani"
+        );
+        assert!(VerificationConfig::from_json(r#"{ "version": 0 }"#).is_err());
+    }
+}
@@ -1,16 +1,25 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+mod config;
 #[cfg(feature = "unsound_experiments")]
 mod unsound_experiments;
 
+pub use config::{
+    matches_harness, FunctionConfig, HarnessConfig, ReachabilityConfig, VerificationConfig,
+};
+
 #[cfg(feature = "unsound_experiments")]
 use crate::unsound_experiments::UnsoundExperiments;
 
 #[derive(Debug, Clone, Copy, AsRefStr, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
 #[strum(serialize_all = "snake_case")]
 pub enum ReachabilityType {
     /// Start the cross-crate reachability analysis from all harnesses in the local crate.
@@ -31,6 +40,140 @@ fn default() -> Self {
     }
 }
 
+/// The formats in which the call graph of the reachable items can be emitted.
+#[derive(Debug, Clone, Copy, AsRefStr, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case")]
+pub enum CallGraphFormat {
+    /// A Graphviz DOT digraph.
+    Dot,
+    /// A JSON object with the list of nodes and the list of edges.
+    Json,
+}
+
+/// The sources of the stubs of a harness. If two sources map the same function/method to
+/// different stubs, the stub from the source that comes first in the precedence order is used.
+#[derive(Debug, Clone, Copy, AsRefStr, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case")]
+pub enum StubSource {
+    /// The stubbing attributes of the harness, e.g., `kani::stub`.
+    Attribute,
+    /// The stubs listed for the harness in the verification config.
+    Config,
+}
+
+/// The default precedence of the stub sources: the verification config can override the
+/// attributes without changing the code.
+pub const DEFAULT_STUB_SOURCE_PRECEDENCE: [StubSource; 2] =
+    [StubSource::Config, StubSource::Attribute];
+
+/// The editions of Rust that the stub paths can be resolved against. The edition changes how
+/// some paths are resolved, e.g., a path that starts with `::` is relative to the root of the
+/// crate in the 2015 edition and to the extern prelude in later editions.
+#[derive(Debug, Clone, Copy, AsRefStr, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub enum RustEdition {
+    #[strum(serialize = "2015")]
+    Edition2015,
+    #[strum(serialize = "2018")]
+    Edition2018,
+    #[strum(serialize = "2021")]
+    Edition2021,
+}
+
+/// The formats in which the reports that the compiler prints can be emitted.
+#[derive(Debug, Clone, Copy, AsRefStr, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReportFormat {
+    /// A pretty-printed JSON object.
+    Json,
+    /// A human-readable table.
+    Text,
+}
+
+/// A non-fatal message produced by a compiler pass. Unlike errors and warnings, advisories are not
+/// printed as they happen; instead, they are accumulated throughout the compilation and emitted
+/// together at the end of the run in a machine-readable format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Advisory {
+    /// The name of the pass that produced this advisory.
+    pub pass: String,
+    /// A human-readable description of the advisory.
+    pub message: String,
+    /// The structured data of the advisory, if the pass attached any, so tools do not have to
+    /// parse the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<AdvisoryPayload>,
+}
+
+impl Advisory {
+    pub fn new(pass: &str, message: impl Into<String>) -> Self {
+        Advisory { pass: pass.to_string(), message: message.into(), payload: None }
+    }
+
+    /// Attach the given structured data to this advisory.
+    pub fn with_payload(mut self, payload: AdvisoryPayload) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+}
+
+/// The structured data of an advisory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdvisoryPayload {
+    /// A stable identifier of the kind of advisory (e.g., `std-stub`).
+    pub code: String,
+    pub severity: AdvisorySeverity,
+    /// The source code the advisory refers to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<AdvisorySpan>,
+}
+
+impl AdvisoryPayload {
+    pub fn new(code: &str, severity: AdvisorySeverity) -> Self {
+        AdvisoryPayload { code: code.to_string(), severity, span: None }
+    }
+
+    /// Set the source code the advisory refers to.
+    pub fn with_span(mut self, span: AdvisorySpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+}
+
+/// How much attention an advisory deserves.
+#[derive(Debug, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AdvisorySeverity {
+    /// The advisory is informative; the result of the verification is not affected.
+    Note,
+    /// The advisory is also reported as a warning, since the result of the verification may not be
+    /// what the user expects.
+    Warning,
+}
+
+/// The lines of a source file an advisory refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdvisorySpan {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// A stub resolved for a harness when the stubbing attributes are collected.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ResolvedStub {
+    /// The qualified path of the original function/method.
+    pub original: String,
+    /// The qualified path of the stub.
+    pub replacement: String,
+    /// The qualified path of the caller, if the stub only replaces the calls made by this caller.
+    pub caller: Option<String>,
+}
+
 pub trait UserInput {
     fn set_emit_vtable_restrictions(&mut self, restrictions: bool);
     fn get_emit_vtable_restrictions(&self) -> bool;
@@ -50,6 +193,150 @@ pub trait UserInput {
     fn set_stubbing_enabled(&mut self, stubbing_enabled: bool);
     fn get_stubbing_enabled(&self) -> bool;
 
+    fn set_emit_advisories(&mut self, emit_advisories: bool);
+    fn get_emit_advisories(&self) -> bool;
+
+    fn set_emit_pass_trace(&mut self, emit_pass_trace: bool);
+    fn get_emit_pass_trace(&self) -> bool;
+
+    fn set_current_harness(&mut self, harness: Option<String>);
+    fn get_current_harness(&self) -> Option<String>;
+
+    fn set_allow_std_stubbing(&mut self, allow_std_stubbing: bool);
+    fn get_allow_std_stubbing(&self) -> bool;
+
+    fn set_stub_harness_body(&mut self, stub_harness_body: bool);
+    fn get_stub_harness_body(&self) -> bool;
+
+    fn set_verify_stub_types(&mut self, verify_stub_types: bool);
+    fn get_verify_stub_types(&self) -> bool;
+
+    fn set_preserve_stub_signature(&mut self, preserve_stub_signature: bool);
+    fn get_preserve_stub_signature(&self) -> bool;
+
+    fn set_stub_resolution_stats(&mut self, stub_resolution_stats: bool);
+    fn get_stub_resolution_stats(&self) -> bool;
+
+    fn set_stub_collection_stats(&mut self, stub_collection_stats: bool);
+    fn get_stub_collection_stats(&self) -> bool;
+
+    fn set_stub_summary(&mut self, stub_summary: bool);
+    fn get_stub_summary(&self) -> bool;
+
+    fn set_emit_stub_diagnostics(&mut self, emit_stub_diagnostics: bool);
+    fn get_emit_stub_diagnostics(&self) -> bool;
+
+    fn set_deny_chained_stubs(&mut self, deny_chained_stubs: bool);
+    fn get_deny_chained_stubs(&self) -> bool;
+
+    fn set_deny_unapplied_stubs(&mut self, deny_unapplied_stubs: bool);
+    fn get_deny_unapplied_stubs(&self) -> bool;
+
+    fn set_check_stub_call_sites(&mut self, check_stub_call_sites: bool);
+    fn get_check_stub_call_sites(&self) -> bool;
+
+    fn set_stub_without_kani_cfg(&mut self, stub_without_kani_cfg: bool);
+    fn get_stub_without_kani_cfg(&self) -> bool;
+
+    fn set_warn_mock_imports(&mut self, warn_mock_imports: bool);
+    fn get_warn_mock_imports(&self) -> bool;
+
+    fn set_stub_body_hashes(&mut self, path: Option<PathBuf>);
+    fn get_stub_body_hashes(&self) -> Option<PathBuf>;
+
+    fn set_emit_stubbed_mir(&mut self, harness: Option<String>);
+    fn get_emit_stubbed_mir(&self) -> Option<String>;
+
+    fn set_stub_source_precedence(&mut self, precedence: Vec<StubSource>);
+    fn get_stub_source_precedence(&self) -> Vec<StubSource>;
+
+    fn set_enable_experimental_stubs(&mut self, enable_experimental_stubs: bool);
+    fn get_enable_experimental_stubs(&self) -> bool;
+
+    fn set_allow_stub_shared_refs(&mut self, allow_stub_shared_refs: bool);
+    fn get_allow_stub_shared_refs(&self) -> bool;
+
+    fn set_stub_regex_limit(&mut self, limit: Option<usize>);
+    fn get_stub_regex_limit(&self) -> Option<usize>;
+
+    fn set_stub_complexity_ratio(&mut self, ratio: Option<usize>);
+    fn get_stub_complexity_ratio(&self) -> Option<usize>;
+
+    fn set_stub_resolution_depth(&mut self, depth: Option<usize>);
+    fn get_stub_resolution_depth(&self) -> Option<usize>;
+
+    fn set_stub_error_candidates(&mut self, count: Option<usize>);
+    fn get_stub_error_candidates(&self) -> Option<usize>;
+
+    fn set_stub_path_edition(&mut self, edition: Option<RustEdition>);
+    fn get_stub_path_edition(&self) -> Option<RustEdition>;
+
+    fn set_trace_stub_resolution(&mut self, trace_stub_resolution: bool);
+    fn get_trace_stub_resolution(&self) -> bool;
+
+    fn set_stub_exact_paths(&mut self, stub_exact_paths: bool);
+    fn get_stub_exact_paths(&self) -> bool;
+
+    fn set_emit_stub_mapping(&mut self, emit_stub_mapping: bool);
+    fn get_emit_stub_mapping(&self) -> bool;
+
+    fn set_print_stub_mapping(&mut self, print_stub_mapping: bool);
+    fn get_print_stub_mapping(&self) -> bool;
+
+    fn set_emit_stub_candidates(&mut self, emit_stub_candidates: bool);
+    fn get_emit_stub_candidates(&self) -> bool;
+
+    fn set_emit_stub_caller_counts(&mut self, emit_stub_caller_counts: bool);
+    fn get_emit_stub_caller_counts(&self) -> bool;
+
+    fn set_stub_candidates_include_foreign(&mut self, include_foreign: bool);
+    fn get_stub_candidates_include_foreign(&self) -> bool;
+
+    fn set_emit_harness_metadata(&mut self, emit_harness_metadata: bool);
+    fn get_emit_harness_metadata(&self) -> bool;
+
+    fn set_emit_verification_plan(&mut self, emit_verification_plan: bool);
+    fn get_emit_verification_plan(&self) -> bool;
+
+    fn set_emit_no_mir_report(&mut self, emit_no_mir_report: bool);
+    fn get_emit_no_mir_report(&self) -> bool;
+
+    fn set_emit_harness_reachability(&mut self, emit_harness_reachability: bool);
+    fn get_emit_harness_reachability(&self) -> bool;
+
+    fn set_resolved_stubs(&mut self, resolved_stubs: BTreeMap<String, Vec<ResolvedStub>>);
+    fn get_resolved_stubs(&self, harness: &str) -> Vec<ResolvedStub>;
+
+    fn set_continue_on_stub_errors(&mut self, continue_on_stub_errors: bool);
+    fn get_continue_on_stub_errors(&self) -> bool;
+
+    fn set_failed_stub_harnesses(&mut self, harnesses: Vec<String>);
+    fn get_failed_stub_harnesses(&self) -> &[String];
+
+    fn set_reachability_cache_dir(&mut self, dir: Option<PathBuf>);
+    fn get_reachability_cache_dir(&self) -> Option<PathBuf>;
+
+    fn set_output_dir(&mut self, dir: Option<PathBuf>);
+    fn get_output_dir(&self) -> Option<PathBuf>;
+
+    fn set_call_graph_format(&mut self, format: Option<CallGraphFormat>);
+    fn get_call_graph_format(&self) -> Option<CallGraphFormat>;
+
+    fn set_reachable_counts_format(&mut self, format: Option<ReportFormat>);
+    fn get_reachable_counts_format(&self) -> Option<ReportFormat>;
+
+    fn set_include_synthetic_items(&mut self, include_synthetic_items: bool);
+    fn get_include_synthetic_items(&self) -> bool;
+
+    fn set_identity_passes_only(&mut self, identity_passes_only: bool);
+    fn get_identity_passes_only(&self) -> bool;
+
+    fn set_function_unwinds(&mut self, function_unwinds: BTreeMap<String, u32>);
+    fn get_function_unwind(&self, path: &str) -> Option<u32>;
+
+    fn set_verification_config(&mut self, config: Option<VerificationConfig>);
+    fn get_verification_config(&self) -> Option<&VerificationConfig>;
+
     #[cfg(feature = "unsound_experiments")]
     fn get_unsound_experiments(&self) -> UnsoundExperiments;
     #[cfg(feature = "unsound_experiments")]
@@ -57,7 +344,7 @@ pub trait UserInput {
 }
 
 /// This structure should only be used behind a synchronized reference or a snapshot.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryDb {
     check_assertion_reachability: bool,
     emit_vtable_restrictions: bool,
@@ -65,22 +352,274 @@ pub struct QueryDb {
     ignore_global_asm: bool,
     reachability_analysis: ReachabilityType,
     stubbing_enabled: bool,
+    /// The harness selected by the user, if any. Stubs are only applied for this harness.
+    current_harness: Option<String>,
+    /// Whether the user explicitly opted in to stubbing functions from the standard library.
+    allow_std_stubbing: bool,
+    /// Whether a stub may replace the body of the harness itself. By default, the harness is
+    /// left intact even if it is the original function of one of its stubs.
+    stub_harness_body: bool,
+    /// Whether stubs should only be applied if their compatibility can be fully verified.
+    verify_stub_types: bool,
+    /// Whether stubs should only replace the body of the original function, keeping its
+    /// signature metadata.
+    preserve_stub_signature: bool,
+    /// Whether to report how long the resolution of the stub paths took.
+    stub_resolution_stats: bool,
+    /// Whether to report the time and memory taken by the compilation that collects the stubs.
+    stub_collection_stats: bool,
+    /// Whether to print a summary of the stubs of each harness at the end of the compilation.
+    stub_summary: bool,
+    /// Whether to emit the errors and warnings of the stub collection as a json file.
+    emit_stub_diagnostics: bool,
+    /// Whether it is an error for the stub of a function to be stubbed itself in the same harness.
+    deny_chained_stubs: bool,
+    /// Whether to report an error for the stubs of the harness that were never applied.
+    deny_unapplied_stubs: bool,
+    /// Whether to check that each call to a stubbed function/method type checks with the stub.
+    check_stub_call_sites: bool,
+    /// Whether to collect and apply the stubs of a crate that is not compiled with the `kani` cfg.
+    stub_without_kani_cfg: bool,
+    /// Whether to warn about the harnesses without stubs that import a mock module.
+    warn_mock_imports: bool,
+    /// The file that records the hash of the body of each stub, if any.
+    stub_body_hashes: Option<PathBuf>,
+    /// The harness whose reachable bodies are dumped after stubbing, if any.
+    emit_stubbed_mir: Option<String>,
+    /// The stub sources from the highest to the lowest precedence. A source that is not listed
+    /// has a lower precedence than all the listed ones.
+    stub_source_precedence: Vec<StubSource>,
+    /// Whether the stubs marked `kani::experimental_stub` are applied.
+    enable_experimental_stubs: bool,
+    /// Whether a stub may take `&T` for a parameter of type `&mut T` of the original function.
+    allow_stub_shared_refs: bool,
+    /// The maximum number of functions a `kani::stub_regex` attribute may match, if set.
+    stub_regex_limit: Option<usize>,
+    /// The ratio of the size of a stub to the size of the original function above which the
+    /// stub is reported as oversized, if set.
+    stub_complexity_ratio: Option<usize>,
+    /// The maximum number of modules the resolution of a stub path may go through, if set.
+    stub_resolution_depth: Option<usize>,
+    /// The maximum number of nearest functions listed in the error for a stub path that does not
+    /// resolve, if set. No function is listed if it is 0.
+    stub_error_candidates: Option<usize>,
+    /// The edition the stub paths are resolved against, if set. It must match the edition of the
+    /// crate, which is set to it if the crate does not declare one.
+    stub_path_edition: Option<RustEdition>,
+    /// Whether to print the `use` statements that the resolution of each stub path goes through.
+    trace_stub_resolution: bool,
+    /// Whether the stub paths that are exactly the `def_path_str` of a function/method resolve
+    /// to it directly.
+    stub_exact_paths: bool,
+    /// Whether to emit the stub mapping of the current harness as a Rust source file.
+    emit_stub_mapping: bool,
+    /// Whether to print the stub mapping of the current harness once all the sources of stubs
+    /// are merged.
+    print_stub_mapping: bool,
+    /// Whether to emit the functions and methods that may be stubbed.
+    emit_stub_candidates: bool,
+    /// Whether to emit the number of calls to each stubbed function/method in each caller.
+    emit_stub_caller_counts: bool,
+    /// Whether the emitted stub candidates include the ones from other crates.
+    stub_candidates_include_foreign: bool,
+    /// Whether to emit the metadata of each harness together with its resolved stubs.
+    emit_harness_metadata: bool,
+    /// Whether to emit the verification plan of the crate, which includes the harness metadata.
+    emit_verification_plan: bool,
+    /// The stubs resolved for each harness, keyed by the harness name. This is only collected if
+    /// `emit_harness_metadata` or `emit_verification_plan` is set.
+    resolved_stubs: BTreeMap<String, Vec<ResolvedStub>>,
+    /// Whether the stub errors of the harnesses other than the current one are reported as
+    /// warnings, with the stubs of these harnesses skipped, instead of stopping the compilation.
+    continue_on_stub_errors: bool,
+    /// The harnesses whose stubs were skipped because of errors, sorted by name. This is only
+    /// collected if `continue_on_stub_errors` is set.
+    failed_stub_harnesses: Vec<String>,
+    /// Whether to emit the reachable functions that have no MIR (e.g., foreign functions and
+    /// intrinsics).
+    emit_no_mir_report: bool,
+    /// Whether to emit the functions reachable from each proof harness on its own.
+    emit_harness_reachability: bool,
+    /// Directory where the result of the reachability analysis is cached, if any.
+    reachability_cache_dir: Option<PathBuf>,
+    /// Directory where the files emitted on request (e.g., the call graph) are written. They are
+    /// written next to the other artifacts of the crate if it is not set.
+    output_dir: Option<PathBuf>,
+    /// The format of the call graph of the reachable items to emit, if any.
+    call_graph_format: Option<CallGraphFormat>,
+    /// The format in which to print the number of reachable functions of each crate, if any.
+    reachable_counts_format: Option<ReportFormat>,
+    /// Whether the reachable items that are reported include the compiler-generated items, such as
+    /// drop glue, that the reachability analysis omits. This doesn't affect which items are
+    /// compiled.
+    include_synthetic_items: bool,
+    /// Whether the Kani MIR passes are replaced by a pass that leaves the bodies unchanged, which
+    /// isolates the cost of the pass infrastructure from the cost of stubbing.
+    identity_passes_only: bool,
+    /// The unwind bounds of functions that are not harnesses, keyed by the function path. They
+    /// are collected for the backends but not applied yet.
+    function_unwinds: BTreeMap<String, u32>,
+    /// The verification config of the crate, if one was given.
+    verification_config: Option<VerificationConfig>,
+    emit_advisories: bool,
+    /// The advisories are shared among all copies of this database, so passes that only have a
+    /// snapshot can still report them.
+    #[serde(with = "shared")]
+    advisories: Arc<Mutex<Vec<Advisory>>>,
+    /// Whether to emit the timings of the Kani MIR passes in the Chrome Trace Event format.
+    emit_pass_trace: bool,
     #[cfg(feature = "unsound_experiments")]
     unsound_experiments: UnsoundExperiments,
 }
 
-impl QueryDb {
-    pub fn new() -> Arc<Mutex<QueryDb>> {
-        Arc::new(Mutex::new(QueryDb {
+impl Default for QueryDb {
+    fn default() -> Self {
+        QueryDb {
             check_assertion_reachability: false,
             emit_vtable_restrictions: false,
             json_pretty_print: false,
             ignore_global_asm: false,
             reachability_analysis: ReachabilityType::None,
             stubbing_enabled: false,
+            current_harness: None,
+            allow_std_stubbing: false,
+            stub_harness_body: false,
+            verify_stub_types: false,
+            preserve_stub_signature: false,
+            stub_resolution_stats: false,
+            stub_collection_stats: false,
+            stub_summary: false,
+            emit_stub_diagnostics: false,
+            deny_chained_stubs: false,
+            deny_unapplied_stubs: false,
+            check_stub_call_sites: false,
+            stub_without_kani_cfg: false,
+            warn_mock_imports: false,
+            stub_body_hashes: None,
+            emit_stubbed_mir: None,
+            stub_source_precedence: DEFAULT_STUB_SOURCE_PRECEDENCE.to_vec(),
+            enable_experimental_stubs: false,
+            allow_stub_shared_refs: false,
+            stub_regex_limit: None,
+            stub_complexity_ratio: None,
+            stub_resolution_depth: None,
+            stub_error_candidates: None,
+            stub_path_edition: None,
+            trace_stub_resolution: false,
+            stub_exact_paths: false,
+            emit_stub_mapping: false,
+            print_stub_mapping: false,
+            emit_stub_candidates: false,
+            emit_stub_caller_counts: false,
+            stub_candidates_include_foreign: false,
+            emit_harness_metadata: false,
+            emit_verification_plan: false,
+            resolved_stubs: BTreeMap::new(),
+            continue_on_stub_errors: false,
+            failed_stub_harnesses: Vec::new(),
+            emit_no_mir_report: false,
+            emit_harness_reachability: false,
+            reachability_cache_dir: None,
+            output_dir: None,
+            call_graph_format: None,
+            reachable_counts_format: None,
+            include_synthetic_items: false,
+            identity_passes_only: false,
+            function_unwinds: BTreeMap::new(),
+            verification_config: None,
+            emit_advisories: false,
+            advisories: Arc::new(Mutex::new(Vec::new())),
+            emit_pass_trace: false,
             #[cfg(feature = "unsound_experiments")]
-            unsound_experiments: unsound_experiments::UnsoundExperiments { zero_init_vars: false },
-        }))
+            unsound_experiments: unsound_experiments::UnsoundExperiments {
+                zero_init_vars: false,
+                stub_all_except: None,
+            },
+        }
+    }
+}
+
+impl QueryDb {
+    pub fn new() -> Arc<Mutex<QueryDb>> {
+        Arc::new(Mutex::new(QueryDb::default()))
+    }
+
+    /// Record an advisory produced by the pass `pass`.
+    pub fn add_advisory(&self, pass: &str, message: impl Into<String>) {
+        self.push_advisory(Advisory::new(pass, message));
+    }
+
+    /// Record an advisory, which may carry structured data (see [Advisory::with_payload]).
+    pub fn push_advisory(&self, advisory: Advisory) {
+        tracing::debug!(?advisory, "push_advisory");
+        self.advisories.lock().unwrap().push(advisory);
+    }
+
+    /// Return all the advisories recorded so far in the order they were added.
+    pub fn get_advisories(&self) -> Vec<Advisory> {
+        self.advisories.lock().unwrap().clone()
+    }
+
+    /// Take a snapshot of the current state of this database, including the advisories.
+    /// Unlike a clone, the snapshot does not share the advisories with this database, so it is
+    /// not affected by any later change.
+    pub fn snapshot(&self) -> QueryDbSnapshot {
+        QueryDbSnapshot(QueryDb {
+            advisories: Arc::new(Mutex::new(self.get_advisories())),
+            ..self.clone()
+        })
+    }
+
+    /// Reset the state of this database to the given snapshot. The advisories are restored in
+    /// place, so the copies of this database that share them observe the restored state too.
+    pub fn restore(&mut self, snapshot: &QueryDbSnapshot) {
+        let advisories = Arc::clone(&self.advisories);
+        *advisories.lock().unwrap() = snapshot.0.get_advisories();
+        *self = QueryDb { advisories, ..snapshot.0.clone() };
+    }
+
+    /// Return the options whose values differ from the ones of [QueryDb::default], keyed by the
+    /// name of the option, in their serialized form. This is meant for concise bug reports, so the
+    /// state collected during the compilation (the advisories, the resolved stubs and the
+    /// harnesses whose stubs failed) is left out.
+    pub fn changed_options(&self) -> BTreeMap<String, serde_json::Value> {
+        let to_object = |queries: &QueryDb| match serde_json::to_value(queries).unwrap() {
+            serde_json::Value::Object(object) => object,
+            value => unreachable!("Expected the options to serialize to an object, got `{value}`"),
+        };
+        let defaults = to_object(&QueryDb::default());
+        to_object(self)
+            .into_iter()
+            .filter(|(name, _)| {
+                !matches!(name.as_str(), "advisories" | "resolved_stubs" | "failed_stub_harnesses")
+            })
+            .filter(|(name, value)| defaults.get(name) != Some(value))
+            .collect()
+    }
+}
+
+/// The state of a [QueryDb] at some point of the compilation, which can be used to reset the
+/// database via [QueryDb::restore]. Snapshots can be serialized, e.g., to hand them over to a
+/// different process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryDbSnapshot(QueryDb);
+
+/// (De)serializes the value behind an `Arc<Mutex<_>>`. A deserialized value is never shared.
+mod shared {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::sync::{Arc, Mutex};
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &Arc<Mutex<T>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.lock().unwrap().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<Mutex<T>>, D::Error> {
+        T::deserialize(deserializer).map(|value| Arc::new(Mutex::new(value)))
     }
 }
 
@@ -133,9 +672,393 @@ fn get_stubbing_enabled(&self) -> bool {
         self.stubbing_enabled
     }
 
+    fn set_current_harness(&mut self, harness: Option<String>) {
+        self.current_harness = harness;
+    }
+
+    fn get_current_harness(&self) -> Option<String> {
+        self.current_harness.clone()
+    }
+
+    fn set_allow_std_stubbing(&mut self, allow_std_stubbing: bool) {
+        self.allow_std_stubbing = allow_std_stubbing;
+    }
+
+    fn get_allow_std_stubbing(&self) -> bool {
+        self.allow_std_stubbing
+    }
+
+    fn set_stub_harness_body(&mut self, stub_harness_body: bool) {
+        self.stub_harness_body = stub_harness_body;
+    }
+
+    fn get_stub_harness_body(&self) -> bool {
+        self.stub_harness_body
+    }
+
+    fn set_verify_stub_types(&mut self, verify_stub_types: bool) {
+        self.verify_stub_types = verify_stub_types;
+    }
+
+    fn get_verify_stub_types(&self) -> bool {
+        self.verify_stub_types
+    }
+
+    fn set_preserve_stub_signature(&mut self, preserve_stub_signature: bool) {
+        self.preserve_stub_signature = preserve_stub_signature;
+    }
+
+    fn get_preserve_stub_signature(&self) -> bool {
+        self.preserve_stub_signature
+    }
+
+    fn set_stub_resolution_stats(&mut self, stub_resolution_stats: bool) {
+        self.stub_resolution_stats = stub_resolution_stats;
+    }
+
+    fn get_stub_resolution_stats(&self) -> bool {
+        self.stub_resolution_stats
+    }
+
+    fn set_stub_collection_stats(&mut self, stub_collection_stats: bool) {
+        self.stub_collection_stats = stub_collection_stats;
+    }
+
+    fn get_stub_collection_stats(&self) -> bool {
+        self.stub_collection_stats
+    }
+
+    fn set_stub_summary(&mut self, stub_summary: bool) {
+        self.stub_summary = stub_summary;
+    }
+
+    fn get_stub_summary(&self) -> bool {
+        self.stub_summary
+    }
+
+    fn set_emit_stub_diagnostics(&mut self, emit_stub_diagnostics: bool) {
+        self.emit_stub_diagnostics = emit_stub_diagnostics;
+    }
+
+    fn get_emit_stub_diagnostics(&self) -> bool {
+        self.emit_stub_diagnostics
+    }
+
+    fn set_deny_chained_stubs(&mut self, deny_chained_stubs: bool) {
+        self.deny_chained_stubs = deny_chained_stubs;
+    }
+
+    fn get_deny_chained_stubs(&self) -> bool {
+        self.deny_chained_stubs
+    }
+
+    fn set_deny_unapplied_stubs(&mut self, deny_unapplied_stubs: bool) {
+        self.deny_unapplied_stubs = deny_unapplied_stubs;
+    }
+
+    fn get_deny_unapplied_stubs(&self) -> bool {
+        self.deny_unapplied_stubs
+    }
+
+    fn set_check_stub_call_sites(&mut self, check_stub_call_sites: bool) {
+        self.check_stub_call_sites = check_stub_call_sites;
+    }
+
+    fn get_check_stub_call_sites(&self) -> bool {
+        self.check_stub_call_sites
+    }
+
+    fn set_stub_without_kani_cfg(&mut self, stub_without_kani_cfg: bool) {
+        self.stub_without_kani_cfg = stub_without_kani_cfg;
+    }
+
+    fn get_stub_without_kani_cfg(&self) -> bool {
+        self.stub_without_kani_cfg
+    }
+
+    fn set_warn_mock_imports(&mut self, warn_mock_imports: bool) {
+        self.warn_mock_imports = warn_mock_imports;
+    }
+
+    fn get_warn_mock_imports(&self) -> bool {
+        self.warn_mock_imports
+    }
+
+    fn set_stub_body_hashes(&mut self, path: Option<PathBuf>) {
+        self.stub_body_hashes = path;
+    }
+
+    fn get_stub_body_hashes(&self) -> Option<PathBuf> {
+        self.stub_body_hashes.clone()
+    }
+
+    fn set_emit_stubbed_mir(&mut self, harness: Option<String>) {
+        self.emit_stubbed_mir = harness;
+    }
+
+    fn get_emit_stubbed_mir(&self) -> Option<String> {
+        self.emit_stubbed_mir.clone()
+    }
+
+    fn set_stub_source_precedence(&mut self, precedence: Vec<StubSource>) {
+        self.stub_source_precedence = precedence;
+    }
+
+    fn get_stub_source_precedence(&self) -> Vec<StubSource> {
+        self.stub_source_precedence.clone()
+    }
+
+    fn set_enable_experimental_stubs(&mut self, enable_experimental_stubs: bool) {
+        self.enable_experimental_stubs = enable_experimental_stubs;
+    }
+
+    fn get_enable_experimental_stubs(&self) -> bool {
+        self.enable_experimental_stubs
+    }
+
+    fn set_allow_stub_shared_refs(&mut self, allow_stub_shared_refs: bool) {
+        self.allow_stub_shared_refs = allow_stub_shared_refs;
+    }
+
+    fn get_allow_stub_shared_refs(&self) -> bool {
+        self.allow_stub_shared_refs
+    }
+
+    fn set_stub_regex_limit(&mut self, limit: Option<usize>) {
+        self.stub_regex_limit = limit;
+    }
+
+    fn get_stub_regex_limit(&self) -> Option<usize> {
+        self.stub_regex_limit
+    }
+
+    fn set_stub_complexity_ratio(&mut self, ratio: Option<usize>) {
+        self.stub_complexity_ratio = ratio;
+    }
+
+    fn get_stub_complexity_ratio(&self) -> Option<usize> {
+        self.stub_complexity_ratio
+    }
+
+    fn set_stub_resolution_depth(&mut self, depth: Option<usize>) {
+        self.stub_resolution_depth = depth;
+    }
+
+    fn get_stub_resolution_depth(&self) -> Option<usize> {
+        self.stub_resolution_depth
+    }
+
+    fn set_stub_error_candidates(&mut self, count: Option<usize>) {
+        self.stub_error_candidates = count;
+    }
+
+    fn get_stub_error_candidates(&self) -> Option<usize> {
+        self.stub_error_candidates
+    }
+
+    fn set_stub_path_edition(&mut self, edition: Option<RustEdition>) {
+        self.stub_path_edition = edition;
+    }
+
+    fn get_stub_path_edition(&self) -> Option<RustEdition> {
+        self.stub_path_edition
+    }
+
+    fn set_trace_stub_resolution(&mut self, trace_stub_resolution: bool) {
+        self.trace_stub_resolution = trace_stub_resolution;
+    }
+
+    fn get_trace_stub_resolution(&self) -> bool {
+        self.trace_stub_resolution
+    }
+
+    fn set_stub_exact_paths(&mut self, stub_exact_paths: bool) {
+        self.stub_exact_paths = stub_exact_paths;
+    }
+
+    fn get_stub_exact_paths(&self) -> bool {
+        self.stub_exact_paths
+    }
+
+    fn set_emit_stub_mapping(&mut self, emit_stub_mapping: bool) {
+        self.emit_stub_mapping = emit_stub_mapping;
+    }
+
+    fn get_emit_stub_mapping(&self) -> bool {
+        self.emit_stub_mapping
+    }
+
+    fn set_print_stub_mapping(&mut self, print_stub_mapping: bool) {
+        self.print_stub_mapping = print_stub_mapping;
+    }
+
+    fn get_print_stub_mapping(&self) -> bool {
+        self.print_stub_mapping
+    }
+
+    fn set_emit_stub_candidates(&mut self, emit_stub_candidates: bool) {
+        self.emit_stub_candidates = emit_stub_candidates;
+    }
+
+    fn get_emit_stub_candidates(&self) -> bool {
+        self.emit_stub_candidates
+    }
+
+    fn set_emit_stub_caller_counts(&mut self, emit_stub_caller_counts: bool) {
+        self.emit_stub_caller_counts = emit_stub_caller_counts;
+    }
+
+    fn get_emit_stub_caller_counts(&self) -> bool {
+        self.emit_stub_caller_counts
+    }
+
+    fn set_stub_candidates_include_foreign(&mut self, include_foreign: bool) {
+        self.stub_candidates_include_foreign = include_foreign;
+    }
+
+    fn get_stub_candidates_include_foreign(&self) -> bool {
+        self.stub_candidates_include_foreign
+    }
+
+    fn set_emit_harness_metadata(&mut self, emit_harness_metadata: bool) {
+        self.emit_harness_metadata = emit_harness_metadata;
+    }
+
+    fn get_emit_harness_metadata(&self) -> bool {
+        self.emit_harness_metadata
+    }
+
+    fn set_emit_verification_plan(&mut self, emit_verification_plan: bool) {
+        self.emit_verification_plan = emit_verification_plan;
+    }
+
+    fn get_emit_verification_plan(&self) -> bool {
+        self.emit_verification_plan
+    }
+
+    fn set_emit_no_mir_report(&mut self, emit_no_mir_report: bool) {
+        self.emit_no_mir_report = emit_no_mir_report;
+    }
+
+    fn get_emit_no_mir_report(&self) -> bool {
+        self.emit_no_mir_report
+    }
+
+    fn set_emit_harness_reachability(&mut self, emit_harness_reachability: bool) {
+        self.emit_harness_reachability = emit_harness_reachability;
+    }
+
+    fn get_emit_harness_reachability(&self) -> bool {
+        self.emit_harness_reachability
+    }
+
+    fn set_resolved_stubs(&mut self, resolved_stubs: BTreeMap<String, Vec<ResolvedStub>>) {
+        self.resolved_stubs = resolved_stubs;
+    }
+
+    fn get_resolved_stubs(&self, harness: &str) -> Vec<ResolvedStub> {
+        self.resolved_stubs.get(harness).cloned().unwrap_or_default()
+    }
+
+    fn set_continue_on_stub_errors(&mut self, continue_on_stub_errors: bool) {
+        self.continue_on_stub_errors = continue_on_stub_errors;
+    }
+
+    fn get_continue_on_stub_errors(&self) -> bool {
+        self.continue_on_stub_errors
+    }
+
+    fn set_failed_stub_harnesses(&mut self, harnesses: Vec<String>) {
+        self.failed_stub_harnesses = harnesses;
+    }
+
+    fn get_failed_stub_harnesses(&self) -> &[String] {
+        &self.failed_stub_harnesses
+    }
+
+    fn set_reachability_cache_dir(&mut self, dir: Option<PathBuf>) {
+        self.reachability_cache_dir = dir;
+    }
+
+    fn get_reachability_cache_dir(&self) -> Option<PathBuf> {
+        self.reachability_cache_dir.clone()
+    }
+
+    fn set_output_dir(&mut self, dir: Option<PathBuf>) {
+        self.output_dir = dir;
+    }
+
+    fn get_output_dir(&self) -> Option<PathBuf> {
+        self.output_dir.clone()
+    }
+
+    fn set_call_graph_format(&mut self, format: Option<CallGraphFormat>) {
+        self.call_graph_format = format;
+    }
+
+    fn get_call_graph_format(&self) -> Option<CallGraphFormat> {
+        self.call_graph_format
+    }
+
+    fn set_reachable_counts_format(&mut self, format: Option<ReportFormat>) {
+        self.reachable_counts_format = format;
+    }
+
+    fn get_reachable_counts_format(&self) -> Option<ReportFormat> {
+        self.reachable_counts_format
+    }
+
+    fn set_include_synthetic_items(&mut self, include_synthetic_items: bool) {
+        self.include_synthetic_items = include_synthetic_items;
+    }
+
+    fn get_include_synthetic_items(&self) -> bool {
+        self.include_synthetic_items
+    }
+
+    fn set_identity_passes_only(&mut self, identity_passes_only: bool) {
+        self.identity_passes_only = identity_passes_only;
+    }
+
+    fn get_identity_passes_only(&self) -> bool {
+        self.identity_passes_only
+    }
+
+    fn set_function_unwinds(&mut self, function_unwinds: BTreeMap<String, u32>) {
+        self.function_unwinds = function_unwinds;
+    }
+
+    fn get_function_unwind(&self, path: &str) -> Option<u32> {
+        self.function_unwinds.get(path).copied()
+    }
+
+    fn set_verification_config(&mut self, config: Option<VerificationConfig>) {
+        self.verification_config = config;
+    }
+
+    fn get_verification_config(&self) -> Option<&VerificationConfig> {
+        self.verification_config.as_ref()
+    }
+
+    fn set_emit_advisories(&mut self, emit_advisories: bool) {
+        self.emit_advisories = emit_advisories;
+    }
+
+    fn get_emit_advisories(&self) -> bool {
+        self.emit_advisories
+    }
+
+    fn set_emit_pass_trace(&mut self, emit_pass_trace: bool) {
+        self.emit_pass_trace = emit_pass_trace;
+    }
+
+    fn get_emit_pass_trace(&self) -> bool {
+        self.emit_pass_trace
+    }
+
     #[cfg(feature = "unsound_experiments")]
     fn get_unsound_experiments(&self) -> UnsoundExperiments {
-        self.unsound_experiments
+        self.unsound_experiments.clone()
     }
 
     #[cfg(feature = "unsound_experiments")]
@@ -143,3 +1066,125 @@ fn set_unsound_experiments(&mut self, experiments: UnsoundExperiments) {
         self.unsound_experiments = experiments
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advisories_are_shared_among_copies() {
+        let queries = QueryDb::new();
+        let snapshot = queries.lock().unwrap().clone();
+        snapshot.add_advisory("stubbing", "skipped pointer call");
+        queries.lock().unwrap().add_advisory("codegen", "weakened bound");
+        let expected = vec![
+            Advisory::new("stubbing", "skipped pointer call"),
+            Advisory::new("codegen", "weakened bound"),
+        ];
+        assert_eq!(queries.lock().unwrap().get_advisories(), expected);
+        assert_eq!(snapshot.get_advisories(), expected);
+    }
+
+    #[test]
+    fn advisories_serialize_to_json() {
+        let queries = QueryDb::new();
+        queries.lock().unwrap().add_advisory("stubbing", "attribute ignored");
+        let json = serde_json::to_string(&queries.lock().unwrap().get_advisories()).unwrap();
+        assert_eq!(json, r#"[{"pass":"stubbing","message":"attribute ignored"}]"#);
+    }
+
+    #[test]
+    fn structured_advisories_roundtrip_through_json() {
+        let queries = QueryDb::new();
+        let span = AdvisorySpan { file: "src/lib.rs".into(), start_line: 3, end_line: 5 };
+        let payload = AdvisoryPayload::new("std-stub", AdvisorySeverity::Warning).with_span(span);
+        let advisory = Advisory::new("stubbing", "stubbed std").with_payload(payload);
+        queries.lock().unwrap().push_advisory(advisory.clone());
+        queries.lock().unwrap().add_advisory("codegen", "weakened bound");
+        let json = serde_json::to_value(queries.lock().unwrap().get_advisories()).unwrap();
+        let expected = serde_json::json!([
+            {
+                "pass": "stubbing",
+                "message": "stubbed std",
+                "payload": {
+                    "code": "std-stub",
+                    "severity": "warning",
+                    "span": { "file": "src/lib.rs", "start_line": 3, "end_line": 5 },
+                },
+            },
+            { "pass": "codegen", "message": "weakened bound" },
+        ]);
+        assert_eq!(json, expected);
+        let advisories: Vec<Advisory> = serde_json::from_value(json).unwrap();
+        assert_eq!(advisories, vec![advisory, Advisory::new("codegen", "weakened bound")]);
+    }
+
+    #[test]
+    fn restore_resets_to_snapshot() {
+        let queries = QueryDb::new();
+        let mut queries = queries.lock().unwrap();
+        queries.set_current_harness(Some("check_first".into()));
+        queries.set_stub_regex_limit(Some(5));
+        queries.add_advisory("stubbing", "attribute ignored");
+        let snapshot = queries.snapshot();
+        let expected = serde_json::to_value(&snapshot).unwrap();
+
+        let copy = queries.clone();
+        queries.set_current_harness(Some("check_second".into()));
+        queries.set_stub_regex_limit(None);
+        queries.set_emit_advisories(true);
+        queries.add_advisory("codegen", "weakened bound");
+        assert_eq!(snapshot.0.get_current_harness(), Some("check_first".into()));
+        assert_eq!(snapshot.0.get_advisories().len(), 1);
+
+        queries.restore(&snapshot);
+        assert_eq!(serde_json::to_value(queries.snapshot()).unwrap(), expected);
+        assert_eq!(queries.get_current_harness(), Some("check_first".into()));
+        assert_eq!(queries.get_stub_regex_limit(), Some(5));
+        assert!(!queries.get_emit_advisories());
+        // Copies that share the advisories observe the restored ones.
+        assert_eq!(copy.get_advisories(), snapshot.0.get_advisories());
+    }
+
+    #[test]
+    fn changed_options_only_reports_deviations() {
+        let mut queries = QueryDb::default();
+        queries.add_advisory("stubbing", "attribute ignored");
+        assert!(queries.changed_options().is_empty());
+
+        queries.set_stub_regex_limit(Some(5));
+        let expected = BTreeMap::from([("stub_regex_limit".to_string(), serde_json::json!(5))]);
+        assert_eq!(queries.changed_options(), expected);
+
+        queries.set_stub_regex_limit(None);
+        assert!(queries.changed_options().is_empty());
+    }
+
+    #[test]
+    fn snapshot_roundtrips_through_json() {
+        let queries = QueryDb::new();
+        let mut queries = queries.lock().unwrap();
+        queries.set_reachability_analysis(ReachabilityType::Harnesses);
+        queries.add_advisory("stubbing", "attribute ignored");
+        let json = serde_json::to_string(&queries.snapshot()).unwrap();
+        let snapshot: QueryDbSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut other = QueryDb::new().lock().unwrap().clone();
+        other.restore(&snapshot);
+        assert_eq!(other.get_reachability_analysis(), ReachabilityType::Harnesses);
+        assert_eq!(other.get_advisories(), queries.get_advisories());
+    }
+
+    #[test]
+    fn stub_path_edition_roundtrips_through_snapshot() {
+        let queries = QueryDb::new();
+        let mut queries = queries.lock().unwrap();
+        queries.set_stub_path_edition(Some(RustEdition::Edition2015));
+        let json = serde_json::to_string(&queries.snapshot()).unwrap();
+        let snapshot: QueryDbSnapshot = serde_json::from_str(&json).unwrap();
+
+        queries.set_stub_path_edition(None);
+        queries.restore(&snapshot);
+        assert_eq!(queries.get_stub_path_edition(), Some(RustEdition::Edition2015));
+    }
+}
@@ -3,11 +3,20 @@
 
 #![cfg(feature = "unsound_experiments")]
 
-#[derive(Debug, Clone, Copy, Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UnsoundExperiments {
     /// Zero initilize variables.
     /// This is useful for experiments to see whether assigning constant values produces better
     /// performance by allowing CBMC to do more constant propegation.
     /// Unfortunatly, it is unsafe to use for production code, since it may unsoundly hide bugs.
     pub zero_init_vars: bool,
+    /// Replace the body of every local function other than the harnesses and the given ones
+    /// by one that returns a nondeterministic value.
+    /// This is useful for experiments to see how verification scales when the function under
+    /// test is isolated from everything it calls.
+    /// It is unsound, since the replaced functions may return values that the original
+    /// functions never return, and their side effects are lost.
+    pub stub_all_except: Option<Vec<String>>,
 }
@@ -4,11 +4,12 @@
 //! This file contains functions related to codegenning MIR functions into gotoc
 
 use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::advisory_span;
 use crate::kani_middle::attributes::{extract_integer_argument, partition_kanitool_attributes};
 use cbmc::goto_program::{Expr, Stmt, Symbol};
 use cbmc::InternString;
 use kani_metadata::{CbmcSolver, HarnessMetadata};
-use kani_queries::UserInput;
+use kani_queries::{Advisory, AdvisoryPayload, AdvisorySeverity, UserInput};
 use rustc_ast::{Attribute, MetaItemKind};
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
@@ -90,7 +91,9 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
             self.codegen_function_prelude();
             self.codegen_declare_variables();
 
-            reverse_postorder(mir).for_each(|(bb, bbd)| self.codegen_block(bb, bbd));
+            if !self.codegen_abstracted_body() {
+                reverse_postorder(mir).for_each(|(bb, bbd)| self.codegen_block(bb, bbd));
+            }
 
             let loc = self.codegen_span(&mir.span);
             let stmts = self.current_fn_mut().extract_block();
@@ -103,6 +106,14 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
         self.reset_current_fn();
     }
 
+    /// Codegens a body that replaces the body of the current function, if the
+    /// function is abstracted away. Returns whether it did so. By default, no
+    /// function is abstracted away.
+    #[cfg(not(feature = "unsound_experiments"))]
+    fn codegen_abstracted_body(&mut self) -> bool {
+        false
+    }
+
     /// Codegen changes required due to the function ABI.
     /// We currently untuple arguments for RustCall ABI where the `spread_arg` is set.
     fn codegen_function_prelude(&mut self) {
@@ -290,16 +301,19 @@ pub fn is_proof_harness(&self, def_id: DefId) -> bool {
     pub fn check_attributes(&self, def_id: DefId) {
         let all_attributes = self.tcx.get_attrs_unchecked(def_id);
         let (proof_attributes, other_attributes) = partition_kanitool_attributes(all_attributes);
+        // Functions other than harnesses may have unwind attributes, which are checked when they
+        // are collected by `collect_function_unwinds`, and no_stub and experimental_stub
+        // attributes.
         if !proof_attributes.is_empty() {
             self.check_proof_attribute(def_id, proof_attributes);
-        } else if !other_attributes.is_empty() {
+        } else if let Some((name, attr)) = other_attributes
+            .iter()
+            .find(|(name, _)| !matches!(name.as_str(), "unwind" | "no_stub" | "experimental_stub"))
+        {
             self.tcx.sess.span_err(
-                other_attributes[0].1.span,
-                format!(
-                    "The {} attribute also requires the '#[kani::proof]' attribute",
-                    other_attributes[0].0
-                )
-                .as_str(),
+                attr.span,
+                format!("The {name} attribute also requires the '#[kani::proof]' attribute")
+                    .as_str(),
             );
         }
     }
@@ -381,12 +395,23 @@ fn create_proof_harness(&mut self, other_attributes: Vec<(String, &Attribute)>)
         for attr in other_attributes.iter() {
             match attr.0.as_str() {
                 "solver" => self.handle_kanitool_solver(attr.1, &mut harness),
-                "stub" => {
+                "stub" | "stub_regex" | "stub_cfg" | "stub_default" | "stub_spec" | "stub_in" => {
                     if !self.queries.get_stubbing_enabled() {
-                        self.tcx.sess.span_warn(
-                            attr.1.span,
-                            "Stubbing is not enabled; attribute `kani::stub` will be ignored",
-                        )
+                        let msg = format!(
+                            "Stubbing is not enabled; attribute `kani::{}` will be ignored",
+                            attr.0
+                        );
+                        self.tcx.sess.span_warn(attr.1.span, msg.as_str());
+                        let msg = format!("{}: {msg}", harness.pretty_name);
+                        self.queries.push_advisory(
+                            Advisory::new("codegen", msg).with_payload(
+                                AdvisoryPayload::new(
+                                    "stubbing-disabled",
+                                    AdvisorySeverity::Warning,
+                                )
+                                .with_span(advisory_span(self.tcx, attr.1.span)),
+                            ),
+                        );
                     }
                 }
                 "unwind" => self.handle_kanitool_unwind(attr.1, &mut harness),
@@ -398,9 +423,47 @@ fn create_proof_harness(&mut self, other_attributes: Vec<(String, &Attribute)>)
                 }
             }
         }
+        self.handle_config_harness(&mut harness);
         self.proof_harnesses.push(harness);
     }
 
+    /// Applies the settings of the verification config for the current harness, if any. The
+    /// unwind value must agree with the `kani::unwind` attribute of the harness.
+    fn handle_config_harness(&mut self, harness: &mut HarnessMetadata) {
+        let def_id = self.current_fn().instance().def_id();
+        let name = self.tcx.def_path_str(def_id);
+        let Some(config) = self.queries.get_verification_config().and_then(|config| config.harness(&name)).cloned() else {
+            return;
+        };
+        if let Some(unwind) = config.unwind {
+            match harness.unwind_value {
+                Some(value) if value != unwind => {
+                    self.tcx.sess.span_err(
+                        self.tcx.def_span(def_id),
+                        format!(
+                            "Conflicting unwind values `{value}` and `{unwind}` for harness \
+                            `{name}`: the verification config does not match the `kani::unwind` \
+                            attribute"
+                        ),
+                    );
+                }
+                _ => harness.unwind_value = Some(unwind),
+            }
+        }
+        if !config.stubs.is_empty() && !self.queries.get_stubbing_enabled() {
+            let msg = "Stubbing is not enabled; the stubs of the verification config will be \
+                ignored";
+            self.tcx.sess.span_warn(self.tcx.def_span(def_id), msg);
+            let msg = format!("{}: {msg}", harness.pretty_name);
+            self.queries.push_advisory(
+                Advisory::new("codegen", msg).with_payload(
+                    AdvisoryPayload::new("stubbing-disabled", AdvisorySeverity::Warning)
+                        .with_span(advisory_span(self.tcx, self.tcx.def_span(def_id))),
+                ),
+            );
+        }
+    }
+
     /// Create the default proof harness for the current function
     fn default_kanitool_proof(&mut self) -> HarnessMetadata {
         let current_fn = self.current_fn();
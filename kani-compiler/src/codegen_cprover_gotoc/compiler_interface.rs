@@ -5,15 +5,22 @@
 
 use crate::codegen_cprover_gotoc::archive::ArchiveBuilder;
 use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::attributes::collect_function_unwinds;
+use crate::kani_middle::emitted_file_base;
+use crate::kani_middle::pass_trace;
 use crate::kani_middle::provide;
 use crate::kani_middle::reachability::{
-    collect_reachable_items, filter_closures_in_const_crate_items, filter_crate_items,
+    collect_call_graph, collect_items_without_mir, collect_reachable_items,
+    collect_synthetic_items, filter_closures_in_const_crate_items, filter_crate_items,
 };
+use crate::kani_middle::reachability_cache::load_or_collect;
+use crate::kani_middle::reachable_counts::print_reachable_counts;
+use crate::kani_middle::stubbing;
 use bitflags::_core::any::Any;
 use cbmc::goto_program::Location;
 use cbmc::{InternedString, MachineModel};
 use kani_metadata::{ArtifactType, HarnessMetadata, KaniMetadata};
-use kani_queries::{QueryDb, ReachabilityType, UserInput};
+use kani_queries::{CallGraphFormat, QueryDb, ReachabilityType, UserInput};
 use rustc_codegen_ssa::back::metadata::create_wrapper_file;
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_codegen_ssa::{CodegenResults, CrateInfo};
@@ -21,12 +28,13 @@
 use rustc_data_structures::temp_dir::MaybeTempDir;
 use rustc_errors::ErrorGuaranteed;
 use rustc_hir::def::DefKind;
-use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_metadata::fs::{emit_wrapper_file, METADATA_FILENAME};
 use rustc_metadata::EncodedMetadata;
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
 use rustc_middle::mir::write_mir_pretty;
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, InstanceDef, TyCtxt};
 use rustc_session::config::{CrateType, OutputFilenames, OutputType};
@@ -36,7 +44,7 @@
 use rustc_span::def_id::DefId;
 use rustc_target::abi::Endian;
 use rustc_target::spec::PanicStrategy;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsString;
 use std::fmt::Write;
 use std::fs::File;
@@ -86,6 +94,10 @@ fn codegen_crate(
     ) -> Box<dyn Any> {
         super::utils::init();
 
+        let verification_config = self.queries.lock().unwrap().get_verification_config().cloned();
+        let function_unwinds = collect_function_unwinds(tcx, verification_config.as_ref());
+        self.queries.lock().unwrap().set_function_unwinds(function_unwinds);
+
         // Follow rustc naming convention (cx is abbrev for context).
         // https://rustc-dev-guide.rust-lang.org/conventions.html#naming-conventions
         let mut gcx = GotocCtx::new(tcx, (*self.queries.lock().unwrap()).clone());
@@ -93,12 +105,31 @@ fn codegen_crate(
         check_options(tcx.sess);
         check_crate_items(&gcx);
 
-        let items = with_timer(|| collect_codegen_items(&gcx), "codegen reachability analysis");
+        let (items, call_graph) =
+            with_timer(|| collect_codegen_items(&gcx), "codegen reachability analysis");
         if items.is_empty() {
             // There's nothing to do.
             return codegen_results(tcx, rustc_metadata, gcx.symbol_table.machine_model());
         }
-        dump_mir_items(tcx, &items);
+        dump_mir_items(tcx, &gcx.queries, &items);
+        stubbing::record_reachable_stubs(tcx, &items);
+        if let Some(harness) = gcx.queries.get_emit_stubbed_mir() {
+            dump_stubbed_mir(&gcx, &harness);
+        }
+        stubbing::report_trivial_stubs(tcx, &gcx.queries);
+        stubbing::report_oversized_stubs(tcx, &gcx.queries);
+        stubbing::report_experimental_stubs(tcx, &gcx.queries);
+        if gcx.queries.get_deny_unapplied_stubs() {
+            stubbing::report_unapplied_stubs(tcx);
+        }
+        if gcx.queries.get_check_stub_call_sites() {
+            let allow_shared_refs = gcx.queries.get_allow_stub_shared_refs();
+            stubbing::check_stub_call_sites(tcx, &items, allow_shared_refs);
+        }
+        let (reported_items, reported_edges) = reported_items(&gcx, &items, &call_graph);
+        if let Some(format) = gcx.queries.get_reachable_counts_format() {
+            print_reachable_counts(tcx, &reported_items, format);
+        }
 
         with_timer(
             || {
@@ -127,8 +158,8 @@ fn codegen_crate(
                 }
 
                 // then we move on to codegen
-                for item in items {
-                    match item {
+                for item in &items {
+                    match *item {
                         MonoItem::Fn(instance) => {
                             gcx.call_with_panic_debug_info(
                                 |ctx| ctx.codegen_function(instance),
@@ -142,7 +173,7 @@ fn codegen_crate(
                         }
                         MonoItem::Static(def_id) => {
                             gcx.call_with_panic_debug_info(
-                                |ctx| ctx.codegen_static(def_id, item),
+                                |ctx| ctx.codegen_static(def_id, *item),
                                 format!("codegen_static: {def_id:?}"),
                                 def_id,
                             );
@@ -182,6 +213,40 @@ fn codegen_crate(
             if let Some(restrictions) = vtable_restrictions {
                 write_file(&base_filename, ArtifactType::VTableRestriction, &restrictions, pretty);
             }
+            // The files emitted on request may go to their own directory.
+            let emitted_base = emitted_file_base(tcx, &gcx.queries);
+            if gcx.queries.get_emit_advisories() {
+                let advisories = gcx.queries.get_advisories();
+                write_file(&emitted_base, ArtifactType::Advisories, &advisories, pretty);
+            }
+            if let Some(trace) = pass_trace::trace_json() {
+                write_file(&emitted_base, ArtifactType::PassTrace, &trace, pretty);
+            }
+            if gcx.queries.get_emit_stub_candidates() {
+                let include_foreign = gcx.queries.get_stub_candidates_include_foreign();
+                let candidates = stubbing::stub_candidates(tcx, include_foreign);
+                write_file(&emitted_base, ArtifactType::StubCandidates, &candidates, pretty);
+            }
+            if gcx.queries.get_emit_stub_caller_counts() {
+                let counts = stubbing::stub_caller_counts(tcx);
+                write_file(&emitted_base, ArtifactType::StubCallerCounts, &counts, pretty);
+            }
+            if gcx.queries.get_emit_harness_metadata() {
+                write_harness_metadata(&gcx, &emitted_base, pretty);
+            }
+            if gcx.queries.get_emit_verification_plan() {
+                write_verification_plan(&gcx, &emitted_base, pretty);
+            }
+            if gcx.queries.get_emit_no_mir_report() {
+                write_no_mir_report(&gcx, &emitted_base, &items, pretty);
+            }
+            if gcx.queries.get_emit_harness_reachability() {
+                write_harness_reachability(&gcx, &emitted_base, pretty);
+            }
+            if let Some(format) = gcx.queries.get_call_graph_format() {
+                let (items, edges) = (&reported_items, &reported_edges);
+                write_call_graph(&gcx, &emitted_base, format, items, edges, pretty);
+            }
             symbol_table_to_gotoc(&tcx, &base_filename);
         }
         codegen_results(tcx, rustc_metadata, gcx.symbol_table.machine_model())
@@ -383,41 +448,299 @@ fn codegen_results(
 /// crate items (such as generic functions and functions candidate to be inlined).
 /// - PubFns: Cross-crate reachability analysis that use the local public fns as starting point.
 /// - Tests: Cross-crate collection of all reachable items starting from test harnesses.
-fn collect_codegen_items<'tcx>(gcx: &GotocCtx<'tcx>) -> Vec<MonoItem<'tcx>> {
+fn collect_codegen_items<'tcx>(
+    gcx: &GotocCtx<'tcx>,
+) -> (Vec<MonoItem<'tcx>>, Vec<CallGraphEdge<'tcx>>) {
     let tcx = gcx.tcx;
     let reach = gcx.queries.get_reachability_analysis();
     debug!(?reach, "collect_codegen_items");
     match reach {
         ReachabilityType::Legacy => {
+            if gcx.queries.get_call_graph_format().is_some() {
+                tcx.sess.warn(format!(
+                    "The call graph is not available with `--reachability={}`",
+                    reach.as_ref()
+                ));
+            }
             // Use rustc monomorphizer to retrieve items to codegen.
             let codegen_units: &'tcx [CodegenUnit<'_>] = tcx.collect_and_partition_mono_items(()).1;
-            codegen_units
+            let items = codegen_units
                 .iter()
                 .flat_map(|cgu| cgu.items_in_deterministic_order(tcx))
                 .map(|(item, _)| item)
-                .collect()
+                .collect();
+            (items, Vec::new())
+        }
+        ReachabilityType::None => (Vec::new(), Vec::new()),
+        ReachabilityType::Harnesses | ReachabilityType::Tests | ReachabilityType::PubFns => {
+            if gcx.queries.get_call_graph_format().is_some() {
+                // The cache doesn't store the edges, so always run the analysis in this case.
+                collect_call_graph(tcx, &reachability_roots(gcx, reach))
+            } else if let Some(cache_dir) = gcx.queries.get_reachability_cache_dir() {
+                let items = load_or_collect(tcx, &cache_dir, || {
+                    collect_reachable_items(tcx, &reachability_roots(gcx, reach))
+                });
+                (items, Vec::new())
+            } else {
+                (collect_reachable_items(tcx, &reachability_roots(gcx, reach)), Vec::new())
+            }
         }
+    }
+}
+
+/// The roots of Kani's cross-crate reachability analysis for the given mode.
+fn reachability_roots<'tcx>(gcx: &GotocCtx<'tcx>, reach: ReachabilityType) -> Vec<MonoItem<'tcx>> {
+    let tcx = gcx.tcx;
+    match reach {
         ReachabilityType::Harnesses => {
             // Cross-crate collecting of all items that are reachable from the crate harnesses.
-            let harnesses = filter_crate_items(tcx, |_, def_id| gcx.is_proof_harness(def_id));
-            collect_reachable_items(tcx, &harnesses).into_iter().collect()
+            filter_crate_items(tcx, |_, def_id| gcx.is_proof_harness(def_id))
         }
         ReachabilityType::Tests => {
             // We're iterating over crate items here, so what we have to codegen is the "test description" containing the
             // test closure that we want to execute
-            let harnesses = filter_closures_in_const_crate_items(tcx, |_, def_id| {
+            filter_closures_in_const_crate_items(tcx, |_, def_id| {
                 gcx.is_test_harness_description(def_id)
-            });
-            collect_reachable_items(tcx, &harnesses).into_iter().collect()
+            })
         }
-        ReachabilityType::None => Vec::new(),
         ReachabilityType::PubFns => {
             let entry_fn = tcx.entry_fn(()).map(|(id, _)| id);
-            let local_reachable = filter_crate_items(tcx, |_, def_id| {
+            filter_crate_items(tcx, |_, def_id| {
                 (tcx.is_reachable_non_generic(def_id) && tcx.def_kind(def_id).is_fn_like())
                     || entry_fn == Some(def_id)
-            });
-            collect_reachable_items(tcx, &local_reachable).into_iter().collect()
+            })
+        }
+        ReachabilityType::Legacy | ReachabilityType::None => {
+            unreachable!("No roots for reachability type {reach:?}")
+        }
+    }
+}
+
+/// An edge of the call graph from the first item to the second one.
+type CallGraphEdge<'tcx> = (MonoItem<'tcx>, MonoItem<'tcx>);
+
+/// The reachable items and the call graph edges that are reported to the user. If
+/// `--include-synthetic-items` is set, they include the compiler-generated items that the
+/// reachability analysis omits, even though these items are not compiled.
+fn reported_items<'tcx>(
+    gcx: &GotocCtx<'tcx>,
+    items: &[MonoItem<'tcx>],
+    edges: &[CallGraphEdge<'tcx>],
+) -> (Vec<MonoItem<'tcx>>, Vec<CallGraphEdge<'tcx>>) {
+    let mut items = items.to_vec();
+    let mut edges = edges.to_vec();
+    if gcx.queries.get_include_synthetic_items() {
+        let (synthetic_items, synthetic_edges) = collect_synthetic_items(gcx.tcx, &items);
+        items.extend(synthetic_items);
+        edges.extend(synthetic_edges);
+    }
+    (items, edges)
+}
+
+/// Write the call graph of the reachable items in the given format.
+///
+/// Items are identified by their readable names, so the generic parameters of a function are
+/// part of its name. Items with the same name, such as different shims of a function, are merged.
+fn write_call_graph<'tcx>(
+    gcx: &GotocCtx<'tcx>,
+    base_filename: &Path,
+    format: CallGraphFormat,
+    items: &[MonoItem<'tcx>],
+    edges: &[CallGraphEdge<'tcx>],
+    pretty: bool,
+) {
+    // Each node is tagged with the crate it comes from, so users can tell their own code apart
+    // from the code of their dependencies.
+    let nodes: BTreeMap<String, CrateNum> =
+        items.iter().map(|item| (item_name(gcx, item), item_def_id(item).krate)).collect();
+    let edges: BTreeSet<(String, String)> = edges
+        .iter()
+        .map(|(caller, callee)| (item_name(gcx, caller), item_name(gcx, callee)))
+        .collect();
+    match format {
+        CallGraphFormat::Json => {
+            let nodes: Vec<_> = nodes
+                .iter()
+                .map(|(name, krate)| {
+                    serde_json::json!({
+                        "name": name,
+                        "crate": gcx.tcx.crate_name(*krate).as_str(),
+                        "local": *krate == LOCAL_CRATE,
+                    })
+                })
+                .collect();
+            let edges: Vec<_> = edges
+                .iter()
+                .map(|(caller, callee)| serde_json::json!({ "caller": caller, "callee": callee }))
+                .collect();
+            let graph = serde_json::json!({ "nodes": nodes, "edges": edges });
+            write_file(base_filename, ArtifactType::CallGraph, &graph, pretty);
+        }
+        CallGraphFormat::Dot => {
+            let mut dot = String::from("digraph call_graph {\n");
+            for node in nodes.keys() {
+                writeln!(dot, "    {node:?};").unwrap();
+            }
+            for (caller, callee) in &edges {
+                writeln!(dot, "    {caller:?} -> {callee:?};").unwrap();
+            }
+            dot.push_str("}\n");
+            let filename = base_filename.with_extension(ArtifactType::CallGraphDot);
+            debug!(?filename, "write_call_graph");
+            std::fs::write(&filename, dot).unwrap();
+        }
+    }
+}
+
+/// Write the reachable functions that have no MIR, sorted by their name, together with the kind of
+/// function (`foreign`, `intrinsic` or `unavailable`) and the reachable items that call them.
+/// Kani cannot analyze the body of these functions, so this tells users where the gaps are.
+fn write_no_mir_report<'tcx>(
+    gcx: &GotocCtx<'tcx>,
+    base_filename: &Path,
+    items: &[MonoItem<'tcx>],
+    pretty: bool,
+) {
+    let tcx = gcx.tcx;
+    let mut functions: BTreeMap<String, (&str, BTreeSet<String>)> = BTreeMap::new();
+    for (caller, callee) in collect_items_without_mir(tcx, items) {
+        let kind = match callee.def {
+            InstanceDef::Intrinsic(_) => "intrinsic",
+            _ if tcx.is_foreign_item(callee.def_id()) => "foreign",
+            _ => "unavailable",
+        };
+        let name = gcx.readable_instance_name(callee);
+        let (_, callers) = functions.entry(name).or_insert_with(|| (kind, BTreeSet::new()));
+        callers.insert(item_name(gcx, &caller));
+    }
+    let functions: Vec<_> = functions
+        .iter()
+        .map(|(name, (kind, callers))| {
+            serde_json::json!({ "name": name, "kind": kind, "callers": callers })
+        })
+        .collect();
+    let report = serde_json::json!({ "functions": functions });
+    write_file(base_filename, ArtifactType::NoMirReport, &report, pretty);
+}
+
+/// Write the metadata of each proof harness together with the stubs resolved for it, sorted by the
+/// harness name, so other tools can plan verification runs without compiling the crate again.
+fn write_harness_metadata(gcx: &GotocCtx, base_filename: &Path, pretty: bool) {
+    let crate_name = gcx.tcx.crate_name(LOCAL_CRATE);
+    let metadata =
+        serde_json::json!({ "crate": crate_name.as_str(), "harnesses": harness_entries(gcx) });
+    write_file(base_filename, ArtifactType::Harnesses, &metadata, pretty);
+}
+
+/// The name, location, unwind bound and resolved stubs of each proof harness, sorted by the
+/// harness name.
+fn harness_entries(gcx: &GotocCtx) -> Vec<serde_json::Value> {
+    let mut harnesses: Vec<_> = gcx.proof_harnesses.iter().collect();
+    harnesses.sort_by(|harness1, harness2| harness1.pretty_name.cmp(&harness2.pretty_name));
+    harnesses
+        .into_iter()
+        .map(|harness| {
+            serde_json::json!({
+                "name": harness.pretty_name,
+                "span": {
+                    "file": harness.original_file,
+                    "start_line": harness.original_start_line,
+                    "end_line": harness.original_end_line,
+                },
+                "unwind": harness.unwind_value,
+                "stubs": gcx.queries.get_resolved_stubs(&harness.pretty_name),
+                "stub_errors":
+                    gcx.queries.get_failed_stub_harnesses().contains(&harness.pretty_name),
+            })
+        })
+        .collect()
+}
+
+/// Write the verification plan of the crate, which combines the metadata of the harnesses (see
+/// [write_harness_metadata]), the roots of the reachability analysis, the goto model and the
+/// options that affect the model. This is everything an external tool needs to drive the
+/// verification of each harness and to reproduce the run.
+fn write_verification_plan(gcx: &GotocCtx, base_filename: &Path, pretty: bool) {
+    let tcx = gcx.tcx;
+    let queries = &gcx.queries;
+    let reach = queries.get_reachability_analysis();
+    let roots: Vec<_> = match reach {
+        ReachabilityType::Legacy | ReachabilityType::None => vec![],
+        ReachabilityType::Harnesses | ReachabilityType::Tests | ReachabilityType::PubFns => {
+            reachability_roots(gcx, reach).iter().map(|item| item_name(gcx, item)).collect()
+        }
+    };
+    let model = tcx
+        .output_filenames(())
+        .output_path(OutputType::Object)
+        .with_extension(ArtifactType::SymTabGoto);
+    let options = serde_json::json!({
+        "current_harness": queries.get_current_harness(),
+        "check_assertion_reachability": queries.get_check_assertion_reachability(),
+        "emit_vtable_restrictions": queries.get_emit_vtable_restrictions(),
+        "ignore_global_asm": queries.get_ignore_global_asm(),
+        "enable_stubbing": queries.get_stubbing_enabled(),
+        "allow_std_stubbing": queries.get_allow_std_stubbing(),
+        "stub_harness_body": queries.get_stub_harness_body(),
+        "verify_stub_types": queries.get_verify_stub_types(),
+        "preserve_stub_signature": queries.get_preserve_stub_signature(),
+        "enable_experimental_stubs": queries.get_enable_experimental_stubs(),
+        "allow_stub_shared_refs": queries.get_allow_stub_shared_refs(),
+        "verification_config": queries.get_verification_config(),
+    });
+    #[cfg(feature = "unsound_experiments")]
+    let options = {
+        let mut options = options;
+        options["unsound_experiments"] = serde_json::json!(queries.get_unsound_experiments());
+        options
+    };
+    let plan = serde_json::json!({
+        "crate": tcx.crate_name(LOCAL_CRATE).as_str(),
+        "model": model,
+        "harnesses": harness_entries(gcx),
+        "reachability": { "mode": reach.as_ref(), "roots": roots },
+        "options": options,
+    });
+    write_file(base_filename, ArtifactType::VerificationPlan, &plan, pretty);
+}
+
+/// Write the functions reachable from each proof harness on its own, keyed by the harness name.
+/// Only these functions are candidates for stubbing in the harness, so users can tell which
+/// stubs are relevant to each proof. The harness itself is not part of its set.
+fn write_harness_reachability(gcx: &GotocCtx, base_filename: &Path, pretty: bool) {
+    let tcx = gcx.tcx;
+    let harnesses = filter_crate_items(tcx, |_, def_id| gcx.is_proof_harness(def_id));
+    let reachability: BTreeMap<String, BTreeSet<String>> = harnesses
+        .iter()
+        .map(|harness| {
+            let functions = collect_reachable_items(tcx, &[*harness])
+                .iter()
+                .filter(|item| matches!(item, MonoItem::Fn(_)) && *item != harness)
+                .map(|item| item_name(gcx, item))
+                .collect();
+            (item_name(gcx, harness), functions)
+        })
+        .collect();
+    let report = serde_json::json!({ "harnesses": reachability });
+    write_file(base_filename, ArtifactType::HarnessReachability, &report, pretty);
+}
+
+/// The definition an item is an instance of.
+fn item_def_id(item: &MonoItem) -> DefId {
+    match item {
+        MonoItem::Fn(instance) => instance.def_id(),
+        MonoItem::Static(def_id) => *def_id,
+        MonoItem::GlobalAsm(item_id) => item_id.owner_id.to_def_id(),
+    }
+}
+
+/// The readable name of an item used in the call graph.
+fn item_name<'tcx>(gcx: &GotocCtx<'tcx>, item: &MonoItem<'tcx>) -> String {
+    match item {
+        MonoItem::Fn(instance) => gcx.readable_instance_name(*instance),
+        MonoItem::Static(def_id) => with_no_trimmed_paths!(gcx.tcx.def_path_str(*def_id)),
+        MonoItem::GlobalAsm(item_id) => {
+            with_no_trimmed_paths!(gcx.tcx.def_path_str(item_id.owner_id.to_def_id()))
         }
     }
 }
@@ -456,26 +779,25 @@ fn symbol_table_to_gotoc(tcx: &TyCtxt, file: &Path) -> PathBuf {
     output_filename
 }
 
-/// Print MIR for the reachable items if the `--emit mir` option was provided to rustc.
-fn dump_mir_items(tcx: TyCtxt, items: &[MonoItem]) {
-    /// Convert MonoItem into a DefId.
-    /// Skip stuff that we cannot generate the MIR items.
-    fn visible_item<'tcx>(item: &MonoItem<'tcx>) -> Option<(MonoItem<'tcx>, DefId)> {
-        match item {
-            // Exclude FnShims and others that cannot be dumped.
-            MonoItem::Fn(instance) if matches!(instance.def, InstanceDef::Item(..)) => {
-                Some((*item, instance.def_id()))
-            }
-            MonoItem::Fn(..) => None,
-            MonoItem::Static(def_id) => Some((*item, *def_id)),
-            MonoItem::GlobalAsm(_) => None,
+/// Convert MonoItem into a DefId.
+/// Skip stuff that we cannot generate the MIR items.
+fn visible_item<'tcx>(item: &MonoItem<'tcx>) -> Option<(MonoItem<'tcx>, DefId)> {
+    match item {
+        // Exclude FnShims and others that cannot be dumped.
+        MonoItem::Fn(instance) if matches!(instance.def, InstanceDef::Item(..)) => {
+            Some((*item, instance.def_id()))
         }
+        MonoItem::Fn(..) => None,
+        MonoItem::Static(def_id) => Some((*item, *def_id)),
+        MonoItem::GlobalAsm(_) => None,
     }
+}
 
+/// Print MIR for the reachable items if the `--emit mir` option was provided to rustc.
+fn dump_mir_items(tcx: TyCtxt, queries: &QueryDb, items: &[MonoItem]) {
     if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
         // Create output buffer.
-        let outputs = tcx.output_filenames(());
-        let path = outputs.output_path(OutputType::Mir).with_extension("kani.mir");
+        let path = emitted_file_base(tcx, queries).with_extension("kani.mir");
         let out_file = File::create(&path).unwrap();
         let mut writer = BufWriter::new(out_file);
 
@@ -487,6 +809,53 @@ fn visible_item<'tcx>(item: &MonoItem<'tcx>) -> Option<(MonoItem<'tcx>, DefId)>
     }
 }
 
+/// Print the MIR of the given harness and of the items reachable from it for
+/// `--emit-stubbed-mir`. The bodies are the ones returned by the `optimized_mir`
+/// query, which runs the stubbing pass, so the stubbed functions/methods have
+/// the body of their stub. The harness name may be partially qualified, as with
+/// `--harness`.
+fn dump_stubbed_mir(gcx: &GotocCtx, harness: &str) {
+    let tcx = gcx.tcx;
+    let harnesses = filter_crate_items(tcx, |_, def_id| gcx.is_proof_harness(def_id));
+    let mut matches: Vec<_> = harnesses
+        .into_iter()
+        .filter(|item| {
+            let MonoItem::Fn(instance) = item else { return false };
+            let name = with_no_trimmed_paths!(tcx.def_path_str(instance.def_id()));
+            kani_queries::matches_harness(harness, &name)
+        })
+        .collect();
+    let root = match matches.len() {
+        1 => matches.pop().unwrap(),
+        0 => {
+            tcx.sess.err(format!("`--emit-stubbed-mir`: no proof harness named `{harness}`"));
+            return;
+        }
+        count => {
+            tcx.sess.err(format!(
+                "`--emit-stubbed-mir`: {count} proof harnesses are named `{harness}`; use its \
+                fully qualified name"
+            ));
+            return;
+        }
+    };
+    let items = collect_reachable_items(tcx, &[root]);
+    let path = emitted_file_base(tcx, &gcx.queries).with_extension("stubbed.mir");
+    debug!(?path, "dump_stubbed_mir");
+    let out_file = match File::create(&path) {
+        Ok(out_file) => out_file,
+        Err(err) => {
+            tcx.sess.err(format!("Failed to write the stubbed MIR to `{}`: {err}", path.display()));
+            return;
+        }
+    };
+    let mut writer = BufWriter::new(out_file);
+    for (item, def_id) in items.iter().filter_map(visible_item) {
+        writeln!(writer, "// Item: {item:?}").unwrap();
+        write_mir_pretty(tcx, Some(def_id), &mut writer).unwrap();
+    }
+}
+
 /// Method that generates `KaniMetadata` from the given compilation context.
 /// This is a temporary method used until we generate a model per-harness.
 /// See <https://github.com/model-checking/kani/issues/1855> for more details.
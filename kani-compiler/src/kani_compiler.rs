@@ -16,40 +16,83 @@
 //! `-C llvm-args`.
 
 use crate::codegen_cprover_gotoc::GotocCodegenBackend;
-use crate::kani_middle::stubbing;
+use crate::kani_middle::attributes::partition_kanitool_attributes;
+use crate::kani_middle::{advisory_span, emitted_file_base, resolve, stubbing};
 use crate::parser::{self, KaniCompilerParser};
 use crate::session::init_session;
 use clap::ArgMatches;
-use kani_queries::{QueryDb, ReachabilityType, UserInput};
+use kani_queries::{
+    matches_harness, Advisory, AdvisoryPayload, AdvisorySeverity, QueryDb, ReachabilityType,
+    ResolvedStub, StubSource, UserInput, VerificationConfig,
+};
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_driver::{Callbacks, Compilation, RunCompiler};
+use rustc_hir::def_id::{CRATE_DEF_ID, LOCAL_CRATE};
 use rustc_hir::definitions::DefPathHash;
 use rustc_interface::Config;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::ErrorOutputType;
+use rustc_session::early_error;
+use rustc_span::edition::Edition;
+use rustc_span::Span;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::debug;
 
 /// Run the Kani flavour of the compiler.
 /// This may require multiple runs of the rustc driver ([RunCompiler::run]).
 pub fn run(mut args: Vec<String>) -> ExitCode {
     let mut kani_compiler = KaniCompiler::new();
+    kani_compiler.declares_edition = declares_edition(&args);
+    let mut first_run = true;
     while !args.is_empty() {
         let queries = kani_compiler.queries.clone();
+        let start = Instant::now();
         let mut compiler = RunCompiler::new(&args, &mut kani_compiler);
         compiler.set_make_codegen_backend(Some(Box::new(move |_cfg| backend(queries))));
         if compiler.run().is_err() {
+            kani_compiler.print_stub_summary();
             return ExitCode::FAILURE;
         }
 
         args = kani_compiler.post_process(args).unwrap_or_default();
+        // The first run only collects the stubs if the compiler has to run again to apply them.
+        if first_run
+            && !args.is_empty()
+            && kani_compiler.queries.lock().unwrap().get_stub_collection_stats()
+        {
+            print_stub_collection_stats(start.elapsed());
+        }
+        first_run = false;
         debug!("Finish driver run. {}", if args.is_empty() { "Done" } else { "Run again" });
     }
+    kani_compiler.print_stub_summary();
     ExitCode::SUCCESS
 }
 
+/// Print how long the compilation that collects the stubs took, and the peak memory usage of the
+/// compiler so far, which is the one of that compilation since it is the first one.
+fn print_stub_collection_stats(elapsed: Duration) {
+    let memory = match peak_memory_kib() {
+        Some(kib) => format!("{} MiB", kib / 1024),
+        None => "unavailable".to_string(),
+    };
+    println!("Collected the stubs in {}s (peak memory: {memory})", elapsed.as_secs_f32());
+}
+
+/// The peak resident set size of the process in KiB, which is only available on Linux.
+fn peak_memory_kib() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.trim_start_matches("VmHWM:").trim().trim_end_matches("kB").trim().parse().ok()
+}
+
 /// Configure the cprover backend that generate goto-programs.
 #[cfg(feature = "cprover")]
 fn backend(queries: Arc<Mutex<QueryDb>>) -> Box<dyn CodegenBackend> {
@@ -72,14 +115,30 @@ struct KaniCompiler {
     pub queries: Arc<Mutex<QueryDb>>,
     /// Store the stubs that shall be applied if any.
     stubs: Option<FxHashMap<DefPathHash, DefPathHash>>,
+    /// Store the stubs that shall only be applied to the calls made by one caller.
+    caller_stubs: stubbing::CallerStubs,
+    /// Store the specifications of the stubs given by `kani::stub_spec`.
+    spec_stubs: stubbing::SpecStubs,
+    /// Store the summary of the stubs if `--stub-summary` is set.
+    stub_summary: Option<stubbing::StubSummary>,
     /// Store the arguments for kani compiler.
     args: Option<ArgMatches>,
+    /// Whether the arguments of rustc declare the edition of the crate.
+    declares_edition: bool,
 }
 
 impl KaniCompiler {
     /// Create a new [KaniCompiler] instance.
     pub fn new() -> KaniCompiler {
-        KaniCompiler { queries: QueryDb::new(), stubs: None, args: None }
+        KaniCompiler {
+            queries: QueryDb::new(),
+            stubs: None,
+            caller_stubs: FxHashMap::default(),
+            spec_stubs: FxHashMap::default(),
+            stub_summary: None,
+            args: None,
+            declares_edition: false,
+        }
     }
 
     /// Method to be invoked after a rustc driver run.
@@ -87,26 +146,203 @@ pub fn new() -> KaniCompiler {
     /// driver. It will return None if it has finished compiling everything.
     pub fn post_process(&mut self, old_args: Vec<String>) -> Option<Vec<String>> {
         let stubs = self.stubs.replace(FxHashMap::default()).unwrap_or_default();
-        if stubs.is_empty() {
+        let caller_stubs = std::mem::take(&mut self.caller_stubs);
+        let spec_stubs = std::mem::take(&mut self.spec_stubs);
+        if stubs.is_empty() && caller_stubs.is_empty() {
             None
         } else {
             let mut new_args = old_args;
+            // The crate must be compiled with the edition its stub paths were resolved against.
+            if let Some(edition) = self.queries.lock().unwrap().get_stub_path_edition() {
+                if !self.declares_edition {
+                    new_args.push(format!("--edition={}", edition.as_ref()));
+                }
+            }
             new_args.push(stubbing::mk_rustc_arg(&stubs));
+            if !caller_stubs.is_empty() {
+                new_args.push(stubbing::mk_caller_rustc_arg(&caller_stubs));
+            }
+            if !spec_stubs.is_empty() {
+                new_args.push(stubbing::mk_spec_rustc_arg(&spec_stubs));
+            }
             Some(new_args)
         }
     }
 
-    /// Collect the stubs that shall be applied in the next run.
-    fn collect_stubs(&self, tcx: TyCtxt) -> FxHashMap<DefPathHash, DefPathHash> {
-        let all_stubs = stubbing::collect_stub_mappings(tcx);
+    /// Print the summary of the stubs, if any, once the compilation is done.
+    /// The summary is printed as json if `--pretty-json-files` is set.
+    fn print_stub_summary(&mut self) {
+        if let Some(mut summary) = self.stub_summary.take() {
+            summary.add_outcomes(stubbing::take_outcomes());
+            if self.queries.lock().unwrap().get_output_pretty_json() {
+                println!("{}", serde_json::to_string_pretty(&summary.to_json()).unwrap());
+            } else {
+                print!("{}", summary.to_text());
+            }
+        }
+    }
+
+    /// Collect the stubs that shall be applied in the next run. The stubs of callers are stored in
+    /// `caller_stubs`.
+    /// Returns the errors found in the stubbing attributes of any harness instead, so the caller
+    /// can report them and stop the compilation.
+    fn collect_stubs(
+        &mut self,
+        tcx: TyCtxt,
+    ) -> Result<FxHashMap<DefPathHash, DefPathHash>, Vec<stubbing::StubError>> {
+        let mut queries = self.queries.lock().unwrap();
+        let mut collection = if queries.get_stub_resolution_stats() {
+            let start = Instant::now();
+            let collection = stubbing::collect_stub_mappings(tcx, queries.get_stub_regex_limit());
+            // Each stub mapping resolves the path of the original function and of its stub.
+            let paths: usize = collection.mappings.values().map(|stubs| 2 * stubs.len()).sum();
+            println!("Resolved {paths} stub paths in {}s", start.elapsed().as_secs_f32());
+            collection
+        } else {
+            stubbing::collect_stub_mappings(tcx, queries.get_stub_regex_limit())
+        };
+        if let Some(config) = queries.get_verification_config() {
+            let precedence = queries.get_stub_source_precedence();
+            stubbing::add_config_stubs(tcx, &mut collection, config, &precedence);
+        }
+        if queries.get_deny_chained_stubs() {
+            stubbing::check_chained_stubs(tcx, &mut collection);
+        }
+        if let Some(edition) = queries.get_stub_path_edition() {
+            // Only the crates with stubs need to match, since `--stub-path-edition` is passed to
+            // every crate of the build.
+            if edition.as_ref() != tcx.sess.edition().to_string()
+                && collection.mappings.values().any(|stubs| !stubs.is_empty())
+            {
+                tcx.sess.err(format!(
+                    "the stub paths of crate `{}` are resolved against edition {}, but \
+                    `--stub-path-edition` is {}",
+                    tcx.crate_name(LOCAL_CRATE),
+                    tcx.sess.edition(),
+                    edition.as_ref()
+                ));
+                return Err(vec![]);
+            }
+        }
+        let ignored_warning = warn_ignored_attributes(tcx, &queries, &collection.ignored);
+        let mock_warnings = if queries.get_warn_mock_imports() {
+            warn_mock_imports(tcx, &queries, &collection)
+        } else {
+            vec![]
+        };
+        let conflict_warnings = warn_source_conflicts(tcx, &queries, &collection.source_conflicts);
+        let regex_warnings = warn_unmatched_regexes(tcx, &queries, &collection.unmatched_regexes);
+        if queries.get_emit_stub_diagnostics() {
+            let warnings = ignored_warning
+                .iter()
+                .map(|msg| ("ignored-attributes", msg))
+                .chain(mock_warnings.iter().map(|msg| ("mock-import-without-stubs", msg)))
+                .chain(conflict_warnings.iter().map(|msg| ("stub-source-conflict", msg)))
+                .chain(regex_warnings.iter().map(|msg| ("unmatched-stub-regex", msg)));
+            write_stub_diagnostics(tcx, &queries, &collection.errors, warnings);
+        }
+        if queries.get_stub_summary() {
+            let mut summary = stub_summary(&collection);
+            summary.warnings.extend(ignored_warning);
+            summary.warnings.extend(mock_warnings);
+            summary.warnings.extend(conflict_warnings);
+            summary.warnings.extend(regex_warnings);
+            self.stub_summary = Some(summary);
+        }
+        if queries.get_emit_harness_metadata() || queries.get_emit_verification_plan() {
+            queries.set_resolved_stubs(resolved_stubs(tcx, &collection));
+        }
+        if !collection.errors.is_empty() {
+            if !queries.get_continue_on_stub_errors() {
+                return Err(collection.errors);
+            }
+            // The current harness cannot be verified without its stubs, so its errors are still
+            // fatal; the errors of the other harnesses only skip their stubs.
+            let current = queries.get_current_harness();
+            let is_current = |error: &stubbing::StubError| {
+                current.as_ref().map_or(false, |harness| matches_harness(harness, &error.harness))
+            };
+            let (fatal, skipped): (Vec<_>, Vec<_>) =
+                collection.skip_failed_harnesses().into_iter().partition(is_current);
+            if !fatal.is_empty() {
+                return Err(fatal);
+            }
+            skipped.iter().for_each(|error| error.emit_warning(tcx));
+            let mut failed: Vec<_> = skipped.into_iter().map(|error| error.harness).collect();
+            failed.sort();
+            failed.dedup();
+            tcx.sess.warn(format!(
+                "skipped the stubs of {} harness(es) with stub errors: {}",
+                failed.len(),
+                failed.join(", ")
+            ));
+            queries.set_failed_stub_harnesses(failed);
+        }
+        let all_stubs = collection.mappings;
+        let mut caller_mappings = collection.caller_mappings;
+        let mut spec_mappings = collection.spec_mappings;
         if all_stubs.is_empty() {
-            FxHashMap::default()
-        } else if let Some(harness) = self.args.as_ref().unwrap().get_one::<String>(parser::HARNESS)
-        {
-            find_harness_stub_mapping(harness, all_stubs).unwrap_or_default()
+            return Ok(FxHashMap::default());
+        }
+        if let Some(harness) = queries.get_current_harness() {
+            let mut warnings = vec![];
+            let stubs = match find_harness_stub_mapping(&harness, all_stubs) {
+                Some((name, mut stubs)) => {
+                    if !queries.get_stub_harness_body() {
+                        warnings.extend(skip_harness_stub(tcx, &queries, &name, &mut stubs));
+                    }
+                    self.caller_stubs = caller_mappings.remove(&name).unwrap_or_default();
+                    self.spec_stubs = spec_mappings.remove(&name).unwrap_or_default();
+                    if let Some(summary) = &mut self.stub_summary {
+                        // Updated with the stubs that were applied once the compilation is done.
+                        let entries = summary.harnesses.iter_mut();
+                        entries.filter(|entry| entry.harness == name).for_each(|entry| {
+                            entry.applied = Some(0);
+                        });
+                    }
+                    stubs
+                }
+                None => {
+                    let msg = format!(
+                        "Harness `{harness}` was not found in crate `{}`; no stubs will be applied",
+                        tcx.crate_name(LOCAL_CRATE)
+                    );
+                    tcx.sess.warn(&msg);
+                    warnings.push(msg);
+                    FxHashMap::default()
+                }
+            };
+            if !queries.get_allow_std_stubbing() {
+                warnings.extend(warn_std_stubs(tcx, &queries, &stubs));
+            }
+            if let Some(path) = queries.get_stub_body_hashes() {
+                warnings.extend(check_stub_body_hashes(
+                    tcx,
+                    &queries,
+                    &path,
+                    &stubs,
+                    &self.caller_stubs,
+                ));
+            }
+            if let Some(summary) = &mut self.stub_summary {
+                summary.warnings.extend(warnings);
+            }
+            if queries.get_emit_stub_mapping() {
+                write_stub_mapping(tcx, &queries, &harness, &stubs);
+            }
+            if queries.get_print_stub_mapping() {
+                let resolved = resolve_stub_entries(tcx, &stubs, &self.caller_stubs);
+                if queries.get_output_pretty_json() {
+                    let json = stubbing::mapping_to_json(&harness, &resolved);
+                    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                } else {
+                    print!("{}", stubbing::mapping_to_text(&harness, &resolved));
+                }
+            }
+            Ok(stubs)
         } else {
             // No harness was provided. Nothing to do.
-            FxHashMap::default()
+            Ok(FxHashMap::default())
         }
     }
 }
@@ -123,6 +359,12 @@ fn config(&mut self, config: &mut Config) {
                 matches!(config.opts.error_format, ErrorOutputType::Json { .. }),
             );
 
+            let verification_config =
+                matches.get_one::<String>(parser::VERIFICATION_CONFIG).map(|path| {
+                    VerificationConfig::from_file(Path::new(path))
+                        .unwrap_or_else(|err| early_error(config.opts.error_format, &err))
+                });
+
             // Configure queries.
             let queries = &mut (*self.queries.lock().unwrap());
             queries.set_emit_vtable_restrictions(matches.get_flag(parser::RESTRICT_FN_PTRS));
@@ -131,11 +373,100 @@ fn config(&mut self, config: &mut Config) {
             queries.set_output_pretty_json(matches.get_flag(parser::PRETTY_OUTPUT_FILES));
             queries.set_ignore_global_asm(matches.get_flag(parser::IGNORE_GLOBAL_ASM));
             queries.set_reachability_analysis(matches.reachability_type());
+            queries.set_emit_advisories(matches.get_flag(parser::EMIT_ADVISORIES));
+            queries.set_emit_pass_trace(matches.get_flag(parser::EMIT_PASS_TRACE));
+            queries.set_current_harness(matches.get_one::<String>(parser::HARNESS).cloned());
+            queries.set_allow_std_stubbing(matches.get_flag(parser::ALLOW_STD_STUBBING));
+            queries.set_stub_harness_body(matches.get_flag(parser::STUB_HARNESS_BODY));
+            queries.set_verify_stub_types(matches.get_flag(parser::VERIFY_STUB_TYPES));
+            queries.set_preserve_stub_signature(matches.get_flag(parser::PRESERVE_STUB_SIGNATURE));
+            queries.set_stub_resolution_stats(matches.get_flag(parser::STUB_RESOLUTION_STATS));
+            queries.set_stub_collection_stats(matches.get_flag(parser::STUB_COLLECTION_STATS));
+            queries.set_stub_summary(matches.get_flag(parser::STUB_SUMMARY));
+            queries.set_emit_stub_diagnostics(matches.get_flag(parser::EMIT_STUB_DIAGNOSTICS));
+            queries.set_deny_chained_stubs(matches.get_flag(parser::DENY_CHAINED_STUBS));
+            queries.set_deny_unapplied_stubs(matches.get_flag(parser::DENY_UNAPPLIED_STUBS));
+            queries.set_check_stub_call_sites(matches.get_flag(parser::CHECK_STUB_CALL_SITES));
+            queries.set_stub_without_kani_cfg(matches.get_flag(parser::STUB_WITHOUT_KANI_CFG));
+            queries.set_warn_mock_imports(matches.get_flag(parser::WARN_MOCK_IMPORTS));
+            queries.set_stub_body_hashes(
+                matches.get_one::<String>(parser::STUB_BODY_HASHES).map(PathBuf::from),
+            );
+            queries
+                .set_emit_stubbed_mir(matches.get_one::<String>(parser::EMIT_STUBBED_MIR).cloned());
+            if let Some(precedence) = matches.stub_source_precedence() {
+                queries.set_stub_source_precedence(precedence);
+            }
+            queries
+                .set_enable_experimental_stubs(matches.get_flag(parser::ENABLE_EXPERIMENTAL_STUBS));
+            queries.set_allow_stub_shared_refs(matches.get_flag(parser::ALLOW_STUB_SHARED_REFS));
+            queries
+                .set_stub_regex_limit(matches.get_one::<usize>(parser::STUB_REGEX_LIMIT).copied());
+            queries.set_stub_complexity_ratio(
+                matches.get_one::<usize>(parser::STUB_COMPLEXITY_RATIO).copied(),
+            );
+            queries.set_stub_resolution_depth(
+                matches.get_one::<usize>(parser::STUB_RESOLUTION_DEPTH).copied(),
+            );
+            resolve::set_max_resolution_depth(
+                queries
+                    .get_stub_resolution_depth()
+                    .unwrap_or(resolve::DEFAULT_MAX_RESOLUTION_DEPTH),
+            );
+            queries.set_stub_error_candidates(
+                matches.get_one::<usize>(parser::STUB_ERROR_CANDIDATES).copied(),
+            );
+            stubbing::set_error_candidates(
+                queries.get_stub_error_candidates().unwrap_or(stubbing::DEFAULT_ERROR_CANDIDATES),
+            );
+            queries.set_trace_stub_resolution(matches.get_flag(parser::TRACE_STUB_RESOLUTION));
+            resolve::set_trace_uses(queries.get_trace_stub_resolution());
+            queries.set_stub_exact_paths(matches.get_flag(parser::STUB_EXACT_PATHS));
+            resolve::set_exact_paths(queries.get_stub_exact_paths());
+            queries.set_stub_path_edition(matches.stub_path_edition());
+            if let Some(edition) = queries.get_stub_path_edition() {
+                if !self.declares_edition {
+                    config.opts.edition = Edition::from_str(edition.as_ref()).unwrap();
+                }
+            }
+            queries.set_emit_stub_mapping(matches.get_flag(parser::EMIT_STUB_MAPPING));
+            queries.set_print_stub_mapping(matches.get_flag(parser::PRINT_STUB_MAPPING));
+            queries.set_emit_stub_candidates(matches.get_flag(parser::EMIT_STUB_CANDIDATES));
+            queries.set_emit_stub_caller_counts(matches.get_flag(parser::EMIT_STUB_CALLER_COUNTS));
+            queries.set_stub_candidates_include_foreign(
+                matches.get_flag(parser::STUB_CANDIDATES_INCLUDE_FOREIGN),
+            );
+            queries.set_emit_harness_metadata(matches.get_flag(parser::EMIT_HARNESS_METADATA));
+            queries.set_emit_verification_plan(matches.get_flag(parser::EMIT_VERIFICATION_PLAN));
+            queries.set_continue_on_stub_errors(matches.get_flag(parser::CONTINUE_ON_STUB_ERRORS));
+            queries.set_emit_no_mir_report(matches.get_flag(parser::EMIT_NO_MIR_REPORT));
+            queries
+                .set_emit_harness_reachability(matches.get_flag(parser::EMIT_HARNESS_REACHABILITY));
+            // The command line options take precedence over the verification config.
+            queries.set_reachability_cache_dir(
+                matches
+                    .get_one::<String>(parser::REACHABILITY_CACHE_DIR)
+                    .map(PathBuf::from)
+                    .or_else(|| {
+                        let config = verification_config.as_ref()?;
+                        config.reachability.cache_dir.clone()
+                    }),
+            );
+            queries
+                .set_output_dir(matches.get_one::<String>(parser::OUTPUT_DIR).map(PathBuf::from));
+            queries.set_call_graph_format(matches.call_graph_format());
+            queries.set_reachable_counts_format(matches.reachable_counts_format());
+            queries.set_include_synthetic_items(matches.get_flag(parser::INCLUDE_SYNTHETIC_ITEMS));
+            queries.set_identity_passes_only(matches.get_flag(parser::IDENTITY_PASSES_ONLY));
+            queries.set_verification_config(verification_config);
 
             #[cfg(feature = "unsound_experiments")]
             crate::unsound_experiments::arg_parser::add_unsound_experiment_args_to_queries(
                 queries, &matches,
             );
+            #[cfg(feature = "unsound_experiments")]
+            crate::unsound_experiments::arg_parser::dump_unsound_experiments(queries, &matches)
+                .unwrap_or_else(|err| early_error(config.opts.error_format, &err));
 
             // If appropriate, collect and set the stub mapping.
             if matches.get_flag(parser::ENABLE_STUBBING)
@@ -154,11 +485,48 @@ fn after_analysis<'tcx>(
         _compiler: &rustc_interface::interface::Compiler,
         rustc_queries: &'tcx rustc_interface::Queries<'tcx>,
     ) -> Compilation {
-        if self.stubs.is_none() && self.queries.lock().unwrap().get_stubbing_enabled() {
+        let args = self.args.as_ref().unwrap();
+        if self.stubs.is_none() {
+            if let Some(config) = self.queries.lock().unwrap().get_verification_config() {
+                rustc_queries
+                    .global_ctxt()
+                    .unwrap()
+                    .peek_mut()
+                    .enter(|tcx| check_verification_config(tcx, config));
+            }
+        }
+        if let Some(path) = args.get_one::<String>(parser::VALIDATE_STUB_MAPPING) {
             rustc_queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
-                let stubs = self.stubs.insert(self.collect_stubs(tcx));
-                debug!(?stubs, "after_analysis");
-                if stubs.is_empty() { Compilation::Continue } else { Compilation::Stop }
+                validate_stub_mapping_file(tcx, &self.queries.lock().unwrap(), Path::new(path))
+            });
+            Compilation::Stop
+        } else if self.stubs.is_none() && self.queries.lock().unwrap().get_stubbing_enabled() {
+            rustc_queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+                // The stubs only apply to the crates built for verification, so that a crate
+                // built with the Kani compiler for another purpose behaves as written.
+                if !stubbing::is_kani_cfg_set(tcx)
+                    && !self.queries.lock().unwrap().get_stub_without_kani_cfg()
+                {
+                    debug!("after_analysis: the `kani` cfg is not set, so no stub is collected");
+                    self.stubs = Some(FxHashMap::default());
+                    return Compilation::Continue;
+                }
+                match self.collect_stubs(tcx) {
+                    Ok(stubs) => {
+                        let stubs = self.stubs.insert(stubs);
+                        debug!(?stubs, caller_stubs=?self.caller_stubs, "after_analysis");
+                        if stubs.is_empty() && self.caller_stubs.is_empty() {
+                            Compilation::Continue
+                        } else {
+                            Compilation::Stop
+                        }
+                    }
+                    Err(errors) => {
+                        debug!(?errors, "after_analysis");
+                        errors.iter().for_each(|error| error.emit(tcx));
+                        Compilation::Stop
+                    }
+                }
             })
         } else {
             // There is no need to initialize stubs, keep compiling.
@@ -167,20 +535,470 @@ fn after_analysis<'tcx>(
     }
 }
 
+/// Return whether the arguments of rustc declare the edition of the crate.
+fn declares_edition(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--edition" || arg.starts_with("--edition="))
+}
+
 /// Find the stub mapping for the given harness.
 ///
 /// This function is necessary because Kani currently allows a harness to be
 /// specified by a partially qualified name, whereas stub mappings use fully
 /// qualified names.
+///
+/// If more than one harness matches, an exact match is preferred. Otherwise, we
+/// pick the first harness by name so the choice does not depend on the
+/// iteration order of `stub_mappings`. Returns the name of the harness that was
+/// picked together with its mapping.
 fn find_harness_stub_mapping(
     harness: &str,
     stub_mappings: FxHashMap<String, FxHashMap<DefPathHash, DefPathHash>>,
-) -> Option<FxHashMap<DefPathHash, DefPathHash>> {
+) -> Option<(String, FxHashMap<DefPathHash, DefPathHash>)> {
     let suffix = String::from("::") + harness;
-    for (name, mapping) in stub_mappings {
-        if name == harness || name.ends_with(&suffix) {
-            return Some(mapping);
+    stub_mappings
+        .into_iter()
+        .filter(|(name, _)| name == harness || name.ends_with(&suffix))
+        .min_by(|(name1, _), (name2, _)| (name1 != harness, name1).cmp(&(name2 != harness, name2)))
+}
+
+/// Remove the stub of the harness itself from its stub mapping, e.g. when the harness matches the
+/// pattern of a `kani::stub_regex` attribute, so the harness is verified as written. Returns the
+/// warning that is emitted if there was such a stub.
+fn skip_harness_stub(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    harness: &str,
+    stubs: &mut FxHashMap<DefPathHash, DefPathHash>,
+) -> Option<String> {
+    let harness_hash = *stubs.keys().find(|orig_hash| {
+        tcx.def_path_str(tcx.def_path_hash_to_def_id(**orig_hash, &mut || panic!())) == harness
+    })?;
+    let stub_id =
+        tcx.def_path_hash_to_def_id(stubs.remove(&harness_hash).unwrap(), &mut || panic!());
+    let msg = format!(
+        "Harness `{harness}` is not replaced by its stub `{}`; use `--stub-harness-body` to \
+        apply stubs to the harness itself",
+        resolve::qualified_path_str(tcx, stub_id)
+    );
+    tcx.sess.warn(&msg);
+    let harness_id = tcx.def_path_hash_to_def_id(harness_hash, &mut || panic!());
+    queries.push_advisory(
+        Advisory::new("stubbing", msg.clone()).with_payload(
+            AdvisoryPayload::new("harness-stub-skipped", AdvisorySeverity::Warning)
+                .with_span(advisory_span(tcx, tcx.def_span(harness_id))),
+        ),
+    );
+    Some(msg)
+}
+
+/// Warn about the harnesses and functions of the verification config that do not refer to any
+/// harness or function of the crate. The config is passed to every crate of the build, so this is
+/// only done for the crates that have harnesses.
+fn check_verification_config(tcx: TyCtxt, config: &VerificationConfig) {
+    let harnesses: Vec<_> = tcx
+        .hir_crate_items(())
+        .items()
+        .map(|item| item.owner_id.to_def_id())
+        .filter(|def_id| {
+            let (proof, _) = partition_kanitool_attributes(tcx.get_attrs_unchecked(*def_id));
+            !proof.is_empty()
+        })
+        .map(|def_id| tcx.def_path_str(def_id))
+        .collect();
+    if harnesses.is_empty() {
+        return;
+    }
+    let krate = tcx.crate_name(LOCAL_CRATE);
+    for key in config.harnesses.keys() {
+        if !harnesses.iter().any(|harness| matches_harness(key, harness)) {
+            tcx.sess.warn(format!(
+                "Harness `{key}` of the verification config does not match any harness in crate \
+                `{krate}`"
+            ));
+        }
+    }
+    for path in config.functions.keys() {
+        if resolve::resolve_path(tcx, CRATE_DEF_ID, path).is_none() {
+            tcx.sess.warn(format!(
+                "Function `{path}` of the verification config does not resolve to any \
+                function/method in crate `{krate}`"
+            ));
+        }
+    }
+}
+
+/// Build the summary of the stubs that were collected, before any of them is applied.
+fn stub_summary(collection: &stubbing::StubCollection) -> stubbing::StubSummary {
+    let mut harnesses: Vec<_> = collection
+        .mappings
+        .iter()
+        .map(|(harness, stubs)| stubbing::HarnessStubSummary {
+            harness: harness.clone(),
+            requested: collection.requested.get(harness).copied().unwrap_or_default(),
+            resolved: stubs.len()
+                + collection.caller_mappings.get(harness).map_or(0, |stubs| stubs.len()),
+            applied: None,
+        })
+        .collect();
+    harnesses.sort_by(|entry1, entry2| entry1.harness.cmp(&entry2.harness));
+    stubbing::StubSummary { harnesses, warnings: vec![] }
+}
+
+/// List the stubs resolved for each harness by their qualified paths, sorted by the path of the
+/// original function/method, so they can be emitted together with the metadata of the harness.
+fn resolved_stubs(
+    tcx: TyCtxt,
+    collection: &stubbing::StubCollection,
+) -> BTreeMap<String, Vec<ResolvedStub>> {
+    collection
+        .mappings
+        .iter()
+        .map(|(harness, stubs)| {
+            let no_caller_stubs = stubbing::CallerStubs::default();
+            let caller_stubs = collection.caller_mappings.get(harness).unwrap_or(&no_caller_stubs);
+            (harness.clone(), resolve_stub_entries(tcx, stubs, caller_stubs))
+        })
+        .collect()
+}
+
+/// List the stubs of one harness by their qualified paths, followed by the stubs that only apply
+/// to the calls made by one caller, sorted by the path of the original function/method.
+fn resolve_stub_entries(
+    tcx: TyCtxt,
+    stubs: &FxHashMap<DefPathHash, DefPathHash>,
+    caller_stubs: &stubbing::CallerStubs,
+) -> Vec<ResolvedStub> {
+    let path = |hash: &DefPathHash| {
+        resolve::qualified_path_str(tcx, tcx.def_path_hash_to_def_id(*hash, &mut || panic!()))
+    };
+    let mut resolved: Vec<_> = stubs
+        .iter()
+        .map(|(original, replacement)| ResolvedStub {
+            original: path(original),
+            replacement: path(replacement),
+            caller: None,
+        })
+        .collect();
+    resolved.extend(caller_stubs.iter().map(|((caller, original), replacement)| ResolvedStub {
+        original: path(original),
+        replacement: path(replacement),
+        caller: Some(path(caller)),
+    }));
+    resolved.sort();
+    resolved
+}
+
+/// Write the stub mapping of the harness as a Rust source file next to the other artifacts, or in
+/// the output directory if it is set.
+fn write_stub_mapping(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    harness: &str,
+    stubs: &FxHashMap<DefPathHash, DefPathHash>,
+) {
+    let path = emitted_file_base(tcx, queries).with_extension("stubs.rs");
+    debug!(?path, "write_stub_mapping");
+    if let Err(err) = fs::write(&path, stubbing::mk_rust_source(tcx, harness, stubs)) {
+        tcx.sess.err(format!("Failed to write the stub mapping to `{}`: {err}", path.display()));
+    }
+}
+
+/// Write the errors and warnings found while collecting the stubs as a json array next to the
+/// other artifacts, or in the output directory if it is set. The warnings are given with their
+/// code; they have no span.
+fn write_stub_diagnostics<'a>(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    errors: &[stubbing::StubError],
+    warnings: impl Iterator<Item = (&'static str, &'a String)>,
+) {
+    let warnings = warnings.map(|(code, msg)| {
+        serde_json::json!({
+            "severity": "warning",
+            "code": code,
+            "harness": null,
+            "message": msg,
+            "span": null,
+            "paths": [],
+        })
+    });
+    let diagnostics: Vec<_> =
+        errors.iter().map(|error| error.to_json(tcx)).chain(warnings).collect();
+    let data = if queries.get_output_pretty_json() {
+        serde_json::to_string_pretty(&diagnostics).unwrap()
+    } else {
+        serde_json::to_string(&diagnostics).unwrap()
+    };
+    let path = emitted_file_base(tcx, queries).with_extension("stub_diagnostics.json");
+    debug!(?path, "write_stub_diagnostics");
+    if let Err(err) = fs::write(&path, data) {
+        tcx.sess
+            .err(format!("Failed to write the stub diagnostics to `{}`: {err}", path.display()));
+    }
+}
+
+/// Validate the stub mapping stored as a json object in the given file and print the report as
+/// json. Report an error if the mapping cannot be read or if any of its entries is invalid.
+fn validate_stub_mapping_file(tcx: TyCtxt, queries: &QueryDb, path: &Path) {
+    let mapping = fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|data| {
+        serde_json::from_str::<HashMap<String, String>>(&data).map_err(|err| err.to_string())
+    });
+    let mapping = match mapping {
+        Ok(mapping) => mapping,
+        Err(err) => {
+            tcx.sess
+                .err(format!("Failed to read the stub mapping from `{}`: {err}", path.display()));
+            return;
         }
+    };
+    let report = stubbing::validate_stub_mapping(
+        tcx,
+        &mapping,
+        queries.get_verify_stub_types(),
+        queries.get_allow_stub_shared_refs(),
+    );
+    let entries: Vec<_> = report
+        .entries
+        .iter()
+        .map(|entry| {
+            let (status, reasons) = match &entry.status {
+                stubbing::StubStatus::Valid => ("valid", &[][..]),
+                stubbing::StubStatus::Unresolved => ("unresolved", &[][..]),
+                stubbing::StubStatus::Incompatible(reasons) => ("incompatible", &reasons[..]),
+            };
+            serde_json::json!({
+                "original": entry.original,
+                "replacement": entry.replacement,
+                "resolved_original": entry.resolved_original,
+                "resolved_replacement": entry.resolved_replacement,
+                "status": status,
+                "reasons": reasons,
+            })
+        })
+        .collect();
+    let json = serde_json::json!({ "valid": report.is_valid(), "entries": entries });
+    if queries.get_output_pretty_json() {
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    } else {
+        println!("{json}");
+    }
+    let invalid =
+        report.entries.iter().filter(|entry| entry.status != stubbing::StubStatus::Valid).count();
+    if invalid > 0 {
+        tcx.sess.err(format!(
+            "{invalid} of the {} entries of the stub mapping in `{}` are invalid",
+            report.entries.len(),
+            path.display()
+        ));
     }
-    None
+}
+
+/// Warn once about all the stubbing attributes that are ignored because their items are not
+/// harnesses, which usually means that the wrong function was annotated. Returns the warning.
+fn warn_ignored_attributes(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    ignored: &[(String, usize)],
+) -> Option<String> {
+    let count: usize = ignored.iter().map(|(_, count)| count).sum();
+    let items: Vec<_> = ignored.iter().map(|(path, _)| format!("`{path}`")).collect();
+    let msg = match count {
+        0 => return None,
+        1 => format!(
+            "1 stubbing attribute was ignored because its item is not a harness: {}; did you \
+            forget `#[kani::proof]`?",
+            items[0]
+        ),
+        _ => format!(
+            "{count} stubbing attributes were ignored because their items are not harnesses: {}; \
+            did you forget `#[kani::proof]`?",
+            items.join(", ")
+        ),
+    };
+    tcx.sess.warn(&msg);
+    queries.push_advisory(
+        Advisory::new("stubbing", msg.clone())
+            .with_payload(AdvisoryPayload::new("ignored-attributes", AdvisorySeverity::Warning)),
+    );
+    Some(msg)
+}
+
+/// Warn about the harnesses without stubs that import a mock module (e.g., `use crate::mocks::*`),
+/// which usually means that a stubbing attribute was forgotten, and return the warnings.
+fn warn_mock_imports(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    collection: &stubbing::StubCollection,
+) -> Vec<String> {
+    let has_stubs = |harness: &String| {
+        !collection.mappings.get(harness).map_or(true, |stubs| stubs.is_empty())
+            || !collection.caller_mappings.get(harness).map_or(true, |stubs| stubs.is_empty())
+    };
+    collection
+        .mock_imports
+        .iter()
+        .filter(|(harness, ..)| !has_stubs(harness))
+        .map(|(harness, span, path)| {
+            let msg = format!(
+                "Harness `{harness}` imports `{path}` but has no stubs; did you forget a \
+                `kani::stub` attribute?"
+            );
+            tcx.sess.span_warn(*span, &msg);
+            queries.push_advisory(
+                Advisory::new("stubbing", msg.clone()).with_payload(
+                    AdvisoryPayload::new("mock-import-without-stubs", AdvisorySeverity::Warning)
+                        .with_span(advisory_span(tcx, *span)),
+                ),
+            );
+            msg
+        })
+        .collect()
+}
+
+/// Warn about the functions/methods that the stubbing attributes and the verification config of a
+/// harness map to different stubs, naming the source whose stub is used, and return the warnings.
+fn warn_source_conflicts(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    conflicts: &[stubbing::SourceConflict],
+) -> Vec<String> {
+    let source_name = |source| match source {
+        StubSource::Attribute => "a stubbing attribute",
+        StubSource::Config => "the verification config",
+    };
+    conflicts
+        .iter()
+        .map(|conflict| {
+            let ((winner, winner_stub), (loser, loser_stub)) = (&conflict.winner, &conflict.loser);
+            let msg = format!(
+                "Harness `{}` stubs `{}` with `{winner_stub}` from {} and with `{loser_stub}` from \
+                {}; using `{winner_stub}` from {} (see `--stub-source-precedence`)",
+                conflict.harness,
+                conflict.original,
+                source_name(*winner),
+                source_name(*loser),
+                source_name(*winner),
+            );
+            tcx.sess.warn(&msg);
+            queries.push_advisory(Advisory::new("stubbing", msg.clone()).with_payload(
+                AdvisoryPayload::new("stub-source-conflict", AdvisorySeverity::Warning),
+            ));
+            msg
+        })
+        .collect()
+}
+
+/// Warn about the `kani::stub_regex` attributes whose pattern does not match any function/method,
+/// naming the harness they belong to, and return the warnings.
+fn warn_unmatched_regexes(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    unmatched: &[(String, Span, String)],
+) -> Vec<String> {
+    unmatched
+        .iter()
+        .map(|(harness, span, pattern)| {
+            let msg = format!(
+                "regular expression `{pattern}` does not match any function/method; it adds no \
+                stubs to harness `{harness}`"
+            );
+            tcx.sess.span_warn(*span, &msg);
+            queries.push_advisory(
+                Advisory::new("stubbing", msg.clone()).with_payload(
+                    AdvisoryPayload::new("unmatched-stub-regex", AdvisorySeverity::Warning)
+                        .with_span(advisory_span(tcx, *span)),
+                ),
+            );
+            msg
+        })
+        .collect()
+}
+
+/// Warn about every stub that replaces a function from one of the standard library crates, and
+/// return the warnings.
+///
+/// Stubbing these functions changes the behavior of any code that depends on them, including
+/// other functions in the standard library, which can easily lead to misleading results.
+fn warn_std_stubs(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    stubs: &FxHashMap<DefPathHash, DefPathHash>,
+) -> Vec<String> {
+    let mut std_stubs: Vec<_> = stubs
+        .keys()
+        .map(|orig_hash| tcx.def_path_hash_to_def_id(*orig_hash, &mut || panic!()))
+        .filter(|orig_id| {
+            matches!(tcx.crate_name(orig_id.krate).as_str(), "std" | "core" | "alloc")
+        })
+        .map(|orig_id| (tcx.def_path_str(orig_id), tcx.crate_name(orig_id.krate)))
+        .collect();
+    // Sort the warnings so they do not depend on the iteration order of `stubs`.
+    std_stubs.sort_by(|(path1, _), (path2, _)| path1.cmp(path2));
+    std_stubs
+        .into_iter()
+        .map(|(path, krate)| {
+            let msg = format!(
+                "Stubbing `{path}` from the `{krate}` crate may change the behavior of the \
+                standard library; use `--allow-std-stubbing` to silence this warning"
+            );
+            tcx.sess.warn(&msg);
+            queries.push_advisory(
+                Advisory::new("stubbing", msg.clone())
+                    .with_payload(AdvisoryPayload::new("std-stub", AdvisorySeverity::Warning)),
+            );
+            msg
+        })
+        .collect()
+}
+
+/// Check the body of every stub of the harness against the hash recorded in the file given by
+/// `--stub-body-hashes`, and warn about the stubs whose body changed since then. Returns the
+/// warnings. The stubs synthesized by Kani (e.g., from a `kani::stub_spec` attribute) have no
+/// body of their own and are skipped.
+fn check_stub_body_hashes(
+    tcx: TyCtxt,
+    queries: &QueryDb,
+    path: &Path,
+    stubs: &FxHashMap<DefPathHash, DefPathHash>,
+    caller_stubs: &stubbing::CallerStubs,
+) -> Vec<String> {
+    let markers = [stubbing::default_stub(tcx), stubbing::spec_stub(tcx)];
+    let mut replacements: Vec<_> = stubs
+        .values()
+        .chain(caller_stubs.values())
+        .map(|hash| tcx.def_path_hash_to_def_id(*hash, &mut || panic!()))
+        .filter(|stub_id| !markers.contains(&Some(*stub_id)) && tcx.is_mir_available(*stub_id))
+        .collect();
+    replacements.sort();
+    replacements.dedup();
+    let changed = match stubbing::check_stub_body_hashes(tcx, path, replacements) {
+        Ok(changed) => changed,
+        Err(err) => {
+            tcx.sess.err(format!(
+                "Failed to check the stub body hashes in `{}`: {err}",
+                path.display()
+            ));
+            return vec![];
+        }
+    };
+    changed
+        .into_iter()
+        .map(|stub| {
+            let msg = format!(
+                "The body of stub `{}` changed since its hash was recorded in `{}`; review the \
+                stub and remove its entry to record the new hash",
+                stub.path,
+                path.display()
+            );
+            let span = tcx.def_span(stub.stub);
+            tcx.sess.span_warn(span, &msg);
+            queries.push_advisory(
+                Advisory::new("stubbing", msg.clone()).with_payload(
+                    AdvisoryPayload::new("stub-body-changed", AdvisorySeverity::Warning)
+                        .with_span(advisory_span(tcx, span)),
+                ),
+            );
+            msg
+        })
+        .collect()
 }
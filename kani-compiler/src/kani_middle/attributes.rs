@@ -2,7 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! This module contains code for processing Rust attributes (like `kani::proof`).
 
+use std::collections::BTreeMap;
+
+use kani_queries::VerificationConfig;
 use rustc_ast::{AttrKind, Attribute, LitKind, MetaItem};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::CRATE_DEF_ID;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::sym;
+use rustc_span::Span;
+
+use super::resolve::{qualified_path_str, resolve_path};
 
 /// Partition all the attributes into two buckets, proof_attributes and other_attributes
 pub fn partition_kanitool_attributes(
@@ -45,6 +55,98 @@ pub fn extract_integer_argument(attr: &Attribute) -> Option<u128> {
     }
 }
 
+/// Collects the `kani::unwind` annotations of the local functions and methods
+/// that are not harnesses, keyed by the path of the function. The unwind value
+/// of a harness is handled by the codegen of the harness itself.
+///
+/// The unwind values of the `functions` section of the verification config are
+/// added too, if their path resolves relative to the crate root.
+///
+/// Errors if an annotation does not have exactly one integer argument that fits
+/// in an `u32`, or if a function has annotations with conflicting values.
+///
+/// The values are collected for the backends but not applied yet, so this warns
+/// about each function that has one: its loops are only bounded by the unwind
+/// value of the harness.
+pub fn collect_function_unwinds(
+    tcx: TyCtxt,
+    config: Option<&VerificationConfig>,
+) -> BTreeMap<String, u32> {
+    let mut function_unwinds = BTreeMap::new();
+    for local_def_id in tcx.hir_crate_items(()).definitions() {
+        let def_id = local_def_id.to_def_id();
+        if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+            continue;
+        }
+        let (proof, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(def_id));
+        if !proof.is_empty() {
+            continue;
+        }
+        for (_, attr) in other.iter().filter(|(name, _)| name == "unwind") {
+            let Some(value) = extract_integer_argument(attr) else {
+                tcx.sess.span_err(attr.span, "Exactly one Unwind Argument as Integer accepted");
+                continue;
+            };
+            let Ok(value) = u32::try_from(value) else {
+                tcx.sess.span_err(attr.span, "Value above maximum permitted value - u32::MAX");
+                continue;
+            };
+            let path = qualified_path_str(tcx, def_id);
+            match function_unwinds.get(&path) {
+                Some(other) if *other != value => {
+                    tcx.sess.span_err(
+                        attr.span,
+                        format!(
+                            "Conflicting unwind values `{other}` and `{value}` for function `{path}`"
+                        ),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    tracing::debug!(?path, value, "collect_function_unwinds");
+                    warn_unapplied_unwind(tcx, attr.span, &path, value);
+                    function_unwinds.insert(path, value);
+                }
+            }
+        }
+    }
+    for (config_path, function) in config.map(|config| &config.functions).into_iter().flatten() {
+        let Some(def_id) = resolve_path(tcx, CRATE_DEF_ID, config_path) else { continue };
+        let path = qualified_path_str(tcx, def_id);
+        match function_unwinds.get(&path) {
+            Some(other) if *other != function.unwind => {
+                tcx.sess.span_err(
+                    tcx.def_span(def_id),
+                    format!(
+                        "Conflicting unwind values `{other}` and `{}` for function `{path}`: the \
+                        verification config does not match the `kani::unwind` attribute",
+                        function.unwind
+                    ),
+                );
+            }
+            Some(_) => {}
+            None => {
+                tracing::debug!(?path, value = function.unwind, "collect_function_unwinds");
+                warn_unapplied_unwind(tcx, tcx.def_span(def_id), &path, function.unwind);
+                function_unwinds.insert(path, function.unwind);
+            }
+        }
+    }
+    function_unwinds
+}
+
+/// Warns that the unwind value of a function that is not a harness is not
+/// applied (see [collect_function_unwinds]).
+fn warn_unapplied_unwind(tcx: TyCtxt, span: Span, path: &str, value: u32) {
+    tcx.sess.span_warn(
+        span,
+        format!(
+            "The unwind value `{value}` of function `{path}` is not applied yet, since it is not \
+            a harness; its loops are bounded by the unwind value of the harness instead"
+        ),
+    );
+}
+
 /// Extracts a vector with the path arguments of an attribute.
 /// The length of the returned vector is equal to the number of arguments in the
 /// attribute; an entry is `None` if the argument is not syntactically a path,
@@ -65,6 +167,55 @@ pub fn extract_path_arguments(attr: &Attribute) -> Vec<Option<String>> {
     paths
 }
 
+/// Extracts a vector with the string literal arguments of an attribute.
+/// The length of the returned vector is equal to the number of arguments in the
+/// attribute; an entry is `None` if the argument is not a string literal.
+///
+/// For example, on `stub_regex("foo::.*", 42)`, this returns
+/// `vec![Some("foo::.*"), None]`.
+pub fn extract_string_arguments(attr: &Attribute) -> Vec<Option<String>> {
+    let attr_args = attr.meta_item_list();
+    if attr_args.is_none() {
+        return vec![];
+    }
+    let mut strings = Vec::new();
+    for arg in attr_args.unwrap() {
+        let entry = arg.lit().and_then(|lit| match lit.kind {
+            LitKind::Str(value, ..) => Some(value.to_string()),
+            _ => None,
+        });
+        strings.push(entry)
+    }
+    strings
+}
+
+/// Extracts the arguments of a `stub_cfg` attribute: the path of the original
+/// function/method, followed by alternatives of the form `cfg(<predicate>, <path>)`.
+/// Returns `None` if the arguments do not have this form.
+///
+/// For example, on `stub_cfg(foo, cfg(feature = "fast", fast_foo))`, this returns
+/// `Some(("foo", vec![(<feature = "fast">, "fast_foo")]))`.
+pub fn extract_cfg_alternatives(attr: &Attribute) -> Option<(String, Vec<(MetaItem, String)>)> {
+    let attr_args = attr.meta_item_list()?;
+    let (original, alternatives) = attr_args.split_first()?;
+    let original = original.meta_item().and_then(extract_path)?;
+    if alternatives.is_empty() {
+        return None;
+    }
+    let alternatives = alternatives
+        .iter()
+        .map(|alternative| {
+            let alternative = alternative.meta_item()?;
+            if !alternative.has_name(sym::cfg) {
+                return None;
+            }
+            let [predicate, stub] = alternative.meta_item_list()? else { return None };
+            Some((predicate.meta_item()?.clone(), stub.meta_item().and_then(extract_path)?))
+        })
+        .collect::<Option<_>>()?;
+    Some((original, alternatives))
+}
+
 /// Extracts a path from an attribute item, returning `None` if the item is not
 /// syntactically a path.
 fn extract_path(meta_item: &MetaItem) -> Option<String> {
@@ -4,7 +4,42 @@
 //! and transformations.
 pub mod attributes;
 pub mod coercion;
+pub mod pass_trace;
 pub mod provide;
 pub mod reachability;
+pub mod reachability_cache;
+pub mod reachable_counts;
 pub mod resolve;
 pub mod stubbing;
+
+use std::fs;
+use std::path::PathBuf;
+
+use kani_queries::{AdvisorySpan, QueryDb, UserInput};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::OutputType;
+use rustc_span::Span;
+
+/// The lines of the source file covered by the span, to attach to an advisory.
+pub fn advisory_span(tcx: TyCtxt, span: Span) -> AdvisorySpan {
+    let source_map = tcx.sess.source_map();
+    let start = source_map.lookup_char_pos(span.lo());
+    let end = source_map.lookup_char_pos(span.hi());
+    AdvisorySpan {
+        file: start.file.name.prefer_local().to_string(),
+        start_line: start.line,
+        end_line: end.line,
+    }
+}
+
+/// The path that the files emitted on request (e.g., the call graph) are named after, to which
+/// each file adds its own extension. This is the path of the other artifacts of the crate, moved
+/// to the directory given by `--output-dir` if any. The directory is created if needed.
+pub fn emitted_file_base(tcx: TyCtxt, queries: &QueryDb) -> PathBuf {
+    let base = tcx.output_filenames(()).output_path(OutputType::Object);
+    let Some(dir) = queries.get_output_dir() else { return base };
+    if let Err(err) = fs::create_dir_all(&dir) {
+        tcx.sess.fatal(format!("Failed to create the output directory `{}`: {err}", dir.display()));
+    }
+    dir.join(base.file_name().unwrap())
+}
@@ -0,0 +1,83 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module records how long the Kani MIR passes take on each body, which is emitted as a
+//! json file in the Chrome Trace Event format if `--emit-pass-trace` is set. The file can be
+//! loaded in `chrome://tracing` (or any viewer that supports the format, such as Perfetto).
+//!
+//! Every run of a pass is a complete event (`"ph": "X"`) whose timestamp and duration are in
+//! microseconds since the start of the session.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use serde_json::json;
+
+/// A run of a pass on one body.
+#[derive(Debug)]
+struct PassEvent {
+    /// The name of the pass, e.g., `stubbing`.
+    pass: &'static str,
+    /// The path of the function/method whose body the pass ran on.
+    item: String,
+    start: Duration,
+    duration: Duration,
+}
+
+/// The trace of the current session, which is `None` unless `--emit-pass-trace` is set. The MIR
+/// providers are plain function pointers, so the trace is stored here.
+static TRACE: Mutex<Option<(Instant, Vec<PassEvent>)>> = Mutex::new(None);
+
+/// Starts recording the runs of the passes, dropping the events of any previous session.
+pub fn enable() {
+    *TRACE.lock().unwrap() = Some((Instant::now(), Vec::new()));
+}
+
+/// Whether the runs of the passes are recorded.
+pub fn is_enabled() -> bool {
+    TRACE.lock().unwrap().is_some()
+}
+
+/// Runs a pass on the body of `def_id` and records how long it took if the trace is enabled.
+pub fn run<T>(tcx: TyCtxt, pass: &'static str, def_id: DefId, run_pass: impl FnOnce() -> T) -> T {
+    let Some(session_start) = TRACE.lock().unwrap().as_ref().map(|(start, _)| *start) else {
+        return run_pass();
+    };
+    let start = Instant::now();
+    let result = run_pass();
+    let duration = start.elapsed();
+    let event = PassEvent {
+        pass,
+        item: tcx.def_path_str(def_id),
+        start: start.duration_since(session_start),
+        duration,
+    };
+    if let Some((_, events)) = TRACE.lock().unwrap().as_mut() {
+        events.push(event);
+    }
+    result
+}
+
+/// Returns the trace recorded so far as a Chrome Trace Event json object, and `None` if the trace
+/// is not enabled.
+pub fn trace_json() -> Option<serde_json::Value> {
+    let trace = TRACE.lock().unwrap();
+    let (_, events) = trace.as_ref()?;
+    let events: Vec<_> = events
+        .iter()
+        .map(|event| {
+            json!({
+                "name": event.pass,
+                "cat": "kani",
+                "ph": "X",
+                "ts": event.start.as_micros() as u64,
+                "dur": event.duration.as_micros() as u64,
+                "pid": std::process::id(),
+                "tid": 0,
+                "args": { "item": event.item },
+            })
+        })
+        .collect();
+    Some(json!({ "traceEvents": events, "displayTimeUnit": "ms" }))
+}
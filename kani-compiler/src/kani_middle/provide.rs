@@ -4,6 +4,7 @@
 //! to run during code generation. For example, this can be used to hook up
 //! custom MIR transformations.
 
+use crate::kani_middle::pass_trace;
 use crate::kani_middle::reachability::{collect_reachable_items, filter_crate_items};
 use crate::kani_middle::stubbing;
 use kani_queries::{QueryDb, UserInput};
@@ -14,10 +15,26 @@
     mir::Body,
     ty::{query::ExternProviders, query::Providers, TyCtxt},
 };
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The MIR providers are plain function pointers, so whether they only run the
+/// [IdentityPass] is stored here once the compiler configuration is known.
+static IDENTITY_PASSES_ONLY: AtomicBool = AtomicBool::new(false);
 
 /// Sets up rustc's query mechanism to apply Kani's custom queries to code from
 /// the present crate.
 pub fn provide(providers: &mut Providers, queries: &QueryDb) {
+    stubbing::set_options(stubbing::StubbingOptions {
+        verify_types: queries.get_verify_stub_types(),
+        preserve_signature: queries.get_preserve_stub_signature(),
+        experimental_stubs: queries.get_enable_experimental_stubs(),
+        allow_shared_refs: queries.get_allow_stub_shared_refs(),
+        count_caller_calls: queries.get_emit_stub_caller_counts(),
+    });
+    IDENTITY_PASSES_ONLY.store(queries.get_identity_passes_only(), Ordering::Relaxed);
+    if queries.get_emit_pass_trace() {
+        pass_trace::enable();
+    }
     providers.optimized_mir = run_mir_passes::<false>;
     if queries.get_stubbing_enabled() {
         providers.collect_and_partition_mono_items = collect_and_partition_mono_items;
@@ -46,14 +63,49 @@ fn run_mir_passes<const EXTERN: bool>(tcx: TyCtxt, def_id: DefId) -> &Body {
 
 /// Returns the optimized code for the function associated with `def_id` by
 /// running Kani-specific passes. The argument `body` should be the optimized
-/// code rustc generates for this function.
+/// code rustc generates for this function. With `--identity-passes-only`, only
+/// the [IdentityPass] is run, so the stubs are never applied. With
+/// `--emit-pass-trace`, the [IdentityPass] also runs before the other passes,
+/// so its timings give the baseline cost of running a pass.
 fn run_kani_mir_passes<'tcx>(
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
     body: &'tcx Body<'tcx>,
 ) -> &'tcx Body<'tcx> {
     tracing::debug!(?def_id, "Run Kani transformation passes");
-    stubbing::transform(tcx, def_id, body)
+    let identity_only = IDENTITY_PASSES_ONLY.load(Ordering::Relaxed);
+    let body = if identity_only || pass_trace::is_enabled() {
+        let diagnostics = tracing::enabled!(tracing::Level::DEBUG);
+        pass_trace::run(tcx, "identity", def_id, || {
+            IdentityPass { diagnostics }.run_pass(def_id, body)
+        })
+    } else {
+        body
+    };
+    if identity_only {
+        return body;
+    }
+    pass_trace::run(tcx, "stubbing", def_id, || stubbing::transform(tcx, def_id, body))
+}
+
+/// A pass that returns the body it is given, which measures the cost of
+/// running a Kani pass over every body without transforming any of them.
+struct IdentityPass {
+    /// Whether to log the size of every body. Counting the statements is
+    /// skipped otherwise, so it does not add to the cost being measured.
+    diagnostics: bool,
+}
+
+impl IdentityPass {
+    fn run_pass<'tcx>(&self, def_id: DefId, body: &'tcx Body<'tcx>) -> &'tcx Body<'tcx> {
+        if self.diagnostics {
+            let blocks = body.basic_blocks.len();
+            let statements: usize =
+                body.basic_blocks.iter().map(|block| block.statements.len()).sum();
+            tracing::debug!(?def_id, blocks, statements, "Run identity pass");
+        }
+        body
+    }
 }
 
 /// Runs a reachability analysis before running the default
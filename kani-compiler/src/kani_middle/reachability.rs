@@ -35,16 +35,181 @@
 };
 
 use crate::kani_middle::coercion;
+use crate::kani_middle::resolve::qualified_path_str;
 use crate::kani_middle::stubbing::get_stub;
 
+/// An observer of the reachability analysis, which is notified of every edge of the call graph as
+/// soon as it is found, i.e., before the analysis is over. This allows tools to follow the
+/// traversal without waiting for the final set of items (see [collect_call_graph] for the latter).
+///
+/// Every method has a default implementation that does nothing, so a visitor only implements the
+/// events it is interested in.
+pub trait ReachabilityVisitor<'tcx> {
+    /// Called for every item found while visiting `caller`, e.g.: the functions that it calls or
+    /// whose address it takes. The edges are reported in the order they are traversed, and the
+    /// same edge may be reported more than once if `caller` is reachable from several of the
+    /// starting points. `callee` may have been visited already.
+    fn visit_edge(&mut self, _caller: MonoItem<'tcx>, _callee: MonoItem<'tcx>) {}
+}
+
+/// The visitor that ignores every event.
+impl<'tcx> ReachabilityVisitor<'tcx> for () {}
+
+/// A visitor that records the set of edges of the call graph.
+#[derive(Default)]
+struct EdgeRecorder<'tcx> {
+    edges: FxHashSet<(MonoItem<'tcx>, MonoItem<'tcx>)>,
+}
+
+impl<'tcx> ReachabilityVisitor<'tcx> for EdgeRecorder<'tcx> {
+    fn visit_edge(&mut self, caller: MonoItem<'tcx>, callee: MonoItem<'tcx>) {
+        self.edges.insert((caller, callee));
+    }
+}
+
 /// Collect all reachable items starting from the given starting points.
 pub fn collect_reachable_items<'tcx>(
     tcx: TyCtxt<'tcx>,
     starting_points: &[MonoItem<'tcx>],
+) -> Vec<MonoItem<'tcx>> {
+    collect_items(tcx, starting_points, &mut ())
+}
+
+/// Collect all reachable items starting from the given starting points, and notify the given
+/// visitor of every edge of the call graph as it is traversed.
+pub fn collect_reachable_items_with_visitor<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    starting_points: &[MonoItem<'tcx>],
+    visitor: &mut dyn ReachabilityVisitor<'tcx>,
+) -> Vec<MonoItem<'tcx>> {
+    collect_items(tcx, starting_points, visitor)
+}
+
+/// Collect all reachable items starting from the given starting points, as well as the edges of
+/// the call graph between them.
+///
+/// There is an edge `(caller, callee)` for every item found while visiting another item, e.g.:
+/// the functions that a function calls or whose address it takes, or the drop function of a
+/// static object.
+pub fn collect_call_graph<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    starting_points: &[MonoItem<'tcx>],
+) -> (Vec<MonoItem<'tcx>>, Vec<(MonoItem<'tcx>, MonoItem<'tcx>)>) {
+    collect_items_and_edges(tcx, starting_points)
+}
+
+/// Collect all reachable items starting from the given starting points and the sorted edges of
+/// the call graph between them.
+fn collect_items_and_edges<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    starting_points: &[MonoItem<'tcx>],
+) -> (Vec<MonoItem<'tcx>>, Vec<(MonoItem<'tcx>, MonoItem<'tcx>)>) {
+    let mut recorder = EdgeRecorder::default();
+    let items = collect_items(tcx, starting_points, &mut recorder);
+    let mut sorted_edges: Vec<_> = recorder.edges.into_iter().collect();
+    sorted_edges.sort_by_cached_key(|(caller, callee)| {
+        (to_fingerprint(tcx, caller), to_fingerprint(tcx, callee))
+    });
+    (items, sorted_edges)
+}
+
+/// Collect the compiler-generated items that are omitted by the analysis starting from the given
+/// reachable items, as well as the items reachable from them and the edges of the call graph
+/// between them. These are the drop glue of the types of the locals of every reachable function,
+/// even if those locals are never dropped (e.g.: they are moved into `std::mem::forget`), as well
+/// as the drop glue that has nothing to drop.
+///
+/// These items are only meant to be reported to the user; they must not be added to codegen.
+pub fn collect_synthetic_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    reachable: &[MonoItem<'tcx>],
+) -> (Vec<MonoItem<'tcx>>, Vec<(MonoItem<'tcx>, MonoItem<'tcx>)>) {
+    let reachable_set: FxHashSet<_> = reachable.iter().copied().collect();
+    let mut root_edges = FxHashSet::default();
+    for item in reachable {
+        let MonoItem::Fn(instance) = *item else { continue };
+        let body = tcx.instance_mir(instance.def);
+        for local in body.local_decls.iter() {
+            let ty = instance.subst_mir_and_normalize_erasing_regions(
+                tcx,
+                ParamEnv::reveal_all(),
+                local.ty,
+            );
+            let glue = Instance::resolve_drop_in_place(tcx, ty);
+            let glue_item = MonoItem::Fn(glue.polymorphize(tcx));
+            if should_codegen_locally(tcx, &glue) && !reachable_set.contains(&glue_item) {
+                root_edges.insert((*item, glue_item));
+            }
+        }
+    }
+    let roots: Vec<_> = root_edges.iter().map(|(_, glue)| *glue).collect();
+    let (items, edges) = collect_items_and_edges(tcx, &roots);
+    let items = items.into_iter().filter(|item| !reachable_set.contains(item)).collect();
+    let mut edges: Vec<_> = edges
+        .into_iter()
+        .filter(|(caller, _)| !reachable_set.contains(caller))
+        .chain(root_edges)
+        .collect();
+    edges.sort_by_cached_key(|(caller, callee)| {
+        (to_fingerprint(tcx, caller), to_fingerprint(tcx, callee))
+    });
+    (items, edges)
+}
+
+/// Collect the functions called by the given reachable items that have no MIR, i.e.: foreign
+/// functions, intrinsics and the functions whose MIR is not available, together with the items
+/// that call them. These functions are not part of the reachable items since there is no body to
+/// traverse, so their calls are found by visiting the body of every reachable function.
+///
+/// The result is a list of `(caller, callee)` pairs.
+pub fn collect_items_without_mir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    reachable: &[MonoItem<'tcx>],
+) -> Vec<(MonoItem<'tcx>, Instance<'tcx>)> {
+    let mut calls = FxHashSet::default();
+    for item in reachable {
+        let MonoItem::Fn(instance) = *item else { continue };
+        let body = tcx.instance_mir(instance.def);
+        for block in body.basic_blocks.iter() {
+            let TerminatorKind::Call { func, .. } = &block.terminator().kind else { continue };
+            let fn_ty = instance.subst_mir_and_normalize_erasing_regions(
+                tcx,
+                ParamEnv::reveal_all(),
+                func.ty(body, tcx),
+            );
+            let TyKind::FnDef(def_id, substs) = *fn_ty.kind() else { continue };
+            let Ok(Some(callee)) = Instance::resolve(tcx, ParamEnv::reveal_all(), def_id, substs)
+            else {
+                continue;
+            };
+            let has_mir = match callee.def {
+                InstanceDef::Intrinsic(_) => false,
+                InstanceDef::Item(def) => {
+                    !tcx.is_foreign_item(def.did) && tcx.is_mir_available(def.did)
+                }
+                _ => true,
+            };
+            if !has_mir {
+                calls.insert((*item, callee));
+            }
+        }
+    }
+    let mut calls: Vec<_> = calls.into_iter().collect();
+    calls.sort_by_cached_key(|(caller, callee)| {
+        (to_fingerprint(tcx, caller), to_fingerprint(tcx, &MonoItem::Fn(*callee)))
+    });
+    calls
+}
+
+fn collect_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    starting_points: &[MonoItem<'tcx>],
+    visitor: &mut dyn ReachabilityVisitor<'tcx>,
 ) -> Vec<MonoItem<'tcx>> {
     // For each harness, collect items using the same collector.
     // I.e.: This will return any item that is reachable from one or more of the starting points.
-    let mut collector = MonoItemsCollector { tcx, collected: FxHashSet::default(), queue: vec![] };
+    let mut collector =
+        MonoItemsCollector { tcx, collected: FxHashSet::default(), queue: vec![], visitor };
     for item in starting_points {
         collector.collect(*item);
     }
@@ -133,16 +298,18 @@ fn to_mono_root(tcx: TyCtxt, item_id: ItemId, def_id: DefId) -> Option<MonoItem>
     }
 }
 
-struct MonoItemsCollector<'tcx> {
+struct MonoItemsCollector<'v, 'tcx> {
     /// The compiler context.
     tcx: TyCtxt<'tcx>,
     /// Set of collected items used to avoid entering recursion loops.
     collected: FxHashSet<MonoItem<'tcx>>,
     /// Items enqueued for visiting.
     queue: Vec<MonoItem<'tcx>>,
+    /// The visitor notified of the call graph edges as they are found.
+    visitor: &'v mut dyn ReachabilityVisitor<'tcx>,
 }
 
-impl<'tcx> MonoItemsCollector<'tcx> {
+impl<'v, 'tcx> MonoItemsCollector<'v, 'tcx> {
     /// Collects all reachable items starting from the given root.
     pub fn collect(&mut self, root: MonoItem<'tcx>) {
         debug!(?root, "collect");
@@ -178,6 +345,7 @@ fn visit_fn(&mut self, instance: Instance<'tcx>) {
         let mut collector =
             MonoItemsFnCollector { tcx: self.tcx, collected: FxHashSet::default(), instance, body };
         collector.visit_body(body);
+        self.add_edges(MonoItem::Fn(instance), &collector.collected);
         self.queue.extend(collector.collected.iter().filter(|item| !self.collected.contains(item)));
     }
 
@@ -189,13 +357,15 @@ fn visit_static(&mut self, def_id: DefId) {
         // Collect drop function.
         let static_ty = instance.ty(self.tcx, ParamEnv::reveal_all());
         let instance = Instance::resolve_drop_in_place(self.tcx, static_ty);
-        self.queue.push(MonoItem::Fn(instance.polymorphize(self.tcx)));
+        let mut items = vec![MonoItem::Fn(instance.polymorphize(self.tcx))];
 
         // Collect initialization.
         let alloc = self.tcx.eval_static_initializer(def_id).unwrap();
         for id in alloc.inner().provenance().provenances() {
-            self.queue.extend(collect_alloc_items(self.tcx, id).iter());
+            items.extend(collect_alloc_items(self.tcx, id).iter());
         }
+        self.add_edges(MonoItem::Static(def_id), &items);
+        self.queue.extend(items);
     }
 
     /// Visit global assembly and collect its item.
@@ -203,6 +373,19 @@ fn visit_asm(&mut self, item: MonoItem<'tcx>) {
         debug!(?item, "visit_asm");
         self.collected.insert(item);
     }
+
+    /// Notify the visitor of an edge from `caller` to each of the given items.
+    fn add_edges<'a>(
+        &mut self,
+        caller: MonoItem<'tcx>,
+        callees: impl IntoIterator<Item = &'a MonoItem<'tcx>>,
+    ) where
+        'tcx: 'a,
+    {
+        for callee in callees {
+            self.visitor.visit_edge(caller, *callee);
+        }
+    }
 }
 
 struct MonoItemsFnCollector<'a, 'tcx> {
@@ -447,7 +630,7 @@ fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location
                             .unwrap();
                     match instance_opt {
                         None => {
-                            let caller = tcx.def_path_str(self.instance.def_id());
+                            let caller = qualified_path_str(tcx, self.instance.def_id());
                             let callee = tcx.def_path_str(def_id);
                             // Check if the current function has been stubbed.
                             if let Some(stub) = get_stub(tcx, self.instance.def_id()) {
@@ -455,13 +638,17 @@ fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location
                                 // force type variables in the stub's signature to
                                 // implement the same traits as those in the
                                 // original function/method. A trait mismatch shows
-                                // up here, when we try to resolve a trait method
-                                let generic_ty = args[0].ty(self.body, tcx).peel_refs();
-                                let receiver_ty = tcx.subst_and_normalize_erasing_regions(
-                                    substs,
-                                    ParamEnv::reveal_all(),
-                                    generic_ty,
-                                );
+                                // up here, when we try to resolve a trait method.
+                                // A method without receiver, e.g., `T::default()`,
+                                // reports its `Self` type instead.
+                                let receiver_ty = match args.first() {
+                                    Some(arg) => tcx.subst_and_normalize_erasing_regions(
+                                        substs,
+                                        ParamEnv::reveal_all(),
+                                        arg.ty(self.body, tcx).peel_refs(),
+                                    ),
+                                    None => substs.type_at(0),
+                                };
                                 let sep = callee.rfind("::").unwrap();
                                 let trait_ = &callee[..sep];
                                 tcx.sess.span_err(
@@ -471,7 +658,7 @@ fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location
                                         `{trait_}`. The function `{caller}` \
                                         cannot be stubbed by `{}` due to \
                                         generic bounds not being met.",
-                                        tcx.def_path_str(stub)
+                                        qualified_path_str(tcx, stub)
                                     ),
                                 );
                             } else {
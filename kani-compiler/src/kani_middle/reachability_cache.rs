@@ -0,0 +1,298 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module implements an on-disk cache for the result of the reachability analysis.
+//!
+//! The cache stores the reachable items of a crate together with a fingerprint of every input
+//! that may affect the analysis: the hash of the local crate and of all its dependencies, the
+//! version of Kani, and the Kani options (including the stub mapping), which are all passed via
+//! `-C llvm-args`. A cache entry is only used if its fingerprint matches the current session.
+//!
+//! The items are encoded with rustc's type encoder, where every `DefId` is replaced by its
+//! `DefPathHash` so it can be mapped back to a `DefId` in a later session.
+
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::iter;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LOCAL_CRATE};
+use rustc_hir::definitions::DefPathHash;
+use rustc_hir::{ItemId, OwnerId};
+use rustc_middle::mir::interpret::AllocId;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::codec::{TyDecoder, TyEncoder};
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
+use rustc_serialize::opaque::{MemDecoder, MemEncoder};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_span::def_id::StableCrateId;
+use tracing::{debug, info, warn};
+
+/// Load the reachable items from the cache in `cache_dir` if the cached entry is still valid.
+/// Otherwise, run `collect` and store its result in the cache.
+pub fn load_or_collect<'tcx, F>(
+    tcx: TyCtxt<'tcx>,
+    cache_dir: &Path,
+    collect: F,
+) -> Vec<MonoItem<'tcx>>
+where
+    F: FnOnce() -> Vec<MonoItem<'tcx>>,
+{
+    let fingerprint = crate_fingerprint(tcx);
+    let cache_file = cache_file(tcx, cache_dir);
+    if let Some(items) = load(tcx, &cache_file, fingerprint) {
+        info!(file=?cache_file, "Loaded reachable items from cache");
+        return items;
+    }
+    let items = collect();
+    if let Err(err) = store(tcx, cache_dir, &cache_file, fingerprint, &items) {
+        warn!(file=?cache_file, ?err, "Failed to store reachable items in cache");
+    }
+    items
+}
+
+/// Compute a fingerprint of every input of the reachability analysis.
+fn crate_fingerprint(tcx: TyCtxt) -> Fingerprint {
+    let mut hasher = StableHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    for krate in iter::once(LOCAL_CRATE).chain(tcx.crates(()).iter().copied()) {
+        tcx.crate_hash(krate).hash(&mut hasher);
+    }
+    tcx.sess.opts.cg.llvm_args.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The cache file for the local crate. The stable crate id distinguishes crates with the same
+/// name that share the same cache directory.
+fn cache_file(tcx: TyCtxt, cache_dir: &Path) -> PathBuf {
+    let crate_id = tcx.stable_crate_id(LOCAL_CRATE).to_u64();
+    cache_dir.join(format!("{}-{crate_id:016x}.reach", tcx.crate_name(LOCAL_CRATE)))
+}
+
+fn load<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    cache_file: &Path,
+    fingerprint: Fingerprint,
+) -> Option<Vec<MonoItem<'tcx>>> {
+    let data = fs::read(cache_file).ok()?;
+    let mut decoder = CacheDecoder {
+        tcx,
+        opaque: MemDecoder::new(&data, 0),
+        type_shorthands: Default::default(),
+    };
+    if Fingerprint::decode(&mut decoder) != fingerprint {
+        debug!(file=?cache_file, "Ignoring stale cache entry");
+        return None;
+    }
+    let len = decoder.read_usize();
+    Some((0..len).map(|_| decode_item(&mut decoder)).collect())
+}
+
+fn store<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    cache_dir: &Path,
+    cache_file: &Path,
+    fingerprint: Fingerprint,
+    items: &[MonoItem<'tcx>],
+) -> io::Result<()> {
+    let mut encoder = CacheEncoder {
+        tcx,
+        opaque: MemEncoder::new(),
+        type_shorthands: Default::default(),
+        predicate_shorthands: Default::default(),
+        has_alloc_ids: false,
+    };
+    fingerprint.encode(&mut encoder);
+    encoder.emit_usize(items.len());
+    for item in items {
+        encode_item(&mut encoder, item);
+    }
+    if encoder.has_alloc_ids {
+        // We cannot restore allocations from a different session.
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "items refer to allocations"));
+    }
+    fs::create_dir_all(cache_dir)?;
+    fs::write(cache_file, encoder.opaque.finish())
+}
+
+fn encode_item<'tcx>(encoder: &mut CacheEncoder<'tcx>, item: &MonoItem<'tcx>) {
+    match item {
+        MonoItem::Fn(instance) => encoder.emit_enum_variant(0, |e| instance.encode(e)),
+        MonoItem::Static(def_id) => encoder.emit_enum_variant(1, |e| def_id.encode(e)),
+        MonoItem::GlobalAsm(item_id) => {
+            encoder.emit_enum_variant(2, |e| item_id.owner_id.to_def_id().encode(e))
+        }
+    }
+}
+
+fn decode_item<'tcx>(decoder: &mut CacheDecoder<'_, 'tcx>) -> MonoItem<'tcx> {
+    match decoder.read_usize() {
+        0 => MonoItem::Fn(Instance::decode(decoder)),
+        1 => MonoItem::Static(DefId::decode(decoder)),
+        2 => {
+            let def_id = DefId::decode(decoder).expect_local();
+            MonoItem::GlobalAsm(ItemId { owner_id: OwnerId { def_id } })
+        }
+        tag => panic!("Invalid reachability cache entry: unexpected item tag `{tag}`"),
+    }
+}
+
+struct CacheEncoder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    opaque: MemEncoder,
+    type_shorthands: FxHashMap<Ty<'tcx>, usize>,
+    predicate_shorthands: FxHashMap<ty::PredicateKind<'tcx>, usize>,
+    /// Whether we found any allocation while encoding the items.
+    has_alloc_ids: bool,
+}
+
+macro_rules! encoder_methods {
+    ($($name:ident($ty:ty);)*) => {
+        $(fn $name(&mut self, value: $ty) {
+            self.opaque.$name(value)
+        })*
+    }
+}
+
+impl<'tcx> Encoder for CacheEncoder<'tcx> {
+    encoder_methods! {
+        emit_usize(usize);
+        emit_u128(u128);
+        emit_u64(u64);
+        emit_u32(u32);
+        emit_u16(u16);
+        emit_u8(u8);
+
+        emit_isize(isize);
+        emit_i128(i128);
+        emit_i64(i64);
+        emit_i32(i32);
+        emit_i16(i16);
+        emit_i8(i8);
+
+        emit_bool(bool);
+        emit_f64(f64);
+        emit_f32(f32);
+        emit_char(char);
+        emit_str(&str);
+        emit_raw_bytes(&[u8]);
+    }
+}
+
+impl<'tcx> TyEncoder for CacheEncoder<'tcx> {
+    type I = TyCtxt<'tcx>;
+    const CLEAR_CROSS_CRATE: bool = true;
+
+    fn position(&self) -> usize {
+        self.opaque.position()
+    }
+
+    fn type_shorthands(&mut self) -> &mut FxHashMap<Ty<'tcx>, usize> {
+        &mut self.type_shorthands
+    }
+
+    fn predicate_shorthands(&mut self) -> &mut FxHashMap<ty::PredicateKind<'tcx>, usize> {
+        &mut self.predicate_shorthands
+    }
+
+    fn encode_alloc_id(&mut self, _alloc_id: &AllocId) {
+        self.has_alloc_ids = true;
+    }
+}
+
+impl<'tcx> Encodable<CacheEncoder<'tcx>> for CrateNum {
+    fn encode(&self, s: &mut CacheEncoder<'tcx>) {
+        s.tcx.stable_crate_id(*self).encode(s);
+    }
+}
+
+impl<'tcx> Encodable<CacheEncoder<'tcx>> for DefId {
+    fn encode(&self, s: &mut CacheEncoder<'tcx>) {
+        s.tcx.def_path_hash(*self).encode(s);
+    }
+}
+
+impl<'tcx> Encodable<CacheEncoder<'tcx>> for DefIndex {
+    fn encode(&self, _: &mut CacheEncoder<'tcx>) {
+        unreachable!("Cannot encode `DefIndex` without its crate");
+    }
+}
+
+struct CacheDecoder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    opaque: MemDecoder<'a>,
+    type_shorthands: FxHashMap<usize, Ty<'tcx>>,
+}
+
+impl<'a, 'tcx> TyDecoder for CacheDecoder<'a, 'tcx> {
+    type I = TyCtxt<'tcx>;
+    const CLEAR_CROSS_CRATE: bool = true;
+
+    fn interner(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn peek_byte(&self) -> u8 {
+        self.opaque.data[self.opaque.position()]
+    }
+
+    fn position(&self) -> usize {
+        self.opaque.position()
+    }
+
+    fn cached_ty_for_shorthand<F>(&mut self, shorthand: usize, or_insert_with: F) -> Ty<'tcx>
+    where
+        F: FnOnce(&mut Self) -> Ty<'tcx>,
+    {
+        if let Some(ty) = self.type_shorthands.get(&shorthand) {
+            return *ty;
+        }
+        let ty = or_insert_with(self);
+        self.type_shorthands.insert(shorthand, ty);
+        ty
+    }
+
+    fn with_position<F, R>(&mut self, pos: usize, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let new_opaque = MemDecoder::new(self.opaque.data, pos);
+        let old_opaque = mem::replace(&mut self.opaque, new_opaque);
+        let result = f(self);
+        self.opaque = old_opaque;
+        result
+    }
+
+    fn decode_alloc_id(&mut self) -> AllocId {
+        unreachable!("Reachability cache entries never include allocations");
+    }
+}
+
+rustc_middle::implement_ty_decoder!(CacheDecoder<'a, 'tcx>);
+
+impl<'a, 'tcx> Decodable<CacheDecoder<'a, 'tcx>> for CrateNum {
+    fn decode(d: &mut CacheDecoder<'a, 'tcx>) -> Self {
+        let stable_id = StableCrateId::decode(d);
+        d.tcx.stable_crate_id_to_crate_num(stable_id)
+    }
+}
+
+impl<'a, 'tcx> Decodable<CacheDecoder<'a, 'tcx>> for DefId {
+    fn decode(d: &mut CacheDecoder<'a, 'tcx>) -> Self {
+        // The fingerprint covers every crate, so the definition must exist in this session.
+        let def_path_hash = DefPathHash::decode(d);
+        d.tcx.def_path_hash_to_def_id(def_path_hash, &mut || {
+            panic!("Invalid reachability cache entry: unknown DefPathHash {def_path_hash:?}")
+        })
+    }
+}
+
+impl<'a, 'tcx> Decodable<CacheDecoder<'a, 'tcx>> for DefIndex {
+    fn decode(_: &mut CacheDecoder<'a, 'tcx>) -> Self {
+        unreachable!("Cannot decode `DefIndex` without its crate");
+    }
+}
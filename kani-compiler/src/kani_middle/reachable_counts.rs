@@ -0,0 +1,151 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module counts how many of the reachable functions come from each crate, which is printed
+//! after the reachability analysis if `--reachable-counts` is set.
+
+use std::fmt::Write;
+
+use kani_queries::ReportFormat;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::TyCtxt;
+
+/// The number of reachable functions that come from one crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateCount {
+    pub krate: String,
+    /// Whether this is the crate being compiled.
+    pub local: bool,
+    pub functions: usize,
+}
+
+/// Counts the functions of each crate among the given crate names, one per reachable function.
+/// The local crate comes first, followed by the dependencies sorted by decreasing count and then
+/// by name, so the dependencies that dominate the reachable set come first.
+pub fn count_functions<'a>(
+    local: &str,
+    crates: impl IntoIterator<Item = &'a str>,
+) -> Vec<CrateCount> {
+    let mut counts: FxHashMap<&str, usize> = FxHashMap::default();
+    for krate in crates {
+        *counts.entry(krate).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts
+        .into_iter()
+        .map(|(krate, functions)| CrateCount {
+            krate: krate.to_string(),
+            local: krate == local,
+            functions,
+        })
+        .collect();
+    counts.sort_by(|count1, count2| {
+        (!count1.local, count2.functions, &count1.krate).cmp(&(
+            !count2.local,
+            count1.functions,
+            &count2.krate,
+        ))
+    });
+    counts
+}
+
+/// Formats the counts as a table with one row per crate, which includes the share of the
+/// reachable functions that come from the crate.
+pub fn to_text(counts: &[CrateCount]) -> String {
+    let total: usize = counts.iter().map(|count| count.functions).sum();
+    let name_width =
+        counts.iter().map(|count| count.krate.len() + 8).chain(["Crate".len()]).max().unwrap();
+    let mut text = String::from("Reachable functions per crate:\n");
+    writeln!(text, "{:name_width$}  Functions   Share", "Crate").unwrap();
+    for count in counts {
+        let name =
+            if count.local { format!("{} (local)", count.krate) } else { count.krate.clone() };
+        let share = 100.0 * count.functions as f64 / total as f64;
+        writeln!(text, "{name:name_width$}  {:>9}  {share:>5.1}%", count.functions).unwrap();
+    }
+    writeln!(text, "{:name_width$}  {total:>9}", "Total").unwrap();
+    text
+}
+
+/// Formats the counts as a json object.
+pub fn to_json(counts: &[CrateCount]) -> serde_json::Value {
+    let total: usize = counts.iter().map(|count| count.functions).sum();
+    let crates: Vec<_> = counts
+        .iter()
+        .map(|count| {
+            serde_json::json!({
+                "crate": count.krate,
+                "local": count.local,
+                "functions": count.functions,
+            })
+        })
+        .collect();
+    serde_json::json!({ "crates": crates, "total": total })
+}
+
+/// Prints how many of the reachable functions come from each crate in the given format. Each
+/// instance of a generic function counts as a separate function.
+pub fn print_reachable_counts(tcx: TyCtxt, items: &[MonoItem], format: ReportFormat) {
+    let crates: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            MonoItem::Fn(instance) => Some(tcx.crate_name(instance.def_id().krate)),
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => None,
+        })
+        .collect();
+    let local = tcx.crate_name(LOCAL_CRATE);
+    let counts = count_functions(local.as_str(), crates.iter().map(|krate| krate.as_str()));
+    match format {
+        ReportFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&to_json(&counts)).unwrap())
+        }
+        ReportFormat::Text => print!("{}", to_text(&counts)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_functions, to_json, to_text, CrateCount};
+
+    #[test]
+    fn counts_per_crate() {
+        // The functions of a local crate `app` that calls into two dependencies.
+        let crates = ["core", "app", "parser", "core", "app", "core", "parser", "app", "alloc"];
+        let counts = count_functions("app", crates);
+        let count = |krate: &str, local, functions| CrateCount {
+            krate: krate.to_string(),
+            local,
+            functions,
+        };
+        assert_eq!(
+            counts,
+            [
+                count("app", true, 3),
+                count("core", false, 3),
+                count("parser", false, 2),
+                count("alloc", false, 1)
+            ]
+        );
+        assert_eq!(
+            to_text(&counts),
+            "Reachable functions per crate:\n\
+            Crate           Functions   Share\n\
+            app (local)             3   33.3%\n\
+            core                    3   33.3%\n\
+            parser                  2   22.2%\n\
+            alloc                   1   11.1%\n\
+            Total                   9\n"
+        );
+        let json = to_json(&counts);
+        assert_eq!(json["total"], 9);
+        assert_eq!(json["crates"][0]["crate"], "app");
+        assert_eq!(json["crates"][0]["local"], true);
+        assert_eq!(json["crates"][2]["functions"], 2);
+    }
+
+    #[test]
+    fn no_local_functions() {
+        let counts = count_functions("app", ["core"]);
+        assert_eq!(counts, [CrateCount { krate: "core".to_string(), local: false, functions: 1 }]);
+    }
+}
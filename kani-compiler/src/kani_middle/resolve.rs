@@ -4,45 +4,336 @@
 //! `DefId`s for functions and methods. For the definition of a simple path, see
 //! <https://doc.rust-lang.org/reference/paths.html#simple-paths>.
 //!
-//! TODO: Extend this logic to support resolving qualified paths.
+//! Qualified paths are only supported in the form `<Type as Trait>::method`,
+//! which resolves to the method of the implementation of the trait for the type.
+//! A path of the form `Type::method` also resolves to a trait method if the
+//! type has no inherent method with this name and a single trait implemented
+//! for the type has one.
+//! TODO: Extend this logic to support resolving other qualified paths.
 //! <https://github.com/model-checking/kani/issues/1997>
 
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
-use rustc_hir::def::{DefKind, Res};
-use rustc_hir::def_id::{DefId, LocalDefId, CRATE_DEF_INDEX, LOCAL_CRATE};
-use rustc_hir::{ItemKind, UseKind};
-use rustc_middle::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def::{DefKind, Namespace, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LocalDefId, CRATE_DEF_ID, CRATE_DEF_INDEX, LOCAL_CRATE};
+use rustc_hir::{Item, ItemKind, UseKind};
+use rustc_middle::ty::print::with_no_visible_paths;
+use rustc_middle::ty::{self, DefIdTree, Instance, TyCtxt};
+use rustc_span::symbol::{kw, Symbol};
+
+/// The maximum number of modules that the resolution of a path may go through
+/// if `--stub-resolution-depth` is not provided. Like rustc's default recursion
+/// limit, this is far more than any sensible re-export chain needs.
+pub const DEFAULT_MAX_RESOLUTION_DEPTH: usize = 128;
+
+/// The limit applies to every path that Kani resolves, so it is stored here
+/// once the compiler configuration is known.
+static MAX_RESOLUTION_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_RESOLUTION_DEPTH);
+
+/// Sets the maximum number of modules that the resolution of a path may go
+/// through before it is reported as a likely re-export cycle.
+pub fn set_max_resolution_depth(depth: usize) {
+    MAX_RESOLUTION_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+/// Whether to print the `use` statements that the resolution of each path
+/// goes through, which is set by `--trace-stub-resolution`.
+static TRACE_USES: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether to print the chain of `use` statements that the resolution of
+/// each path goes through, e.g., ``resolved `c::d` via `use a::b as c` ->
+/// `a::b::d` ``. This shows which re-exports were followed when a relative
+/// path resolves to an unexpected target.
+pub fn set_trace_uses(enabled: bool) {
+    TRACE_USES.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the paths that exactly match the `def_path_str` of a function/method
+/// resolve to it directly, which is set by `--stub-exact-paths`.
+static EXACT_PATHS: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether a path that is exactly the `def_path_str` of a function/method
+/// (e.g., taken from a previous dump of the MIR) resolves to it without going
+/// through the relative resolution, so the `use` statements in scope cannot
+/// make it ambiguous.
+pub fn set_exact_paths(enabled: bool) {
+    EXACT_PATHS.store(enabled, Ordering::Relaxed);
+}
 
 /// Attempts to resolve a simple path (in the form of a string) to a `DefId`.
 /// The current module is provided as an argument in order to resolve relative
-/// paths.
+/// paths. Reports an error if the resolution goes through more modules than
+/// allowed, which happens with re-export cycles (e.g., two modules that
+/// glob-import each other).
+///
+/// A qualified path of the form `<Type as Trait>::method` (e.g.,
+/// `<Vec<u8> as Clone>::clone`) resolves to the method of the implementation
+/// of the trait for the type (see [resolve_qualified_path]).
 ///
-/// TODO: Extend this implementation to handle qualified paths and simple paths
-/// corresponding to trait methods.
+/// If `--stub-exact-paths` is set, a path that is exactly the `def_path_str`
+/// of a function/method resolves to it first (see [resolve_exact_path]).
+///
+/// A simple path of the form `Type::method` may refer to a method of the
+/// implementation of a trait for the type (see [resolve_in_type]).
+///
+/// TODO: Extend this implementation to handle other qualified paths.
 /// <https://github.com/model-checking/kani/issues/1997>
 pub fn resolve_path(tcx: TyCtxt, current_module: LocalDefId, path_str: &str) -> Option<DefId> {
     let span = tracing::span!(tracing::Level::DEBUG, "path_resolution");
     let _enter = span.enter();
 
+    let path_str = normalize_whitespace(path_str);
+    if EXACT_PATHS.load(Ordering::Relaxed) {
+        if let Some(def_id) = resolve_exact_path(tcx, &path_str) {
+            tracing::debug!(?def_id, "Resolved `{path_str}` as an exact path");
+            return Some(def_id);
+        }
+    }
+    let resolved = if path_str.starts_with('<') {
+        resolve_qualified_path(tcx, current_module, &path_str)
+    } else {
+        resolve_simple_path(tcx, current_module, &path_str, Namespace::ValueNS)
+    };
+    resolved.filter(|def_id| {
+        let is_function = is_function_or_ctor(tcx, *def_id);
+        if !is_function {
+            tracing::debug!(
+                "Unable to resolve `{path_str}`: `{}` is a {}, not a function/method",
+                canonical_path_str(tcx, *def_id),
+                tcx.def_kind(*def_id).descr(*def_id)
+            );
+        }
+        is_function
+    })
+}
+
+/// Resolves a path that is exactly the `def_path_str` of a function/method,
+/// without following any `use` statement. The candidates are the functions and
+/// methods of the local crate, whose paths do not start with the crate name,
+/// and the functions and inherent methods of the crate named by the first
+/// segment of the path (see [foreign_functions]).
+pub fn resolve_exact_path(tcx: TyCtxt, path_str: &str) -> Option<DefId> {
+    let local = tcx
+        .hir_crate_items(())
+        .definitions()
+        .map(|local_def_id| local_def_id.to_def_id())
+        .filter(|def_id| is_function_or_ctor(tcx, *def_id));
+    let first_segment = path_str.split("::").next()?;
+    let foreign = tcx
+        .crates(())
+        .iter()
+        .filter(|krate| tcx.crate_name(**krate).as_str() == first_segment)
+        .flat_map(|krate| foreign_functions(tcx, *krate));
+    local.chain(foreign).find(|def_id| tcx.def_path_str(*def_id) == path_str)
+}
+
+/// Whether the definition is a function, a method or the constructor of a
+/// tuple struct or variant, i.e., something that a stub path may refer to.
+/// The closures defined in a function have a path that nests under the path of
+/// the function (e.g., `foo::{closure#0}`), so they are ruled out explicitly.
+fn is_function_or_ctor(tcx: TyCtxt, def_id: DefId) -> bool {
+    matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn | DefKind::Ctor(..))
+}
+
+/// Replaces every run of whitespace in a path (including newlines, which are
+/// common in paths that span several lines of a config file) with a single
+/// space, and drops the whitespace at its ends.
+fn normalize_whitespace(path_str: &str) -> String {
+    path_str.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Resolves a simple path to a function/method if `ns` is the value namespace,
+/// or to a type or trait if it is the type namespace. A type or trait with a
+/// single segment that is not defined in scope is looked up in the standard
+/// library prelude (e.g., `Vec` or `Clone`).
+fn resolve_simple_path(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    path_str: &str,
+    ns: Namespace,
+) -> Option<DefId> {
     let path = to_path(tcx, current_module, path_str)?;
-    match &path.base {
-        Base::ExternPrelude => resolve_external(tcx, path.segments),
+    let exceeded = Cell::new(false);
+    let uses = RefCell::new(Vec::new());
+    let depth = Depth { current: 0, exceeded: &exceeded, uses: &uses };
+    let resolved = match &path.base {
+        Base::ExternPrelude => resolve_external(tcx, path.segments, ns, depth),
         Base::LocalModule { id, may_be_external_path } => {
             // Try to resolve it as a relative path first; if this fails and the
             // path might be external (it wasn't qualified with `self`, etc.)
             // and the current module does not have a submodule with the same
             // first segment, try resolving it as an external path.
-            resolve_relative(tcx, *id, path.segments.clone()).or_else(|| {
-                if *may_be_external_path
-                    && !has_submodule_with_name(tcx, current_module, path.segments.front()?)
+            resolve_relative(tcx, *id, path.segments.clone(), ns, depth).or_else(|| {
+                if !*may_be_external_path
+                    || has_submodule_with_name(tcx, current_module, path.segments.front()?)
                 {
-                    resolve_external(tcx, path.segments)
-                } else {
                     None
+                } else if ns == Namespace::TypeNS && path.segments.len() == 1 {
+                    let prelude = ["std", "prelude", "v1"].into_iter().map(str::to_string);
+                    let in_prelude = prelude.chain(path.segments.iter().cloned()).collect();
+                    resolve_external(tcx, in_prelude, ns, depth)
+                        .or_else(|| resolve_external(tcx, path.segments, ns, depth))
+                } else {
+                    resolve_external(tcx, path.segments, ns, depth)
                 }
             })
         }
+    };
+    if exceeded.get() {
+        tcx.sess.err(format!(
+            "stub path resolution too deep; possible re-export cycle: resolving `{path_str}` \
+            goes through more than {} modules; use `--stub-resolution-depth` to raise the limit",
+            MAX_RESOLUTION_DEPTH.load(Ordering::Relaxed)
+        ));
+        return None;
+    }
+    if let Some(def_id) = resolved {
+        let uses = uses.into_inner();
+        if TRACE_USES.load(Ordering::Relaxed) && !uses.is_empty() {
+            let via: Vec<_> = uses.iter().map(|use_str| format!("`{use_str}`")).collect();
+            println!(
+                "resolved `{path_str}` via {} -> `{}`",
+                via.join(" -> "),
+                canonical_path_str(tcx, def_id)
+            );
+        }
+    }
+    resolved
+}
+
+/// Resolves a qualified path of the form `<Type as Trait>::method` to the
+/// method of the implementation of the trait for the type. The type must be a
+/// struct, enum or union (possibly through a type alias). The generic
+/// arguments of the type and of the trait are ignored, since a stub replaces
+/// every instance of the method; reports an error if this makes the path
+/// ambiguous (e.g., `<Foo as From<u8>>::from` with both `From<u8>` and
+/// `From<u16>` implemented for `Foo`).
+fn resolve_qualified_path(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    path_str: &str,
+) -> Option<DefId> {
+    let Some((ty, trait_, method)) = split_qualified_path(path_str) else {
+        tracing::debug!("Unable to parse qualified path `{path_str}`");
+        return None;
+    };
+    tracing::debug!("Resolving `{method}` in the implementation of `{trait_}` for `{ty}`");
+    let type_id =
+        resolve_simple_path(tcx, current_module, without_generic_args(ty), Namespace::TypeNS)?;
+    let trait_id =
+        resolve_simple_path(tcx, current_module, without_generic_args(trait_), Namespace::TypeNS)?;
+    let type_id = match tcx.def_kind(type_id) {
+        DefKind::Struct | DefKind::Enum | DefKind::Union => type_id,
+        DefKind::TyAlias => tcx.type_of(type_id).ty_adt_def()?.did(),
+        _ => return None,
+    };
+    if tcx.def_kind(trait_id) != DefKind::Trait {
+        tracing::debug!("Unable to resolve `{trait_}` as a trait");
+        return None;
+    }
+    let candidates: Vec<_> = tcx
+        .all_impls(trait_id)
+        .filter(|impl_| tcx.type_of(*impl_).ty_adt_def().map(|adt| adt.did()) == Some(type_id))
+        .filter_map(|impl_| resolve_in_impl(tcx, impl_, method))
+        .collect();
+    match candidates[..] {
+        [] => {
+            tracing::debug!("Unable to resolve `{method}` in an implementation of `{trait_}`");
+            None
+        }
+        [def_id] => Some(def_id),
+        _ => {
+            let mut possibilities: Vec<_> =
+                candidates.iter().map(|def_id| tcx.def_path_str(*def_id)).collect();
+            possibilities.sort();
+            tcx.sess.err(format!(
+                "the generic arguments in `{path_str}` are ignored, which makes it impossible to \
+                unambiguously resolve path; the possibilities are:\n\t{}",
+                possibilities.join("\n\t")
+            ));
+            None
+        }
+    }
+}
+
+/// Splits a qualified path of the form `<Type as Trait>::method` into the type,
+/// the trait and the method, or returns `None` if the path does not have this
+/// form.
+fn split_qualified_path(path_str: &str) -> Option<(&str, &str, &str)> {
+    let inner = path_str.trim().strip_prefix('<')?;
+    // Find the `>` that closes the initial `<`.
+    let mut open = 0;
+    let close = inner.find(|c| match c {
+        '<' => {
+            open += 1;
+            false
+        }
+        '>' if open > 0 => {
+            open -= 1;
+            false
+        }
+        '>' => true,
+        _ => false,
+    })?;
+    let method = inner[close + 1..].trim_start().strip_prefix("::")?.trim();
+    let (ty, trait_) = inner[..close].rsplit_once(" as ")?;
+    let is_ident =
+        |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_ident(method).then(|| (ty.trim(), trait_.trim(), method))
+}
+
+/// Returns the path of a type or trait without its generic arguments (e.g.,
+/// `Vec` for `Vec<u8>`).
+fn without_generic_args(path_str: &str) -> &str {
+    path_str.split('<').next().unwrap().trim()
+}
+
+/// The number of modules the resolution of a path went through so far.
+#[derive(Clone, Copy)]
+struct Depth<'a> {
+    current: usize,
+    /// Whether the resolution of the path went through too many modules. Once
+    /// set, the resolution stops everywhere, since each glob import may start
+    /// a new branch of the same cycle.
+    exceeded: &'a Cell<bool>,
+    /// The `use` statements followed by the branch of the resolution that is
+    /// explored, in order. A statement is removed once the resolution through
+    /// it fails, so only the chain of the resolved path is left at the end.
+    uses: &'a RefCell<Vec<String>>,
+}
+
+impl<'a> Depth<'a> {
+    /// Resolves a path through a `use` statement, which is recorded in the
+    /// chain of `use` statements if the resolution succeeds.
+    fn follow_use(
+        self,
+        use_str: String,
+        resolve: impl FnOnce(Depth<'a>) -> Option<DefId>,
+    ) -> Option<DefId> {
+        let len = self.uses.borrow().len();
+        self.uses.borrow_mut().push(use_str);
+        let resolved = resolve(self);
+        if resolved.is_none() {
+            self.uses.borrow_mut().truncate(len);
+        }
+        resolved
+    }
+}
+
+impl<'a> Depth<'a> {
+    /// Returns the depth inside one more module, or `None` if this exceeds the
+    /// limit.
+    fn enter(self) -> Option<Depth<'a>> {
+        if self.exceeded.get() || self.current >= MAX_RESOLUTION_DEPTH.load(Ordering::Relaxed) {
+            tracing::debug!(depth = self.current, "Resolution is too deep");
+            self.exceeded.set(true);
+            None
+        } else {
+            Some(Depth { current: self.current + 1, ..self })
+        }
     }
 }
 
@@ -92,8 +383,10 @@ fn to_path(tcx: TyCtxt, current_module: LocalDefId, name: &str) -> Option<Path>
     const SELF: &str = "self";
     const SUPER: &str = "super";
 
-    // Split the string into segments separated by `::`.
-    let mut segments: Segments = name.split("::").map(str::to_string).collect();
+    // Split the string into segments separated by `::`. The whitespace around
+    // the segments is dropped, e.g., from a path like `foo :: bar` that was
+    // copied from formatted code.
+    let mut segments: Segments = name.split("::").map(|seg| seg.trim().to_string()).collect();
     if segments.is_empty() {
         return Some(Path::new(
             Base::LocalModule { id: current_module, may_be_external_path: false },
@@ -102,15 +395,17 @@ fn to_path(tcx: TyCtxt, current_module: LocalDefId, name: &str) -> Option<Path>
     }
 
     // Resolve qualifiers `crate`, initial `::`, and `self`. The qualifier
-    // `self` may be followed be `super` (handled below).
+    // `self` may be followed be `super` (handled below). An initial `::`
+    // refers to the root of the crate in the 2015 edition, where the external
+    // crates are declared.
     let first = segments[0].as_str();
     let may_be_external_path = !matches!(first, CRATE | SELF | SUPER);
     match first {
-        ROOT => {
+        ROOT if !tcx.sess.edition().rust_2015() => {
             segments.pop_front();
             return Some(Path::new(Base::ExternPrelude, segments));
         }
-        CRATE => {
+        ROOT | CRATE => {
             segments.pop_front();
             // Find the module at the root of the crate.
             let current_module_hir_id = tcx.hir().local_def_id_to_hir_id(current_module);
@@ -146,29 +441,83 @@ fn to_path(tcx: TyCtxt, current_module: LocalDefId, name: &str) -> Option<Path>
 }
 
 /// Resolves an external path.
-fn resolve_external(tcx: TyCtxt, mut segments: Segments) -> Option<DefId> {
+fn resolve_external(
+    tcx: TyCtxt,
+    mut segments: Segments,
+    ns: Namespace,
+    depth: Depth,
+) -> Option<DefId> {
     tracing::debug!("Resolving `{}` in the external prelude", segments_to_string(&segments));
     let first = segments.pop_front().or_else(|| {
         tracing::debug!("Unable to resolve the empty path");
         None
     })?;
+    if let Some(crate_num) = extern_crate_rename(tcx, &first) {
+        tracing::debug!(
+            "Resolved `{first}` as the name given to external crate `{}` by `--extern`",
+            tcx.crate_name(crate_num)
+        );
+        let crate_def_id = DefId { index: CRATE_DEF_INDEX, krate: crate_num };
+        return resolve_in_foreign_module(tcx, crate_def_id, segments, ns, depth);
+    }
     for crate_num in tcx.crates(()) {
         let crate_name = tcx.crate_name(*crate_num);
         if crate_name.as_str() == first {
             let crate_def_id = DefId { index: CRATE_DEF_INDEX, krate: *crate_num };
-            return resolve_in_foreign_module(tcx, crate_def_id, segments);
+            return resolve_in_foreign_module(tcx, crate_def_id, segments, ns, depth);
         }
     }
+    if let Some(crate_num) = extern_crate_alias(tcx, &first) {
+        tracing::debug!(
+            "Resolved `{first}` as an alias of external crate `{}`",
+            tcx.crate_name(crate_num)
+        );
+        let crate_def_id = DefId { index: CRATE_DEF_INDEX, krate: crate_num };
+        return resolve_in_foreign_module(tcx, crate_def_id, segments, ns, depth);
+    }
     tracing::debug!("Unable to resolve `{first}` as an external crate");
     None
 }
 
+/// Returns the crate that `--extern name=path` adds to the external prelude
+/// under the given name, if that is not the name of the crate. This is how
+/// Cargo passes a dependency that is renamed in `Cargo.toml` (e.g.,
+/// `alias = { package = "real-name", .. }`), so the paths use the name that
+/// appears in the `use` statements of the crate. The crate is found by the
+/// files it was loaded from.
+fn extern_crate_rename(tcx: TyCtxt, name: &str) -> Option<CrateNum> {
+    let files: Vec<_> = tcx.sess.opts.externs.get(name)?.files()?.collect();
+    tcx.crates(()).iter().copied().find(|crate_num| {
+        tcx.crate_name(*crate_num).as_str() != name
+            && tcx.used_crate_source(*crate_num).paths().any(|path| {
+                files.iter().any(|file| file.canonicalized() == path || file.original() == path)
+            })
+    })
+}
+
+/// Returns the crate that an `extern crate foo as alias;` item at the root of
+/// the local crate adds to the external prelude under the name `alias`.
+fn extern_crate_alias(tcx: TyCtxt, alias: &str) -> Option<CrateNum> {
+    tcx.hir().module_items(CRATE_DEF_ID).find_map(|item_id| {
+        let item = tcx.hir().item(item_id);
+        match item.kind {
+            ItemKind::ExternCrate(Some(_)) if item.ident.as_str() == alias => {
+                tcx.extern_mod_stmt_cnum(item.owner_id.def_id)
+            }
+            _ => None,
+        }
+    })
+}
+
 /// Resolves a path relative to a foreign module.
 fn resolve_in_foreign_module(
     tcx: TyCtxt,
     foreign_mod: DefId,
     mut segments: Segments,
+    ns: Namespace,
+    depth: Depth,
 ) -> Option<DefId> {
+    let depth = depth.enter()?;
     tracing::debug!(
         "Resolving `{}` in foreign module `{}`",
         segments_to_string(&segments),
@@ -181,26 +530,66 @@ fn resolve_in_foreign_module(
     })?;
     for child in tcx.module_children(foreign_mod) {
         match child.res {
-            Res::Def(DefKind::Fn, def_id) => {
+            Res::Def(DefKind::Fn, def_id) if ns == Namespace::ValueNS => {
+                if first == child.ident.as_str() && segments.len() == 1 {
+                    // The children of a foreign module include its re-exports (e.g., the ones
+                    // in a `prelude` module), which already refer to the canonical definition.
+                    if tcx.parent(def_id) == foreign_mod {
+                        tracing::debug!(
+                            "Resolved `{first}` as a function in foreign module `{}`",
+                            tcx.def_path_str(foreign_mod)
+                        );
+                    } else {
+                        tracing::debug!(
+                            "Resolved `{first}` as a re-export of `{}` in foreign module `{}`",
+                            canonical_path_str(tcx, def_id),
+                            tcx.def_path_str(foreign_mod)
+                        );
+                    }
+                    return Some(def_id);
+                }
+            }
+            Res::Def(DefKind::Ctor(..), ctor_id) if ns == Namespace::ValueNS => {
                 if first == child.ident.as_str() && segments.len() == 1 {
                     tracing::debug!(
-                        "Resolved `{first}` as a function in foreign module `{}`",
+                        "Resolved `{first}` as a constructor in foreign module `{}`",
                         tcx.def_path_str(foreign_mod)
                     );
-                    return Some(def_id);
+                    return Some(ctor_id);
                 }
             }
             Res::Def(DefKind::Mod, inner_mod_id) => {
-                if first == child.ident.as_str() {
+                // A path cannot end with a module, but there may be a function with the same name
+                // (e.g., a re-export that imports both).
+                if first == child.ident.as_str() && segments.len() > 1 {
                     segments.pop_front();
-                    return resolve_in_foreign_module(tcx, inner_mod_id, segments);
+                    return resolve_in_foreign_module(tcx, inner_mod_id, segments, ns, depth);
                 }
             }
-            Res::Def(DefKind::Struct | DefKind::Enum | DefKind::Union, type_id) => {
-                if first == child.ident.as_str() && segments.len() == 2 {
+            Res::Def(kind @ (DefKind::Struct | DefKind::Enum | DefKind::Union), type_id) => {
+                if first == child.ident.as_str() && segments.len() == 1 && ns == Namespace::TypeNS {
+                    tracing::debug!(
+                        "Resolved `{first}` as a {} in foreign module `{}`",
+                        kind.descr(type_id),
+                        tcx.def_path_str(foreign_mod)
+                    );
+                    return Some(type_id);
+                }
+                if first == child.ident.as_str() && segments.len() == 2 && ns == Namespace::ValueNS
+                {
                     return resolve_in_type(tcx, type_id, &segments[1]);
                 }
             }
+            Res::Def(DefKind::TyAlias | DefKind::Trait, def_id) if ns == Namespace::TypeNS => {
+                if first == child.ident.as_str() && segments.len() == 1 {
+                    return Some(def_id);
+                }
+            }
+            Res::Def(DefKind::TyAlias, alias_id) => {
+                if first == child.ident.as_str() && segments.len() == 2 {
+                    return resolve_in_type_alias(tcx, alias_id, &segments[1]);
+                }
+            }
             _ => {}
         }
     }
@@ -212,6 +601,109 @@ fn resolve_in_foreign_module(
     None
 }
 
+/// Returns the functions and inherent methods of a foreign crate that can be
+/// resolved by a path, i.e., the ones reachable from the crate root through
+/// its modules (including re-exports).
+pub fn foreign_functions(tcx: TyCtxt, krate: CrateNum) -> Vec<DefId> {
+    let mut functions = Vec::new();
+    let mut visited = FxHashSet::default();
+    let mut modules = vec![DefId { index: CRATE_DEF_INDEX, krate }];
+    while let Some(module) = modules.pop() {
+        if !visited.insert(module) {
+            continue;
+        }
+        for child in tcx.module_children(module) {
+            match child.res {
+                Res::Def(DefKind::Fn, def_id) => functions.push(def_id),
+                Res::Def(DefKind::Mod, inner_mod_id) => modules.push(inner_mod_id),
+                Res::Def(DefKind::Struct | DefKind::Enum | DefKind::Union, type_id) => {
+                    for impl_ in tcx.inherent_impls(type_id) {
+                        functions.extend(
+                            tcx.associated_item_def_ids(*impl_)
+                                .iter()
+                                .filter(|item| tcx.def_kind(**item) == DefKind::AssocFn),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    functions
+}
+
+/// Attempts to resolve a symbol name (e.g., the name of a `#[no_mangle]`
+/// function called through FFI, or a mangled name) to the `DefId` of the
+/// function/method emitted with this symbol.
+///
+/// The candidates are the functions/methods with a body in the local crate, and
+/// the ones that the other crates export. Generic functions/methods do not have
+/// a single symbol, so they are never found this way.
+pub fn resolve_symbol(tcx: TyCtxt, symbol: &str) -> Option<DefId> {
+    let local = tcx
+        .hir_crate_items(())
+        .definitions()
+        .map(|local_def_id| local_def_id.to_def_id())
+        .filter(|def_id| matches!(tcx.def_kind(*def_id), DefKind::Fn | DefKind::AssocFn));
+    let foreign = tcx
+        .crates(())
+        .iter()
+        .flat_map(|krate| tcx.reachable_non_generics(*krate).keys().copied())
+        .filter(|def_id| matches!(tcx.def_kind(*def_id), DefKind::Fn | DefKind::AssocFn));
+    local.chain(foreign).find(|def_id| {
+        tcx.is_mir_available(*def_id)
+            && !tcx.generics_of(*def_id).requires_monomorphization(tcx)
+            && tcx.symbol_name(Instance::mono(tcx, *def_id)).name == symbol
+    })
+}
+
+/// Resolves a source location, given by the file and the line and column
+/// (both starting at 1), to the `DefId` of the innermost function/method of
+/// the local crate whose definition contains it. This is meant for the
+/// functions/methods of generated code, whose paths are not stable.
+///
+/// The file matches the files whose path ends with it, so it may be given
+/// relative to the crate (e.g., `src/generated.rs`).
+pub fn resolve_location(tcx: TyCtxt, file: &str, line: usize, col: usize) -> Option<DefId> {
+    let source_map = tcx.sess.source_map();
+    let matches_file = |name: String| {
+        name == file || name.strip_suffix(file).map_or(false, |prefix| prefix.ends_with('/'))
+    };
+    tcx.hir_crate_items(())
+        .definitions()
+        .filter(|local_def_id| {
+            matches!(tcx.def_kind(*local_def_id), DefKind::Fn | DefKind::AssocFn)
+                && tcx.hir().maybe_body_owned_by(*local_def_id).is_some()
+        })
+        .filter_map(|local_def_id| {
+            let hir_id = tcx.hir().local_def_id_to_hir_id(local_def_id);
+            let span = tcx.hir().span_with_body(hir_id);
+            let (lo, hi) =
+                (source_map.lookup_char_pos(span.lo()), source_map.lookup_char_pos(span.hi()));
+            let contains = matches_file(lo.file.name.prefer_local().to_string())
+                && (lo.line, lo.col.0 + 1) <= (line, col)
+                && (line, col) <= (hi.line, hi.col.0 + 1);
+            contains.then(|| (span.hi() - span.lo(), local_def_id.to_def_id()))
+        })
+        .min_by_key(|(len, _)| *len)
+        .map(|(_, def_id)| def_id)
+}
+
+/// Returns the path of the definition itself (e.g., `my_crate::ops::f`), instead of the
+/// path through which it is visible (e.g., the `my_crate::prelude::f` re-export), which is what
+/// `def_path_str` prints for items from other crates.
+pub fn canonical_path_str(tcx: TyCtxt, def_id: DefId) -> String {
+    with_no_visible_paths!(tcx.def_path_str(def_id))
+}
+
+/// Returns the canonical path of the definition, always starting with the name of its crate.
+/// `def_path_str` omits the crate name for local definitions, which makes it impossible to tell
+/// them apart from foreign ones with a similar path.
+pub fn qualified_path_str(tcx: TyCtxt, def_id: DefId) -> String {
+    let path = canonical_path_str(tcx, def_id);
+    if def_id.is_local() { format!("{}::{path}", tcx.crate_name(LOCAL_CRATE)) } else { path }
+}
+
 /// Generates a more friendly string representation of a local module's name
 /// (the default representation for the crate root is the empty string).
 fn module_to_string(tcx: TyCtxt, current_module: LocalDefId) -> String {
@@ -223,12 +715,26 @@ fn module_to_string(tcx: TyCtxt, current_module: LocalDefId) -> String {
     }
 }
 
+/// The name of a local item as it appears in its definition path, i.e., the
+/// name that users write in paths. The identifier of an item generated by a
+/// macro may carry the hygiene of the macro, so it is only used for the items
+/// that do not define a name of their own (like `use` statements).
+fn local_item_name(tcx: TyCtxt, item: &Item) -> Symbol {
+    match item.kind {
+        ItemKind::Use(..) => item.ident.name,
+        _ => tcx.opt_item_name(item.owner_id.to_def_id()).unwrap_or(item.ident.name),
+    }
+}
+
 /// Resolves a path relative to a local module.
 fn resolve_relative(
     tcx: TyCtxt,
     current_module: LocalDefId,
-    mut segments: Segments,
+    segments: Segments,
+    ns: Namespace,
+    depth: Depth,
 ) -> Option<DefId> {
+    let depth = depth.enter()?;
     tracing::debug!(
         "Resolving `{}` in local {}",
         segments_to_string(&segments),
@@ -240,34 +746,87 @@ fn resolve_relative(
         None
     })?;
     let mut glob_imports = Vec::new();
+    // Whether a non-glob item has the name of the first segment, in which case
+    // the glob imports are shadowed.
+    let mut shadowed = false;
     for item_id in tcx.hir().module_items(current_module) {
         let item = tcx.hir().item(item_id);
         let def_id = item.owner_id.def_id.to_def_id();
+        let name = local_item_name(tcx, item);
         match item.kind {
             ItemKind::Fn(..) => {
-                if first == item.ident.as_str() && segments.len() == 1 {
+                if first == name.as_str() && segments.len() == 1 && ns == Namespace::ValueNS {
                     tracing::debug!(
                         "Resolved `{first}` as a function in local {}",
                         module_to_string(tcx, current_module)
                     );
                     return Some(def_id);
                 }
+                if first == name.as_str() && segments.len() > 1 && ns == Namespace::ValueNS {
+                    let rest = segments.iter().skip(1).cloned().collect();
+                    let resolved = resolve_nested_fn(tcx, current_module, def_id, rest);
+                    if resolved.is_some() {
+                        return resolved;
+                    }
+                }
             }
+            // A module, `use` statement or crate may share its name with a
+            // function of the same module, so the other items are still
+            // considered if the rest of the path does not resolve through it.
             ItemKind::Mod(..) => {
-                if first == item.ident.as_str() {
-                    segments.pop_front();
-                    return resolve_relative(tcx, def_id.expect_local(), segments);
+                if first == name.as_str() && segments.len() > 1 {
+                    shadowed = true;
+                    let rest = segments.iter().skip(1).cloned().collect();
+                    let resolved = resolve_relative(tcx, def_id.expect_local(), rest, ns, depth);
+                    if resolved.is_some() {
+                        return resolved;
+                    }
+                }
+            }
+            ItemKind::Enum(..) | ItemKind::Struct(..) | ItemKind::Union(..)
+                if ns == Namespace::TypeNS =>
+            {
+                if first == name.as_str() && segments.len() == 1 {
+                    return Some(def_id);
                 }
             }
             ItemKind::Enum(..) | ItemKind::Struct(..) | ItemKind::Union(..) => {
-                if first == item.ident.as_str() && segments.len() == 2 {
+                if first == name.as_str() && segments.len() == 2 {
                     return resolve_in_type(tcx, def_id, &segments[1]);
                 }
+                if first == name.as_str() && segments.len() == 1 {
+                    if let Some(ctor_id) = struct_constructor(tcx, def_id) {
+                        return Some(ctor_id);
+                    }
+                }
+            }
+            ItemKind::TyAlias(..) | ItemKind::Trait(..) if ns == Namespace::TypeNS => {
+                if first == name.as_str() && segments.len() == 1 {
+                    return Some(def_id);
+                }
+            }
+            ItemKind::TyAlias(..) => {
+                if first == name.as_str() && segments.len() == 2 {
+                    return resolve_in_type_alias(tcx, def_id, &segments[1]);
+                }
             }
             ItemKind::Use(use_path, UseKind::Single) => {
-                if first == item.ident.as_str() {
-                    segments.pop_front();
-                    return resolve_in_use(tcx, use_path, segments);
+                if first == name.as_str() {
+                    shadowed = true;
+                    let rest = segments.iter().skip(1).cloned().collect();
+                    let use_path_str = use_path_to_string(use_path);
+                    let use_str = match use_path.segments.last() {
+                        Some(segment) if segment.ident.name == name => {
+                            format!("use {use_path_str}")
+                        }
+                        _ => format!("use {use_path_str} as {name}"),
+                    };
+                    let resolved = depth.follow_use(use_str, |depth| {
+                        resolve_in_use(tcx, use_path, rest, ns, depth)
+                    });
+                    if resolved.is_some() {
+                        return resolved;
+                    }
                 }
             }
             ItemKind::Use(use_path, UseKind::Glob) => {
@@ -276,18 +835,26 @@ fn resolve_relative(
                 glob_imports.push(use_path);
             }
             ItemKind::ExternCrate(orig_name_opt) => {
-                if first == item.ident.as_str() {
+                if first == name.as_str() {
+                    shadowed = true;
+                    let mut external = segments.clone();
                     if let Some(orig_name) = orig_name_opt {
-                        segments[0] = orig_name.to_string();
+                        external[0] = orig_name.to_string();
+                    }
+                    let resolved = resolve_external(tcx, external, ns, depth);
+                    if resolved.is_some() {
+                        return resolved;
                     }
-                    return resolve_external(tcx, segments);
                 }
             }
             _ => (),
         }
     }
 
-    resolve_in_glob_uses(tcx, current_module, glob_imports, &segments).or_else(|| {
+    if shadowed {
+        glob_imports.clear();
+    }
+    resolve_in_glob_uses(tcx, current_module, glob_imports, &segments, ns, depth).or_else(|| {
         tracing::debug!(
             "Unable to resolve `{first}` as an item in local {}",
             module_to_string(tcx, current_module)
@@ -296,43 +863,153 @@ fn resolve_relative(
     })
 }
 
+/// Resolves a path to a function nested in the body of a local function of
+/// `current_module`, e.g., `outer::mock` for the function `mock` defined in
+/// the body of `outer`. Each segment names a function defined in the body of
+/// the function named by the previous one. Reports an error if the body
+/// defines several functions with the name of a segment (in different
+/// blocks), since the path cannot tell them apart.
+fn resolve_nested_fn(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    fn_id: DefId,
+    segments: Segments,
+) -> Option<DefId> {
+    let mut parent = fn_id;
+    for segment in &segments {
+        let candidates: Vec<_> = tcx
+            .hir()
+            .module_items(current_module)
+            .map(|item_id| tcx.hir().item(item_id))
+            .filter(|item| matches!(item.kind, ItemKind::Fn(..)))
+            .filter(|item| local_item_name(tcx, item).as_str() == segment)
+            .map(|item| item.owner_id.def_id.to_def_id())
+            .filter(|def_id| tcx.opt_parent(*def_id) == Some(parent))
+            .collect();
+        parent = match candidates[..] {
+            [] => {
+                tracing::debug!(
+                    "Unable to resolve `{segment}` as a function nested in `{}`",
+                    tcx.def_path_str(parent)
+                );
+                return None;
+            }
+            [def_id] => def_id,
+            _ => {
+                let parent = tcx.def_path_str(parent);
+                tcx.sess.err(format!(
+                    "`{parent}` defines {} functions named `{segment}` in different blocks, \
+                    which makes it impossible to unambiguously resolve path `{parent}::{segment}`",
+                    candidates.len(),
+                ));
+                return None;
+            }
+        };
+    }
+    tracing::debug!("Resolved `{}` as a nested function", tcx.def_path_str(parent));
+    Some(parent)
+}
+
 /// Resolves a path relative to a local or foreign module.
-fn resolve_in_module(tcx: TyCtxt, current_module: DefId, segments: Segments) -> Option<DefId> {
+fn resolve_in_module(
+    tcx: TyCtxt,
+    current_module: DefId,
+    segments: Segments,
+    ns: Namespace,
+    depth: Depth,
+) -> Option<DefId> {
     match current_module.as_local() {
-        None => resolve_in_foreign_module(tcx, current_module, segments),
-        Some(local_id) => resolve_relative(tcx, local_id, segments),
+        None => resolve_in_foreign_module(tcx, current_module, segments, ns, depth),
+        Some(local_id) => resolve_relative(tcx, local_id, segments, ns, depth),
     }
 }
 
 /// Resolves a path by exploring a non-glob use statement.
-fn resolve_in_use(tcx: TyCtxt, use_path: &rustc_hir::UsePath, segments: Segments) -> Option<DefId> {
-    if let Res::Def(def_kind, def_id) = use_path.res[0] {
-        tracing::debug!(
-            "Resolving `{}` via `use` import of `{}`",
-            segments_to_string(&segments),
-            tcx.def_path_str(def_id)
-        );
-        match def_kind {
-            DefKind::Fn => {
-                if segments.is_empty() {
-                    tracing::debug!(
-                        "Resolved `{}` to a function via `use` import of `{}`",
-                        segments_to_string(&segments),
-                        tcx.def_path_str(def_id)
-                    );
-                    return Some(def_id);
-                }
+///
+/// A `use` statement imports the items with the given name from every namespace (e.g., both a
+/// module and a function named `f`), so we try each of its resolutions. The statement may also
+/// rename the item (e.g., `pub use inner::f as g;`), in which case the path resolves to the
+/// original definition.
+fn resolve_in_use(
+    tcx: TyCtxt,
+    use_path: &rustc_hir::UsePath,
+    segments: Segments,
+    ns: Namespace,
+    depth: Depth,
+) -> Option<DefId> {
+    let resolved = use_path
+        .res
+        .iter()
+        .find_map(|res| resolve_in_use_res(tcx, *res, segments.clone(), ns, depth));
+    if resolved.is_none() {
+        tracing::debug!("Unable to resolve `{}` via `use` import", segments_to_string(&segments));
+    }
+    resolved
+}
+
+/// Resolves a path via one of the resolutions of a non-glob use statement.
+fn resolve_in_use_res(
+    tcx: TyCtxt,
+    res: Res,
+    segments: Segments,
+    ns: Namespace,
+    depth: Depth,
+) -> Option<DefId> {
+    let Res::Def(def_kind, def_id) = res else { return None };
+    tracing::debug!(
+        "Resolving `{}` via `use` import of `{}`",
+        segments_to_string(&segments),
+        tcx.def_path_str(def_id)
+    );
+    match def_kind {
+        DefKind::Struct | DefKind::Enum | DefKind::Union | DefKind::TyAlias | DefKind::Trait
+            if ns == Namespace::TypeNS =>
+        {
+            if segments.is_empty() {
+                tracing::debug!(
+                    "Resolved to {} `{}` via `use` import",
+                    def_kind.descr(def_id),
+                    canonical_path_str(tcx, def_id)
+                );
+                return Some(def_id);
             }
-            DefKind::Mod => return resolve_in_module(tcx, def_id, segments),
-            DefKind::Struct | DefKind::Enum | DefKind::Union => {
-                if segments.len() == 1 {
-                    return resolve_in_type(tcx, def_id, &segments[0]);
-                }
+        }
+        DefKind::Fn if ns == Namespace::ValueNS => {
+            if segments.is_empty() {
+                tracing::debug!(
+                    "Resolved to function `{}` via `use` import",
+                    canonical_path_str(tcx, def_id)
+                );
+                return Some(def_id);
+            }
+        }
+        DefKind::Ctor(..) if ns == Namespace::ValueNS => {
+            if segments.is_empty() {
+                tracing::debug!(
+                    "Resolved to constructor `{}` via `use` import",
+                    canonical_path_str(tcx, def_id)
+                );
+                return Some(def_id);
+            }
+        }
+        DefKind::Mod => {
+            // A path cannot end with a module, so skip it in favor of other namespaces.
+            if !segments.is_empty() {
+                return resolve_in_module(tcx, def_id, segments, ns, depth);
+            }
+        }
+        DefKind::Struct | DefKind::Enum | DefKind::Union => {
+            if segments.len() == 1 {
+                return resolve_in_type(tcx, def_id, &segments[0]);
+            }
+        }
+        DefKind::TyAlias => {
+            if segments.len() == 1 {
+                return resolve_in_type_alias(tcx, def_id, &segments[0]);
             }
-            _ => (),
         }
+        _ => (),
     }
-    tracing::debug!("Unable to resolve `{}` via `use` import", segments_to_string(&segments));
     None
 }
 
@@ -342,18 +1019,22 @@ fn resolve_in_glob_uses(
     current_module: LocalDefId,
     glob_imports: Vec<&rustc_hir::UsePath>,
     segments: &Segments,
+    ns: Namespace,
+    depth: Depth,
 ) -> Option<DefId> {
+    let len = depth.uses.borrow().len();
     let glob_resolves = glob_imports
         .iter()
         .filter_map(|use_path| {
             let span = tracing::span!(tracing::Level::DEBUG, "glob_resolution");
             let _enter = span.enter();
-            resolve_in_glob_use(tcx, use_path, segments.clone())
+            resolve_in_glob_use(tcx, use_path, segments.clone(), ns, depth)
         })
         .collect::<Vec<_>>();
     if glob_resolves.len() == 1 {
         return glob_resolves.first().copied();
     }
+    depth.uses.borrow_mut().truncate(len);
     if glob_resolves.len() > 1 {
         // Raise an error if it's ambiguous which glob import a function comes
         // from. rustc will also raise an error in this case if the ambiguous
@@ -365,9 +1046,13 @@ fn resolve_in_glob_uses(
             "glob imports in local {location} make it impossible to \
             unambiguously resolve path; the possibilities are:"
         );
-        for def_id in glob_resolves {
+        // List the possibilities in a deterministic order.
+        let mut possibilities: Vec<_> =
+            glob_resolves.iter().map(|def_id| tcx.def_path_str(*def_id)).collect();
+        possibilities.sort();
+        for path in possibilities {
             msg.push_str("\n\t");
-            msg.push_str(&tcx.def_path_str(def_id));
+            msg.push_str(&path);
         }
         tcx.sess.err(msg);
     }
@@ -379,16 +1064,35 @@ fn resolve_in_glob_use(
     tcx: TyCtxt,
     use_path: &rustc_hir::UsePath,
     segments: Segments,
+    ns: Namespace,
+    depth: Depth,
 ) -> Option<DefId> {
     if let Res::Def(DefKind::Mod, def_id) = use_path.res[0] {
-        resolve_in_module(tcx, def_id, segments)
+        let use_str = format!("use {}::*", use_path_to_string(use_path));
+        depth.follow_use(use_str, |depth| resolve_in_module(tcx, def_id, segments, ns, depth))
     } else {
         None
     }
 }
 
-/// Resolves a method in a type. It currently does not resolve trait methods
-/// (see <https://github.com/model-checking/kani/issues/1997>).
+/// Returns the path of a `use` statement as it is written, e.g., `a::b` for
+/// `use a::b as c`.
+fn use_path_to_string(use_path: &rustc_hir::UsePath) -> String {
+    let segments: Vec<_> = use_path
+        .segments
+        .iter()
+        .filter(|segment| segment.ident.name != kw::PathRoot)
+        .map(|segment| segment.ident.as_str())
+        .collect();
+    segments.join("::")
+}
+
+/// Resolves a method or an enum variant constructor in a type. Like rustc, an
+/// inherent method takes precedence over a trait method with the same name.
+/// The trait methods are only those of the implementations of traits for the
+/// type itself, not of blanket implementations; reports an error if several
+/// traits implemented for the type have a method with the name, since the path
+/// is then ambiguous and `<Type as Trait>::method` should be used instead.
 fn resolve_in_type(tcx: TyCtxt, type_id: DefId, name: &str) -> Option<DefId> {
     tracing::debug!("Resolving `{name}` in type `{}`", tcx.def_path_str(type_id));
     // Try the inherent `impl` blocks (i.e., non-trait `impl`s).
@@ -398,8 +1102,94 @@ fn resolve_in_type(tcx: TyCtxt, type_id: DefId, name: &str) -> Option<DefId> {
             return maybe_resolved;
         }
     }
-    tracing::debug!("Unable to resolve `{name}` in type `{}`", tcx.def_path_str(type_id));
-    None
+    if tcx.def_kind(type_id) == DefKind::Enum {
+        let variant =
+            tcx.adt_def(type_id).variants().iter().find(|variant| variant.name.as_str() == name);
+        if let Some(ctor_id) = variant.and_then(|variant| variant.ctor_def_id()) {
+            tracing::debug!(
+                "Resolved `{name}` as a variant constructor in type `{}`",
+                tcx.def_path_str(type_id)
+            );
+            return Some(ctor_id);
+        }
+    }
+    resolve_in_trait_impls(tcx, type_id, name)
+}
+
+/// Resolves a method in the implementations of traits for a type (e.g.,
+/// `next` in `Counter::next`, where `impl Iterator for Counter`).
+fn resolve_in_trait_impls(tcx: TyCtxt, type_id: DefId, name: &str) -> Option<DefId> {
+    let type_path = tcx.def_path_str(type_id);
+    let candidates: Vec<_> = tcx
+        .all_traits()
+        .flat_map(|trait_id| tcx.all_impls(trait_id).map(move |impl_| (trait_id, impl_)))
+        .filter(|(_, impl_)| tcx.type_of(*impl_).ty_adt_def().map(|adt| adt.did()) == Some(type_id))
+        .filter_map(|(trait_id, impl_)| {
+            let method = resolve_in_impl(tcx, impl_, name)?;
+            (tcx.def_kind(method) == DefKind::AssocFn).then_some((trait_id, method))
+        })
+        .collect();
+    match candidates[..] {
+        [] => {
+            tracing::debug!("Unable to resolve `{name}` in type `{type_path}`");
+            None
+        }
+        [(trait_id, def_id)] => {
+            tracing::debug!(
+                "Resolved `{name}` as a method of the implementation of `{}` for `{type_path}`",
+                tcx.def_path_str(trait_id)
+            );
+            Some(def_id)
+        }
+        _ => {
+            let mut possibilities: Vec<_> = candidates
+                .iter()
+                .map(|(trait_id, _)| {
+                    format!("<{type_path} as {}>::{name}", tcx.def_path_str(*trait_id))
+                })
+                .collect();
+            possibilities.sort();
+            possibilities.dedup();
+            tcx.sess.err(format!(
+                "`{type_path}::{name}` is ambiguous, since several traits implemented for \
+                `{type_path}` have a method `{name}`; use a fully-qualified path instead:\n\t{}",
+                possibilities.join("\n\t")
+            ));
+            None
+        }
+    }
+}
+
+/// Returns the constructor of a tuple or unit struct, which shares the name of
+/// the struct.
+fn struct_constructor(tcx: TyCtxt, type_id: DefId) -> Option<DefId> {
+    let adt_def = tcx.adt_def(type_id);
+    if adt_def.is_struct() { adt_def.non_enum_variant().ctor_def_id() } else { None }
+}
+
+/// Resolves a method in the type that a type alias refers to (e.g., `m` in
+/// `Foo::m`, where `type Foo = Bar;`). Nested aliases are already expanded by
+/// rustc. Since the generic "unable to resolve" error does not mention the
+/// alias, this reports an error if the aliased type is not a struct, enum, or
+/// union, or if it does not have the method.
+fn resolve_in_type_alias(tcx: TyCtxt, alias_id: DefId, name: &str) -> Option<DefId> {
+    let alias = tcx.def_path_str(alias_id);
+    let aliased_ty = tcx.type_of(alias_id);
+    tracing::debug!("Resolving `{name}` in type alias `{alias}` of `{aliased_ty}`");
+    let ty::Adt(adt_def, _) = aliased_ty.kind() else {
+        tcx.sess.err(format!(
+            "type alias `{alias}` refers to `{aliased_ty}`, which is not a struct, enum, or \
+            union; unable to resolve method `{name}`"
+        ));
+        return None;
+    };
+    let resolved = resolve_in_type(tcx, adt_def.did(), name);
+    if resolved.is_none() {
+        tcx.sess.err(format!(
+            "type alias `{alias}` refers to `{aliased_ty}`, which does not have a method `{name}`"
+        ));
+    }
+    resolved
 }
 
 /// Resolves a name in an `impl` block.
@@ -422,7 +1212,7 @@ fn has_submodule_with_name(tcx: TyCtxt, current_module: LocalDefId, name: &str)
     for item_id in tcx.hir().module_items(current_module) {
         let item = tcx.hir().item(item_id);
         if let ItemKind::Mod(..) = item.kind {
-            if name == item.ident.as_str() {
+            if name == local_item_name(tcx, item).as_str() {
                 return true;
             }
         }
@@ -2,108 +2,1579 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! This file contains code for extracting stubbing-related attributes.
 
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use regex::Regex;
 use rustc_ast::Attribute;
+use rustc_ast::CRATE_NODE_ID;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId, CRATE_DEF_ID};
 use rustc_hir::definitions::DefPathHash;
-use rustc_middle::ty::TyCtxt;
+use rustc_hir::ItemKind;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::ty::{DefIdTree, Ty, TyCtxt};
+use rustc_span::lev_distance::lev_distance;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::{Span, DUMMY_SP};
+use rustc_trait_selection::infer::InferCtxtExt;
 
-use crate::kani_middle::attributes::{extract_path_arguments, partition_kanitool_attributes};
-use crate::kani_middle::resolve::resolve_path;
+use crate::kani_middle::advisory_span;
+use crate::kani_middle::attributes::{
+    extract_cfg_alternatives, extract_path_arguments, extract_string_arguments,
+    partition_kanitool_attributes,
+};
+use crate::kani_middle::resolve::{
+    canonical_path_str, foreign_functions, qualified_path_str, resolve_location, resolve_path,
+    resolve_symbol,
+};
+use crate::kani_middle::stubbing::spec::{check_precondition, check_predicate, StubSpec};
+use crate::kani_middle::stubbing::{default_stub, spec_stub};
+use kani_queries::{StubSource, VerificationConfig};
 
-/// Collects the stubs from the harnesses in a crate, running rustc (to
-/// expansion) with the supplied arguments `rustc_args`.
-pub fn collect_stub_mappings(
-    tcx: TyCtxt,
-) -> FxHashMap<String, FxHashMap<DefPathHash, DefPathHash>> {
-    tcx.hir_crate_items(())
-        .items()
-        .filter_map(|item| {
-            let local_def_id = item.owner_id.def_id;
+/// The maximum number of functions a `kani::stub_regex` attribute may match
+/// if `--stub-regex-limit` is not provided.
+const DEFAULT_STUB_REGEX_LIMIT: usize = 100;
+
+/// The maximum number of nearest functions/methods listed in the error for a
+/// path that does not resolve if `--stub-error-candidates` is not provided.
+pub const DEFAULT_ERROR_CANDIDATES: usize = 3;
+
+/// The limit applies to every unresolved path, so it is stored here once the
+/// compiler configuration is known.
+static ERROR_CANDIDATES: AtomicUsize = AtomicUsize::new(DEFAULT_ERROR_CANDIDATES);
+
+/// Sets the maximum number of nearest functions/methods listed in the error
+/// for a path that does not resolve (see [nearest_paths]). None is listed if
+/// it is 0.
+pub fn set_error_candidates(count: usize) {
+    ERROR_CANDIDATES.store(count, Ordering::Relaxed);
+}
+
+/// The attributes that add stubs to the mapping of a harness.
+const STUB_ATTRIBUTES: [&str; 6] =
+    ["stub", "stub_regex", "stub_cfg", "stub_default", "stub_spec", "stub_in"];
+
+/// The names of the modules that conventionally contain the stubs of a crate.
+/// A harness that imports such a module likely means to use its stubs.
+const MOCK_MODULE_NAMES: [&str; 4] = ["mock", "mocks", "stub", "stubs"];
+
+/// The prefix of a string argument in the original position of a `kani::stub`
+/// attribute that identifies the original function/method by its symbol name.
+const SYMBOL_PREFIX: &str = "symbol:";
+
+/// The prefix of a string argument in the original position of a `kani::stub`
+/// attribute that identifies the original function/method by a source location
+/// of the form `file:line:col` within its definition.
+const LOCATION_PREFIX: &str = "location:";
+
+/// Maps a pair of a caller and an original function/method to the stub that
+/// replaces the calls to the original in the body of the caller.
+pub type CallerStubs = FxHashMap<(DefPathHash, DefPathHash), DefPathHash>;
+
+/// Maps an original function/method stubbed with `kani::stub_spec` to the
+/// specification its body is synthesized from.
+pub type SpecStubs = FxHashMap<DefPathHash, StubSpec>;
+
+/// The result of collecting the stubs from the harnesses in a crate.
+#[derive(Debug, Default)]
+pub struct StubCollection {
+    /// The stub mapping of each harness, keyed by the harness name. A harness
+    /// without stubs has an empty mapping; the pairs that failed to be
+    /// collected are not included.
+    pub mappings: FxHashMap<String, FxHashMap<DefPathHash, DefPathHash>>,
+    /// The stubs of each harness that only apply to the calls made by one
+    /// caller (see [CallerStubs]), keyed by the harness name.
+    pub caller_mappings: FxHashMap<String, CallerStubs>,
+    /// The specifications of the stubs of each harness that are given by a
+    /// `kani::stub_spec` attribute, keyed by the harness name. These stubs are
+    /// also in the stub mapping of the harness.
+    pub spec_mappings: FxHashMap<String, SpecStubs>,
+    /// The number of stubbing attributes of each harness, keyed by the harness
+    /// name. A `kani::stub_regex` attribute may add several pairs to the
+    /// mapping, and a faulty attribute adds none.
+    pub requested: FxHashMap<String, usize>,
+    /// The errors found while collecting the stubs, in the order they were
+    /// found. The collector does not emit them; see [StubError::emit].
+    pub errors: Vec<StubError>,
+    /// The paths of the items that have stubbing attributes but are not
+    /// harnesses, with the number of such attributes each. These attributes
+    /// are ignored; the items are sorted by path.
+    pub ignored: Vec<(String, usize)>,
+    /// The imports of mock modules (see [MOCK_MODULE_NAMES]) in the scope of
+    /// each harness, as pairs of the harness name and the span and path of
+    /// the import, sorted by harness name.
+    pub mock_imports: Vec<(String, Span, String)>,
+    /// The functions/methods that two stub sources map to different stubs,
+    /// which are resolved by the precedence of the sources.
+    pub source_conflicts: Vec<SourceConflict>,
+    /// The `kani::stub_regex` attributes whose pattern does not match any
+    /// function/method, as triples of the harness name, the span of the
+    /// attribute and the pattern, in the order they were found.
+    pub unmatched_regexes: Vec<(String, Span, String)>,
+}
+
+impl StubCollection {
+    /// Removes the errors and the stubs of the harnesses that have errors, so
+    /// the other harnesses keep their stubs, and returns the errors for the
+    /// caller to report. The number of stubbing attributes of these harnesses
+    /// is kept, so they still show up in the summary.
+    pub fn skip_failed_harnesses(&mut self) -> Vec<StubError> {
+        let errors = std::mem::take(&mut self.errors);
+        for error in &errors {
+            self.mappings.remove(&error.harness);
+            self.caller_mappings.remove(&error.harness);
+            self.spec_mappings.remove(&error.harness);
+        }
+        errors
+    }
+}
+
+/// A function/method that two stub sources map to different stubs. The stub
+/// from the source with the higher precedence is in the stub mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceConflict {
+    /// The name of the harness with the conflicting stubs.
+    pub harness: String,
+    /// The path of the original function/method.
+    pub original: String,
+    /// The source and the path of the stub that is used.
+    pub winner: (StubSource, String),
+    /// The source and the path of the stub that is ignored.
+    pub loser: (StubSource, String),
+}
+
+/// An error found while collecting the stubs of a harness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubError {
+    /// The name of the harness with the faulty attribute.
+    pub harness: String,
+    /// The span of the faulty attribute.
+    pub span: Span,
+    pub kind: StubErrorKind,
+}
+
+impl StubError {
+    /// Emits this error as a compiler error.
+    pub fn emit(&self, tcx: TyCtxt) {
+        tcx.sess.span_err(self.span, self.kind.to_string());
+    }
+
+    /// Emits this error as a warning, for the harnesses whose stubs are
+    /// skipped instead (see `--continue-on-stub-errors`).
+    pub fn emit_warning(&self, tcx: TyCtxt) {
+        tcx.sess.span_warn(self.span, format!("{} (harness `{}`)", self.kind, self.harness));
+    }
+
+    /// Returns this error as a json diagnostic (see `--emit-stub-diagnostics`).
+    /// The errors in the verification config have no span.
+    pub fn to_json(&self, tcx: TyCtxt) -> serde_json::Value {
+        let span = (!self.span.is_dummy()).then(|| advisory_span(tcx, self.span));
+        serde_json::json!({
+            "severity": "error",
+            "code": self.kind.code(),
+            "harness": self.harness,
+            "message": self.kind.to_string(),
+            "span": span,
+            "paths": self.kind.paths(),
+        })
+    }
+}
+
+/// The kinds of errors that can be found while collecting stubs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StubErrorKind {
+    /// A `kani::stub` attribute does not have exactly two arguments.
+    WrongArgumentCount(usize),
+    /// An argument of a `kani::stub` attribute is not a path.
+    NonPathArgument,
+    /// The arguments of a `kani::stub_regex` attribute are not two strings.
+    InvalidRegexArguments,
+    /// The pattern of a `kani::stub_regex` attribute is not a valid regular expression.
+    InvalidRegex { pattern: String, reason: String },
+    /// The pattern of a `kani::stub_regex` attribute matches more functions/methods than
+    /// allowed.
+    RegexLimitExceeded { pattern: String, matches: usize, limit: usize },
+    /// The arguments of a `kani::stub_cfg` attribute are not a path followed by
+    /// `cfg(<predicate>, <path>)` alternatives.
+    InvalidCfgArguments,
+    /// No alternative of a `kani::stub_cfg` attribute is active.
+    NoActiveCfgAlternative { original: String },
+    /// More than one alternative of a `kani::stub_cfg` attribute is active.
+    MultipleActiveCfgAlternatives { original: String, stubs: Vec<String> },
+    /// A `kani::stub_default` attribute does not have exactly one path argument.
+    InvalidDefaultArguments,
+    /// A `kani::stub_spec` attribute does not have a path and a string argument.
+    InvalidSpecArguments,
+    /// The specification of a `kani::stub_spec` attribute cannot be parsed, or
+    /// does not match the return type of the function/method.
+    InvalidSpec { original: String, spec: String, reason: String },
+    /// A `kani::stub_in` attribute does not have exactly three path arguments.
+    InvalidCallerArguments,
+    /// The return type of a function/method in a `kani::stub_default` attribute
+    /// does not implement `Default`.
+    ReturnTypeNotDefault { original: String, ty: String },
+    /// A path does not resolve to a function/method.
+    Unresolved { path: String },
+    /// A path does not resolve to a function/method, but it does once the case
+    /// of its segments is fixed.
+    UnresolvedCase { path: String, suggestion: String },
+    /// A path does not resolve to a function/method, but there are local
+    /// functions/methods with a similar name (see [nearest_paths]).
+    UnresolvedNear { path: String, nearest: Vec<String> },
+    /// A symbol name does not belong to a non-generic function/method.
+    UnresolvedSymbol { symbol: String },
+    /// A source location is not of the form `file:line:col`.
+    InvalidLocation { location: String },
+    /// A source location does not fall within a function/method of the crate.
+    UnresolvedLocation { location: String },
+    /// The stub path obtained from a `kani::stub_regex` match does not resolve to a
+    /// function/method.
+    UnresolvedRegexStub { stub_path: String, original: String, pattern: String },
+    /// A function/method is mapped to two different stubs.
+    DuplicateMapping { original: String, stub: String, other: String },
+    /// A function/method marked `kani::no_stub` is the target of a stub.
+    NoStub { original: String },
+    /// A path of a stubbing attribute resolves to the constructor of a tuple
+    /// struct or enum variant, which is not a function that can be stubbed.
+    Constructor { path: String },
+    /// The stub of a function/method is itself stubbed in the same harness,
+    /// which is only an error with `--deny-chained-stubs`.
+    ChainedStub { original: String, stub: String, next: String },
+    /// An error in a stub of the verification config instead of an attribute.
+    InConfig { harness: String, error: Box<StubErrorKind> },
+}
+
+impl fmt::Display for StubErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StubErrorKind::WrongArgumentCount(count) => {
+                write!(f, "Attribute `kani::stub` takes two path arguments; found {count}")
+            }
+            StubErrorKind::NonPathArgument => write!(
+                f,
+                "Attribute `kani::stub` takes two path arguments; found argument that is not a path"
+            ),
+            StubErrorKind::InvalidRegexArguments => write!(
+                f,
+                "Attribute `kani::stub_regex` takes two string arguments: a regular expression \
+                and a replacement path"
+            ),
+            StubErrorKind::InvalidRegex { pattern, reason } => {
+                write!(f, "invalid regular expression `{pattern}`: {reason}")
+            }
+            StubErrorKind::RegexLimitExceeded { pattern, matches, limit } => write!(
+                f,
+                "regular expression `{pattern}` matches {matches} functions/methods, which \
+                exceeds the limit of {limit}; use `--stub-regex-limit` to raise it"
+            ),
+            StubErrorKind::InvalidCfgArguments => write!(
+                f,
+                "Attribute `kani::stub_cfg` takes a path followed by one or more \
+                `cfg(<predicate>, <path>)` alternatives"
+            ),
+            StubErrorKind::NoActiveCfgAlternative { original } => {
+                write!(f, "no alternative of `kani::stub_cfg` is active for {original}")
+            }
+            StubErrorKind::MultipleActiveCfgAlternatives { original, stubs } => write!(
+                f,
+                "multiple alternatives of `kani::stub_cfg` are active for {original}: {}",
+                stubs.join(", ")
+            ),
+            StubErrorKind::InvalidDefaultArguments => {
+                write!(f, "Attribute `kani::stub_default` takes one path argument")
+            }
+            StubErrorKind::ReturnTypeNotDefault { original, ty } => write!(
+                f,
+                "unable to stub {original} with `Default::default()`: its return type `{ty}` \
+                does not implement `Default`"
+            ),
+            StubErrorKind::InvalidSpecArguments => write!(
+                f,
+                "Attribute `kani::stub_spec` takes a path and a string argument: the original \
+                function/method and the specification of its replacement"
+            ),
+            StubErrorKind::InvalidSpec { original, spec, reason } => {
+                write!(f, "unable to stub {original} with specification `{spec}`: {reason}")
+            }
+            StubErrorKind::InvalidCallerArguments => write!(
+                f,
+                "Attribute `kani::stub_in` takes three path arguments: the caller, the original \
+                function/method and its replacement"
+            ),
+            StubErrorKind::Unresolved { path } => {
+                write!(f, "unable to resolve function/method: {path}")
+            }
+            StubErrorKind::UnresolvedCase { path, suggestion } => write!(
+                f,
+                "unable to resolve function/method: {path}; did you mean `{suggestion}`? Paths \
+                are case-sensitive"
+            ),
+            StubErrorKind::UnresolvedNear { path, nearest } => {
+                let nearest: Vec<_> = nearest.iter().map(|path| format!("`{path}`")).collect();
+                match nearest.as_slice() {
+                    [one] => write!(
+                        f,
+                        "unable to resolve function/method: {path}; the closest function/method \
+                        is {one}"
+                    ),
+                    _ => write!(
+                        f,
+                        "unable to resolve function/method: {path}; the closest functions/methods \
+                        are {}",
+                        nearest.join(", ")
+                    ),
+                }
+            }
+            StubErrorKind::UnresolvedSymbol { symbol } => write!(
+                f,
+                "unable to find a non-generic function/method with symbol name `{symbol}`"
+            ),
+            StubErrorKind::InvalidLocation { location } => write!(
+                f,
+                "invalid source location `{location}`; expected a location of the form \
+                `file:line:col`"
+            ),
+            StubErrorKind::UnresolvedLocation { location } => {
+                write!(f, "source location `{location}` is not within a function/method")
+            }
+            StubErrorKind::UnresolvedRegexStub { stub_path, original, pattern } => write!(
+                f,
+                "unable to resolve function/method: {stub_path} (the stub for `{original}` \
+                expanded from `{pattern}`)"
+            ),
+            StubErrorKind::DuplicateMapping { original, stub, other } => {
+                write!(f, "duplicate stub mapping: {original} mapped to {stub} and {other}")
+            }
+            StubErrorKind::NoStub { original } => {
+                write!(f, "unable to stub {original}: it is marked `#[kani::no_stub]`")
+            }
+            StubErrorKind::Constructor { path } => write!(
+                f,
+                "cannot stub constructor `{path}`; stub the function that wraps it instead"
+            ),
+            StubErrorKind::ChainedStub { original, stub, next } => write!(
+                f,
+                "stub {stub} of {original} is itself stubbed by {next}; stub {original} by \
+                {next} directly or remove one of the stubs (`--deny-chained-stubs`)"
+            ),
+            StubErrorKind::InConfig { harness, error } => {
+                write!(f, "{error} (in the verification config of harness `{harness}`)")
+            }
+        }
+    }
+}
+
+impl StubErrorKind {
+    /// A stable identifier of the kind of error (e.g., `unresolved-path`) for
+    /// the tools that read the json diagnostics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StubErrorKind::WrongArgumentCount(_) => "wrong-argument-count",
+            StubErrorKind::NonPathArgument => "non-path-argument",
+            StubErrorKind::InvalidRegexArguments => "invalid-regex-arguments",
+            StubErrorKind::InvalidRegex { .. } => "invalid-regex",
+            StubErrorKind::RegexLimitExceeded { .. } => "regex-limit-exceeded",
+            StubErrorKind::InvalidCfgArguments => "invalid-cfg-arguments",
+            StubErrorKind::NoActiveCfgAlternative { .. } => "no-active-cfg-alternative",
+            StubErrorKind::MultipleActiveCfgAlternatives { .. } => {
+                "multiple-active-cfg-alternatives"
+            }
+            StubErrorKind::InvalidDefaultArguments => "invalid-default-arguments",
+            StubErrorKind::InvalidSpecArguments => "invalid-spec-arguments",
+            StubErrorKind::InvalidSpec { .. } => "invalid-spec",
+            StubErrorKind::InvalidCallerArguments => "invalid-caller-arguments",
+            StubErrorKind::ReturnTypeNotDefault { .. } => "return-type-not-default",
+            StubErrorKind::Unresolved { .. } | StubErrorKind::UnresolvedNear { .. } => {
+                "unresolved-path"
+            }
+            StubErrorKind::UnresolvedCase { .. } => "unresolved-path-case",
+            StubErrorKind::UnresolvedSymbol { .. } => "unresolved-symbol",
+            StubErrorKind::InvalidLocation { .. } => "invalid-location",
+            StubErrorKind::UnresolvedLocation { .. } => "unresolved-location",
+            StubErrorKind::UnresolvedRegexStub { .. } => "unresolved-regex-stub",
+            StubErrorKind::DuplicateMapping { .. } => "duplicate-mapping",
+            StubErrorKind::NoStub { .. } => "no-stub",
+            StubErrorKind::Constructor { .. } => "constructor",
+            StubErrorKind::ChainedStub { .. } => "chained-stub",
+            StubErrorKind::InConfig { error, .. } => error.code(),
+        }
+    }
+
+    /// The paths of the functions/methods that the error refers to, as they
+    /// appear in its message.
+    pub fn paths(&self) -> Vec<String> {
+        match self {
+            StubErrorKind::NoActiveCfgAlternative { original }
+            | StubErrorKind::InvalidSpec { original, .. }
+            | StubErrorKind::ReturnTypeNotDefault { original, .. }
+            | StubErrorKind::NoStub { original } => vec![original.clone()],
+            StubErrorKind::MultipleActiveCfgAlternatives { original, stubs } => {
+                std::iter::once(original).chain(stubs).cloned().collect()
+            }
+            StubErrorKind::Unresolved { path } | StubErrorKind::Constructor { path } => {
+                vec![path.clone()]
+            }
+            StubErrorKind::UnresolvedCase { path, suggestion } => {
+                vec![path.clone(), suggestion.clone()]
+            }
+            StubErrorKind::UnresolvedNear { path, nearest } => {
+                std::iter::once(path).chain(nearest).cloned().collect()
+            }
+            StubErrorKind::UnresolvedRegexStub { stub_path, original, .. } => {
+                vec![stub_path.clone(), original.clone()]
+            }
+            StubErrorKind::DuplicateMapping { original, stub, other } => {
+                vec![original.clone(), stub.clone(), other.clone()]
+            }
+            StubErrorKind::ChainedStub { original, stub, next } => {
+                vec![original.clone(), stub.clone(), next.clone()]
+            }
+            StubErrorKind::InConfig { error, .. } => error.paths(),
+            StubErrorKind::WrongArgumentCount(_)
+            | StubErrorKind::NonPathArgument
+            | StubErrorKind::InvalidRegexArguments
+            | StubErrorKind::InvalidRegex { .. }
+            | StubErrorKind::RegexLimitExceeded { .. }
+            | StubErrorKind::InvalidCfgArguments
+            | StubErrorKind::InvalidDefaultArguments
+            | StubErrorKind::InvalidSpecArguments
+            | StubErrorKind::InvalidCallerArguments
+            | StubErrorKind::UnresolvedSymbol { .. }
+            | StubErrorKind::InvalidLocation { .. }
+            | StubErrorKind::UnresolvedLocation { .. } => vec![],
+        }
+    }
+}
+
+/// Returns whether the crate is compiled with the `kani` cfg, which Kani sets
+/// for the crates it verifies. The stubs are only collected for these crates,
+/// unless `--stub-without-kani-cfg` is set.
+pub fn is_kani_cfg_set(tcx: TyCtxt) -> bool {
+    tcx.sess.parse_sess.config.contains(&(Symbol::intern("kani"), None))
+}
+
+/// Collects the stubs from the harnesses in a crate. The `stub_regex_limit`
+/// bounds the number of functions that a single `kani::stub_regex` attribute
+/// may match.
+///
+/// Errors are accumulated in the returned [StubCollection] so the caller can
+/// decide how to report them.
+pub fn collect_stub_mappings(tcx: TyCtxt, stub_regex_limit: Option<usize>) -> StubCollection {
+    let mut collection = StubCollection::default();
+    // The candidates for `kani::stub_regex` are only computed if needed.
+    let mut regex_candidates = None;
+    // The imports of mock modules, keyed by the module or function they are in.
+    let mut mock_imports: FxHashMap<LocalDefId, Vec<(Span, String)>> = FxHashMap::default();
+    let mut harnesses = Vec::new();
+    for item in tcx.hir_crate_items(()).items() {
+        let local_def_id = item.owner_id.def_id;
+        let def_id = local_def_id.to_def_id();
+        if let ItemKind::Use(path, _) = tcx.hir().item(item).kind {
+            if path.segments.iter().any(|seg| MOCK_MODULE_NAMES.contains(&seg.ident.as_str())) {
+                let path_str: Vec<_> = path.segments.iter().map(|seg| seg.ident.as_str()).collect();
+                let scope = tcx.local_parent(local_def_id);
+                mock_imports.entry(scope).or_default().push((path.span, path_str.join("::")));
+            }
+        }
+        let (proof, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(def_id));
+        // Ignore anything that is not a harness
+        if proof.is_empty() {
+            continue;
+        }
+        let harness_name = tcx.def_path_str(def_id);
+        harnesses.push((harness_name.clone(), local_def_id));
+        let mut stub_pairs = FxHashMap::default();
+        let mut caller_stubs = FxHashMap::default();
+        let mut spec_stubs = FxHashMap::default();
+        let mut requested = 0;
+        for (name, attr) in other {
+            if STUB_ATTRIBUTES.contains(&name.as_str()) {
+                requested += 1;
+            }
+            let mut errors = AttributeErrors::new(&harness_name, attr, &mut collection.errors);
+            if name == "stub" {
+                update_stub_mapping(tcx, local_def_id, attr, &mut stub_pairs, &mut errors);
+            } else if name == "stub_regex" {
+                let candidates = regex_candidates.get_or_insert_with(|| local_functions(tcx));
+                let unmatched = update_stub_mapping_regex(
+                    tcx,
+                    local_def_id,
+                    attr,
+                    candidates,
+                    stub_regex_limit.unwrap_or(DEFAULT_STUB_REGEX_LIMIT),
+                    &mut stub_pairs,
+                    &mut errors,
+                );
+                if let Some(pattern) = unmatched {
+                    collection.unmatched_regexes.push((harness_name.clone(), attr.span, pattern));
+                }
+            } else if name == "stub_cfg" {
+                update_stub_mapping_cfg(tcx, local_def_id, attr, &mut stub_pairs, &mut errors);
+            } else if name == "stub_default" {
+                update_stub_mapping_default(tcx, local_def_id, attr, &mut stub_pairs, &mut errors);
+            } else if name == "stub_spec" {
+                update_stub_mapping_spec(
+                    tcx,
+                    local_def_id,
+                    attr,
+                    &mut stub_pairs,
+                    &mut spec_stubs,
+                    &mut errors,
+                );
+            } else if name == "stub_in" {
+                update_caller_stubs(tcx, local_def_id, attr, &mut caller_stubs, &mut errors);
+            }
+        }
+        collection.requested.insert(harness_name.clone(), requested);
+        collection.caller_mappings.insert(harness_name.clone(), caller_stubs);
+        collection.spec_mappings.insert(harness_name.clone(), spec_stubs);
+        collection.mappings.insert(harness_name, stub_pairs);
+    }
+    collection.ignored = ignored_attributes(tcx);
+    // A harness sees the imports of its own body and of its module.
+    for (harness_name, local_def_id) in harnesses {
+        let scopes = [local_def_id, tcx.local_parent(local_def_id)];
+        for (span, path) in scopes.iter().flat_map(|scope| mock_imports.get(scope)).flatten() {
+            collection.mock_imports.push((harness_name.clone(), *span, path.clone()));
+        }
+    }
+    collection.mock_imports.sort_by(|(harness1, ..), (harness2, ..)| harness1.cmp(harness2));
+    collection
+}
+
+/// Records an error for every stub of a harness that is also the original of
+/// another stub of the same harness (e.g., `a -> b` and `b -> c`), so users
+/// state which replacement they mean instead of relying on the body of `b`
+/// being stubbed in turn. The error points at the definition of the stub.
+pub fn check_chained_stubs(tcx: TyCtxt, collection: &mut StubCollection) {
+    let mut harnesses: Vec<_> = collection.mappings.iter().collect();
+    harnesses.sort_by(|(harness1, _), (harness2, _)| harness1.cmp(harness2));
+    for (harness, stubs) in harnesses {
+        let to_def_id = |hash: &DefPathHash| tcx.def_path_hash_to_def_id(*hash, &mut || panic!());
+        let mut chains: Vec<_> = stubs
+            .iter()
+            .filter_map(|(original, stub)| {
+                let next = stubs.get(stub)?;
+                Some((to_def_id(original), to_def_id(stub), to_def_id(next)))
+            })
+            .map(|(original, stub, next)| {
+                let path = |def_id| qualified_path_str(tcx, def_id);
+                (path(original), path(stub), path(next), tcx.def_span(stub))
+            })
+            .collect();
+        chains.sort();
+        for (original, stub, next, span) in chains {
+            let kind = StubErrorKind::ChainedStub { original, stub, next };
+            collection.errors.push(StubError { harness: harness.clone(), span, kind });
+        }
+    }
+}
+
+/// Returns the paths of the local items that have stubbing attributes without
+/// being harnesses, with the number of such attributes each, sorted by path.
+fn ignored_attributes(tcx: TyCtxt) -> Vec<(String, usize)> {
+    let mut ignored: Vec<_> = tcx
+        .hir_crate_items(())
+        .definitions()
+        .filter_map(|local_def_id| {
             let def_id = local_def_id.to_def_id();
             let (proof, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(def_id));
-            // Ignore anything that is not a harness
-            if proof.is_empty() {
-                return None;
-            }
-            let mut stub_pairs = FxHashMap::default();
-            for (name, attr) in other {
-                if name == "stub" {
-                    update_stub_mapping(tcx, local_def_id, attr, &mut stub_pairs);
+            let count =
+                other.iter().filter(|(name, _)| STUB_ATTRIBUTES.contains(&name.as_str())).count();
+            (proof.is_empty() && count > 0).then(|| (tcx.def_path_str(def_id), count))
+        })
+        .collect();
+    ignored.sort();
+    ignored
+}
+
+/// Records the errors found in one stubbing attribute of a harness, or in
+/// the stubs of a harness in the verification config.
+struct AttributeErrors<'a> {
+    harness: &'a str,
+    /// The span of the attribute, or `None` for the verification config.
+    span: Option<Span>,
+    errors: &'a mut Vec<StubError>,
+}
+
+impl<'a> AttributeErrors<'a> {
+    fn new(harness: &'a str, attr: &Attribute, errors: &'a mut Vec<StubError>) -> Self {
+        AttributeErrors { harness, span: Some(attr.span), errors }
+    }
+
+    fn for_config(harness: &'a str, errors: &'a mut Vec<StubError>) -> Self {
+        AttributeErrors { harness, span: None, errors }
+    }
+
+    fn push(&mut self, kind: StubErrorKind) {
+        let (span, kind) = match self.span {
+            Some(span) => (span, kind),
+            None => (
+                DUMMY_SP,
+                StubErrorKind::InConfig {
+                    harness: self.harness.to_string(),
+                    error: Box::new(kind),
+                },
+            ),
+        };
+        self.errors.push(StubError { harness: self.harness.to_string(), span, kind });
+    }
+}
+
+/// Adds the stubs of the verification config to the mappings of the harnesses
+/// they are listed for. The paths are resolved relative to the crate root, and
+/// the pairs are checked like the ones of `kani::stub` attributes.
+///
+/// If the config and the attributes of a harness map the same function/method
+/// to different stubs, the stub from the source that comes first in
+/// `precedence` is used and the conflict is recorded. Two entries of the
+/// config for the same function/method are still an error.
+pub fn add_config_stubs(
+    tcx: TyCtxt,
+    collection: &mut StubCollection,
+    config: &VerificationConfig,
+    precedence: &[StubSource],
+) {
+    let rank = |source| precedence.iter().position(|other| *other == source);
+    let config_wins = rank(StubSource::Config).unwrap_or(usize::MAX)
+        < rank(StubSource::Attribute).unwrap_or(usize::MAX);
+    let (winner, loser) = if config_wins {
+        (StubSource::Config, StubSource::Attribute)
+    } else {
+        (StubSource::Attribute, StubSource::Config)
+    };
+    let path = |hash: DefPathHash| {
+        qualified_path_str(tcx, tcx.def_path_hash_to_def_id(hash, &mut || panic!()))
+    };
+    for (harness, stub_pairs) in collection.mappings.iter_mut() {
+        let Some(harness_config) = config.harness(harness) else { continue };
+        *collection.requested.entry(harness.clone()).or_default() += harness_config.stubs.len();
+        let mut errors = AttributeErrors::for_config(harness, &mut collection.errors);
+        let mut config_pairs = FxHashMap::default();
+        for (original, replacements) in &harness_config.stubs {
+            let orig_id = resolve_config_path(tcx, original, &mut errors);
+            let stub_id = resolve_config_replacement(tcx, original, replacements, &mut errors);
+            if let (Some(orig_id), Some(stub_id)) = (orig_id, stub_id) {
+                insert_stub_pair(tcx, orig_id, stub_id, &mut config_pairs, &mut errors);
+            }
+        }
+        for (orig_hash, config_hash) in config_pairs {
+            match stub_pairs.get(&orig_hash).copied() {
+                Some(attr_hash) if attr_hash != config_hash => {
+                    let (winner_hash, loser_hash) = if config_wins {
+                        (config_hash, attr_hash)
+                    } else {
+                        (attr_hash, config_hash)
+                    };
+                    collection.source_conflicts.push(SourceConflict {
+                        harness: harness.clone(),
+                        original: path(orig_hash),
+                        winner: (winner, path(winner_hash)),
+                        loser: (loser, path(loser_hash)),
+                    });
+                    if config_wins {
+                        stub_pairs.insert(orig_hash, config_hash);
+                        if let Some(specs) = collection.spec_mappings.get_mut(harness) {
+                            specs.remove(&orig_hash);
+                        }
+                    }
+                }
+                _ => {
+                    stub_pairs.insert(orig_hash, config_hash);
                 }
             }
-            let harness_name = tcx.def_path_str(def_id);
-            Some((harness_name, stub_pairs))
-        })
-        .collect()
+        }
+    }
+    collection.source_conflicts.sort_by(|conflict1, conflict2| {
+        (&conflict1.harness, &conflict1.original).cmp(&(&conflict2.harness, &conflict2.original))
+    });
+}
+
+/// Resolves a path of the verification config relative to the crate root.
+/// Records an error if it does not resolve.
+fn resolve_config_path(tcx: TyCtxt, path: &str, errors: &mut AttributeErrors) -> Option<DefId> {
+    let maybe_resolved = resolve_path(tcx, CRATE_DEF_ID, path);
+    if maybe_resolved.is_none() {
+        errors.push(unresolved_error(tcx, CRATE_DEF_ID, path));
+    }
+    maybe_resolved
+}
+
+/// Resolves the replacement of a stub of the verification config, which is
+/// the first of its paths that resolves. Records an error for each path if
+/// none of them resolves.
+fn resolve_config_replacement(
+    tcx: TyCtxt,
+    original: &str,
+    replacements: &[String],
+    errors: &mut AttributeErrors,
+) -> Option<DefId> {
+    let resolved = replacements
+        .iter()
+        .enumerate()
+        .find_map(|(idx, path)| Some((idx, path, resolve_path(tcx, CRATE_DEF_ID, path)?)));
+    match resolved {
+        Some((idx, path, stub_id)) => {
+            if idx > 0 {
+                tracing::info!(
+                    "Stubbing `{original}` with `{path}` since {} did not resolve",
+                    replacements[..idx]
+                        .iter()
+                        .map(|path| format!("`{path}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            Some(stub_id)
+        }
+        None => {
+            for path in replacements {
+                errors.push(unresolved_error(tcx, CRATE_DEF_ID, path));
+            }
+            None
+        }
+    }
 }
 
 /// Given a `kani::stub` attribute, tries to extract a pair of paths (the
-/// original function/method, and its stub). Returns `None` and errors if the
-/// attribute's arguments are not two paths.
+/// original function/method, and its stub). Returns `None` and records an
+/// error if the attribute's arguments are not two paths or cannot be resolved.
+///
+/// Instead of a path, the original function/method may be given by its symbol
+/// name as a string with the prefix [SYMBOL_PREFIX] (e.g.,
+/// `stub("symbol:ffi_read", mock_read)`). This is meant for the functions that
+/// are best known by their linkage name, such as the `#[no_mangle]` functions
+/// exposed through FFI; prefer paths everywhere else.
+///
+/// The original function/method may also be given by a source location within
+/// its definition, as a string with the prefix [LOCATION_PREFIX] (e.g.,
+/// `stub("location:src/generated.rs:12:5", mock_parse)`). This is a fallback for
+/// generated code whose paths are not stable.
+///
+/// A qualified path, which is not syntactically a path argument, may also be
+/// given as a string (e.g., `stub("<Vec<u8> as Clone>::clone", mock_clone)`).
 fn extract_stubbing_pair(
     tcx: TyCtxt,
     harness: LocalDefId,
     attr: &Attribute,
+    errors: &mut AttributeErrors,
 ) -> Option<(DefId, DefId)> {
     // Extract the attribute arguments
     let args = extract_path_arguments(attr);
     if args.len() != 2 {
-        tcx.sess.span_err(
-            attr.span,
-            format!("Attribute `kani::stub` takes two path arguments; found {}", args.len()),
-        );
+        errors.push(StubErrorKind::WrongArgumentCount(args.len()));
         return None;
     }
-    if args.iter().any(|arg| arg.is_none()) {
-        tcx.sess.span_err(
-            attr.span,
-            "Attribute `kani::stub` takes two path arguments; \
-                found argument that is not a path",
-        );
+    let string = extract_string_arguments(attr).swap_remove(0);
+    let symbol =
+        string.as_deref().and_then(|arg| arg.strip_prefix(SYMBOL_PREFIX)).map(str::to_string);
+    let location =
+        string.as_deref().and_then(|arg| arg.strip_prefix(LOCATION_PREFIX)).map(str::to_string);
+    let original = args[0].clone().or(string.filter(|arg| arg.trim_start().starts_with('<')));
+    if args[1].is_none() || (original.is_none() && symbol.is_none() && location.is_none()) {
+        errors.push(StubErrorKind::NonPathArgument);
         return None;
     }
 
     // Resolve the attribute arguments to `DefId`s
+    let orig = match (symbol, location) {
+        (Some(symbol), _) => {
+            let maybe_resolved = resolve_symbol(tcx, &symbol);
+            if maybe_resolved.is_none() {
+                errors.push(StubErrorKind::UnresolvedSymbol { symbol });
+            }
+            maybe_resolved
+        }
+        (None, Some(location)) => resolve_source_location(tcx, location, errors),
+        (None, None) => resolve_function(tcx, harness, original.as_deref().unwrap(), errors),
+    };
+    let stub = resolve_function(tcx, harness, args[1].as_deref().unwrap(), errors);
+    Some((orig?, stub?))
+}
+
+/// Resolves a source location of the form `file:line:col` to the function/method
+/// whose definition contains it. Records an error if the location is malformed
+/// or if it does not fall within a function/method.
+fn resolve_source_location(
+    tcx: TyCtxt,
+    location: String,
+    errors: &mut AttributeErrors,
+) -> Option<DefId> {
+    let mut parts = location.rsplitn(3, ':');
+    let (Some(col), Some(line), Some(file)) = (parts.next(), parts.next(), parts.next()) else {
+        errors.push(StubErrorKind::InvalidLocation { location });
+        return None;
+    };
+    let (Ok(line), Ok(col)) = (line.trim().parse(), col.trim().parse()) else {
+        errors.push(StubErrorKind::InvalidLocation { location });
+        return None;
+    };
+    let maybe_resolved = resolve_location(tcx, file.trim(), line, col);
+    if maybe_resolved.is_none() {
+        errors.push(StubErrorKind::UnresolvedLocation { location });
+    }
+    maybe_resolved
+}
+
+/// Resolves the path `name` relative to the module of the harness. Records an
+/// error if the path cannot be resolved.
+fn resolve_function(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    name: &str,
+    errors: &mut AttributeErrors,
+) -> Option<DefId> {
     let current_module = tcx.parent_module_from_def_id(harness);
-    let resolve = |name: &str| -> Option<DefId> {
-        let maybe_resolved = resolve_path(tcx, current_module, name);
-        if let Some(def_id) = maybe_resolved {
-            tracing::debug!(?def_id, "Resolved {name} to {}", tcx.def_path_str(def_id));
-        } else {
-            tcx.sess.span_err(attr.span, format!("unable to resolve function/method: {name}"));
+    let maybe_resolved = resolve_path(tcx, current_module, name);
+    if let Some(def_id) = maybe_resolved {
+        tracing::debug!(?def_id, "Resolved {name} to {}", qualified_path_str(tcx, def_id));
+    } else {
+        errors.push(unresolved_error(tcx, current_module, name));
+    }
+    maybe_resolved
+}
+
+/// Returns the error for a path that does not resolve in the given module,
+/// with a hint if the path only differs from the path of one of the local
+/// functions and methods in the case of some segments, e.g., `Reader::Read`
+/// for `Reader::read`.
+fn unresolved_error(tcx: TyCtxt, current_module: LocalDefId, path: &str) -> StubErrorKind {
+    unresolved_error_among(tcx, current_module, path, &local_functions(tcx))
+}
+
+/// Same as [unresolved_error], with the local functions and methods (see
+/// [local_functions]) computed by the caller. Without a hint, the error lists
+/// the candidates closest to the path, up to the limit set by
+/// [set_error_candidates].
+fn unresolved_error_among(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    path: &str,
+    candidates: &[(String, DefId)],
+) -> StubErrorKind {
+    if let Some(suggestion) = case_mismatch_hint(tcx, current_module, path, candidates) {
+        return StubErrorKind::UnresolvedCase { path: path.to_string(), suggestion };
+    }
+    let limit = ERROR_CANDIDATES.load(Ordering::Relaxed);
+    let paths = candidates.iter().map(|(candidate, _)| candidate.as_str());
+    match nearest_paths(path, paths, limit) {
+        nearest if nearest.is_empty() => StubErrorKind::Unresolved { path: path.to_string() },
+        nearest => StubErrorKind::UnresolvedNear { path: path.to_string(), nearest },
+    }
+}
+
+/// Returns at most `limit` of the candidate paths whose last segment is
+/// closest to the last segment of the path, nearest first. Only the candidates
+/// within the edit distance that rustc accepts for its own suggestions (a
+/// third of the length of the name) are considered, so a long list of
+/// unrelated paths is never returned.
+fn nearest_paths<'a>(
+    path: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let last_segment = |path: &'a str| path.rsplit("::").next().unwrap_or(path);
+    let name = path.rsplit("::").next().unwrap_or(path);
+    let max_distance = name.len().max(3) / 3;
+    let mut nearest: Vec<_> = candidates
+        .filter_map(|candidate| {
+            let distance = lev_distance(name, last_segment(candidate), max_distance)?;
+            // Among the candidates with the same name, prefer the closest modules.
+            let path_distance = lev_distance(path, candidate, usize::MAX)?;
+            Some((distance, path_distance, candidate))
+        })
+        .collect();
+    nearest.sort();
+    nearest.into_iter().take(limit).map(|(.., candidate)| candidate.to_string()).collect()
+}
+
+/// The result of resolving one candidate pair of a stub annotation with
+/// [resolve_stub_pairs].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubPairResolution {
+    /// The path of the original function/method, as provided.
+    pub original: String,
+    /// The path of the stub, as provided.
+    pub replacement: String,
+    /// The original function/method and the stub, if both paths resolve.
+    pub resolved: Option<(DefId, DefId)>,
+    /// The errors of the paths that do not resolve, in the order of the pair.
+    pub errors: Vec<StubErrorKind>,
+}
+
+impl StubPairResolution {
+    /// Whether both paths of the pair resolve.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved.is_some()
+    }
+}
+
+/// Resolves a batch of candidate `(original, replacement)` pairs relative to
+/// the given module, the way the paths of a `kani::stub` attribute of a
+/// harness in that module are resolved, and returns one result per pair in the
+/// same order. Apart from the ones reported by the path resolution (e.g., when
+/// it goes through too many modules or for ambiguous qualified paths), no error
+/// is emitted. This is meant for tools that validate many potential stubs at
+/// once: the local functions and methods used for the hints of the unresolved
+/// paths are only collected once for the whole batch, but each path is still
+/// resolved on its own.
+pub fn resolve_stub_pairs(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    pairs: &[(String, String)],
+) -> Vec<StubPairResolution> {
+    let mut candidates = None;
+    let mut resolve = |path: &str, errors: &mut Vec<StubErrorKind>| {
+        let maybe_resolved = resolve_path(tcx, current_module, path);
+        if maybe_resolved.is_none() {
+            let candidates = candidates.get_or_insert_with(|| local_functions(tcx));
+            errors.push(unresolved_error_among(tcx, current_module, path, candidates));
         }
         maybe_resolved
     };
-    let orig = resolve(args[0].as_deref().unwrap());
-    let stub = resolve(args[1].as_deref().unwrap());
-    Some((orig?, stub?))
+    pairs
+        .iter()
+        .map(|(original, replacement)| {
+            let mut errors = vec![];
+            let orig_id = resolve(original, &mut errors);
+            let stub_id = resolve(replacement, &mut errors);
+            StubPairResolution {
+                original: original.clone(),
+                replacement: replacement.clone(),
+                resolved: orig_id.zip(stub_id),
+                errors,
+            }
+        })
+        .collect()
+}
+
+/// Returns the path with the case of its segments fixed, if it then resolves
+/// to one of the candidates. The last segments of the path are compared with
+/// the last segments of each candidate path, ignoring the case, so a path
+/// written relative to another module (e.g., `super::Reader::Read`) keeps its
+/// prefix. The candidates are sorted, so the first fix that resolves wins.
+fn case_mismatch_hint(
+    tcx: TyCtxt,
+    current_module: LocalDefId,
+    path: &str,
+    candidates: &[(String, DefId)],
+) -> Option<String> {
+    let segments: Vec<_> = path.split("::").collect();
+    candidates.iter().find_map(|(candidate, def_id)| {
+        let candidate_segments: Vec<_> = candidate.split("::").collect();
+        let len = segments.len().min(candidate_segments.len());
+        let (prefix, suffix) = segments.split_at(segments.len() - len);
+        let candidate_suffix = &candidate_segments[candidate_segments.len() - len..];
+        let same_case = suffix.iter().zip(candidate_suffix).all(|(a, b)| a == b);
+        let same_ignoring_case =
+            suffix.iter().zip(candidate_suffix).all(|(a, b)| a.eq_ignore_ascii_case(b));
+        if same_case || !same_ignoring_case {
+            return None;
+        }
+        let suggestion = prefix.iter().chain(candidate_suffix).copied().collect::<Vec<_>>();
+        let suggestion = suggestion.join("::");
+        (resolve_path(tcx, current_module, &suggestion) == Some(*def_id)).then_some(suggestion)
+    })
 }
 
 /// Updates the running map `stub_pairs` that maps a function/method to its
-/// stub. Errors if a function/method is mapped more than once.
+/// stub. Records an error if a function/method is mapped more than once.
 fn update_stub_mapping(
     tcx: TyCtxt,
     harness: LocalDefId,
     attr: &Attribute,
     stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    errors: &mut AttributeErrors,
 ) {
-    if let Some((orig_id, stub_id)) = extract_stubbing_pair(tcx, harness, attr) {
-        let orig_hash = tcx.def_path_hash(orig_id);
-        let stub_hash = tcx.def_path_hash(stub_id);
-        let other_opt = stub_pairs.insert(orig_hash, stub_hash);
-        if let Some(other) = other_opt {
-            if other != stub_hash {
-                tcx.sess.span_err(
-                    attr.span,
-                    format!(
-                        "duplicate stub mapping: {} mapped to {} and {}",
-                        tcx.def_path_str(orig_id),
-                        tcx.def_path_str(stub_id),
-                        tcx.def_path_str(tcx.def_path_hash_to_def_id(other, &mut || panic!()))
-                    ),
-                );
+    if let Some((orig_id, stub_id)) = extract_stubbing_pair(tcx, harness, attr, errors) {
+        insert_stub_pair(tcx, orig_id, stub_id, stub_pairs, errors);
+    }
+}
+
+/// Adds the pair to the running map `stub_pairs`. Records an error if the
+/// function/method is already mapped to a different stub.
+fn insert_stub_pair(
+    tcx: TyCtxt,
+    orig_id: DefId,
+    stub_id: DefId,
+    stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    errors: &mut AttributeErrors,
+) {
+    if is_constructor(tcx, orig_id, errors) || is_constructor(tcx, stub_id, errors) {
+        return;
+    }
+    if is_no_stub(tcx, orig_id) {
+        errors.push(StubErrorKind::NoStub { original: qualified_path_str(tcx, orig_id) });
+        return;
+    }
+    let orig_hash = tcx.def_path_hash(orig_id);
+    let stub_hash = tcx.def_path_hash(stub_id);
+    let other_opt = stub_pairs.insert(orig_hash, stub_hash);
+    if let Some(other) = other_opt {
+        if other != stub_hash {
+            errors.push(StubErrorKind::DuplicateMapping {
+                original: qualified_path_str(tcx, orig_id),
+                stub: qualified_path_str(tcx, stub_id),
+                other: qualified_path_str(
+                    tcx,
+                    tcx.def_path_hash_to_def_id(other, &mut || panic!()),
+                ),
+            });
+        }
+    }
+}
+
+/// Given a `kani::stub_cfg` attribute, maps the original function/method to the
+/// stub of the one alternative whose cfg predicate holds in the current
+/// compilation (e.g., `stub_cfg(read, cfg(feature = "fast_mock", fast_read))`
+/// maps `read` to `fast_read` if the `fast_mock` feature is enabled). Records
+/// an error if the attribute arguments are malformed, or if not exactly one
+/// alternative is active.
+fn update_stub_mapping_cfg(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    attr: &Attribute,
+    stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    errors: &mut AttributeErrors,
+) {
+    let Some((original, alternatives)) = extract_cfg_alternatives(attr) else {
+        errors.push(StubErrorKind::InvalidCfgArguments);
+        return;
+    };
+    let active: Vec<_> = alternatives
+        .into_iter()
+        .filter(|(predicate, _)| {
+            rustc_attr::cfg_matches(
+                predicate,
+                &tcx.sess.parse_sess,
+                CRATE_NODE_ID,
+                Some(tcx.features()),
+            )
+        })
+        .map(|(_, stub)| stub)
+        .collect();
+    match active.as_slice() {
+        [stub] => {
+            tracing::debug!("Selected stub {stub} for {original}");
+            let orig_id = resolve_function(tcx, harness, &original, errors);
+            let stub_id = resolve_function(tcx, harness, stub, errors);
+            if let (Some(orig_id), Some(stub_id)) = (orig_id, stub_id) {
+                insert_stub_pair(tcx, orig_id, stub_id, stub_pairs, errors);
             }
         }
+        [] => errors.push(StubErrorKind::NoActiveCfgAlternative { original }),
+        _ => errors.push(StubErrorKind::MultipleActiveCfgAlternatives { original, stubs: active }),
+    }
+}
+
+/// Given a `kani::stub_default` attribute, maps the original function/method to
+/// the `kani::default_stub` marker, so its body is replaced by one that returns
+/// `Default::default()`. Records an error if the attribute does not have one
+/// path argument, or if the return type of the function/method does not
+/// implement `Default`.
+fn update_stub_mapping_default(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    attr: &Attribute,
+    stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    errors: &mut AttributeErrors,
+) {
+    let [Some(original)] = &extract_path_arguments(attr)[..] else {
+        errors.push(StubErrorKind::InvalidDefaultArguments);
+        return;
+    };
+    let Some(orig_id) = resolve_function(tcx, harness, original, errors) else { return };
+    let Some(stub_id) = default_stub(tcx) else {
+        errors.push(StubErrorKind::Unresolved { path: "kani::default_stub".to_string() });
+        return;
+    };
+    if let Err(ty) = check_returns_default(tcx, orig_id) {
+        errors.push(StubErrorKind::ReturnTypeNotDefault {
+            original: qualified_path_str(tcx, orig_id),
+            ty: ty.to_string(),
+        });
+        return;
+    }
+    insert_stub_pair(tcx, orig_id, stub_id, stub_pairs, errors);
+}
+
+/// Given a `kani::stub_spec` attribute, maps the original function/method to
+/// the `kani::spec_stub` marker and records its specification, so its body is
+/// replaced by one synthesized from the specification. Records an error if the
+/// attribute does not have a path and a string argument, or if the
+/// specification cannot be parsed or does not match the signature of the
+/// function/method. The predicate of a `returns where` specification is
+/// resolved like the original and recorded by its path from the crate root,
+/// so it resolves the same way when the stubs are applied.
+fn update_stub_mapping_spec(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    attr: &Attribute,
+    stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    spec_stubs: &mut SpecStubs,
+    errors: &mut AttributeErrors,
+) {
+    let paths = extract_path_arguments(attr);
+    let strings = extract_string_arguments(attr);
+    let ([Some(original), None], [None, Some(spec)]) = (&paths[..], &strings[..]) else {
+        errors.push(StubErrorKind::InvalidSpecArguments);
+        return;
+    };
+    let Some(orig_id) = resolve_function(tcx, harness, original, errors) else { return };
+    let Some(stub_id) = spec_stub(tcx) else {
+        errors.push(StubErrorKind::Unresolved { path: "kani::spec_stub".to_string() });
+        return;
+    };
+    let sig = tcx.fn_sig(orig_id);
+    let ret_ty = tcx.erase_late_bound_regions(sig.output());
+    let parsed = spec.parse::<StubSpec>().and_then(|parsed| {
+        parsed.check_return_type(tcx, orig_id, ret_ty)?;
+        let inputs = tcx.erase_late_bound_regions(sig.inputs());
+        parsed.check_inputs(tcx, inputs)?;
+        let current_module = tcx.parent_module_from_def_id(harness);
+        let resolve = |predicate: &str, kind: &str| {
+            let Some(predicate_id) = resolve_path(tcx, current_module, predicate) else {
+                return Err(format!("unable to resolve {kind} `{predicate}`"));
+            };
+            let path = canonical_path_str(tcx, predicate_id);
+            let path = if predicate_id.is_local() { format!("crate::{path}") } else { path };
+            Ok((predicate_id, path))
+        };
+        match &parsed {
+            StubSpec::ReturnsWhere { predicate } => {
+                let (predicate_id, predicate) = resolve(predicate, "predicate")?;
+                check_predicate(tcx, predicate_id, ret_ty)?;
+                Ok(StubSpec::ReturnsWhere { predicate })
+            }
+            StubSpec::Wrap { requires, ensures } => {
+                let requires = match requires {
+                    Some(requires) => {
+                        let (requires_id, requires) = resolve(requires, "precondition")?;
+                        check_precondition(tcx, requires_id, inputs)?;
+                        Some(requires)
+                    }
+                    None => None,
+                };
+                let ensures = match ensures {
+                    Some(ensures) => {
+                        let (ensures_id, ensures) = resolve(ensures, "postcondition")?;
+                        check_predicate(tcx, ensures_id, ret_ty)?;
+                        Some(ensures)
+                    }
+                    None => None,
+                };
+                Ok(StubSpec::Wrap { requires, ensures })
+            }
+            _ => Ok(parsed),
+        }
+    });
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(reason) => {
+            errors.push(StubErrorKind::InvalidSpec {
+                original: qualified_path_str(tcx, orig_id),
+                spec: spec.clone(),
+                reason,
+            });
+            return;
+        }
+    };
+    insert_stub_pair(tcx, orig_id, stub_id, stub_pairs, errors);
+    if let Some(other) = spec_stubs.insert(tcx.def_path_hash(orig_id), parsed.clone()) {
+        if other != parsed {
+            errors.push(StubErrorKind::DuplicateMapping {
+                original: qualified_path_str(tcx, orig_id),
+                stub: format!("`{parsed}`"),
+                other: format!("`{other}`"),
+            });
+        }
+    }
+}
+
+/// Given a `kani::stub_in` attribute, maps the pair of the caller and the
+/// original function/method to the stub. Records an error if the attribute
+/// does not have three path arguments, if they cannot be resolved, or if the
+/// calls to the original in the caller are already mapped to a different stub.
+fn update_caller_stubs(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    attr: &Attribute,
+    caller_stubs: &mut CallerStubs,
+    errors: &mut AttributeErrors,
+) {
+    let [Some(caller), Some(original), Some(replacement)] = &extract_path_arguments(attr)[..] else {
+        errors.push(StubErrorKind::InvalidCallerArguments);
+        return;
+    };
+    let caller_id = resolve_function(tcx, harness, caller, errors);
+    let orig_id = resolve_function(tcx, harness, original, errors);
+    let stub_id = resolve_function(tcx, harness, replacement, errors);
+    let (Some(caller_id), Some(orig_id), Some(stub_id)) = (caller_id, orig_id, stub_id) else {
+        return;
+    };
+    if [caller_id, orig_id, stub_id].into_iter().any(|def_id| is_constructor(tcx, def_id, errors)) {
+        return;
+    }
+    if is_no_stub(tcx, orig_id) {
+        errors.push(StubErrorKind::NoStub { original: qualified_path_str(tcx, orig_id) });
+        return;
+    }
+    let key = (tcx.def_path_hash(caller_id), tcx.def_path_hash(orig_id));
+    let stub_hash = tcx.def_path_hash(stub_id);
+    if let Some(other) = caller_stubs.insert(key, stub_hash) {
+        if other != stub_hash {
+            errors.push(StubErrorKind::DuplicateMapping {
+                original: format!(
+                    "{} (called from {})",
+                    qualified_path_str(tcx, orig_id),
+                    qualified_path_str(tcx, caller_id)
+                ),
+                stub: qualified_path_str(tcx, stub_id),
+                other: qualified_path_str(
+                    tcx,
+                    tcx.def_path_hash_to_def_id(other, &mut || panic!()),
+                ),
+            });
+        }
+    }
+}
+
+/// Checks whether the path of a stubbing attribute resolved to the constructor
+/// of a tuple struct or enum variant, and records an error if so. Calls to a
+/// constructor are usually compiled to plain aggregates, so replacing its body
+/// would have no effect on most of them.
+fn is_constructor(tcx: TyCtxt, def_id: DefId, errors: &mut AttributeErrors) -> bool {
+    let is_ctor = matches!(tcx.def_kind(def_id), DefKind::Ctor(..));
+    if is_ctor {
+        errors.push(StubErrorKind::Constructor { path: qualified_path_str(tcx, def_id) });
+    }
+    is_ctor
+}
+
+/// Checks whether the function/method is marked `kani::no_stub`, in which case
+/// it must never be replaced by a stub.
+pub fn is_no_stub(tcx: TyCtxt, def_id: DefId) -> bool {
+    let (_, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(def_id));
+    other.iter().any(|(name, _)| name == "no_stub")
+}
+
+/// Checks whether the stub is marked `kani::experimental_stub`, in which case
+/// it is only applied with `--enable-experimental-stubs`.
+pub fn is_experimental_stub(tcx: TyCtxt, def_id: DefId) -> bool {
+    let (_, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(def_id));
+    other.iter().any(|(name, _)| name == "experimental_stub")
+}
+
+/// Checks whether the return type of the function/method implements `Default`
+/// under the function's own where clauses. Returns the return type otherwise.
+fn check_returns_default(tcx: TyCtxt, def_id: DefId) -> Result<(), Ty> {
+    let ret_ty = tcx.erase_late_bound_regions(tcx.fn_sig(def_id).output());
+    let implements_default = tcx.get_diagnostic_item(sym::Default).map_or(false, |default| {
+        let infcx = tcx.infer_ctxt().build();
+        infcx
+            .type_implements_trait(default, [ret_ty], tcx.param_env(def_id))
+            .must_apply_modulo_regions()
+    });
+    if implements_default { Ok(()) } else { Err(ret_ty) }
+}
+
+/// Returns the paths of the functions and methods that may be replaced by a
+/// stub: the local functions and inherent methods that are neither harnesses
+/// nor marked `kani::no_stub` and,
+/// if `include_foreign` is set, the functions and inherent methods that can be
+/// resolved in any other crate. Local paths are relative to the crate root, as
+/// they would be written in a `kani::stub` attribute. The local paths come
+/// first, and both the local and the foreign paths are sorted.
+pub fn stub_candidates(tcx: TyCtxt, include_foreign: bool) -> Vec<String> {
+    let mut candidates: Vec<_> = local_functions(tcx).into_iter().map(|(path, _)| path).collect();
+    if include_foreign {
+        let mut foreign: Vec<_> = tcx
+            .crates(())
+            .iter()
+            .flat_map(|krate| foreign_functions(tcx, *krate))
+            .filter(|def_id| !is_no_stub(tcx, *def_id))
+            .map(|def_id| canonical_path_str(tcx, def_id))
+            .collect();
+        foreign.sort();
+        foreign.dedup();
+        candidates.extend(foreign);
+    }
+    candidates
+}
+
+/// Returns the local functions and inherent methods that a `kani::stub_regex`
+/// pattern may match, together with their paths, sorted by path. Proof
+/// harnesses and functions marked `kani::no_stub` are never candidates.
+fn local_functions(tcx: TyCtxt) -> Vec<(String, DefId)> {
+    let crate_items = tcx.hir_crate_items(());
+    let functions = crate_items
+        .items()
+        .map(|item| item.owner_id.to_def_id())
+        .filter(|def_id| tcx.def_kind(*def_id) == DefKind::Fn);
+    let methods = crate_items.impl_items().map(|item| item.owner_id.to_def_id()).filter(|def_id| {
+        tcx.def_kind(*def_id) == DefKind::AssocFn
+            && tcx.impl_trait_ref(tcx.parent(*def_id)).is_none()
+    });
+    let mut candidates: Vec<_> = functions
+        .chain(methods)
+        .filter(|def_id| {
+            let (proof, other) = partition_kanitool_attributes(tcx.get_attrs_unchecked(*def_id));
+            proof.is_empty() && !other.iter().any(|(name, _)| name == "no_stub")
+        })
+        .map(|def_id| (tcx.def_path_str(def_id), def_id))
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// Given a `kani::stub_regex` attribute, maps every candidate whose path
+/// matches the pattern to the stub at the path obtained by replacing the match
+/// with the replacement (e.g., `"logging::(.*)"` and `"mock_logging::$1"` map
+/// `logging::info` to `mock_logging::info`). Records an error if the attribute
+/// arguments are not two strings, if the pattern is not a valid regular
+/// expression, if the pattern matches more candidates than `limit`, or if a
+/// stub cannot be resolved. A pattern that matches no candidate is not an
+/// error; it is returned for the caller to warn about.
+fn update_stub_mapping_regex(
+    tcx: TyCtxt,
+    harness: LocalDefId,
+    attr: &Attribute,
+    candidates: &[(String, DefId)],
+    limit: usize,
+    stub_pairs: &mut FxHashMap<DefPathHash, DefPathHash>,
+    errors: &mut AttributeErrors,
+) -> Option<String> {
+    let args = extract_string_arguments(attr);
+    let [Some(pattern), Some(replacement)] = args.as_slice() else {
+        errors.push(StubErrorKind::InvalidRegexArguments);
+        return None;
+    };
+    if let Err(err) = Regex::new(pattern) {
+        errors.push(StubErrorKind::InvalidRegex {
+            pattern: pattern.clone(),
+            reason: err.to_string(),
+        });
+        return None;
+    }
+    // The pattern must match the whole path.
+    let regex = Regex::new(&format!("^(?:{pattern})$")).unwrap();
+
+    let matches: Vec<_> = candidates.iter().filter(|(path, _)| regex.is_match(path)).collect();
+    if matches.len() > limit {
+        errors.push(StubErrorKind::RegexLimitExceeded {
+            pattern: pattern.clone(),
+            matches: matches.len(),
+            limit,
+        });
+        return None;
+    }
+    if matches.is_empty() {
+        return Some(pattern.clone());
+    }
+    tracing::info!("Expanded `{pattern}` to {} stub mapping(s)", matches.len());
+
+    let current_module = tcx.parent_module_from_def_id(harness);
+    for (path, orig_id) in matches {
+        let stub_path = regex.replace(path, replacement.as_str());
+        if let Some(stub_id) = resolve_path(tcx, current_module, &stub_path) {
+            tracing::debug!(
+                "Resolved {path} to stub {}, expanded from `{pattern}`",
+                qualified_path_str(tcx, stub_id)
+            );
+            insert_stub_pair(tcx, *orig_id, stub_id, stub_pairs, errors);
+        } else {
+            errors.push(StubErrorKind::UnresolvedRegexStub {
+                stub_path: stub_path.to_string(),
+                original: path.clone(),
+                pattern: pattern.clone(),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_hir::def_id::CRATE_DEF_ID;
+    use rustc_middle::ty::TyCtxt;
+
+    use super::{is_kani_cfg_set, nearest_paths, resolve_stub_pairs, StubErrorKind};
+    use crate::kani_middle::resolve::{qualified_path_str, resolve_exact_path, resolve_path};
+    use crate::kani_middle::stubbing::test_utils::check_crate;
+
+    const SOURCE: &str = r#"
+        pub mod io {
+            pub fn read() -> u32 { 0 }
+        }
+        pub mod mocks {
+            pub fn read() -> u32 { 1 }
+        }
+        pub struct Reader;
+        impl Reader {
+            pub fn read(&self) -> u32 { 0 }
+        }
+        pub use io::read as reexported_read;
+    "#;
+
+    /// Runs the check against [SOURCE], which is compiled as crate
+    /// `annotations_test` with the given extra arguments.
+    fn check(name: &str, extra_args: &[&str], check: impl for<'tcx> FnMut(TyCtxt<'tcx>) + Send) {
+        check_crate(SOURCE, "annotations_test", name, extra_args, check);
+    }
+
+    /// Returns whether the `kani` cfg is set when compiling with the given
+    /// extra arguments, which decides whether the stubs are collected.
+    fn kani_cfg(name: &str, extra_args: &[&str]) -> bool {
+        let mut kani_cfg = None;
+        check(name, extra_args, |tcx| kani_cfg = Some(is_kani_cfg_set(tcx)));
+        kani_cfg.expect("the crate should compile")
+    }
+
+    #[test]
+    fn stubs_require_kani_cfg() {
+        assert!(!kani_cfg("no-cfg", &[]));
+        assert!(!kani_cfg("other-cfg", &["--cfg=kani_mock", "--cfg=feature=\"kani\""]));
+        assert!(kani_cfg("kani-cfg", &["--cfg=kani"]));
+    }
+
+    #[test]
+    fn batch_resolution() {
+        let pairs: Vec<_> = [
+            ("io::read", "mocks::read"),
+            ("io::write", "mocks::read"),
+            ("Reader::Read", "mocks::missing"),
+            ("Reader::read", "io::read"),
+        ]
+        .iter()
+        .map(|(original, replacement)| (original.to_string(), replacement.to_string()))
+        .collect();
+        let mut results = vec![];
+        check("batch", &[], |tcx| {
+            for resolution in resolve_stub_pairs(tcx, CRATE_DEF_ID, &pairs) {
+                let resolved = resolution.resolved.map(|(orig_id, stub_id)| {
+                    (qualified_path_str(tcx, orig_id), qualified_path_str(tcx, stub_id))
+                });
+                let errors: Vec<_> =
+                    resolution.errors.iter().map(|error| error.to_string()).collect();
+                results.push((resolution.original, resolved, errors));
+            }
+        });
+        let resolved = |orig: &str, stub: &str| {
+            Some((format!("annotations_test::{orig}"), format!("annotations_test::{stub}")))
+        };
+        assert_eq!(
+            results,
+            [
+                ("io::read".to_string(), resolved("io::read", "mocks::read"), vec![]),
+                (
+                    "io::write".to_string(),
+                    None,
+                    vec!["unable to resolve function/method: io::write".to_string()]
+                ),
+                (
+                    "Reader::Read".to_string(),
+                    None,
+                    vec![
+                        "unable to resolve function/method: Reader::Read; did you mean \
+                        `Reader::read`? Paths are case-sensitive"
+                            .to_string(),
+                        "unable to resolve function/method: mocks::missing".to_string()
+                    ]
+                ),
+                ("Reader::read".to_string(), resolved("Reader::read", "io::read"), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn exact_paths() {
+        let mut results = vec![];
+        check("exact", &[], |tcx| {
+            let exact = |path| resolve_exact_path(tcx, path).map(|def_id| tcx.def_path_str(def_id));
+            results = vec![
+                exact("io::read"),
+                exact("Reader::read"),
+                exact("annotations_test::io::read"),
+                // The re-export is a `use` statement, which is not followed.
+                exact("reexported_read"),
+            ];
+            let reexported = resolve_path(tcx, CRATE_DEF_ID, "reexported_read");
+            assert_eq!(reexported, resolve_exact_path(tcx, "io::read"));
+        });
+        assert_eq!(
+            results,
+            [Some("io::read".to_string()), Some("Reader::read".to_string()), None, None]
+        );
+    }
+
+    #[test]
+    fn stub_error_messages() {
+        assert_eq!(
+            StubErrorKind::WrongArgumentCount(3).to_string(),
+            "Attribute `kani::stub` takes two path arguments; found 3"
+        );
+        assert_eq!(
+            StubErrorKind::Unresolved { path: "foo::bar".to_string() }.to_string(),
+            "unable to resolve function/method: foo::bar"
+        );
+        assert_eq!(
+            StubErrorKind::UnresolvedCase {
+                path: "Reader::Read".to_string(),
+                suggestion: "Reader::read".to_string()
+            }
+            .to_string(),
+            "unable to resolve function/method: Reader::Read; did you mean `Reader::read`? Paths \
+            are case-sensitive"
+        );
+        assert_eq!(
+            StubErrorKind::RegexLimitExceeded {
+                pattern: "foo::.*".to_string(),
+                matches: 3,
+                limit: 2
+            }
+            .to_string(),
+            "regular expression `foo::.*` matches 3 functions/methods, which exceeds the limit \
+            of 2; use `--stub-regex-limit` to raise it"
+        );
+    }
+
+    #[test]
+    fn nearest_candidates_are_capped() {
+        let candidates =
+            ["io::read", "io::reads", "mocks::read", "net::recv", "Reader::read", "io::write"];
+        let nearest = |path, limit| nearest_paths(path, candidates.iter().copied(), limit);
+        assert_eq!(nearest("io::reed", 2), ["io::read", "mocks::read"]);
+        assert_eq!(nearest("io::reed", 10), ["io::read", "mocks::read", "Reader::read"]);
+        assert!(nearest("io::reed", 0).is_empty());
+        assert!(nearest("serialize", 10).is_empty());
+        assert_eq!(
+            StubErrorKind::UnresolvedNear {
+                path: "io::reed".to_string(),
+                nearest: vec!["io::read".to_string(), "mocks::read".to_string()]
+            }
+            .to_string(),
+            "unable to resolve function/method: io::reed; the closest functions/methods are \
+            `io::read`, `mocks::read`"
+        );
+    }
+
+    #[test]
+    fn stub_error_codes_and_paths() {
+        let unresolved = StubErrorKind::Unresolved { path: "foo::bar".to_string() };
+        assert_eq!(unresolved.code(), "unresolved-path");
+        assert_eq!(unresolved.paths(), vec!["foo::bar"]);
+        let in_config = StubErrorKind::InConfig {
+            harness: "check".to_string(),
+            error: Box::new(StubErrorKind::DuplicateMapping {
+                original: "a".to_string(),
+                stub: "b".to_string(),
+                other: "c".to_string(),
+            }),
+        };
+        assert_eq!(in_config.code(), "duplicate-mapping");
+        assert_eq!(in_config.paths(), vec!["a", "b", "c"]);
+        assert!(StubErrorKind::NonPathArgument.paths().is_empty());
     }
 }
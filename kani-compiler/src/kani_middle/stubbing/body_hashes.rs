@@ -0,0 +1,95 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This file implements the sidecar file of `--stub-body-hashes`, which records
+//! a hash of the MIR body of every stub so that a later run can tell whether
+//! the body of a stub changed since it was reviewed.
+//!
+//! The file is a json object that maps the qualified path of each stub to the
+//! hash of its body. It is meant to be committed together with the stubs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::Path;
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+
+use crate::kani_middle::resolve;
+
+/// A stub whose body no longer matches the hash recorded for it.
+#[derive(Debug)]
+pub struct ChangedStubBody {
+    /// The stub whose body changed.
+    pub stub: DefId,
+    /// The qualified path of the stub, which is the key of its entry.
+    pub path: String,
+    /// The hash recorded in the file.
+    pub recorded: String,
+    /// The hash of the current body.
+    pub current: String,
+}
+
+/// Compare the hash of the body of each stub with the hash recorded in the file
+/// at `path`, and return the stubs whose body changed. The hashes of the stubs
+/// that have no entry yet are added to the file, which is created if needed.
+/// The entries of the stubs that changed are kept as they are, so the warning
+/// is repeated until the entry is removed from the file.
+pub fn check_stub_body_hashes(
+    tcx: TyCtxt,
+    path: &Path,
+    stubs: impl IntoIterator<Item = DefId>,
+) -> Result<Vec<ChangedStubBody>, String> {
+    let mut hashes: BTreeMap<String, String> = match fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data).map_err(|err| err.to_string())?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut changed = vec![];
+    let mut added = false;
+    for stub in stubs {
+        let stub_path = resolve::qualified_path_str(tcx, stub);
+        let current = body_hash(tcx, stub);
+        match hashes.get(&stub_path) {
+            Some(recorded) if *recorded != current => changed.push(ChangedStubBody {
+                stub,
+                path: stub_path,
+                recorded: recorded.clone(),
+                current,
+            }),
+            Some(_) => {}
+            None => {
+                hashes.insert(stub_path, current);
+                added = true;
+            }
+        }
+    }
+    if added {
+        let data = serde_json::to_string_pretty(&hashes).unwrap();
+        fs::write(path, data + "\n").map_err(|err| err.to_string())?;
+    }
+    changed.sort_by(|stub1, stub2| stub1.path.cmp(&stub2.path));
+    Ok(changed)
+}
+
+/// Compute the hash of the optimized MIR of a stub. Spans are left out, so
+/// moving the stub around or editing its comments does not change the hash.
+fn body_hash(tcx: TyCtxt, stub: DefId) -> String {
+    let body = tcx.optimized_mir(stub);
+    let mut hasher = StableHasher::new();
+    body.arg_count.hash(&mut hasher);
+    for decl in body.local_decls.iter() {
+        decl.ty.to_string().hash(&mut hasher);
+    }
+    for block in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            format!("{:?}", statement.kind).hash(&mut hasher);
+        }
+        format!("{:?}", block.terminator().kind).hash(&mut hasher);
+    }
+    let fingerprint: Fingerprint = hasher.finish();
+    fingerprint.to_hex()
+}
@@ -3,7 +3,22 @@
 //! This module contains code for implementing stubbing.
 
 mod annotations;
+mod body_hashes;
+mod spec;
+mod summary;
+#[cfg(test)]
+mod test_utils;
 mod transform;
+mod validate;
 
-pub use annotations::collect_stub_mappings;
+pub use annotations::{
+    add_config_stubs, check_chained_stubs, collect_stub_mappings, is_experimental_stub,
+    is_kani_cfg_set, is_no_stub, resolve_stub_pairs, set_error_candidates, stub_candidates,
+    CallerStubs, SourceConflict, SpecStubs, StubCollection, StubError, StubErrorKind,
+    StubPairResolution, DEFAULT_ERROR_CANDIDATES,
+};
+pub use body_hashes::{check_stub_body_hashes, ChangedStubBody};
+pub use spec::StubSpec;
+pub use summary::{mapping_to_json, mapping_to_text, HarnessStubSummary, StubSummary};
 pub use transform::*;
+pub use validate::{validate_stub_mapping, StubMappingEntry, StubMappingReport, StubStatus};
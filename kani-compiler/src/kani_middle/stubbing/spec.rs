@@ -0,0 +1,526 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This file contains the specification language of `kani::stub_spec`, which
+//! describes the behavior of a stub instead of naming a function that
+//! implements it. The body of the stub is synthesized from the specification
+//! when the stubs are applied.
+//!
+//! Four forms are supported: one constrains the value returned by a function
+//! with an integer return type to a range, e.g., `returns in 0..10`, another
+//! abstracts a function by a contract on its return value, which is given by a
+//! predicate, e.g., `returns where is_valid`, another replaces a function of
+//! one integer or `bool` argument by a lookup table, e.g.,
+//! `table [(0, false), (1, true)]`, and the last one keeps the function but
+//! wraps it with assertions, e.g., `wrap requires is_open ensures is_small`.
+//! The stub assumes the contract instead of running the function
+//! ("assume-guarantee" stubbing); a table is exact, and the stub panics for the
+//! inputs that are not in the table unless a default is given
+//! (`table [(0, false)] else true`). A wrapper runs the original body, so it
+//! instruments the function without abstracting it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rustc_hir::def_id::DefId;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::ty::layout::IntegerExt;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::symbol::Symbol;
+use rustc_target::abi::{Integer, Size};
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
+
+use crate::kani_middle::resolve::qualified_path_str;
+
+/// The behavior of a stub given by a `kani::stub_spec` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StubSpec {
+    /// Returns any value between the bounds (both included). A missing bound
+    /// is the minimum or maximum value of the return type.
+    ReturnsInRange { lower: Option<i128>, upper: Option<i128> },
+    /// Returns any value for which the predicate holds. The predicate is the
+    /// path of a function that takes a reference to the returned value and
+    /// returns a `bool`.
+    ReturnsWhere { predicate: String },
+    /// Returns the output of the row whose input is the argument, or the
+    /// default if no row matches (the stub panics if there is no default).
+    /// The inputs of the rows are distinct.
+    Table { rows: Vec<(TableValue, TableValue)>, default: Option<TableValue> },
+    /// Runs the original body, asserting that the precondition holds for the
+    /// arguments before and that the postcondition holds for the returned
+    /// value after. Each condition is the path of a function that takes
+    /// references to the values it checks and returns a `bool`. At least one
+    /// of them is given.
+    Wrap { requires: Option<String>, ensures: Option<String> },
+}
+
+/// An input or output of the table of a `table` specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableValue {
+    Int(i128),
+    Bool(bool),
+}
+
+impl FromStr for TableValue {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "true" => Ok(TableValue::Bool(true)),
+            "false" => Ok(TableValue::Bool(false)),
+            value => value
+                .replace('_', "")
+                .parse()
+                .map(TableValue::Int)
+                .map_err(|_| format!("expected an integer or `bool` value, found `{value}`")),
+        }
+    }
+}
+
+impl fmt::Display for TableValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableValue::Int(value) => write!(f, "{value}"),
+            TableValue::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl TableValue {
+    /// The bits of the value, as compared by a `SwitchInt` on a value of a
+    /// type of the given size.
+    pub fn bits(&self, size: Size) -> u128 {
+        match self {
+            TableValue::Int(value) => size.truncate(*value as u128),
+            TableValue::Bool(value) => *value as u128,
+        }
+    }
+
+    /// Checks that the value is a value of the given type, and returns the
+    /// reason why it is not otherwise.
+    fn check_type<'tcx>(&self, tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Result<(), String> {
+        let matches = match self {
+            TableValue::Int(value) => {
+                int_range(tcx, ty).map_or(false, |(min, max)| (min..=max).contains(value))
+            }
+            TableValue::Bool(_) => ty.is_bool(),
+        };
+        if matches { Ok(()) } else { Err(format!("`{self}` is not a value of type `{ty}`")) }
+    }
+}
+
+impl FromStr for StubSpec {
+    type Err = String;
+
+    /// Parses a specification of one of the following forms:
+    /// - `returns in <range>`, where the range is written as in Rust (`a..b`,
+    ///   `a..=b`, `a..`, `..b` or `..=b`) with integer literals as bounds;
+    /// - `returns where <path>`, where the path is the predicate on the
+    ///   returned value;
+    /// - `table [(<input>, <output>), ..] [else <output>]`, where the inputs
+    ///   and outputs are integer or `bool` literals (see [parse_table]);
+    /// - `wrap requires <path> ensures <path>`, where either condition may be
+    ///   left out (see [parse_wrap]).
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        if let Some(table) = spec.trim().strip_prefix("table ") {
+            return parse_table(table);
+        }
+        if let Some(conditions) = spec.trim().strip_prefix("wrap ") {
+            return parse_wrap(conditions);
+        }
+        if let Some(predicate) = spec.trim().strip_prefix("returns where ") {
+            let predicate = predicate.trim();
+            if predicate.is_empty() || predicate.contains(char::is_whitespace) {
+                return Err(format!("expected the path of a predicate, found `{predicate}`"));
+            }
+            return Ok(StubSpec::ReturnsWhere { predicate: predicate.to_string() });
+        }
+        let Some(range) = spec.trim().strip_prefix("returns in ") else {
+            return Err("expected a specification of the form `returns in <range>`, \
+                `returns where <predicate>`, `table [(<input>, <output>), ..]` or \
+                `wrap requires <predicate> ensures <predicate>`"
+                .to_string());
+        };
+        let range = range.trim();
+        let (lower, upper, inclusive) = if let Some((lower, upper)) = range.split_once("..=") {
+            (lower, upper, true)
+        } else if let Some((lower, upper)) = range.split_once("..") {
+            (lower, upper, false)
+        } else {
+            return Err(format!("expected a range such as `0..10`, found `{range}`"));
+        };
+        let lower = parse_bound(lower)?;
+        let upper = match (parse_bound(upper)?, inclusive) {
+            (None, true) => {
+                return Err(format!("inclusive range `{range}` must have an upper bound"));
+            }
+            (Some(upper), false) => {
+                Some(upper.checked_sub(1).ok_or_else(|| format!("range `{range}` is empty"))?)
+            }
+            (upper, _) => upper,
+        };
+        if matches!((lower, upper), (Some(lower), Some(upper)) if lower > upper) {
+            return Err(format!("range `{range}` is empty"));
+        }
+        Ok(StubSpec::ReturnsInRange { lower, upper })
+    }
+}
+
+/// Parses the table of a specification of the form `table [(a, b), ..]`,
+/// optionally followed by `else <default>`.
+fn parse_table(table: &str) -> Result<StubSpec, String> {
+    let table = table.trim();
+    let (rows_str, rest) =
+        table.strip_prefix('[').and_then(|table| table.split_once(']')).ok_or_else(|| {
+            format!("expected a table such as `[(0, false), (1, true)]`, found `{table}`")
+        })?;
+    let default = match rest.trim() {
+        "" => None,
+        rest => match rest.strip_prefix("else ") {
+            Some(default) => Some(default.parse()?),
+            None => {
+                return Err(format!("expected `else <default>` after the table, found `{rest}`"));
+            }
+        },
+    };
+    let mut rows: Vec<(TableValue, TableValue)> = vec![];
+    let mut remaining = rows_str.trim();
+    while !remaining.is_empty() {
+        let row_str = remaining.strip_prefix('(').and_then(|row| row.split_once(')'));
+        let Some((row_str, rest)) = row_str else {
+            return Err(format!("expected a row such as `(0, false)`, found `{remaining}`"));
+        };
+        let Some((input, output)) = row_str.split_once(',') else {
+            return Err(format!("expected a row with an input and an output, found `({row_str})`"));
+        };
+        let (input, output) = (input.parse()?, output.parse()?);
+        if rows.iter().any(|(other, _)| *other == input) {
+            return Err(format!("input `{input}` appears in more than one row"));
+        }
+        rows.push((input, output));
+        let rest = rest.trim();
+        remaining = rest.strip_prefix(',').unwrap_or(rest).trim();
+    }
+    if rows.is_empty() {
+        return Err("the table must have at least one row".to_string());
+    }
+    Ok(StubSpec::Table { rows, default })
+}
+
+/// Parses the conditions of a specification of the form
+/// `wrap requires <pre> ensures <post>`, where either condition may be omitted.
+fn parse_wrap(conditions: &str) -> Result<StubSpec, String> {
+    let (mut requires, mut ensures) = (None, None);
+    let mut words = conditions.split_whitespace();
+    while let Some(keyword) = words.next() {
+        let condition = match keyword {
+            "requires" => &mut requires,
+            "ensures" => &mut ensures,
+            _ => return Err(format!("expected `requires` or `ensures`, found `{keyword}`")),
+        };
+        let Some(predicate) = words.next() else {
+            return Err(format!("expected the path of a predicate after `{keyword}`"));
+        };
+        if condition.replace(predicate.to_string()).is_some() {
+            return Err(format!("`{keyword}` appears more than once"));
+        }
+    }
+    if requires.is_none() && ensures.is_none() {
+        return Err("a wrapper must have a `requires` or an `ensures` condition".to_string());
+    }
+    Ok(StubSpec::Wrap { requires, ensures })
+}
+
+/// Parses a bound of a range, which is `None` if it is missing.
+fn parse_bound(bound: &str) -> Result<Option<i128>, String> {
+    let bound = bound.trim();
+    if bound.is_empty() {
+        return Ok(None);
+    }
+    bound
+        .replace('_', "")
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("expected an integer bound, found `{bound}`"))
+}
+
+/// Writes the specification in a form that is parsed back to the same
+/// specification, which is used to pass it to the next run of the compiler.
+impl fmt::Display for StubSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lower, upper) = match self {
+            StubSpec::ReturnsInRange { lower, upper } => (lower, upper),
+            StubSpec::ReturnsWhere { predicate } => return write!(f, "returns where {predicate}"),
+            StubSpec::Table { rows, default } => {
+                let rows: Vec<_> =
+                    rows.iter().map(|(input, output)| format!("({input}, {output})")).collect();
+                write!(f, "table [{}]", rows.join(", "))?;
+                return match default {
+                    Some(default) => write!(f, " else {default}"),
+                    None => Ok(()),
+                };
+            }
+            StubSpec::Wrap { requires, ensures } => {
+                write!(f, "wrap")?;
+                if let Some(requires) = requires {
+                    write!(f, " requires {requires}")?;
+                }
+                if let Some(ensures) = ensures {
+                    write!(f, " ensures {ensures}")?;
+                }
+                return Ok(());
+            }
+        };
+        write!(f, "returns in ")?;
+        if let Some(lower) = lower {
+            write!(f, "{lower}")?;
+        }
+        match upper {
+            Some(upper) => write!(f, "..={upper}"),
+            None => write!(f, ".."),
+        }
+    }
+}
+
+impl StubSpec {
+    /// Checks that a function/method with the given return type can behave as
+    /// specified. For a range, the return type must be an integer type and the
+    /// bounds of the range values of that type; for a predicate, the return
+    /// type must implement `kani::Arbitrary` (the predicate itself is checked
+    /// by [check_predicate]); for a table, the outputs and the default must be
+    /// values of the return type. A wrapper accepts any return type (its
+    /// conditions are checked by [check_predicate] and [check_precondition]).
+    /// Returns the reason why it cannot otherwise.
+    pub fn check_return_type<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        ty: Ty<'tcx>,
+    ) -> Result<(), String> {
+        let (lower, upper) = match self {
+            StubSpec::ReturnsInRange { lower, upper } => (lower, upper),
+            StubSpec::ReturnsWhere { .. } => {
+                let arbitrary = tcx.get_diagnostic_item(Symbol::intern("KaniArbitrary"));
+                let implements_arbitrary = arbitrary.map_or(false, |arbitrary| {
+                    let infcx = tcx.infer_ctxt().build();
+                    infcx
+                        .type_implements_trait(arbitrary, [ty], tcx.param_env(def_id))
+                        .must_apply_modulo_regions()
+                });
+                return if implements_arbitrary {
+                    Ok(())
+                } else {
+                    Err(format!("return type `{ty}` does not implement `kani::Arbitrary`"))
+                };
+            }
+            StubSpec::Table { rows, default } => {
+                let outputs = rows.iter().map(|(_, output)| output).chain(default);
+                return outputs
+                    .map(|output| output.check_type(tcx, ty))
+                    .collect::<Result<_, _>>()
+                    .map_err(|reason| format!("output {reason}"));
+            }
+            StubSpec::Wrap { .. } => return Ok(()),
+        };
+        let Some((min, max)) = int_range(tcx, ty) else {
+            return Err(format!("return type `{ty}` is not an integer type"));
+        };
+        match [lower, upper].into_iter().flatten().find(|bound| !(min..=max).contains(*bound)) {
+            Some(bound) => Err(format!("bound `{bound}` is not a value of return type `{ty}`")),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that a function/method with the given parameter types can be
+    /// replaced as specified. Only a table constrains the parameters: there
+    /// must be exactly one, and the inputs of the table must be values of its
+    /// type. Returns the reason why it cannot otherwise.
+    pub fn check_inputs<'tcx>(&self, tcx: TyCtxt<'tcx>, inputs: &[Ty<'tcx>]) -> Result<(), String> {
+        let StubSpec::Table { rows, .. } = self else { return Ok(()) };
+        let [ty] = inputs else {
+            return Err(format!(
+                "a table only replaces a function/method with one argument, found {}",
+                inputs.len()
+            ));
+        };
+        rows.iter()
+            .map(|(input, _)| input.check_type(tcx, *ty))
+            .collect::<Result<_, _>>()
+            .map_err(|reason| format!("input {reason}"))
+    }
+}
+
+/// The minimum and maximum values of an integer type, or `None` if the type
+/// is not an integer type.
+fn int_range(tcx: TyCtxt, ty: Ty) -> Option<(i128, i128)> {
+    match *ty.kind() {
+        ty::Int(int_ty) => {
+            let size = Integer::from_int_ty(&tcx, int_ty).size();
+            Some((size.signed_int_min(), size.signed_int_max()))
+        }
+        ty::Uint(uint_ty) => {
+            let size = Integer::from_uint_ty(&tcx, uint_ty).size();
+            Some((0, i128::try_from(size.unsigned_int_max()).unwrap_or(i128::MAX)))
+        }
+        _ => None,
+    }
+}
+
+/// Checks that the predicate of a `returns where` specification can be called
+/// on the value returned by a function/method with the given return type,
+/// i.e., that it is a function that is not generic and whose signature is
+/// `fn(&T) -> bool`, where `T` is the return type. Returns the reason why it
+/// cannot otherwise.
+pub fn check_predicate<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    predicate: DefId,
+    ret_ty: Ty<'tcx>,
+) -> Result<(), String> {
+    let sig = tcx.erase_late_bound_regions(tcx.fn_sig(predicate));
+    let expected = tcx.mk_imm_ref(tcx.lifetimes.re_erased, tcx.erase_regions(ret_ty));
+    let matches = tcx.generics_of(predicate).count() == 0
+        && matches!(sig.inputs(), [input] if tcx.erase_regions(*input) == expected)
+        && sig.output().is_bool();
+    if matches {
+        Ok(())
+    } else {
+        Err(format!(
+            "predicate `{}` must be a function with signature `fn({expected}) -> bool`",
+            qualified_path_str(tcx, predicate)
+        ))
+    }
+}
+
+/// Checks that the precondition of a `wrap` specification can be called on
+/// the arguments of a function/method with the given parameter types, i.e.,
+/// that it is a function that is not generic and whose signature is
+/// `fn(&A, &B, ..) -> bool`, where `A`, `B`, .. are the parameter types.
+/// Returns the reason why it cannot otherwise.
+pub fn check_precondition<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    predicate: DefId,
+    inputs: &[Ty<'tcx>],
+) -> Result<(), String> {
+    let sig = tcx.erase_late_bound_regions(tcx.fn_sig(predicate));
+    let expected: Vec<_> = inputs
+        .iter()
+        .map(|input| tcx.mk_imm_ref(tcx.lifetimes.re_erased, tcx.erase_regions(*input)))
+        .collect();
+    let matches = tcx.generics_of(predicate).count() == 0
+        && sig.inputs().len() == expected.len()
+        && sig
+            .inputs()
+            .iter()
+            .zip(&expected)
+            .all(|(input, expected)| tcx.erase_regions(*input) == *expected)
+        && sig.output().is_bool();
+    if matches {
+        Ok(())
+    } else {
+        let expected: Vec<_> = expected.iter().map(|ty| ty.to_string()).collect();
+        Err(format!(
+            "precondition `{}` must be a function with signature `fn({}) -> bool`",
+            qualified_path_str(tcx, predicate),
+            expected.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StubSpec, TableValue};
+
+    fn range(lower: Option<i128>, upper: Option<i128>) -> StubSpec {
+        StubSpec::ReturnsInRange { lower, upper }
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!("returns in 0..10".parse(), Ok(range(Some(0), Some(9))));
+        assert_eq!("returns in -5..=5".parse(), Ok(range(Some(-5), Some(5))));
+        assert_eq!(" returns in 1_000.. ".parse(), Ok(range(Some(1000), None)));
+        assert_eq!("returns in ..=7".parse(), Ok(range(None, Some(7))));
+        assert_eq!("returns in ..".parse(), Ok(range(None, None)));
+    }
+
+    #[test]
+    fn reject_invalid_specs() {
+        assert!("returns 0..10".parse::<StubSpec>().is_err());
+        assert!("returns in 10".parse::<StubSpec>().is_err());
+        assert!("returns in a..b".parse::<StubSpec>().is_err());
+        assert!("returns in 5..5".parse::<StubSpec>().is_err());
+        assert!("returns in 0..=".parse::<StubSpec>().is_err());
+        assert!("returns where".parse::<StubSpec>().is_err());
+        assert!("returns where is even".parse::<StubSpec>().is_err());
+        assert!("table []".parse::<StubSpec>().is_err());
+        assert!("table [(0, 1), (0, 2)]".parse::<StubSpec>().is_err());
+        assert!("table [(0 1)]".parse::<StubSpec>().is_err());
+        assert!("table [(0, x)]".parse::<StubSpec>().is_err());
+        assert!("table [(0, 1)] otherwise 2".parse::<StubSpec>().is_err());
+        assert!("wrap".parse::<StubSpec>().is_err());
+        assert!("wrap requires".parse::<StubSpec>().is_err());
+        assert!("wrap checks is_valid".parse::<StubSpec>().is_err());
+        assert!("wrap ensures a ensures b".parse::<StubSpec>().is_err());
+    }
+
+    #[test]
+    fn parse_wrappers() {
+        let wrap = |requires: Option<&str>, ensures: Option<&str>| StubSpec::Wrap {
+            requires: requires.map(str::to_string),
+            ensures: ensures.map(str::to_string),
+        };
+        assert_eq!(
+            "wrap requires is_open ensures is_small".parse(),
+            Ok(wrap(Some("is_open"), Some("is_small")))
+        );
+        assert_eq!(
+            " wrap  ensures checks::is_small requires is_open".parse(),
+            Ok(wrap(Some("is_open"), Some("checks::is_small")))
+        );
+        assert_eq!("wrap ensures is_small".parse(), Ok(wrap(None, Some("is_small"))));
+    }
+
+    #[test]
+    fn parse_tables() {
+        let (int, bool) = (TableValue::Int, TableValue::Bool);
+        assert_eq!(
+            "table [(0, false), (1, true)]".parse(),
+            Ok(StubSpec::Table {
+                rows: vec![(int(0), bool(false)), (int(1), bool(true))],
+                default: None
+            })
+        );
+        assert_eq!(
+            " table [ (-1, 10) ,(1_000, 20), ] else 0 ".parse(),
+            Ok(StubSpec::Table {
+                rows: vec![(int(-1), int(10)), (int(1000), int(20))],
+                default: Some(int(0))
+            })
+        );
+    }
+
+    #[test]
+    fn parse_predicates() {
+        let predicate = |path: &str| StubSpec::ReturnsWhere { predicate: path.to_string() };
+        assert_eq!("returns where is_even".parse(), Ok(predicate("is_even")));
+        assert_eq!(" returns where  checks::is_valid ".parse(), Ok(predicate("checks::is_valid")));
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        let specs = [
+            "returns in 0..10",
+            "returns in -5..=5",
+            "returns in 3..",
+            "returns in ..",
+            "returns where f",
+            "table [(0, false), (1, true)]",
+            "table [(true, -3)] else 4",
+            "wrap requires p",
+            "wrap requires p ensures crate::q",
+        ];
+        for spec in specs {
+            let parsed: StubSpec = spec.parse().unwrap();
+            assert_eq!(parsed.to_string().parse(), Ok(parsed));
+        }
+    }
+}
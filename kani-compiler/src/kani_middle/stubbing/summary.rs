@@ -0,0 +1,236 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This file contains the summary of the stubs of each harness that is printed
+//! at the end of the compilation if `--stub-summary` is set, as well as the
+//! final stub mapping that is printed if `--print-stub-mapping` is set.
+
+use std::fmt::Write;
+
+use kani_queries::ResolvedStub;
+use rustc_data_structures::fx::FxHashSet;
+
+use crate::kani_middle::stubbing::StubOutcomes;
+
+/// The stubbing facts about one harness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HarnessStubSummary {
+    pub harness: String,
+    /// The number of stubbing attributes of the harness.
+    pub requested: usize,
+    /// The number of pairs in the stub mapping of the harness, including the
+    /// stubs that only apply to the calls made by one caller.
+    pub resolved: usize,
+    /// The number of stubs that replaced the body of their original
+    /// function/method. This is only known for the harness that the crate is
+    /// compiled for.
+    pub applied: Option<usize>,
+}
+
+impl HarnessStubSummary {
+    /// The number of resolved stubs that were not applied, either because their
+    /// original function/method is not reachable or because they were rejected.
+    pub fn skipped(&self) -> Option<usize> {
+        self.applied.map(|applied| self.resolved - applied)
+    }
+}
+
+/// The summary of the stubs of every harness in the crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StubSummary {
+    /// One entry per harness, sorted by the harness name.
+    pub harnesses: Vec<HarnessStubSummary>,
+    /// The stubbing warnings, in the order they were emitted.
+    pub warnings: Vec<String>,
+}
+
+impl StubSummary {
+    /// Records the stubs applied to the harness that the crate is compiled
+    /// for, as well as the warnings emitted while applying them. A stub only
+    /// counts as applied if its original function/method is also reachable.
+    pub fn add_outcomes(&mut self, outcomes: StubOutcomes) {
+        let reachable: FxHashSet<_> = outcomes.reachable.into_iter().collect();
+        let applied: FxHashSet<_> =
+            outcomes.applied.into_iter().filter(|hash| reachable.contains(hash)).collect();
+        let caller_applied: FxHashSet<_> = outcomes
+            .caller_applied
+            .into_iter()
+            .filter(|(caller, _)| reachable.contains(caller))
+            .collect();
+        for harness in self.harnesses.iter_mut().filter(|harness| harness.applied.is_some()) {
+            harness.applied = Some(applied.len() + caller_applied.len());
+        }
+        self.warnings.extend(outcomes.warnings);
+    }
+
+    /// Formats the summary as a table with one row per harness, followed by
+    /// the warnings.
+    pub fn to_text(&self) -> String {
+        const HEADERS: [&str; 5] = ["Harness", "Requested", "Resolved", "Applied", "Skipped"];
+        let count = |count: Option<usize>| count.map_or("-".to_string(), |count| count.to_string());
+        let rows: Vec<[String; 5]> = self
+            .harnesses
+            .iter()
+            .map(|harness| {
+                [
+                    harness.harness.clone(),
+                    harness.requested.to_string(),
+                    harness.resolved.to_string(),
+                    count(harness.applied),
+                    count(harness.skipped()),
+                ]
+            })
+            .collect();
+        let name_width =
+            rows.iter().map(|row| row[0].len()).chain([HEADERS[0].len()]).max().unwrap();
+        let mut text = String::from("Stub summary:\n");
+        let headers = HEADERS.map(String::from);
+        for row in std::iter::once(&headers).chain(&rows) {
+            let mut line = format!("{:name_width$}", row[0]);
+            for (cell, header) in row.iter().zip(HEADERS).skip(1) {
+                write!(line, "  {cell:>width$}", width = header.len()).unwrap();
+            }
+            writeln!(text, "{}", line.trim_end()).unwrap();
+        }
+        if !self.warnings.is_empty() {
+            writeln!(text, "Warnings:").unwrap();
+            for warning in &self.warnings {
+                writeln!(text, "  {warning}").unwrap();
+            }
+        }
+        text
+    }
+
+    /// Formats the summary as a json object.
+    pub fn to_json(&self) -> serde_json::Value {
+        let harnesses: Vec<_> = self
+            .harnesses
+            .iter()
+            .map(|harness| {
+                serde_json::json!({
+                    "harness": harness.harness,
+                    "requested": harness.requested,
+                    "resolved": harness.resolved,
+                    "applied": harness.applied,
+                    "skipped": harness.skipped(),
+                })
+            })
+            .collect();
+        serde_json::json!({ "harnesses": harnesses, "warnings": self.warnings })
+    }
+}
+
+/// Formats the final stub mapping of a harness, i.e., the stubs of all its
+/// attributes and of the verification config once they are merged, with one
+/// `original -> replacement` line per stub.
+pub fn mapping_to_text(harness: &str, stubs: &[ResolvedStub]) -> String {
+    let mut text = format!("Stub mapping of harness `{harness}`:\n");
+    if stubs.is_empty() {
+        writeln!(text, "  (none)").unwrap();
+    }
+    for stub in stubs {
+        write!(text, "  {} -> {}", stub.original, stub.replacement).unwrap();
+        match &stub.caller {
+            Some(caller) => writeln!(text, " (in calls made by {caller})").unwrap(),
+            None => writeln!(text).unwrap(),
+        }
+    }
+    text
+}
+
+/// Formats the final stub mapping of a harness as a json object.
+pub fn mapping_to_json(harness: &str, stubs: &[ResolvedStub]) -> serde_json::Value {
+    serde_json::json!({ "harness": harness, "stubs": stubs })
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_data_structures::fingerprint::Fingerprint;
+    use rustc_hir::definitions::DefPathHash;
+
+    use kani_queries::ResolvedStub;
+
+    use super::{mapping_to_json, mapping_to_text, HarnessStubSummary, StubSummary};
+    use crate::kani_middle::stubbing::StubOutcomes;
+
+    fn summary() -> StubSummary {
+        let harness = |harness: &str, requested, resolved, applied| HarnessStubSummary {
+            harness: harness.to_string(),
+            requested,
+            resolved,
+            applied,
+        };
+        StubSummary {
+            harnesses: vec![harness("check_mixed", 3, 4, Some(0)), harness("other", 1, 1, None)],
+            warnings: vec!["first warning".to_string()],
+        }
+    }
+
+    #[test]
+    fn text_summary() {
+        let mut summary = summary();
+        // The body of a function/method may be requested more than once, and
+        // the stub of an unreachable function/method or caller is not applied.
+        let hash = |id| DefPathHash(Fingerprint::new(id, id));
+        let applied = vec![hash(1), hash(2), hash(1), hash(3)];
+        let warnings = vec!["second warning".to_string()];
+        let caller_applied = vec![(hash(5), hash(6)), (hash(7), hash(6))];
+        let reachable = vec![hash(1), hash(2), hash(4), hash(5)];
+        let trivial = vec![];
+        summary.add_outcomes(StubOutcomes {
+            applied,
+            caller_applied,
+            reachable,
+            warnings,
+            trivial,
+            ..Default::default()
+        });
+        assert_eq!(
+            summary.to_text(),
+            "Stub summary:\n\
+            Harness      Requested  Resolved  Applied  Skipped\n\
+            check_mixed          3         4        3        1\n\
+            other                1         1        -        -\n\
+            Warnings:\n  \
+              first warning\n  \
+              second warning\n"
+        );
+    }
+
+    #[test]
+    fn json_summary() {
+        let summary = summary();
+        let json = summary.to_json();
+        assert_eq!(json["harnesses"][0]["skipped"], 4);
+        assert_eq!(json["harnesses"][1]["harness"], "other");
+        assert!(json["harnesses"][1]["applied"].is_null());
+        assert_eq!(json["warnings"][0], "first warning");
+    }
+
+    #[test]
+    fn merged_mapping() {
+        let stub = |original: &str, replacement: &str, caller: Option<&str>| ResolvedStub {
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+            caller: caller.map(String::from),
+        };
+        let stubs = [
+            stub("app::read", "app::mock_read", None),
+            stub("app::write", "app::mock_write", Some("app::flush")),
+        ];
+        assert_eq!(
+            mapping_to_text("check_io", &stubs),
+            "Stub mapping of harness `check_io`:\n  \
+              app::read -> app::mock_read\n  \
+              app::write -> app::mock_write (in calls made by app::flush)\n"
+        );
+        assert_eq!(
+            mapping_to_text("check_io", &[]),
+            "Stub mapping of harness `check_io`:\n  (none)\n"
+        );
+        let json = mapping_to_json("check_io", &stubs);
+        assert_eq!(json["harness"], "check_io");
+        assert_eq!(json["stubs"][0]["original"], "app::read");
+        assert_eq!(json["stubs"][0]["caller"], serde_json::Value::Null);
+        assert_eq!(json["stubs"][1]["caller"], "app::flush");
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This file contains the fixture shared by the unit tests of the stubbing
+//! module, which compiles a small crate and runs a check on its `TyCtxt`.
+
+use std::fs;
+
+use rustc_driver::{Callbacks, Compilation, RunCompiler};
+use rustc_interface::{interface::Compiler, Queries};
+use rustc_middle::ty::TyCtxt;
+
+/// Runs a check once the analysis of the crate is done.
+struct Checker<F: for<'tcx> FnMut(TyCtxt<'tcx>) + Send> {
+    check: F,
+}
+
+impl<F: for<'tcx> FnMut(TyCtxt<'tcx>) + Send> Callbacks for Checker<F> {
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| (self.check)(tcx));
+        Compilation::Stop
+    }
+}
+
+/// Compiles `source` as the library crate `crate_name` with the given extra
+/// rustc arguments, and runs the check once the analysis of the crate is done.
+/// The `name` of the test keeps the temporary directories of the tests apart.
+pub fn check_crate(
+    source: &str,
+    crate_name: &str,
+    name: &str,
+    extra_args: &[&str],
+    check: impl for<'tcx> FnMut(TyCtxt<'tcx>) + Send,
+) {
+    let dir = std::env::temp_dir().join(format!("kani-{crate_name}-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(format!("{crate_name}.rs"));
+    fs::write(&file, source).unwrap();
+    let mut args: Vec<String> = vec![
+        "rustc".into(),
+        file.display().to_string(),
+        "--crate-type=lib".into(),
+        format!("--crate-name={crate_name}"),
+        "--edition=2021".into(),
+        format!("--out-dir={}", dir.display()),
+    ];
+    args.extend(extra_args.iter().map(|arg| arg.to_string()));
+    // The compilation is stopped after the analysis, which the driver reports as an error.
+    let _ = RunCompiler::new(&args, &mut Checker { check }).run();
+    fs::remove_dir_all(&dir).unwrap();
+}
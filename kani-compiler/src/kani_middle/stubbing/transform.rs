@@ -4,13 +4,164 @@
 //! stubbing of functions and methods. The primary function of the module is
 //! `transform`, which takes the `DefId` of a function/method and returns the
 //! body of its stub, if appropriate. The stub mapping it uses is set via rustc
-//! arguments.
+//! arguments; `StubbingPass` applies a mapping that is given directly instead.
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::kani_middle::advisory_span;
+use crate::kani_middle::resolve::{qualified_path_str, resolve_path};
+use crate::kani_middle::stubbing::spec::{StubSpec, TableValue};
+use crate::kani_middle::stubbing::{is_experimental_stub, is_no_stub, CallerStubs, SpecStubs};
+use kani_queries::{Advisory, AdvisoryPayload, AdvisorySeverity, QueryDb};
 use lazy_static::lazy_static;
 use regex::Regex;
-use rustc_data_structures::{fingerprint::Fingerprint, fx::FxHashMap};
-use rustc_hir::{def_id::DefId, definitions::DefPathHash};
-use rustc_middle::{mir::Body, ty::TyCtxt};
+use rustc_attr::InlineAttr;
+use rustc_data_structures::{
+    fingerprint::Fingerprint,
+    fx::{FxHashMap, FxHashSet},
+};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{DefId, CRATE_DEF_ID},
+    definitions::DefPathHash,
+    AsyncGeneratorKind, GeneratorKind, Unsafety,
+};
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_infer::traits::{Obligation, ObligationCause};
+use rustc_middle::{
+    middle::codegen_fn_attrs::CodegenFnAttrFlags,
+    mir::{
+        interpret::{Allocation, ConstValue, Scalar},
+        mono::MonoItem,
+        BasicBlock, BasicBlockData, BinOp, Body, BorrowKind, Constant, ConstantKind, Local,
+        LocalDecl, MirSource, Mutability, Operand, Place, Rvalue, SourceInfo, Statement,
+        StatementKind, SwitchTargets, Terminator, TerminatorKind, VarDebugInfo,
+        VarDebugInfoContents, OUTERMOST_SOURCE_SCOPE, RETURN_PLACE,
+    },
+    ty::{
+        self, subst::GenericArgKind, AssocKind, Clause, DefIdTree, EarlyBinder, GenericParamDef,
+        GenericParamDefKind, InternalSubsts, Predicate, PredicateKind, SubstsRef, Ty, TyCtxt,
+        TypeFoldable, TypeFolder, TypeSuperFoldable, TypeVisitable,
+    },
+};
+use rustc_span::symbol::{kw, sym, Ident, Symbol};
+use rustc_span::Span;
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
+
+/// Options that control how stubs are applied.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StubbingOptions {
+    /// Only apply stubs that also pass the strict compatibility checks.
+    pub verify_types: bool,
+    /// Keep the signature metadata of the original function/method and only
+    /// take the body from the stub.
+    pub preserve_signature: bool,
+    /// Apply the stubs marked `kani::experimental_stub`, which are skipped
+    /// otherwise.
+    pub experimental_stubs: bool,
+    /// Accept the stubs that take `&T` for a parameter of type `&mut T` (see
+    /// [is_shared_ref_narrowing]).
+    pub allow_shared_refs: bool,
+    /// Record the number of calls to the stubbed functions/methods in each
+    /// body (see [StubOutcomes::caller_counts]).
+    pub count_caller_calls: bool,
+}
+
+/// The MIR providers are plain function pointers, so the options are stored
+/// here once the compiler configuration is known.
+static OPTIONS: Mutex<StubbingOptions> = Mutex::new(StubbingOptions {
+    verify_types: false,
+    preserve_signature: false,
+    experimental_stubs: false,
+    allow_shared_refs: false,
+    count_caller_calls: false,
+});
+
+/// Sets the options used by [transform].
+pub fn set_options(options: StubbingOptions) {
+    *OPTIONS.lock().unwrap() = options;
+}
+
+/// What [transform] did with the stubs of the current harness, which is
+/// reported by `--stub-summary`.
+#[derive(Debug, Default)]
+pub struct StubOutcomes {
+    /// The functions/methods whose body was replaced. This includes the ones
+    /// that are not reachable from the harness.
+    pub applied: Vec<DefPathHash>,
+    /// The pairs of a caller and an original function/method whose calls were
+    /// replaced in the body of the caller.
+    pub caller_applied: Vec<(DefPathHash, DefPathHash)>,
+    /// The stubbed functions/methods, and the callers with stubbed calls, that
+    /// are reachable from the harness.
+    pub reachable: Vec<DefPathHash>,
+    /// The warnings emitted while applying the stubs.
+    pub warnings: Vec<String>,
+    /// The pairs of an original function/method and a stub that looks like it
+    /// does nothing meaningful (see [is_trivial_replacement]), together with
+    /// the number of statements of the original.
+    pub trivial: Vec<(DefPathHash, DefPathHash, usize)>,
+    /// The pairs of an original function/method and its stub, together with
+    /// the sizes of their bodies, which [report_oversized_stubs] compares.
+    pub sizes: Vec<(DefPathHash, DefPathHash, BodySize, BodySize)>,
+    /// The pairs of an original function/method and an experimental stub
+    /// that was skipped because `--enable-experimental-stubs` is not set.
+    pub experimental: Vec<(DefPathHash, DefPathHash)>,
+    /// The pairs of a caller and a stubbed function/method, together with the
+    /// number of calls to the stubbed function/method in the body of the
+    /// caller. This is only recorded if `--emit-stub-caller-counts` is set.
+    pub caller_counts: Vec<(DefPathHash, DefPathHash, usize)>,
+}
+
+impl StubOutcomes {
+    /// Appends the outcomes of another run of a pass.
+    pub fn extend(&mut self, other: StubOutcomes) {
+        self.applied.extend(other.applied);
+        self.caller_applied.extend(other.caller_applied);
+        self.reachable.extend(other.reachable);
+        self.warnings.extend(other.warnings);
+        self.trivial.extend(other.trivial);
+        self.sizes.extend(other.sizes);
+        self.experimental.extend(other.experimental);
+        self.caller_counts.extend(other.caller_counts);
+    }
+}
+
+/// The outcomes are recorded by the MIR provider, so they are stored here
+/// until the compilation is done. Only [transform] records into it.
+static OUTCOMES: Mutex<StubOutcomes> = Mutex::new(StubOutcomes {
+    applied: Vec::new(),
+    caller_applied: Vec::new(),
+    reachable: Vec::new(),
+    warnings: Vec::new(),
+    trivial: Vec::new(),
+    sizes: Vec::new(),
+    experimental: Vec::new(),
+    caller_counts: Vec::new(),
+});
+
+/// Returns the outcomes recorded by [transform] so far, and clears them.
+pub fn take_outcomes() -> StubOutcomes {
+    std::mem::take(&mut *OUTCOMES.lock().unwrap())
+}
+
+/// Records which of the stubbed functions/methods, and of the callers with
+/// stubbed calls, are among the reachable items.
+pub fn record_reachable_stubs<'tcx>(tcx: TyCtxt<'tcx>, items: &[MonoItem<'tcx>]) {
+    let mapping = get_stub_mapping(tcx).unwrap_or_default();
+    let callers: FxHashSet<_> =
+        get_caller_stub_mapping(tcx).into_keys().map(|(caller, _)| caller).collect();
+    let reachable = items.iter().filter_map(|item| match item {
+        MonoItem::Fn(instance)
+            if mapping.contains_key(&instance.def_id()) || callers.contains(&instance.def_id()) =>
+        {
+            Some(tcx.def_path_hash(instance.def_id()))
+        }
+        _ => None,
+    });
+    OUTCOMES.lock().unwrap().reachable.extend(reachable);
+}
 
 /// Returns the `DefId` of the stub for the function/method identified by the
 /// parameter `def_id`, and `None` if the function/method is not stubbed.
@@ -20,61 +171,1385 @@ pub fn get_stub(tcx: TyCtxt, def_id: DefId) -> Option<DefId> {
 }
 
 /// Returns the new body of a function/method if it has been stubbed out;
-/// otherwise, returns the old body.
+/// otherwise, returns the old body. This is the pass used by the MIR provider,
+/// which takes its stubs from the rustc arguments and its options from
+/// [set_options]. What the pass did is recorded for `--stub-summary`.
 pub fn transform<'tcx>(
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
     old_body: &'tcx Body<'tcx>,
 ) -> &'tcx Body<'tcx> {
-    if let Some(replacement) = get_stub(tcx, def_id) {
-        let new_body = tcx.optimized_mir(replacement).clone();
-        if check_compatibility(tcx, def_id, old_body, replacement, &new_body) {
+    let (new_body, outcomes) = StubbingPass::from_config(tcx).run_pass(tcx, def_id, old_body);
+    OUTCOMES.lock().unwrap().extend(outcomes);
+    new_body
+}
+
+/// Applies a stub mapping to the body of the function/method `def_id` in place,
+/// and returns whether the body was replaced. The mapping goes from the path of
+/// each original function/method to the path of its stub, which are resolved
+/// from the root of the local crate. Returns the sorted paths that do not
+/// resolve as an error instead, without changing the body, so a typo in the
+/// mapping is not mistaken for a function/method that is not stubbed.
+/// This runs the same checks as [transform] without going through the rustc
+/// arguments, which is how the replacement is tested; unlike [transform], it
+/// does not record anything for `--stub-summary`.
+pub fn apply_stub_mapping<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &mut Body<'tcx>,
+    mapping: &HashMap<String, String>,
+) -> Result<bool, Vec<String>> {
+    let resolve = |path: &String| resolve_path(tcx, CRATE_DEF_ID, path).ok_or_else(|| path.clone());
+    let mut resolved = FxHashMap::default();
+    let mut unresolved = Vec::new();
+    for (original, stub) in mapping {
+        match (resolve(original), resolve(stub)) {
+            (Ok(orig_id), Ok(stub_id)) => {
+                resolved.insert(orig_id, stub_id);
+            }
+            (orig_id, stub_id) => unresolved.extend(orig_id.err().into_iter().chain(stub_id.err())),
+        }
+    }
+    if !unresolved.is_empty() {
+        unresolved.sort();
+        unresolved.dedup();
+        return Err(unresolved);
+    }
+    let old_body: &'tcx Body<'tcx> = tcx.arena.alloc(body.clone());
+    let (new_body, _) = StubbingPass::with_mapping(resolved).run_pass(tcx, def_id, old_body);
+    if std::ptr::eq(new_body, old_body) {
+        return Ok(false);
+    }
+    *body = new_body.clone();
+    Ok(true)
+}
+
+/// The MIR-to-MIR pass that applies a set of stubs. Unlike [transform], a pass
+/// created with [StubbingPass::with_mapping] only depends on its own stubs and
+/// options, so several passes can be used side by side. What a pass does is
+/// returned by [StubbingPass::run_pass] rather than recorded for
+/// `--stub-summary`, which only [transform] does.
+#[derive(Clone, Debug, Default)]
+pub struct StubbingPass {
+    /// The stub of each original function/method.
+    mapping: FxHashMap<DefId, DefId>,
+    /// The stub of each original function/method that is only replaced in the
+    /// calls made by the given caller.
+    caller_stubs: FxHashMap<(DefId, DefId), DefId>,
+    /// The specification of each original function/method that is stubbed
+    /// with `kani::stub_spec`.
+    specs: FxHashMap<DefId, StubSpec>,
+    options: StubbingOptions,
+}
+
+impl StubbingPass {
+    /// Creates a pass that replaces the body of each original function/method
+    /// of the mapping by the body of its stub, with the default options.
+    pub fn with_mapping(mapping: FxHashMap<DefId, DefId>) -> Self {
+        StubbingPass { mapping, ..Default::default() }
+    }
+
+    /// Sets the stubs that only apply to the calls made by one caller, keyed by
+    /// the pair of the caller and the original function/method.
+    pub fn with_caller_stubs(mut self, caller_stubs: FxHashMap<(DefId, DefId), DefId>) -> Self {
+        self.caller_stubs = caller_stubs;
+        self
+    }
+
+    /// Sets the specifications of the original functions/methods that are
+    /// mapped to the `kani::spec_stub` marker.
+    pub fn with_specs(mut self, specs: FxHashMap<DefId, StubSpec>) -> Self {
+        self.specs = specs;
+        self
+    }
+
+    /// Sets the options that control how the stubs are applied.
+    pub fn with_options(mut self, options: StubbingOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Creates the pass from the compiler configuration.
+    fn from_config(tcx: TyCtxt) -> Self {
+        StubbingPass::with_mapping(get_stub_mapping(tcx).unwrap_or_default())
+            .with_caller_stubs(get_caller_stub_mapping(tcx))
+            .with_specs(get_spec_stub_mapping(tcx))
+            .with_options(*OPTIONS.lock().unwrap())
+    }
+
+    /// Returns the new body of a function/method if it has been stubbed out;
+    /// otherwise, returns the old body. In both cases, the calls that are
+    /// stubbed for this function/method as a caller (see `kani::stub_in`) are
+    /// replaced. A function/method marked `kani::no_stub` is never stubbed,
+    /// even if the mapping was not checked by the stub collection. Also
+    /// returns what the pass did with the stubs, apart from `reachable`, which
+    /// is only known once the items are collected.
+    pub fn run_pass<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        old_body: &'tcx Body<'tcx>,
+    ) -> (&'tcx Body<'tcx>, StubOutcomes) {
+        let mut outcomes = StubOutcomes::default();
+        let body = self.stub_body(tcx, def_id, old_body, &mut outcomes);
+        let caller_stubs: FxHashMap<_, _> = self
+            .caller_stubs
+            .iter()
+            .filter(|((caller, original), _)| *caller == def_id && !is_no_stub(tcx, *original))
+            .map(|((_, original), stub)| (*original, *stub))
+            .collect();
+        if self.options.count_caller_calls {
+            self.count_caller_calls(tcx, def_id, body, &caller_stubs, &mut outcomes);
+        }
+        if caller_stubs.is_empty() {
+            (body, outcomes)
+        } else {
+            let new_body = self.replace_calls(tcx, def_id, body, &caller_stubs, &mut outcomes);
+            (tcx.arena.alloc(new_body), outcomes)
+        }
+    }
+
+    /// Records the number of calls in the body of `caller` to each function/method
+    /// that is stubbed, either everywhere or only in the calls made by `caller`.
+    fn count_caller_calls(
+        &self,
+        tcx: TyCtxt,
+        caller: DefId,
+        body: &Body,
+        caller_stubs: &FxHashMap<DefId, DefId>,
+        outcomes: &mut StubOutcomes,
+    ) {
+        let mut counts: FxHashMap<DefId, usize> = FxHashMap::default();
+        for block in body.basic_blocks.iter() {
+            let TerminatorKind::Call { func, .. } = &block.terminator().kind else { continue };
+            let Some(constant) = func.constant() else { continue };
+            let ty::FnDef(callee, _) = *constant.ty().kind() else { continue };
+            let stubbed = self.mapping.contains_key(&callee) && !is_no_stub(tcx, callee);
+            if stubbed || caller_stubs.contains_key(&callee) {
+                *counts.entry(callee).or_default() += 1;
+            }
+        }
+        let caller_hash = tcx.def_path_hash(caller);
+        let counts = counts
+            .into_iter()
+            .map(|(callee, count)| (caller_hash, tcx.def_path_hash(callee), count));
+        outcomes.caller_counts.extend(counts);
+    }
+
+    /// Returns the body of the stub of a function/method, and the old body if
+    /// it is not stubbed. If `--verify-stub-types` is set, the stub is only
+    /// applied if it also passes the strict compatibility checks.
+    fn stub_body<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        old_body: &'tcx Body<'tcx>,
+        outcomes: &mut StubOutcomes,
+    ) -> &'tcx Body<'tcx> {
+        if let Some(new_body) = self.async_generator_body(tcx, def_id) {
             return tcx.arena.alloc(new_body);
         }
+        if let Some(&replacement) = self.mapping.get(&def_id).filter(|_| !is_no_stub(tcx, def_id)) {
+            if Some(replacement) == default_stub(tcx) {
+                outcomes.applied.push(tcx.def_path_hash(def_id));
+                return tcx.arena.alloc(default_body(tcx, old_body));
+            }
+            if Some(replacement) == spec_stub(tcx) {
+                let Some(spec) = self.specs.get(&def_id) else { return old_body };
+                outcomes.applied.push(tcx.def_path_hash(def_id));
+                return tcx.arena.alloc(spec_body(tcx, old_body, spec));
+            }
+            if self.skip_experimental(tcx, def_id, replacement, outcomes) {
+                return old_body;
+            }
+            let mut new_body = tcx.optimized_mir(replacement).clone();
+            let allow_shared_refs = self.options.allow_shared_refs;
+            if check_compatibility(tcx, def_id, old_body, replacement, &new_body, allow_shared_refs)
+                && (!self.options.verify_types
+                    || check_strict_compatibility(tcx, def_id, old_body, replacement, &new_body))
+            {
+                if is_async_fn(tcx, def_id) {
+                    // The original only creates its generator, whose body is
+                    // replaced by [StubbingPass::async_generator_body].
+                    outcomes.applied.push(tcx.def_path_hash(def_id));
+                    return old_body;
+                }
+                let stub_args = stub_generic_args(tcx, def_id, old_body, replacement, &new_body);
+                if let Ok(Some(args)) = stub_args {
+                    new_body = instantiate_stub(tcx, def_id, new_body, args);
+                }
+                if self.options.preserve_signature {
+                    graft_signature(old_body, &mut new_body);
+                }
+                warn_track_caller_mismatch(tcx, def_id, replacement, outcomes);
+                warn_inline_mismatch(tcx, def_id, replacement, outcomes);
+                if is_trivial_replacement(old_body, &new_body) {
+                    let pair = (tcx.def_path_hash(def_id), tcx.def_path_hash(replacement));
+                    outcomes.trivial.push((pair.0, pair.1, count_statements(old_body)));
+                }
+                outcomes.sizes.push((
+                    tcx.def_path_hash(def_id),
+                    tcx.def_path_hash(replacement),
+                    BodySize::of(old_body),
+                    BodySize::of(&new_body),
+                ));
+                outcomes.applied.push(tcx.def_path_hash(def_id));
+                return tcx.arena.alloc(new_body);
+            }
+        }
+        old_body
+    }
+
+    /// Whether the stub is marked `kani::experimental_stub` and experimental
+    /// stubs are not enabled, in which case it is not applied and the skipped
+    /// pair is recorded for [report_experimental_stubs].
+    fn skip_experimental(
+        &self,
+        tcx: TyCtxt,
+        original: DefId,
+        stub: DefId,
+        outcomes: &mut StubOutcomes,
+    ) -> bool {
+        if self.options.experimental_stubs || !is_experimental_stub(tcx, stub) {
+            return false;
+        }
+        let pair = (tcx.def_path_hash(original), tcx.def_path_hash(stub));
+        outcomes.experimental.push(pair);
+        true
+    }
+
+    /// Returns the body of the generator of an async function/method that is
+    /// stubbed by another async function/method, i.e., the body of the
+    /// generator of the stub where the type of that generator is replaced by
+    /// the type of the original one. Calling an async function/method only
+    /// creates its generator (the future), so this is where the logic of the
+    /// function/method is replaced. Returns `None` for any other body.
+    fn async_generator_body<'tcx>(&self, tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<Body<'tcx>> {
+        if tcx.def_kind(def_id) != DefKind::Generator
+            || tcx.generator_kind(def_id) != Some(GeneratorKind::Async(AsyncGeneratorKind::Fn))
+        {
+            return None;
+        }
+        let original = tcx.parent(def_id);
+        let replacement = *self.mapping.get(&original).filter(|_| !is_no_stub(tcx, original))?;
+        let old_generator = tcx.erase_regions(tcx.type_of(def_id));
+        let stub_generator = async_generator(tcx, replacement)?;
+        let (ty::Generator(_, old_substs, _), ty::Generator(stub_def_id, stub_substs, _)) =
+            (old_generator.kind(), stub_generator.kind())
+        else {
+            return None;
+        };
+        // The errors are reported when the original function/method is stubbed.
+        let (old_gen, stub_gen) = (old_substs.as_generator(), stub_substs.as_generator());
+        if old_gen.return_ty() != tcx.erase_regions(stub_gen.return_ty())
+            || old_gen.tupled_upvars_ty() != tcx.erase_regions(stub_gen.tupled_upvars_ty())
+        {
+            return None;
+        }
+        let mut folder =
+            GeneratorReplacer { tcx, stub_generator: *stub_def_id, original: old_generator };
+        let mut new_body = tcx.optimized_mir(*stub_def_id).clone().fold_with(&mut folder);
+        new_body.source = MirSource::item(def_id);
+        Some(new_body)
+    }
+
+    /// Returns a copy of the body of the caller where the calls to each
+    /// original function/method of `stubs` are replaced by calls to its stub.
+    /// Only the direct calls in this body are replaced, and only if the stub
+    /// passes the same compatibility checks as the stubs that replace a
+    /// function/method everywhere.
+    fn replace_calls<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        caller: DefId,
+        body: &Body<'tcx>,
+        stubs: &FxHashMap<DefId, DefId>,
+        outcomes: &mut StubOutcomes,
+    ) -> Body<'tcx> {
+        let compatible: FxHashMap<DefId, (DefId, Option<SubstsRef>)> = stubs
+            .iter()
+            .filter(|(original, stub)| !self.skip_experimental(tcx, **original, **stub, outcomes))
+            .filter(|(original, stub)| {
+                if is_async_fn(tcx, **original) {
+                    // The generator of the original is created by other calls too.
+                    tcx.sess.span_err(
+                        tcx.def_span(**stub),
+                        format!(
+                            "cannot stub async function/method `{}` in the calls made by `{}` only; stub it everywhere instead",
+                            qualified_path_str(tcx, **original),
+                            qualified_path_str(tcx, caller)
+                        ),
+                    );
+                    return false;
+                }
+                check_compatibility(
+                    tcx,
+                    **original,
+                    tcx.optimized_mir(**original),
+                    **stub,
+                    tcx.optimized_mir(**stub),
+                    self.options.allow_shared_refs,
+                ) && (!self.options.verify_types
+                    || check_strict_compatibility(
+                        tcx,
+                        **original,
+                        tcx.optimized_mir(**original),
+                        **stub,
+                        tcx.optimized_mir(**stub),
+                    ))
+            })
+            .map(|(original, stub)| {
+                let (old_body, stub_body) =
+                    (tcx.optimized_mir(*original), tcx.optimized_mir(*stub));
+                let stub_args = stub_generic_args(tcx, *original, old_body, *stub, stub_body);
+                (*original, (*stub, stub_args.ok().flatten()))
+            })
+            .collect();
+        let mut new_body = body.clone();
+        let mut replaced = FxHashSet::default();
+        for block in new_body.basic_blocks_mut().iter_mut() {
+            let Some(Terminator { kind: TerminatorKind::Call { func, .. }, .. }) = &mut block.terminator else {
+                continue;
+            };
+            let Some(constant) = func.constant() else { continue };
+            let ty::FnDef(callee, substs) = *constant.ty().kind() else { continue };
+            if let Some((stub, stub_args)) = compatible.get(&callee) {
+                tracing::debug!(?caller, ?callee, ?stub, "replace_calls");
+                // A generic stub of a function without generic parameters is
+                // called with the arguments inferred from its signature.
+                let substs = stub_args.unwrap_or(substs);
+                *func = Operand::function_handle(tcx, *stub, substs, constant.span);
+                replaced.insert(callee);
+            }
+        }
+        let caller_hash = tcx.def_path_hash(caller);
+        let applied = replaced.into_iter().map(|callee| (caller_hash, tcx.def_path_hash(callee)));
+        outcomes.caller_applied.extend(applied);
+        new_body
+    }
+}
+
+/// Returns the `DefId` of `kani::default_stub`, which is the replacement of the
+/// functions/methods stubbed with `kani::stub_default`.
+pub fn default_stub(tcx: TyCtxt) -> Option<DefId> {
+    tcx.get_diagnostic_item(Symbol::intern("KaniDefaultStub"))
+}
+
+/// Returns a copy of the original body that only returns `Default::default()`.
+/// Whether the return type implements `Default` is checked when the stubs are
+/// collected.
+fn default_body<'tcx>(tcx: TyCtxt<'tcx>, old_body: &Body<'tcx>) -> Body<'tcx> {
+    let mut new_body = old_body.clone();
+    let span = new_body.span;
+    let source_info = SourceInfo::outermost(span);
+    let ret_ty = new_body.local_decls[RETURN_PLACE].ty;
+    let default_trait = tcx.get_diagnostic_item(sym::Default).unwrap();
+    let default_fn = tcx
+        .associated_items(default_trait)
+        .find_by_name_and_kind(
+            tcx,
+            Ident::with_dummy_span(kw::Default),
+            AssocKind::Fn,
+            default_trait,
+        )
+        .unwrap()
+        .def_id;
+    let func = Operand::function_handle(tcx, default_fn, tcx.intern_substs(&[ret_ty.into()]), span);
+    let call = TerminatorKind::Call {
+        func,
+        args: vec![],
+        destination: Place::return_place(),
+        target: Some(BasicBlock::from_u32(1)),
+        cleanup: None,
+        from_hir_call: false,
+        fn_span: span,
+    };
+    let blocks = new_body.basic_blocks_mut();
+    blocks.raw.clear();
+    for kind in [call, TerminatorKind::Return] {
+        blocks.push(BasicBlockData::new(Some(Terminator { source_info, kind })));
+    }
+    new_body
+}
+
+/// Returns the `DefId` of `kani::spec_stub`, which is the replacement of the
+/// functions/methods stubbed with `kani::stub_spec`.
+pub fn spec_stub(tcx: TyCtxt) -> Option<DefId> {
+    tcx.get_diagnostic_item(Symbol::intern("KaniSpecStub"))
+}
+
+/// Returns a copy of the original body that returns `kani::any()`, constrained
+/// with `kani::assume` to behave as specified. For a range, this is:
+///
+/// ```ignore
+/// let ret = kani::any();
+/// kani::assume(ret >= lower);
+/// kani::assume(ret <= upper);
+/// ret
+/// ```
+///
+/// and for a predicate, this is:
+///
+/// ```ignore
+/// let ret = kani::any();
+/// kani::assume(predicate(&ret));
+/// ret
+/// ```
+///
+/// A table is replaced by [table_body] and a wrapper by [wrap_body] instead.
+///
+/// Whether the return type matches the specification is checked when the stubs
+/// are collected.
+fn spec_body<'tcx>(tcx: TyCtxt<'tcx>, old_body: &Body<'tcx>, spec: &StubSpec) -> Body<'tcx> {
+    if let StubSpec::Table { rows, default } = spec {
+        return table_body(tcx, old_body, rows, *default);
+    }
+    if let StubSpec::Wrap { requires, ensures } = spec {
+        return wrap_body(tcx, old_body, requires.as_deref(), ensures.as_deref());
+    }
+    let mut new_body = old_body.clone();
+    let span = new_body.span;
+    let source_info = SourceInfo::outermost(span);
+    let ret_ty = new_body.local_decls[RETURN_PLACE].ty;
+    let any_fn = tcx.get_diagnostic_item(Symbol::intern("KaniAny")).unwrap();
+    let assume_fn = tcx.get_diagnostic_item(Symbol::intern("KaniAssume")).unwrap();
+
+    // Each block ends with a call to the function that continues with the next block.
+    let call = |func, args, destination, target: usize| {
+        let kind = TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            target: Some(BasicBlock::from_usize(target)),
+            cleanup: None,
+            from_hir_call: false,
+            fn_span: span,
+        };
+        BasicBlockData::new(Some(Terminator { source_info, kind }))
+    };
+    let any = Operand::function_handle(tcx, any_fn, tcx.intern_substs(&[ret_ty.into()]), span);
+    let mut blocks = vec![call(any, vec![], Place::return_place(), 1)];
+    // The conditions to assume, each with the statements that compute it.
+    let mut conditions = vec![];
+    match spec {
+        StubSpec::ReturnsInRange { lower, upper } => {
+            let size = tcx.layout_of(ty::ParamEnv::reveal_all().and(ret_ty)).unwrap().size;
+            for (op, bound) in [(BinOp::Ge, *lower), (BinOp::Le, *upper)] {
+                let Some(bound) = bound else { continue };
+                let value = if ret_ty.is_signed() {
+                    Scalar::from_int(bound, size)
+                } else {
+                    Scalar::from_uint(bound as u128, size)
+                };
+                let bound = Operand::const_from_scalar(tcx, ret_ty, value, span);
+                let cond = new_body.local_decls.push(LocalDecl::new(tcx.types.bool, span));
+                let compare =
+                    Rvalue::BinaryOp(op, Box::new((Operand::Copy(Place::return_place()), bound)));
+                let assign = StatementKind::Assign(Box::new((Place::from(cond), compare)));
+                conditions.push((cond, vec![Statement { source_info, kind: assign }]));
+            }
+        }
+        StubSpec::Table { .. } => unreachable!("a table is replaced by `table_body`"),
+        StubSpec::Wrap { .. } => unreachable!("a wrapper is replaced by `wrap_body`"),
+        StubSpec::ReturnsWhere { predicate } => {
+            let predicate = resolve_path(tcx, CRATE_DEF_ID, predicate)
+                .expect("the predicate is resolved when the stubs are collected");
+            let ref_ty = tcx.mk_imm_ref(tcx.lifetimes.re_erased, ret_ty);
+            let reference = new_body.local_decls.push(LocalDecl::new(ref_ty, span));
+            let borrow =
+                Rvalue::Ref(tcx.lifetimes.re_erased, BorrowKind::Shared, Place::return_place());
+            let assign = StatementKind::Assign(Box::new((Place::from(reference), borrow)));
+            let cond = new_body.local_decls.push(LocalDecl::new(tcx.types.bool, span));
+            let func = Operand::function_handle(tcx, predicate, tcx.intern_substs(&[]), span);
+            let args = vec![Operand::Move(Place::from(reference))];
+            let mut block = call(func, args, Place::from(cond), blocks.len() + 1);
+            block.statements.push(Statement { source_info, kind: assign });
+            blocks.push(block);
+            conditions.push((cond, vec![]));
+        }
+    }
+    for (cond, statements) in conditions {
+        let assume = Operand::function_handle(tcx, assume_fn, tcx.intern_substs(&[]), span);
+        let unit = new_body.local_decls.push(LocalDecl::new(tcx.mk_unit(), span));
+        let args = vec![Operand::Move(Place::from(cond))];
+        let mut block = call(assume, args, Place::from(unit), blocks.len() + 1);
+        block.statements.extend(statements);
+        blocks.push(block);
+    }
+    let ret = Terminator { source_info, kind: TerminatorKind::Return };
+    blocks.push(BasicBlockData::new(Some(ret)));
+    let body_blocks = new_body.basic_blocks_mut();
+    body_blocks.raw.clear();
+    body_blocks.raw.extend(blocks);
+    new_body
+}
+
+/// Returns a copy of the original body that looks up its only argument in the
+/// table, which is:
+///
+/// ```ignore
+/// match arg {
+///     input1 => output1,
+///     ...
+///     _ => default, // or `kani::panic("...")` if there is no default
+/// }
+/// ```
+///
+/// Whether the argument and return types match the table is checked when the
+/// stubs are collected.
+fn table_body<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_body: &Body<'tcx>,
+    rows: &[(TableValue, TableValue)],
+    default: Option<TableValue>,
+) -> Body<'tcx> {
+    let mut new_body = old_body.clone();
+    let span = new_body.span;
+    let source_info = SourceInfo::outermost(span);
+    let ret_ty = new_body.local_decls[RETURN_PLACE].ty;
+    let arg = Local::from_usize(1);
+    let arg_ty = new_body.local_decls[arg].ty;
+    let size_of = |ty| tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).unwrap().size;
+    let (arg_size, ret_size) = (size_of(arg_ty), size_of(ret_ty));
+
+    // Block 0 switches on the argument, and the row `i` is handled by block
+    // `i + 1`. Everything else is handled by the block after the rows.
+    let targets = rows
+        .iter()
+        .enumerate()
+        .map(|(idx, (input, _))| (input.bits(arg_size), BasicBlock::from_usize(idx + 1)));
+    let otherwise = BasicBlock::from_usize(rows.len() + 1);
+    let switch = TerminatorKind::SwitchInt {
+        discr: Operand::Copy(Place::from(arg)),
+        targets: SwitchTargets::new(targets, otherwise),
+    };
+    let mut blocks = vec![BasicBlockData::new(Some(Terminator { source_info, kind: switch }))];
+    let outputs = rows.iter().map(|(_, output)| *output).chain(default);
+    for output in outputs {
+        let value = match output {
+            TableValue::Bool(value) => Scalar::from_bool(value),
+            TableValue::Int(value) if ret_ty.is_signed() => Scalar::from_int(value, ret_size),
+            TableValue::Int(value) => Scalar::from_uint(value as u128, ret_size),
+        };
+        let output = Operand::const_from_scalar(tcx, ret_ty, value, span);
+        let assign = StatementKind::Assign(Box::new((Place::return_place(), Rvalue::Use(output))));
+        let ret = Terminator { source_info, kind: TerminatorKind::Return };
+        let mut block = BasicBlockData::new(Some(ret));
+        block.statements.push(Statement { source_info, kind: assign });
+        blocks.push(block);
+    }
+    if default.is_none() {
+        let panic_fn = tcx.get_diagnostic_item(Symbol::intern("KaniPanic")).unwrap();
+        let func = Operand::function_handle(tcx, panic_fn, tcx.intern_substs(&[]), span);
+        let message = str_operand(tcx, "the argument is not in the table of the stub", span);
+        let never = new_body.local_decls.push(LocalDecl::new(tcx.types.never, span));
+        let kind = TerminatorKind::Call {
+            func,
+            args: vec![message],
+            destination: Place::from(never),
+            target: None,
+            cleanup: None,
+            from_hir_call: false,
+            fn_span: span,
+        };
+        blocks.push(BasicBlockData::new(Some(Terminator { source_info, kind })));
+    }
+    let body_blocks = new_body.basic_blocks_mut();
+    body_blocks.raw.clear();
+    body_blocks.raw.extend(blocks);
+    new_body
+}
+
+/// Returns a copy of the original body that checks the conditions of a
+/// wrapper around the original blocks, which is:
+///
+/// ```ignore
+/// kani::assert(requires(&arg1, .., &argn), "...");
+/// let ret = { /* the original body */ };
+/// kani::assert(ensures(&ret), "...");
+/// ret
+/// ```
+///
+/// The original blocks are kept, so a recursive call of the function/method
+/// checks the conditions again. Whether the conditions match the signature is
+/// checked when the stubs are collected.
+fn wrap_body<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_body: &Body<'tcx>,
+    requires: Option<&str>,
+    ensures: Option<&str>,
+) -> Body<'tcx> {
+    let mut new_body = old_body.clone();
+    let span = new_body.span;
+    let source_info = SourceInfo::outermost(span);
+    let assert_fn = tcx.get_diagnostic_item(Symbol::intern("KaniAssert")).unwrap();
+
+    // Calls `condition` on references to `places`, and then asserts that it
+    // holds, which takes two blocks that start at `first` and continue with
+    // `target` (or return if there is none).
+    let check = |body: &mut Body<'tcx>,
+                 condition: &str,
+                 kind: &str,
+                 places: Vec<Place<'tcx>>,
+                 first: usize,
+                 target: Option<usize>| {
+        let condition_id = resolve_path(tcx, CRATE_DEF_ID, condition)
+            .expect("the conditions are resolved when the stubs are collected");
+        let mut statements = vec![];
+        let mut args = vec![];
+        for place in places {
+            let ref_ty = tcx.mk_imm_ref(tcx.lifetimes.re_erased, place.ty(&*body, tcx).ty);
+            let reference = body.local_decls.push(LocalDecl::new(ref_ty, span));
+            let borrow = Rvalue::Ref(tcx.lifetimes.re_erased, BorrowKind::Shared, place);
+            let assign = StatementKind::Assign(Box::new((Place::from(reference), borrow)));
+            statements.push(Statement { source_info, kind: assign });
+            args.push(Operand::Move(Place::from(reference)));
+        }
+        let cond = body.local_decls.push(LocalDecl::new(tcx.types.bool, span));
+        let unit = body.local_decls.push(LocalDecl::new(tcx.mk_unit(), span));
+        let message = format!("{kind} `{condition}` of the wrapper does not hold");
+        let calls = [
+            (condition_id, args, Place::from(cond), Some(first + 1)),
+            (
+                assert_fn,
+                vec![Operand::Move(Place::from(cond)), str_operand(tcx, &message, span)],
+                Place::from(unit),
+                target,
+            ),
+        ];
+        let mut blocks: Vec<_> = calls
+            .into_iter()
+            .map(|(func, args, destination, target)| {
+                let func = Operand::function_handle(tcx, func, tcx.intern_substs(&[]), span);
+                let kind = TerminatorKind::Call {
+                    func,
+                    args,
+                    destination,
+                    target: Some(BasicBlock::from_usize(target.unwrap_or(first + 2))),
+                    cleanup: None,
+                    from_hir_call: false,
+                    fn_span: span,
+                };
+                BasicBlockData::new(Some(Terminator { source_info, kind }))
+            })
+            .collect();
+        blocks[0].statements = statements;
+        if target.is_none() {
+            let ret = Terminator { source_info, kind: TerminatorKind::Return };
+            blocks.push(BasicBlockData::new(Some(ret)));
+        }
+        blocks
+    };
+
+    // The precondition takes the first two blocks, so the original blocks are
+    // shifted, and the postcondition is checked in the blocks after them.
+    let shift = if requires.is_some() { 2 } else { 0 };
+    let post = BasicBlock::from_usize(new_body.basic_blocks.len() + shift);
+    for block in new_body.basic_blocks_mut().iter_mut() {
+        let terminator = block.terminator_mut();
+        for successor in terminator.successors_mut() {
+            *successor = BasicBlock::from_usize(successor.as_usize() + shift);
+        }
+        if ensures.is_some() && matches!(terminator.kind, TerminatorKind::Return) {
+            terminator.kind = TerminatorKind::Goto { target: post };
+        }
+    }
+    let prologue = match requires {
+        Some(requires) => {
+            let args = (1..=new_body.arg_count).map(|idx| Place::from(Local::from_usize(idx)));
+            check(&mut new_body, requires, "precondition", args.collect(), 0, Some(shift))
+        }
+        None => vec![],
+    };
+    let epilogue = match ensures {
+        Some(ensures) => {
+            let ret = vec![Place::return_place()];
+            check(&mut new_body, ensures, "postcondition", ret, post.as_usize(), None)
+        }
+        None => vec![],
+    };
+    let body_blocks = new_body.basic_blocks_mut();
+    body_blocks.raw.splice(0..0, prologue);
+    body_blocks.raw.extend(epilogue);
+    new_body
+}
+
+/// Returns a constant operand of type `&'static str` with the given value.
+fn str_operand<'tcx>(tcx: TyCtxt<'tcx>, value: &str, span: Span) -> Operand<'tcx> {
+    let alloc = Allocation::from_bytes_byte_aligned_immutable(value.as_bytes());
+    let data = tcx.intern_const_alloc(alloc);
+    let value = ConstValue::Slice { data, start: 0, end: value.len() };
+    let literal = ConstantKind::Val(value, tcx.mk_static_str());
+    Operand::Constant(Box::new(Constant { span, user_ty: None, literal }))
+}
+
+/// Makes the stub body look like the body of the original function/method
+/// everywhere outside of its statements: the body keeps the original's source
+/// and span, as well as the spans and debug names of the return place and
+/// parameters. This way, the symbols emitted for the function/method and its
+/// parameters are the same with or without the stub.
+///
+/// Source scopes are specific to each body, so everything taken from the
+/// original is placed in the outermost scope of the stub.
+///
+/// This must only be called after [check_compatibility] succeeded, so the
+/// return place and parameters have the same types in both bodies.
+fn graft_signature<'tcx>(old_body: &Body<'tcx>, new_body: &mut Body<'tcx>) {
+    new_body.source = old_body.source;
+    new_body.span = old_body.span;
+    for idx in 0..=old_body.arg_count {
+        let local = Local::from_usize(idx);
+        new_body.local_decls[local].source_info.span = old_body.local_decls[local].source_info.span;
     }
-    old_body
+    let is_signature_local = |info: &VarDebugInfo| match info.value {
+        VarDebugInfoContents::Place(place) => {
+            place.projection.is_empty() && place.local.as_usize() <= old_body.arg_count
+        }
+        _ => false,
+    };
+    new_body.var_debug_info.retain(|info| !is_signature_local(info));
+    let signature_info = old_body.var_debug_info.iter().filter(|info| is_signature_local(info));
+    let signature_info: Vec<_> = signature_info
+        .map(|info| VarDebugInfo {
+            source_info: SourceInfo { span: info.source_info.span, scope: OUTERMOST_SOURCE_SCOPE },
+            ..info.clone()
+        })
+        .collect();
+    new_body.var_debug_info.splice(0..0, signature_info);
+}
+
+/// Warns if only one of the original function/method and its stub is
+/// `#[track_caller]`, since the locations of the panics in the stub would then
+/// differ from the ones the original reports.
+///
+/// The attribute is not propagated from one function to the other: it is part
+/// of the signature (callers pass the location as an implicit argument), and
+/// Kani does not support the `caller_location` intrinsic that reads it.
+fn warn_track_caller_mismatch(
+    tcx: TyCtxt,
+    old_def_id: DefId,
+    stub_def_id: DefId,
+    outcomes: &mut StubOutcomes,
+) {
+    let track_caller =
+        |def_id| tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::TRACK_CALLER);
+    let old = qualified_path_str(tcx, old_def_id);
+    let stub = qualified_path_str(tcx, stub_def_id);
+    let msg = match (track_caller(old_def_id), track_caller(stub_def_id)) {
+        (true, false) => format!(
+            "original function/method `{old}` is `#[track_caller]` but stub `{stub}` is not; \
+            panics in the stub will be reported at their location in the stub"
+        ),
+        (false, true) => format!(
+            "stub `{stub}` is `#[track_caller]` but original function/method `{old}` is not; \
+            panics in the stub will not be reported at the location of its caller"
+        ),
+        _ => return,
+    };
+    tcx.sess.span_warn(tcx.def_span(stub_def_id), &msg);
+    outcomes.warnings.push(msg);
+}
+
+/// Warns if the stub has an `#[inline]` attribute that contradicts the
+/// `#[inline(always)]` or `#[inline(never)]` of the original function/method.
+///
+/// The body of the stub is codegen'd as the body of the original, so the
+/// inline attributes of the original are the ones that apply and the ones of
+/// the stub are ignored. A stub without inline attributes thus keeps the
+/// inlining behavior of the original.
+fn warn_inline_mismatch(
+    tcx: TyCtxt,
+    old_def_id: DefId,
+    stub_def_id: DefId,
+    outcomes: &mut StubOutcomes,
+) {
+    let describe = |inline| match inline {
+        InlineAttr::Always => "#[inline(always)]",
+        InlineAttr::Never => "#[inline(never)]",
+        InlineAttr::Hint => "#[inline]",
+        InlineAttr::None => "",
+    };
+    let old_inline = tcx.codegen_fn_attrs(old_def_id).inline;
+    let stub_inline = tcx.codegen_fn_attrs(stub_def_id).inline;
+    if !matches!(old_inline, InlineAttr::Always | InlineAttr::Never)
+        || matches!(stub_inline, InlineAttr::None)
+        || old_inline == stub_inline
+    {
+        return;
+    }
+    let msg = format!(
+        "stub `{}` is `{}` but original function/method `{}` is `{}`; \
+        the inline attributes of the original are the ones that apply",
+        qualified_path_str(tcx, stub_def_id),
+        describe(stub_inline),
+        qualified_path_str(tcx, old_def_id),
+        describe(old_inline),
+    );
+    tcx.sess.span_warn(tcx.def_span(stub_def_id), &msg);
+    outcomes.warnings.push(msg);
+}
+
+/// The minimum number of statements of an original function/method for its
+/// logic to be considered substantial by [is_trivial_replacement].
+const SUBSTANTIAL_STATEMENTS: usize = 5;
+
+/// Returns the number of statements of a body that do some work, i.e.,
+/// ignoring the storage markers and the other statements that are not
+/// codegen'd.
+fn count_statements(body: &Body) -> usize {
+    let statements = body.basic_blocks.iter().flat_map(|block| &block.statements);
+    statements
+        .filter(|statement| {
+            !matches!(
+                statement.kind,
+                StatementKind::StorageLive(_)
+                    | StatementKind::StorageDead(_)
+                    | StatementKind::FakeRead(_)
+                    | StatementKind::Retag(..)
+                    | StatementKind::AscribeUserType(..)
+                    | StatementKind::Nop
+                    | StatementKind::Coverage(_)
+            )
+        })
+        .count()
+}
+
+/// Returns whether a stub looks like it accidentally does nothing meaningful:
+/// its body has no statement (see [count_statements]) and calls no function,
+/// while the original function/method has at least [SUBSTANTIAL_STATEMENTS].
+/// This is only a heuristic. A stub that calls `kani::any()` is not trivial,
+/// even if it has no statement.
+fn is_trivial_replacement(old_body: &Body, new_body: &Body) -> bool {
+    let calls = new_body
+        .basic_blocks
+        .iter()
+        .any(|block| matches!(block.terminator().kind, TerminatorKind::Call { .. }));
+    !calls
+        && count_statements(new_body) == 0
+        && count_statements(old_body) >= SUBSTANTIAL_STATEMENTS
+}
+
+/// Records an advisory for each reachable stub that looks like it does
+/// nothing meaningful. This must be called after [record_reachable_stubs].
+pub fn report_trivial_stubs(tcx: TyCtxt, queries: &QueryDb) {
+    let outcomes = OUTCOMES.lock().unwrap();
+    let reachable: FxHashSet<_> = outcomes.reachable.iter().collect();
+    let def_id = |hash| tcx.def_path_hash_to_def_id(hash, &mut || panic!());
+    let mut trivial: Vec<_> = outcomes
+        .trivial
+        .iter()
+        .filter(|(orig_hash, ..)| reachable.contains(orig_hash))
+        .map(|(orig_hash, stub_hash, statements)| {
+            let orig = qualified_path_str(tcx, def_id(*orig_hash));
+            let stub_id = def_id(*stub_hash);
+            (orig, qualified_path_str(tcx, stub_id), *statements, stub_id)
+        })
+        .collect();
+    // Sort the advisories so they do not depend on the order the bodies were built.
+    trivial.sort();
+    trivial.dedup();
+    for (orig, stub, statements, stub_id) in trivial {
+        let msg = format!(
+            "stub `{stub}` of `{orig}` does nothing: it has no statements and calls no function, \
+            while the original has {statements} statements"
+        );
+        queries.push_advisory(
+            Advisory::new("stubbing", msg).with_payload(
+                AdvisoryPayload::new("trivial-stub", AdvisorySeverity::Note)
+                    .with_span(advisory_span(tcx, tcx.def_span(stub_id))),
+            ),
+        );
+    }
+}
+
+/// Returns the number of calls to each stubbed function/method in the body of
+/// each of its callers, as recorded so far, which is emitted by
+/// `--emit-stub-caller-counts`. The json object maps the path of each stubbed
+/// function/method to an object that maps the path of each caller to the
+/// number of calls.
+pub fn stub_caller_counts(tcx: TyCtxt) -> serde_json::Value {
+    let outcomes = OUTCOMES.lock().unwrap();
+    let path = |hash| qualified_path_str(tcx, tcx.def_path_hash_to_def_id(hash, &mut || panic!()));
+    let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for (caller_hash, callee_hash, count) in &outcomes.caller_counts {
+        // A body may be built more than once, so the count is not accumulated.
+        counts.entry(path(*callee_hash)).or_default().insert(path(*caller_hash), *count);
+    }
+    serde_json::json!(counts)
+}
+
+/// The default ratio of the size of a stub to the size of the original
+/// function/method above which [report_oversized_stubs] reports the stub, if
+/// `--stub-complexity-ratio` is not provided.
+const DEFAULT_STUB_COMPLEXITY_RATIO: usize = 4;
+
+/// The size of a body: its number of basic blocks and of statements that do
+/// some work (see [count_statements]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BodySize {
+    pub blocks: usize,
+    pub statements: usize,
+}
+
+impl BodySize {
+    fn of(body: &Body) -> Self {
+        BodySize { blocks: body.basic_blocks.len(), statements: count_statements(body) }
+    }
+}
+
+/// Returns whether a stub looks substantially more complex than the original
+/// function/method: it has more than `ratio` times as many basic blocks or
+/// statements as the original, and at least [SUBSTANTIAL_STATEMENTS]
+/// statements. This is only a heuristic. A stub is meant to simplify, so this
+/// usually means it replaces the wrong function/method.
+fn is_oversized_replacement(old: BodySize, new: BodySize, ratio: usize) -> bool {
+    let exceeds = |old: usize, new: usize| new > old.max(1).saturating_mul(ratio);
+    new.statements >= SUBSTANTIAL_STATEMENTS
+        && (exceeds(old.blocks, new.blocks) || exceeds(old.statements, new.statements))
+}
+
+/// Records an advisory for each reachable stub that is substantially more
+/// complex than the original function/method (see [is_oversized_replacement]),
+/// using the ratio of `--stub-complexity-ratio`. This must be called after
+/// [record_reachable_stubs].
+pub fn report_oversized_stubs(tcx: TyCtxt, queries: &QueryDb) {
+    let ratio = queries.get_stub_complexity_ratio().unwrap_or(DEFAULT_STUB_COMPLEXITY_RATIO);
+    let outcomes = OUTCOMES.lock().unwrap();
+    let reachable: FxHashSet<_> = outcomes.reachable.iter().collect();
+    let def_id = |hash| tcx.def_path_hash_to_def_id(hash, &mut || panic!());
+    let mut oversized: Vec<_> = outcomes
+        .sizes
+        .iter()
+        .filter(|(orig_hash, _, old, new)| {
+            reachable.contains(orig_hash) && is_oversized_replacement(*old, *new, ratio)
+        })
+        .map(|(orig_hash, stub_hash, old, new)| {
+            let orig = qualified_path_str(tcx, def_id(*orig_hash));
+            let stub_id = def_id(*stub_hash);
+            (orig, qualified_path_str(tcx, stub_id), *old, *new, stub_id)
+        })
+        .collect();
+    // Sort the advisories so they do not depend on the order the bodies were built.
+    oversized.sort();
+    oversized.dedup();
+    for (orig, stub, old, new, stub_id) in oversized {
+        let msg = format!(
+            "stub `{stub}` of `{orig}` is much more complex than the original: it has {} basic \
+            blocks and {} statements, while the original has {} basic blocks and {} statements; \
+            check that it replaces the right function/method",
+            new.blocks, new.statements, old.blocks, old.statements
+        );
+        queries.push_advisory(
+            Advisory::new("stubbing", msg).with_payload(
+                AdvisoryPayload::new("oversized-stub", AdvisorySeverity::Warning)
+                    .with_span(advisory_span(tcx, tcx.def_span(stub_id))),
+            ),
+        );
+    }
+}
+
+/// Records an advisory, and a warning for `--stub-summary`, that lists the
+/// experimental stubs that were skipped because `--enable-experimental-stubs`
+/// is not set.
+pub fn report_experimental_stubs(tcx: TyCtxt, queries: &QueryDb) {
+    let mut outcomes = OUTCOMES.lock().unwrap();
+    let def_id = |hash| tcx.def_path_hash_to_def_id(hash, &mut || panic!());
+    let mut skipped: Vec<_> = outcomes
+        .experimental
+        .iter()
+        .map(|(orig_hash, stub_hash)| {
+            let (orig, stub) = (def_id(*orig_hash), def_id(*stub_hash));
+            format!("`{}` -> `{}`", qualified_path_str(tcx, orig), qualified_path_str(tcx, stub))
+        })
+        .collect();
+    if skipped.is_empty() {
+        return;
+    }
+    // Sort the stubs so they do not depend on the order the bodies were built.
+    skipped.sort();
+    skipped.dedup();
+    let msg = format!(
+        "{} experimental stub(s) were not applied: {}; use `--enable-experimental-stubs` to \
+        apply them",
+        skipped.len(),
+        skipped.join(", ")
+    );
+    queries.push_advisory(
+        Advisory::new("stubbing", msg.clone()).with_payload(AdvisoryPayload::new(
+            "experimental-stubs-skipped",
+            AdvisorySeverity::Note,
+        )),
+    );
+    outcomes.warnings.push(msg);
+}
+
+/// Reports an error for each stub of the current harness that was never
+/// applied to a reachable function/method, which is what `--deny-unapplied-stubs`
+/// checks once the reachable items are known. A stub that is not applied
+/// usually targets the wrong function/method. The experimental stubs that were
+/// skipped on purpose are not reported here.
+pub fn report_unapplied_stubs(tcx: TyCtxt) {
+    let outcomes = OUTCOMES.lock().unwrap();
+    let reachable: FxHashSet<_> = outcomes.reachable.iter().copied().collect();
+    let applied: FxHashSet<_> =
+        outcomes.applied.iter().filter(|hash| reachable.contains(hash)).copied().collect();
+    let caller_applied: FxHashSet<_> = outcomes
+        .caller_applied
+        .iter()
+        .filter(|(caller, _)| reachable.contains(caller))
+        .copied()
+        .collect();
+    // The experimental stubs that were deliberately skipped are reported by
+    // [report_experimental_stubs] instead.
+    let experimental: FxHashSet<_> = outcomes.experimental.iter().copied().collect();
+    let is_experimental = |original: DefId, stub: DefId| {
+        experimental.contains(&(tcx.def_path_hash(original), tcx.def_path_hash(stub)))
+    };
+    let mut unapplied: Vec<_> = get_stub_mapping(tcx)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(original, _)| !applied.contains(&tcx.def_path_hash(*original)))
+        .filter(|(original, stub)| !is_experimental(*original, *stub))
+        .map(|(original, stub)| {
+            format!(
+                "stub `{}` of `{}` was never applied",
+                qualified_path_str(tcx, stub),
+                qualified_path_str(tcx, original)
+            )
+        })
+        .collect();
+    unapplied.extend(get_caller_stub_mapping(tcx).into_iter().filter_map(
+        |((caller, original), stub)| {
+            let pair = (tcx.def_path_hash(caller), tcx.def_path_hash(original));
+            let skipped = caller_applied.contains(&pair) || is_experimental(original, stub);
+            (!skipped).then(|| {
+                format!(
+                    "stub `{}` of `{}` was never applied to the calls made by `{}`",
+                    qualified_path_str(tcx, stub),
+                    qualified_path_str(tcx, original),
+                    qualified_path_str(tcx, caller)
+                )
+            })
+        },
+    ));
+    // Sort the errors so they do not depend on the order of the mapping.
+    unapplied.sort();
+    for msg in unapplied {
+        tcx.sess.err(format!(
+            "{msg}; its original function/method may not be reachable from the harness \
+            (see `--deny-unapplied-stubs`)"
+        ));
+    }
+}
+
+/// Checks that each call to a stubbed function/method in the bodies of the
+/// reachable items would still type check if it called the stub instead, which
+/// is enabled by `--check-stub-call-sites`. Unlike [check_compatibility], this
+/// looks at the generic arguments of each call: a stub that takes or returns
+/// other types than the ones the call site has once they are normalized, or
+/// whose trait bounds do not hold for these arguments, is reported at the call
+/// site (e.g., a stub with a `T: Copy` bound called with `T = Vec<u8>`). The
+/// calls to the stubs of `kani::stub_in` are already replaced by then, and
+/// the bodies of `kani::stub_default` and `kani::stub_spec` are built for the
+/// original, so they are not checked. Emits an error for each call site that
+/// would break.
+pub fn check_stub_call_sites<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &[MonoItem<'tcx>],
+    allow_shared_refs: bool,
+) {
+    let mapping = get_stub_mapping(tcx).unwrap_or_default();
+    let generated = [default_stub(tcx), spec_stub(tcx)];
+    let callers: FxHashSet<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            MonoItem::Fn(instance) => Some(instance.def_id()),
+            _ => None,
+        })
+        .filter(|def_id| tcx.is_mir_available(*def_id))
+        .collect();
+    let mut errors = Vec::new();
+    for caller in callers {
+        let body = tcx.optimized_mir(caller);
+        for block in body.basic_blocks.iter() {
+            let TerminatorKind::Call { func, args, destination, fn_span, .. } =
+                &block.terminator().kind else { continue };
+            let Some(constant) = func.constant() else { continue };
+            let ty::FnDef(callee, substs) = *constant.ty().kind() else { continue };
+            let Some(&stub) = mapping.get(&callee) else { continue };
+            if generated.contains(&Some(stub)) || is_no_stub(tcx, callee) || is_async_fn(tcx, stub)
+            {
+                continue;
+            }
+            let arg_tys: Vec<_> = args.iter().map(|arg| arg.ty(body, tcx)).collect();
+            let ret_ty = destination.ty(body, tcx).ty;
+            let call = CallSite { caller, callee, substs, arg_tys: &arg_tys, ret_ty };
+            let reasons = call_site_errors(tcx, &call, stub, allow_shared_refs);
+            errors.extend(reasons.into_iter().map(|reason| {
+                let msg = format!(
+                    "call to `{}` in `{}` does not type check with stub `{}`: {reason}",
+                    qualified_path_str(tcx, callee),
+                    qualified_path_str(tcx, caller),
+                    qualified_path_str(tcx, stub)
+                );
+                (*fn_span, msg)
+            }));
+        }
+    }
+    // Sort the errors so they do not depend on the order of the items.
+    errors.sort_by(|(span1, msg1), (span2, msg2)| (msg1, span1).cmp(&(msg2, span2)));
+    errors.dedup();
+    emit_incompatibilities(tcx, errors);
+}
+
+/// A call to a stubbed function/method that [check_stub_call_sites] checks.
+struct CallSite<'a, 'tcx> {
+    /// The function/method whose body makes the call.
+    caller: DefId,
+    /// The stubbed function/method.
+    callee: DefId,
+    /// The generic arguments of the call.
+    substs: SubstsRef<'tcx>,
+    /// The types of the arguments of the call.
+    arg_tys: &'a [Ty<'tcx>],
+    /// The type of the place where the result of the call is stored.
+    ret_ty: Ty<'tcx>,
+}
+
+/// Returns the reasons why the call would not type check in the body of its
+/// caller if it called `stub` instead. The stubs that take `&T` for `&mut T`
+/// are accepted if `allow_shared_refs` is set.
+fn call_site_errors<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    call: &CallSite<'_, 'tcx>,
+    stub: DefId,
+    allow_shared_refs: bool,
+) -> Vec<String> {
+    let CallSite { caller, callee, substs, arg_tys, ret_ty } = *call;
+    // The generic parameters of the stub take the arguments of their
+    // counterparts in the original function/method, unless the stub is a
+    // generic stub of a function/method without generic parameters.
+    let (callee_body, stub_body) = (tcx.optimized_mir(callee), tcx.optimized_mir(stub));
+    let stub_substs = match stub_generic_args(tcx, callee, callee_body, stub, stub_body) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            let callee_params = non_lifetime_params(tcx, callee);
+            let stub_params = non_lifetime_params(tcx, stub);
+            if callee_params.len() != stub_params.len() {
+                // This is reported by `check_compatibility`.
+                return vec![];
+            }
+            let args: FxHashMap<_, _> = stub_params
+                .iter()
+                .zip(callee_params)
+                .map(|(stub_param, callee_param)| {
+                    (stub_param.index, substs[callee_param.index as usize])
+                })
+                .collect();
+            InternalSubsts::for_item(tcx, stub, |param, _| match args.get(&param.index) {
+                Some(arg) => *arg,
+                None => tcx.lifetimes.re_erased.into(),
+            })
+        }
+        Err(_) => return vec![],
+    };
+    let param_env = tcx.param_env(caller);
+    let sig = tcx.erase_late_bound_regions(EarlyBinder(tcx.fn_sig(stub)).subst(tcx, stub_substs));
+    let Ok(sig) = tcx.try_normalize_erasing_regions(param_env, sig) else {
+        return vec![format!("unable to normalize the signature `{sig}` of the stub")];
+    };
+    let mut errors = Vec::new();
+    let ret_ty = tcx.erase_regions(ret_ty);
+    if sig.output() != ret_ty {
+        errors.push(format!(
+            "the stub returns `{}` where the call site expects `{ret_ty}`",
+            sig.output()
+        ));
+    }
+    for (idx, (stub_ty, arg_ty)) in sig.inputs().iter().zip(arg_tys).enumerate() {
+        let arg_ty = tcx.erase_regions(*arg_ty);
+        let narrowing = allow_shared_refs && is_shared_ref_narrowing(arg_ty, *stub_ty);
+        if *stub_ty != arg_ty && !narrowing {
+            errors.push(format!(
+                "the stub takes `{stub_ty}` for argument {idx} where the call site passes `{arg_ty}`"
+            ));
+        }
+    }
+    let holds = |bound: Predicate<'tcx>| {
+        let infcx = tcx.infer_ctxt().build();
+        let obligation = Obligation::new(tcx, ObligationCause::dummy(), param_env, bound);
+        infcx.predicate_must_hold_modulo_regions(&obligation)
+    };
+    let bounds = tcx.predicates_of(stub).instantiate(tcx, stub_substs).predicates;
+    for bound in bounds
+        .into_iter()
+        .filter(|bound| !is_outlives_bound(*bound))
+        .map(|bound| tcx.erase_regions(bound))
+        .filter(|bound| !holds(*bound))
+    {
+        errors.push(format!("the stub requires `{bound}`, which does not hold at the call site"));
+    }
+    errors
+}
+
+/// A reason why a stub cannot replace the original function/method, together
+/// with the span that the corresponding error points to.
+type Incompatibility = (Span, String);
+
+/// Returns the reasons why the stub cannot replace the original function/method
+/// (empty if it can), without emitting any error. If `strict` is set, this also
+/// performs the checks enabled by `--verify-stub-types`. The stubs that take
+/// `&T` for `&mut T` are accepted if `allow_shared_refs` is set (see
+/// `--allow-stub-shared-refs`).
+pub fn stub_incompatibilities(
+    tcx: TyCtxt,
+    old_def_id: DefId,
+    stub_def_id: DefId,
+    strict: bool,
+    allow_shared_refs: bool,
+) -> Vec<String> {
+    for def_id in [old_def_id, stub_def_id] {
+        if matches!(tcx.def_kind(def_id), DefKind::Ctor(..)) {
+            return vec![format!(
+                "cannot stub constructor `{}`; stub the function that wraps it instead",
+                qualified_path_str(tcx, def_id)
+            )];
+        }
+        if !tcx.is_mir_available(def_id) {
+            return vec![format!(
+                "unable to check function/method `{}`: its MIR is not available",
+                qualified_path_str(tcx, def_id)
+            )];
+        }
+    }
+    let old_body = tcx.optimized_mir(old_def_id);
+    let stub_body = tcx.optimized_mir(stub_def_id);
+    let mut errors =
+        compatibility_errors(tcx, old_def_id, old_body, stub_def_id, stub_body, allow_shared_refs);
+    if strict && errors.is_empty() {
+        errors = strict_compatibility_errors(tcx, old_def_id, old_body, stub_def_id, stub_body);
+    }
+    errors.into_iter().map(|(_, msg)| msg).collect()
+}
+
+/// Emits an error for each incompatibility and returns whether there were none.
+fn emit_incompatibilities(tcx: TyCtxt, errors: Vec<Incompatibility>) -> bool {
+    let compatible = errors.is_empty();
+    for (span, msg) in errors {
+        tcx.sess.span_err(span, msg);
+    }
+    compatible
 }
 
 /// Checks whether the stub is compatible with the original function/method: do
 /// the arities and types (of the parameters and return values) match up? This
 /// does **NOT** check whether the type variables are constrained to implement
 /// the same traits; trait mismatches are checked during monomorphization.
+/// A parameter of type `&mut T` may be `&T` in the stub if `allow_shared_refs`
+/// is set (see [is_shared_ref_narrowing]). Each error comes with notes that
+/// show the signatures of both functions/methods, since the mismatch of one
+/// type is often easier to understand in the context of the whole signature.
 fn check_compatibility<'a, 'tcx>(
-    tcx: TyCtxt,
+    tcx: TyCtxt<'tcx>,
     old_def_id: DefId,
     old_body: &'a Body<'tcx>,
     stub_def_id: DefId,
     stub_body: &'a Body<'tcx>,
+    allow_shared_refs: bool,
 ) -> bool {
-    // Check whether the arities match.
+    let errors =
+        compatibility_errors(tcx, old_def_id, old_body, stub_def_id, stub_body, allow_shared_refs);
+    let compatible = errors.is_empty();
+    for (span, msg) in errors {
+        let mut diag = tcx.sess.struct_span_err(span, msg);
+        diag.note(format!(
+            "original function/method `{}` has signature `{}`",
+            qualified_path_str(tcx, old_def_id),
+            signature_str(tcx, old_def_id)
+        ));
+        diag.note(format!(
+            "stub `{}` has signature `{}`",
+            qualified_path_str(tcx, stub_def_id),
+            signature_str(tcx, stub_def_id)
+        ));
+        diag.emit();
+    }
+    compatible
+}
+
+/// Returns the signature of a function/method as it is shown in the notes of
+/// the compatibility errors, e.g., `fn(bool, &mut u32) -> i32`. Lifetimes are
+/// erased, like in the types that the errors compare.
+fn signature_str(tcx: TyCtxt, def_id: DefId) -> String {
+    tcx.erase_late_bound_regions(tcx.fn_sig(def_id)).to_string()
+}
+
+/// Returns the errors that [check_compatibility] reports.
+fn compatibility_errors<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_def_id: DefId,
+    old_body: &'a Body<'tcx>,
+    stub_def_id: DefId,
+    stub_body: &'a Body<'tcx>,
+    allow_shared_refs: bool,
+) -> Vec<Incompatibility> {
+    // Check whether the arities match. The `self` receiver of a method counts
+    // as its first argument, which lets a function replace a method (and vice
+    // versa) if that argument has the type of the receiver.
+    let (old_receiver, stub_receiver) =
+        (has_self_receiver(tcx, old_def_id), has_self_receiver(tcx, stub_def_id));
     if old_body.arg_count != stub_body.arg_count {
-        tcx.sess.span_err(
+        let receiver_note = if old_receiver != stub_receiver {
+            " (the `self` receiver of a method counts as its first argument)"
+        } else {
+            ""
+        };
+        return vec![(
             tcx.def_span(stub_def_id),
             format!(
-                "arity mismatch: original function/method `{}` takes {} argument(s), stub `{}` takes {}",
-                tcx.def_path_str(old_def_id),
+                "arity mismatch: original function/method `{}` takes {} argument(s), stub `{}` takes {}{receiver_note}",
+                qualified_path_str(tcx, old_def_id),
                 old_body.arg_count,
-                tcx.def_path_str(stub_def_id),
+                qualified_path_str(tcx, stub_def_id),
                 stub_body.arg_count
             ),
-        );
-        return false;
+        )];
+    }
+    // An async function/method returns a future of its own generator type, so
+    // it can only be replaced by another async function/method, whose
+    // generator takes its place (see [StubbingPass::async_generator_body]).
+    let (old_async, stub_async) = (is_async_fn(tcx, old_def_id), is_async_fn(tcx, stub_def_id));
+    if old_async != stub_async {
+        let asyncness = |is_async| if is_async { "async" } else { "not async" };
+        return vec![(
+            tcx.def_span(stub_def_id),
+            format!(
+                "asyncness mismatch: original function/method `{}` is {}, stub `{}` is {}; an async function/method can only be stubbed by another async function/method",
+                qualified_path_str(tcx, old_def_id),
+                asyncness(old_async),
+                qualified_path_str(tcx, stub_def_id),
+                asyncness(stub_async)
+            ),
+        )];
     }
     // Check whether the numbers of generic parameters match.
-    let old_num_generics = tcx.generics_of(old_def_id).count();
-    let stub_num_generics = tcx.generics_of(stub_def_id).count();
-    if old_num_generics != stub_num_generics {
-        tcx.sess.span_err(
+    let old_num_generics = non_lifetime_params(tcx, old_def_id).len();
+    let stub_num_generics = non_lifetime_params(tcx, stub_def_id).len();
+    let stub_args = match stub_generic_args(tcx, old_def_id, old_body, stub_def_id, stub_body) {
+        Ok(stub_args) => stub_args,
+        Err(error) => return vec![error],
+    };
+    if stub_args.is_none() && old_num_generics != stub_num_generics {
+        return vec![(
             tcx.def_span(stub_def_id),
             format!(
                 "mismatch in the number of generic parameters: original function/method `{}` takes {} generic parameters(s), stub `{}` takes {}",
-                tcx.def_path_str(old_def_id),
+                qualified_path_str(tcx, old_def_id),
                 old_num_generics,
-                tcx.def_path_str(stub_def_id),
+                qualified_path_str(tcx, stub_def_id),
                 stub_num_generics
             ),
-        );
-        return false;
+        )];
     }
     // Check whether the types match. Index 0 refers to the returned value,
     // indices [1, `arg_count`] refer to the parameters.
@@ -83,30 +1558,385 @@ fn check_compatibility<'a, 'tcx>(
     // instead, we should be checking for the equivalence of types up to the
     // renaming of generic parameters.
     // <https://github.com/model-checking/kani/issues/1953>
-    let mut matches = true;
+    // The opaque return types of async functions/methods always differ, so the
+    // outputs of their futures are compared instead. Lifetimes are erased in
+    // MIR, so references only need to agree on their referent types and
+    // mutability (e.g., `fn(&'a T) -> &'a U` matches `fn(&'b T) -> &'b U`).
+    let mut errors = Vec::new();
     for i in 0..=old_body.arg_count {
         let old_arg = old_body.local_decls.get(i.into()).unwrap();
         let new_arg = stub_body.local_decls.get(i.into()).unwrap();
-        if old_arg.ty != new_arg.ty {
-            let prefix = if i == 0 {
+        let (old_ty, new_ty) =
+            match (i, async_output(tcx, old_def_id), async_output(tcx, stub_def_id)) {
+                (0, Some(old_output), Some(stub_output)) => (old_output, stub_output),
+                _ => (
+                    old_arg.ty,
+                    stub_args.map_or(new_arg.ty, |args| EarlyBinder(new_arg.ty).subst(tcx, args)),
+                ),
+            };
+        let (old_ty, new_ty) = (tcx.erase_regions(old_ty), tcx.erase_regions(new_ty));
+        let narrowing = i > 0 && is_shared_ref_narrowing(old_ty, new_ty);
+        if old_ty != new_ty && !(narrowing && allow_shared_refs) {
+            let prefix = if i == 0 && old_async {
+                "output type of the future differs".to_string()
+            } else if i == 0 {
                 "return type differs".to_string()
+            } else if i == 1 && (old_receiver || stub_receiver) {
+                // The receiver is compared with the first parameter of a
+                // function/method without receiver.
+                "receiver mismatch: type of the `self` receiver differs".to_string()
             } else {
                 format!("type of parameter {} differs", i - 1)
             };
-            tcx.sess.span_err(
+            let note = if narrowing {
+                "; use `--allow-stub-shared-refs` to let a stub take `&T` for `&mut T`"
+            } else if i > 0 && is_shared_ref_narrowing(new_ty, old_ty) {
+                "; a stub cannot take `&mut T` for `&T`, since the caller may share the referent"
+            } else {
+                ""
+            };
+            errors.push((
                 new_arg.source_info.span,
                 format!(
-                    "{prefix}: stub `{}` has type `{}` where original function/method `{}` has type `{}`",
-                    tcx.def_path_str(stub_def_id),
-                    new_arg.ty,
-                    tcx.def_path_str(old_def_id),
-                    old_arg.ty
+                    "{prefix}: stub `{}` has type `{}` where original function/method `{}` has type `{}`{note}",
+                    qualified_path_str(tcx, stub_def_id),
+                    new_ty,
+                    qualified_path_str(tcx, old_def_id),
+                    old_ty
                 ),
-            );
-            matches = false;
+            ));
         }
     }
-    matches
+    errors
+}
+
+/// Whether the stub takes `&T` for a parameter of type `&mut T` of the
+/// original function/method, which is only accepted with
+/// `--allow-stub-shared-refs`.
+///
+/// This narrowing is sound: the caller gives up exclusive access to the
+/// referent for the duration of the call either way, and whatever the stub
+/// can do with a shared reference, the original could do with the mutable
+/// one. The stub merely models an original that does not write through the
+/// reference (except through interior mutability), which, like any stub, may
+/// leave out behaviors of the original. It is still rejected by default since
+/// a stub that never writes the referent often hides a missing effect.
+///
+/// The other direction (`&mut T` for `&T`) is never accepted: the caller may
+/// hold other references to the referent, so writing through the reference
+/// would be undefined behavior.
+fn is_shared_ref_narrowing(old_ty: Ty, new_ty: Ty) -> bool {
+    match (old_ty.kind(), new_ty.kind()) {
+        (ty::Ref(_, old_referent, Mutability::Mut), ty::Ref(_, new_referent, Mutability::Not)) => {
+            old_referent == new_referent
+        }
+        _ => false,
+    }
+}
+
+/// Whether the function/method is a method with a `self` receiver, which is
+/// its first argument in MIR.
+fn has_self_receiver(tcx: TyCtxt, def_id: DefId) -> bool {
+    tcx.def_kind(def_id) == DefKind::AssocFn && tcx.associated_item(def_id).fn_has_self_parameter
+}
+
+/// Whether the function/method is async, i.e., whether it returns a future of
+/// the generator created from its body.
+fn is_async_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        && tcx.asyncness(def_id).is_async()
+}
+
+/// Returns the type of the generator that an async function/method returns,
+/// with its regions erased, and `None` if the function/method is not async.
+fn async_generator<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<Ty<'tcx>> {
+    if !is_async_fn(tcx, def_id) {
+        return None;
+    }
+    let ty::Opaque(opaque, _) = *tcx.fn_sig(def_id).skip_binder().output().kind() else {
+        return None;
+    };
+    Some(tcx.erase_regions(tcx.type_of(opaque)))
+}
+
+/// Returns the output type of the future that an async function/method
+/// returns, and `None` if the function/method is not async.
+fn async_output<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<Ty<'tcx>> {
+    match async_generator(tcx, def_id)?.kind() {
+        ty::Generator(_, substs, _) => Some(substs.as_generator().return_ty()),
+        _ => None,
+    }
+}
+
+/// Replaces the type of the generator of a stub by the type of the generator of
+/// the original async function/method.
+struct GeneratorReplacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    stub_generator: DefId,
+    original: Ty<'tcx>,
+}
+
+impl<'tcx> TypeFolder<'tcx> for GeneratorReplacer<'tcx> {
+    fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        match ty.kind() {
+            ty::Generator(def_id, ..) if *def_id == self.stub_generator => self.original,
+            _ => ty.super_fold_with(self),
+        }
+    }
+}
+
+/// Returns the generic arguments of a generic stub that replaces an original
+/// function/method without generic parameters, e.g., `T = u32` for
+/// `fn mock<T>() -> T` replacing `fn get() -> u32`. This lets one generic mock
+/// replace many originals with different signatures. The arguments are
+/// inferred from the types of the parameters and of the return value; whether
+/// the instantiated stub matches the original is checked by the caller.
+///
+/// Returns `Ok(None)` if the stub is used as is, i.e., if it is not generic, if
+/// the original is generic, or if the stub is async, and an error if some
+/// generic parameter of the stub does not appear in its signature.
+fn stub_generic_args<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_def_id: DefId,
+    old_body: &Body<'tcx>,
+    stub_def_id: DefId,
+    stub_body: &Body<'tcx>,
+) -> Result<Option<SubstsRef<'tcx>>, Incompatibility> {
+    let stub_generics = tcx.generics_of(stub_def_id);
+    if !non_lifetime_params(tcx, old_def_id).is_empty()
+        || non_lifetime_params(tcx, stub_def_id).is_empty()
+        || is_async_fn(tcx, stub_def_id)
+    {
+        return Ok(None);
+    }
+    let mut inferred = vec![None; stub_generics.count()];
+    for i in 0..=old_body.arg_count.min(stub_body.arg_count) {
+        let old_ty = old_body.local_decls[Local::from_usize(i)].ty;
+        let stub_ty = stub_body.local_decls[Local::from_usize(i)].ty;
+        infer_generic_args(stub_ty, old_ty, &mut inferred);
+    }
+    for (idx, inferred_ty) in inferred.iter().enumerate() {
+        let param = stub_generics.param_at(idx, tcx);
+        let missing = match param.kind {
+            GenericParamDefKind::Lifetime => false,
+            GenericParamDefKind::Type { .. } => inferred_ty.is_none(),
+            GenericParamDefKind::Const { .. } => true,
+        };
+        if missing {
+            return Err((
+                tcx.def_span(stub_def_id),
+                format!(
+                    "unable to infer generic parameter `{}` of stub `{}` from the signature of original function/method `{}`",
+                    param.name,
+                    qualified_path_str(tcx, stub_def_id),
+                    qualified_path_str(tcx, old_def_id),
+                ),
+            ));
+        }
+    }
+    let args = InternalSubsts::for_item(tcx, stub_def_id, |param, _| match param.kind {
+        GenericParamDefKind::Type { .. } => inferred[param.index as usize].unwrap().into(),
+        _ => tcx.lifetimes.re_erased.into(),
+    });
+    Ok(Some(args))
+}
+
+/// Returns the generic parameters of the function/method, including those of
+/// its parent (e.g., of its impl block), that are not lifetimes. Lifetimes are
+/// erased in MIR, so a stub may declare different lifetime parameters than the
+/// original function/method.
+fn non_lifetime_params<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Vec<&'tcx GenericParamDef> {
+    let generics = tcx.generics_of(def_id);
+    (0..generics.count())
+        .map(|idx| generics.param_at(idx, tcx))
+        .filter(|param| !matches!(param.kind, GenericParamDefKind::Lifetime))
+        .collect()
+}
+
+/// Binds each generic parameter of the stub that appears in `stub_ty` to the
+/// type at the same position in `old_ty`, unless it is already bound. The
+/// types are walked side by side, so the bindings are only meaningful if the
+/// two types have the same shape, which the caller must check.
+fn infer_generic_args<'tcx>(
+    stub_ty: Ty<'tcx>,
+    old_ty: Ty<'tcx>,
+    inferred: &mut [Option<Ty<'tcx>>],
+) {
+    let mut stub_walk = stub_ty.walk();
+    let mut old_walk = old_ty.walk();
+    while let (Some(stub_arg), Some(old_arg)) = (stub_walk.next(), old_walk.next()) {
+        if let (GenericArgKind::Type(stub_ty), GenericArgKind::Type(old_ty)) =
+            (stub_arg.unpack(), old_arg.unpack())
+        {
+            if let ty::Param(param) = stub_ty.kind() {
+                inferred[param.index as usize].get_or_insert(old_ty);
+                old_walk.skip_current_subtree();
+            }
+        }
+    }
+}
+
+/// Returns the body of a generic stub instantiated with the generic arguments
+/// inferred by [stub_generic_args], so it can be used as the body of the
+/// original function/method.
+fn instantiate_stub<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_def_id: DefId,
+    stub_body: Body<'tcx>,
+    args: SubstsRef<'tcx>,
+) -> Body<'tcx> {
+    tcx.subst_and_normalize_erasing_regions(args, tcx.param_env(old_def_id), stub_body)
+}
+
+/// Performs the checks enabled by `--verify-stub-types`. These cover the
+/// properties that [check_compatibility] does not verify: the qualifiers of
+/// the function signatures, the kind of each generic parameter, and the trait
+/// bounds of the stub. A stub that requires a trait bound that the original
+/// function/method does not require may fail to type check once the stub is
+/// monomorphized, so it is rejected.
+fn check_strict_compatibility<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_def_id: DefId,
+    old_body: &Body<'tcx>,
+    stub_def_id: DefId,
+    stub_body: &Body<'tcx>,
+) -> bool {
+    let errors = strict_compatibility_errors(tcx, old_def_id, old_body, stub_def_id, stub_body);
+    emit_incompatibilities(tcx, errors)
+}
+
+/// Returns the errors that [check_strict_compatibility] reports.
+fn strict_compatibility_errors<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    old_def_id: DefId,
+    old_body: &Body<'tcx>,
+    stub_def_id: DefId,
+    stub_body: &Body<'tcx>,
+) -> Vec<Incompatibility> {
+    let mut errors = Vec::new();
+    let mut mismatch = |what: &str, stub: String, old: String| {
+        errors.push((
+            tcx.def_span(stub_def_id),
+            format!(
+                "{what} differs: stub `{}` is {stub} where original function/method `{}` is {old}",
+                qualified_path_str(tcx, stub_def_id),
+                qualified_path_str(tcx, old_def_id),
+            ),
+        ));
+    };
+    let old_sig = tcx.fn_sig(old_def_id).skip_binder();
+    let stub_sig = tcx.fn_sig(stub_def_id).skip_binder();
+    if old_sig.unsafety != stub_sig.unsafety {
+        let unsafety = |unsafety| match unsafety {
+            Unsafety::Unsafe => "`unsafe`",
+            Unsafety::Normal => "not `unsafe`",
+        };
+        mismatch(
+            "unsafety",
+            unsafety(stub_sig.unsafety).to_string(),
+            unsafety(old_sig.unsafety).to_string(),
+        );
+    }
+    if old_sig.abi != stub_sig.abi {
+        mismatch("ABI", format!("`{}`", stub_sig.abi), format!("`{}`", old_sig.abi));
+    }
+    if old_sig.c_variadic != stub_sig.c_variadic {
+        let variadic = |is_variadic| if is_variadic { "variadic" } else { "not variadic" };
+        mismatch(
+            "variadicity",
+            variadic(stub_sig.c_variadic).to_string(),
+            variadic(old_sig.c_variadic).to_string(),
+        );
+    }
+    let old_const = tcx.is_const_fn_raw(old_def_id);
+    let stub_const = tcx.is_const_fn_raw(stub_def_id);
+    if old_const != stub_const {
+        let constness = |is_const| if is_const { "`const`" } else { "not `const`" };
+        mismatch("constness", constness(stub_const).to_string(), constness(old_const).to_string());
+    }
+
+    // The number of generic parameters is checked by `check_compatibility`.
+    let old_params = non_lifetime_params(tcx, old_def_id);
+    let stub_params = non_lifetime_params(tcx, stub_def_id);
+    for (idx, (old_param, stub_param)) in old_params.into_iter().zip(stub_params).enumerate() {
+        match (&old_param.kind, &stub_param.kind) {
+            (GenericParamDefKind::Const { .. }, GenericParamDefKind::Const { .. }) => {
+                let old_ty = tcx.type_of(old_param.def_id);
+                let stub_ty = tcx.type_of(stub_param.def_id);
+                if old_ty != stub_ty {
+                    mismatch(
+                        &format!("type of const generic parameter {idx}"),
+                        format!("`{stub_ty}`"),
+                        format!("`{old_ty}`"),
+                    );
+                }
+            }
+            (old_kind, stub_kind) => {
+                if old_kind.descr() != stub_kind.descr() {
+                    mismatch(
+                        &format!("kind of generic parameter {idx}"),
+                        format!("a {}", stub_kind.descr()),
+                        format!("a {}", old_kind.descr()),
+                    );
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        // The trait bounds cannot be compared if the signatures differ.
+        return errors;
+    }
+
+    let old_bounds: Vec<_> = tcx
+        .predicates_of(old_def_id)
+        .instantiate_identity(tcx)
+        .predicates
+        .into_iter()
+        .map(|bound| tcx.erase_regions(bound))
+        .collect();
+    // The bounds of a generic stub that replaces a function/method without
+    // generic parameters are instantiated, so they can be checked directly.
+    let stub_args = stub_generic_args(tcx, old_def_id, old_body, stub_def_id, stub_body);
+    let stub_bounds = match stub_args {
+        Ok(Some(args)) => tcx.predicates_of(stub_def_id).instantiate(tcx, args).predicates,
+        _ => tcx.predicates_of(stub_def_id).instantiate_identity(tcx).predicates,
+    };
+    let holds = |bound: Predicate<'tcx>| {
+        let infcx = tcx.infer_ctxt().build();
+        let param_env = tcx.param_env(old_def_id);
+        let obligation = Obligation::new(tcx, ObligationCause::dummy(), param_env, bound);
+        infcx.predicate_must_hold_modulo_regions(&obligation)
+    };
+    // Lifetimes are erased in MIR, so the outlives bounds of the stub are not
+    // checked and the other bounds are compared with their lifetimes erased.
+    for bound in stub_bounds
+        .into_iter()
+        .filter(|bound| !is_outlives_bound(*bound))
+        .map(|bound| tcx.erase_regions(bound))
+        .filter(|bound| !(old_bounds.contains(bound) || bound.is_global() && holds(*bound)))
+    {
+        errors.push((
+            tcx.def_span(stub_def_id),
+            format!(
+                "unable to verify trait bound: stub `{}` requires `{bound}`, which original function/method `{}` does not require",
+                qualified_path_str(tcx, stub_def_id),
+                qualified_path_str(tcx, old_def_id),
+            ),
+        ));
+    }
+    errors
+}
+
+/// Whether the bound only constrains lifetimes, e.g., `T: 'a` or `'a: 'b`.
+fn is_outlives_bound(bound: Predicate) -> bool {
+    matches!(
+        bound.kind().skip_binder(),
+        PredicateKind::Clause(Clause::RegionOutlives(_) | Clause::TypeOutlives(_))
+    )
 }
 
 /// The prefix we will use when serializing the stub mapping as a rustc argument.
@@ -122,11 +1952,43 @@ pub fn mk_rustc_arg(stub_mapping: &FxHashMap<DefPathHash, DefPathHash>) -> Strin
         let vparts = v.0.as_value();
         pairs.push((kparts, vparts));
     }
+    // Sort the pairs so the argument, and hence anything derived from the
+    // compiler arguments, does not depend on the iteration order of the map.
+    pairs.sort_unstable();
     // Store our serialized mapping as a fake LLVM argument (safe to do since
     // LLVM will never see them).
     format!("-Cllvm-args='{RUSTC_ARG_PREFIX}{}'", serde_json::to_string(&pairs).unwrap())
 }
 
+/// Generates a Rust source file that lists the stub mapping of a harness, so it
+/// can be reviewed and diffed like any other source file. Each pair appears as
+/// a `// original -> replacement` comment and as an entry of the `STUB_MAPPING`
+/// constant. The pairs are sorted by the path of the original function/method.
+pub fn mk_rust_source(
+    tcx: TyCtxt,
+    harness: &str,
+    stub_mapping: &FxHashMap<DefPathHash, DefPathHash>,
+) -> String {
+    let path_str = |hash: &DefPathHash| {
+        qualified_path_str(tcx, tcx.def_path_hash_to_def_id(*hash, &mut || panic!()))
+    };
+    let mut pairs: Vec<_> = stub_mapping.iter().map(|(k, v)| (path_str(k), path_str(v))).collect();
+    pairs.sort();
+
+    let mut source = format!("// Stub mapping of harness `{harness}`, generated by Kani.\n");
+    source.push_str("//\n");
+    for (orig, stub) in &pairs {
+        source.push_str(&format!("// {orig} -> {stub}\n"));
+    }
+    source.push('\n');
+    source.push_str(&format!("pub const STUB_MAPPING: [(&str, &str); {}] = [\n", pairs.len()));
+    for (orig, stub) in &pairs {
+        source.push_str(&format!("    ({orig:?}, {stub:?}),\n"));
+    }
+    source.push_str("];\n");
+    source
+}
+
 /// Deserializes the stub mapping from the rustc argument value.
 fn deserialize_mapping(tcx: TyCtxt, val: &str) -> FxHashMap<DefId, DefId> {
     type Item = (u64, u64);
@@ -144,6 +2006,85 @@ fn deserialize_mapping(tcx: TyCtxt, val: &str) -> FxHashMap<DefId, DefId> {
     m
 }
 
+/// The prefix we will use when serializing the stubs of callers as a rustc
+/// argument.
+const CALLER_RUSTC_ARG_PREFIX: &str = "kani_caller_stubs=";
+
+/// Serializes the stubs of callers into a rustc argument, in the same way as
+/// [mk_rustc_arg] does for the stub mapping.
+pub fn mk_caller_rustc_arg(caller_stubs: &CallerStubs) -> String {
+    let mut triples: Vec<_> = caller_stubs
+        .iter()
+        .map(|((caller, original), stub)| {
+            (caller.0.as_value(), original.0.as_value(), stub.0.as_value())
+        })
+        .collect();
+    triples.sort_unstable();
+    format!("-Cllvm-args='{CALLER_RUSTC_ARG_PREFIX}{}'", serde_json::to_string(&triples).unwrap())
+}
+
+/// Retrieves the stubs of callers from the compiler configuration, as a map
+/// from the pair of a caller and an original function/method to the stub.
+fn get_caller_stub_mapping(tcx: TyCtxt) -> FxHashMap<(DefId, DefId), DefId> {
+    // Use a static so that we compile the regex only once.
+    lazy_static! {
+        static ref RE: Regex = Regex::new(&format!("'{CALLER_RUSTC_ARG_PREFIX}(.*)'")).unwrap();
+    }
+    type Item = (u64, u64);
+    let item_to_def_id = |item: Item| -> DefId {
+        let hash = DefPathHash(Fingerprint::new(item.0, item.1));
+        tcx.def_path_hash_to_def_id(hash, &mut || panic!())
+    };
+    let Some(captures) = tcx.sess.opts.cg.llvm_args.iter().find_map(|arg| RE.captures(arg)) else {
+        return FxHashMap::default();
+    };
+    let triples: Vec<(Item, Item, Item)> =
+        serde_json::from_str(captures.get(1).unwrap().as_str()).unwrap();
+    triples
+        .into_iter()
+        .map(|(caller, original, stub)| {
+            ((item_to_def_id(caller), item_to_def_id(original)), item_to_def_id(stub))
+        })
+        .collect()
+}
+
+/// The prefix we will use when serializing the specifications of the stubs
+/// given by `kani::stub_spec` as a rustc argument.
+const SPEC_RUSTC_ARG_PREFIX: &str = "kani_spec_stubs=";
+
+/// Serializes the specifications of the stubs into a rustc argument, in the
+/// same way as [mk_rustc_arg] does for the stub mapping. Each specification is
+/// written in the form it is parsed from.
+pub fn mk_spec_rustc_arg(spec_stubs: &SpecStubs) -> String {
+    let mut pairs: Vec<_> = spec_stubs
+        .iter()
+        .map(|(original, spec)| (original.0.as_value(), spec.to_string()))
+        .collect();
+    pairs.sort_unstable();
+    format!("-Cllvm-args='{SPEC_RUSTC_ARG_PREFIX}{}'", serde_json::to_string(&pairs).unwrap())
+}
+
+/// Retrieves the specifications of the stubs from the compiler configuration,
+/// keyed by the original function/method.
+fn get_spec_stub_mapping(tcx: TyCtxt) -> FxHashMap<DefId, StubSpec> {
+    // Use a static so that we compile the regex only once.
+    lazy_static! {
+        static ref RE: Regex = Regex::new(&format!("'{SPEC_RUSTC_ARG_PREFIX}(.*)'")).unwrap();
+    }
+    let Some(captures) = tcx.sess.opts.cg.llvm_args.iter().find_map(|arg| RE.captures(arg)) else {
+        return FxHashMap::default();
+    };
+    let pairs: Vec<((u64, u64), String)> =
+        serde_json::from_str(captures.get(1).unwrap().as_str()).unwrap();
+    pairs
+        .into_iter()
+        .map(|(original, spec)| {
+            let hash = DefPathHash(Fingerprint::new(original.0, original.1));
+            (tcx.def_path_hash_to_def_id(hash, &mut || panic!()), spec.parse().unwrap())
+        })
+        .collect()
+}
+
 /// Retrieves the stub mapping from the compiler configuration.
 fn get_stub_mapping(tcx: TyCtxt) -> Option<FxHashMap<DefId, DefId>> {
     // Use a static so that we compile the regex only once.
@@ -157,3 +2098,139 @@ fn get_stub_mapping(tcx: TyCtxt) -> Option<FxHashMap<DefId, DefId>> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rustc_data_structures::fx::FxHashMap;
+    use rustc_hir::def_id::CRATE_DEF_ID;
+    use rustc_middle::mir::TerminatorKind;
+    use rustc_middle::ty::{self, TyCtxt};
+
+    use super::{
+        apply_stub_mapping, is_oversized_replacement, take_outcomes, BodySize, StubbingPass,
+    };
+    use crate::kani_middle::resolve::{qualified_path_str, resolve_path};
+    use crate::kani_middle::stubbing::test_utils::check_crate;
+
+    const SOURCE: &str = r#"
+        pub fn zero() -> u32 { 0 }
+        pub fn one() -> u32 { 1 }
+        pub fn two() -> u32 { 2 }
+        pub fn call_zero() -> u32 { zero() }
+        pub fn add(x: u32, y: u32) -> u32 { x + y }
+    "#;
+
+    /// Runs the check against [SOURCE], which is compiled as crate `transform_test`.
+    fn check(name: &str, check: impl for<'tcx> FnMut(TyCtxt<'tcx>) + Send) {
+        check_crate(SOURCE, "transform_test", name, &[], check);
+    }
+
+    #[test]
+    fn independent_mappings() {
+        let mut checked = false;
+        check("mappings", |tcx| {
+            let def_id = |path| resolve_path(tcx, CRATE_DEF_ID, path).unwrap();
+            let zero = def_id("zero");
+            let to_one = StubbingPass::with_mapping(FxHashMap::from_iter([(zero, def_id("one"))]));
+            let to_two = StubbingPass::with_mapping(FxHashMap::from_iter([(zero, def_id("two"))]));
+            let body = tcx.optimized_mir(zero);
+            let source = |pass: &StubbingPass| {
+                qualified_path_str(tcx, pass.run_pass(tcx, zero, body).0.source.def_id())
+            };
+            assert_eq!(source(&to_one), "transform_test::one");
+            assert_eq!(source(&to_two), "transform_test::two");
+            assert_eq!(source(&to_one), "transform_test::one");
+            // The outcomes are returned by the pass instead of being recorded globally.
+            let (_, outcomes) = to_two.run_pass(tcx, zero, body);
+            assert_eq!(outcomes.applied, [tcx.def_path_hash(zero)]);
+            assert!(take_outcomes().applied.is_empty());
+            // A function/method that is not in the mapping keeps its body.
+            let one = def_id("one");
+            let (new_body, outcomes) = to_two.run_pass(tcx, one, tcx.optimized_mir(one));
+            assert!(std::ptr::eq(new_body, tcx.optimized_mir(one)));
+            assert!(outcomes.applied.is_empty());
+            checked = true;
+        });
+        assert!(checked);
+    }
+
+    #[test]
+    fn independent_caller_stubs() {
+        let mut callees = Vec::new();
+        check("callers", |tcx| {
+            let def_id = |path| resolve_path(tcx, CRATE_DEF_ID, path).unwrap();
+            let (caller, zero) = (def_id("call_zero"), def_id("zero"));
+            let pass = |stub| {
+                let caller_stubs = FxHashMap::from_iter([((caller, zero), def_id(stub))]);
+                StubbingPass::default().with_caller_stubs(caller_stubs)
+            };
+            let passes = [pass("one"), pass("two"), StubbingPass::default()];
+            for pass in &passes {
+                let (body, _) = pass.run_pass(tcx, caller, tcx.optimized_mir(caller));
+                let callee =
+                    body.basic_blocks.iter().find_map(|block| match &block.terminator().kind {
+                        TerminatorKind::Call { func, .. } => match func.constant()?.ty().kind() {
+                            ty::FnDef(callee, _) => Some(qualified_path_str(tcx, *callee)),
+                            _ => None,
+                        },
+                        _ => None,
+                    });
+                callees.push(callee.unwrap());
+            }
+        });
+        assert_eq!(callees, ["transform_test::one", "transform_test::two", "transform_test::zero"]);
+    }
+
+    #[test]
+    fn apply_mapping_outcomes() {
+        let mut outcomes = Vec::new();
+        check("apply", |tcx| {
+            let def_id = |path| resolve_path(tcx, CRATE_DEF_ID, path).unwrap();
+            let zero = def_id("zero");
+            let mapping = |stub: &str| HashMap::from([("zero".to_string(), stub.to_string())]);
+            let apply = |mapping: HashMap<String, String>| {
+                let mut body = tcx.optimized_mir(zero).clone();
+                let replaced = apply_stub_mapping(tcx, zero, &mut body, &mapping);
+                (replaced, qualified_path_str(tcx, body.source.def_id()))
+            };
+            // The stub is applied.
+            outcomes.push(apply(mapping("one")));
+            // The function is not in the mapping.
+            outcomes.push(apply(HashMap::from([("one".to_string(), "two".to_string())])));
+            // The paths that do not resolve are reported.
+            outcomes.push(apply(HashMap::from([
+                ("zero".to_string(), "missing".to_string()),
+                ("absent".to_string(), "one".to_string()),
+            ])));
+            // The signature of the stub does not match, which is reported as an error.
+            outcomes.push(apply(mapping("add")));
+        });
+        let zero = "transform_test::zero".to_string();
+        assert_eq!(
+            outcomes,
+            [
+                (Ok(true), "transform_test::one".to_string()),
+                (Ok(false), zero.clone()),
+                (Err(vec!["absent".to_string(), "missing".to_string()]), zero.clone()),
+                (Ok(false), zero),
+            ]
+        );
+    }
+
+    #[test]
+    fn oversized_replacement_heuristic() {
+        let size = |blocks, statements| BodySize { blocks, statements };
+        // More than 4 times as many statements as the original.
+        assert!(is_oversized_replacement(size(1, 2), size(1, 9), 4));
+        // More than 4 times as many basic blocks as the original.
+        assert!(is_oversized_replacement(size(1, 10), size(5, 10), 4));
+        // Within the ratio.
+        assert!(!is_oversized_replacement(size(2, 2), size(8, 8), 4));
+        assert!(is_oversized_replacement(size(2, 2), size(8, 8), 3));
+        // Too small to matter, even if the original is empty.
+        assert!(!is_oversized_replacement(size(1, 0), size(1, 4), 4));
+        assert!(is_oversized_replacement(size(1, 0), size(1, 5), 4));
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This file contains code for validating a stub mapping provided by the user
+//! (e.g., by an editor or a pre-commit hook) without applying the stubs.
+
+use std::collections::HashMap;
+
+use rustc_hir::def_id::{DefId, CRATE_DEF_ID};
+use rustc_middle::ty::TyCtxt;
+
+use crate::kani_middle::resolve::{qualified_path_str, resolve_path};
+use crate::kani_middle::stubbing::stub_incompatibilities;
+
+/// The outcome of validating one entry of a stub mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StubStatus {
+    /// Both paths resolve and the stub can replace the original function/method.
+    Valid,
+    /// At least one of the paths does not resolve to a function/method.
+    Unresolved,
+    /// Both paths resolve, but the stub cannot replace the original
+    /// function/method for the given reasons.
+    Incompatible(Vec<String>),
+}
+
+/// The result of validating one entry of a stub mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubMappingEntry {
+    /// The path of the original function/method, as provided by the user.
+    pub original: String,
+    /// The path of the stub, as provided by the user.
+    pub replacement: String,
+    /// The qualified path of the original function/method, if it resolves.
+    pub resolved_original: Option<String>,
+    /// The qualified path of the stub, if it resolves.
+    pub resolved_replacement: Option<String>,
+    pub status: StubStatus,
+}
+
+/// The result of validating a stub mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StubMappingReport {
+    /// One entry per pair of the mapping, sorted by the path of the original
+    /// function/method.
+    pub entries: Vec<StubMappingEntry>,
+}
+
+impl StubMappingReport {
+    /// Whether every entry of the mapping is valid.
+    pub fn is_valid(&self) -> bool {
+        self.entries.iter().all(|entry| entry.status == StubStatus::Valid)
+    }
+}
+
+/// Validates a mapping from the path of each original function/method to the
+/// path of its stub. The paths are resolved relative to the crate root, and
+/// the stubs are checked the same way as the ones from `kani::stub`
+/// attributes; if `strict` is set, this includes the checks enabled by
+/// `--verify-stub-types`, and if `allow_shared_refs` is set, the stubs that
+/// take `&T` for `&mut T` are accepted like with `--allow-stub-shared-refs`.
+/// Apart from the ones reported by the path resolution (e.g., for ambiguous
+/// glob imports), no error is emitted.
+pub fn validate_stub_mapping(
+    tcx: TyCtxt,
+    mapping: &HashMap<String, String>,
+    strict: bool,
+    allow_shared_refs: bool,
+) -> StubMappingReport {
+    let mut pairs: Vec<_> = mapping.iter().collect();
+    pairs.sort();
+    let entries = pairs
+        .into_iter()
+        .map(|(original, replacement)| {
+            let orig_id = resolve_path(tcx, CRATE_DEF_ID, original);
+            let stub_id = resolve_path(tcx, CRATE_DEF_ID, replacement);
+            let status = match (orig_id, stub_id) {
+                (Some(orig_id), Some(stub_id)) => {
+                    let reasons =
+                        stub_incompatibilities(tcx, orig_id, stub_id, strict, allow_shared_refs);
+                    if reasons.is_empty() {
+                        StubStatus::Valid
+                    } else {
+                        StubStatus::Incompatible(reasons)
+                    }
+                }
+                _ => StubStatus::Unresolved,
+            };
+            let path = |def_id: Option<DefId>| def_id.map(|def_id| qualified_path_str(tcx, def_id));
+            StubMappingEntry {
+                original: original.clone(),
+                replacement: replacement.clone(),
+                resolved_original: path(orig_id),
+                resolved_replacement: path(stub_id),
+                status,
+            }
+        })
+        .collect();
+    StubMappingReport { entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_stub_mapping, StubMappingReport, StubStatus};
+    use crate::kani_middle::stubbing::test_utils::check_crate;
+
+    const SOURCE: &str = r#"
+        pub mod ops {
+            pub fn zero() -> u32 { 0 }
+            pub fn add(a: u32, b: u32) -> u32 { a + b }
+        }
+        pub fn forty_two() -> u32 { 42 }
+        pub fn small_zero() -> u8 { 0 }
+        pub unsafe fn unsafe_zero() -> u32 { 0 }
+    "#;
+
+    /// Validates the mapping against [SOURCE], which is compiled as crate `validate_test`.
+    fn validate(name: &str, mapping: &[(&str, &str)], strict: bool) -> StubMappingReport {
+        let mapping = mapping.iter().map(|(orig, stub)| (orig.to_string(), stub.to_string()));
+        let mapping = mapping.collect();
+        let mut report = None;
+        check_crate(SOURCE, "validate_test", name, &[], |tcx| {
+            report = Some(validate_stub_mapping(tcx, &mapping, strict, false));
+        });
+        report.expect("the crate should compile")
+    }
+
+    #[test]
+    fn valid_mapping() {
+        let report = validate(
+            "valid",
+            &[("ops::zero", "forty_two"), ("crate::forty_two", "ops::zero")],
+            false,
+        );
+        assert!(report.is_valid());
+        let entry = &report.entries[0];
+        assert_eq!(entry.original, "crate::forty_two");
+        assert_eq!(entry.resolved_original.as_deref(), Some("validate_test::forty_two"));
+        assert_eq!(entry.resolved_replacement.as_deref(), Some("validate_test::ops::zero"));
+    }
+
+    #[test]
+    fn invalid_mapping() {
+        let report = validate(
+            "invalid",
+            &[
+                ("missing", "forty_two"),
+                ("ops::add", "forty_two"),
+                ("ops::zero", "small_zero"),
+                ("forty_two", "ops::zero"),
+            ],
+            false,
+        );
+        assert!(!report.is_valid());
+        let statuses: Vec<_> =
+            report.entries.iter().map(|entry| (entry.original.as_str(), &entry.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("forty_two", &StubStatus::Valid),
+                ("missing", &StubStatus::Unresolved),
+                (
+                    "ops::add",
+                    &StubStatus::Incompatible(vec![
+                        "arity mismatch: original function/method `validate_test::ops::add` takes 2 \
+                        argument(s), stub `validate_test::forty_two` takes 0"
+                            .to_string()
+                    ])
+                ),
+                (
+                    "ops::zero",
+                    &StubStatus::Incompatible(vec![
+                        "return type differs: stub `validate_test::small_zero` has type `u8` where \
+                        original function/method `validate_test::ops::zero` has type `u32`"
+                            .to_string()
+                    ])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_mapping() {
+        let mapping = [("ops::zero", "unsafe_zero")];
+        assert!(validate("lenient", &mapping, false).is_valid());
+        let report = validate("strict", &mapping, true);
+        assert!(matches!(&report.entries[0].status, StubStatus::Incompatible(reasons)
+            if reasons[0].starts_with("unsafety differs")));
+    }
+}
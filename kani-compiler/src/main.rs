@@ -13,19 +13,24 @@
 #![feature(once_cell)]
 #![feature(rustc_private)]
 #![feature(more_qualified_paths)]
+#![feature(min_specialization)]
 extern crate rustc_ast;
+extern crate rustc_attr;
 extern crate rustc_codegen_ssa;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_index;
+extern crate rustc_infer;
 extern crate rustc_interface;
 extern crate rustc_metadata;
 extern crate rustc_middle;
+extern crate rustc_serialize;
 extern crate rustc_session;
 extern crate rustc_span;
 extern crate rustc_target;
+extern crate rustc_trait_selection;
 // We can't add this directly as a dependency because we need the version to match rustc
 extern crate tempfile;
 
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use clap::{builder::PossibleValuesParser, command, Arg, ArgAction, ArgMatches, Command};
-use kani_queries::ReachabilityType;
+use kani_queries::{CallGraphFormat, ReachabilityType, ReportFormat, RustEdition, StubSource};
 use std::env;
 use std::str::FromStr;
 use strum::VariantNames as _;
@@ -43,6 +43,146 @@
 /// Option name used to enable stubbing.
 pub const ENABLE_STUBBING: &str = "enable-stubbing";
 
+/// Option name used to allow stubbing functions from the standard library.
+pub const ALLOW_STD_STUBBING: &str = "allow-std-stubbing";
+
+/// Option name used to let a stub replace the body of the harness itself.
+pub const STUB_HARNESS_BODY: &str = "stub-harness-body";
+
+/// Option name used to only apply stubs whose compatibility can be fully verified.
+pub const VERIFY_STUB_TYPES: &str = "verify-stub-types";
+
+/// Option name used to keep the signature metadata of stubbed functions.
+pub const PRESERVE_STUB_SIGNATURE: &str = "preserve-stub-signature";
+
+/// Option name used to report the time spent resolving stub paths.
+pub const STUB_RESOLUTION_STATS: &str = "stub-resolution-stats";
+
+/// Option name used to report the time and memory taken by the compilation that collects the stubs.
+pub const STUB_COLLECTION_STATS: &str = "stub-collection-stats";
+
+/// Option name used to print a summary of the stubs of each harness at the end of the compilation.
+pub const STUB_SUMMARY: &str = "stub-summary";
+
+/// Option name used to emit the errors and warnings of the stub collection as a json file.
+pub const EMIT_STUB_DIAGNOSTICS: &str = "emit-stub-diagnostics";
+
+/// Option name used to reject the stubs that are stubbed themselves by the same harness.
+pub const DENY_CHAINED_STUBS: &str = "deny-chained-stubs";
+
+/// Option name used to report an error for the stubs that were never applied.
+pub const DENY_UNAPPLIED_STUBS: &str = "deny-unapplied-stubs";
+
+/// Option name used to skip the stubs of the harnesses with stub errors instead of stopping.
+pub const CONTINUE_ON_STUB_ERRORS: &str = "continue-on-stub-errors";
+
+/// Option name used to check that each call to a stubbed function/method type checks with the
+/// stub.
+pub const CHECK_STUB_CALL_SITES: &str = "check-stub-call-sites";
+
+/// Option name used to apply the stubs of a crate that is not compiled with the `kani` cfg.
+pub const STUB_WITHOUT_KANI_CFG: &str = "stub-without-kani-cfg";
+
+/// Option name used to warn about the harnesses without stubs that import a mock module.
+pub const WARN_MOCK_IMPORTS: &str = "warn-mock-imports";
+
+/// Option name used to check the body of each stub against the hash recorded in a file.
+pub const STUB_BODY_HASHES: &str = "stub-body-hashes";
+
+/// Option name used to dump the MIR reachable from a harness once the stubs are applied.
+pub const EMIT_STUBBED_MIR: &str = "emit-stubbed-mir";
+
+/// Option name used to set the precedence of the stub sources.
+pub const STUB_SOURCE_PRECEDENCE: &str = "stub-source-precedence";
+
+/// Option name used to apply the stubs marked `kani::experimental_stub`.
+pub const ENABLE_EXPERIMENTAL_STUBS: &str = "enable-experimental-stubs";
+
+/// Option name used to let a stub take `&T` for a parameter of type `&mut T`.
+pub const ALLOW_STUB_SHARED_REFS: &str = "allow-stub-shared-refs";
+
+/// Option name used to limit the number of functions matched by a `kani::stub_regex` attribute.
+pub const STUB_REGEX_LIMIT: &str = "stub-regex-limit";
+
+/// Option name used to set the ratio above which a stub is reported as more complex than the
+/// function it replaces.
+pub const STUB_COMPLEXITY_RATIO: &str = "stub-complexity-ratio";
+
+/// Option name used to limit the number of modules the resolution of a stub path may go through.
+pub const STUB_RESOLUTION_DEPTH: &str = "stub-resolution-depth";
+
+/// Option name used to limit the number of functions listed in the error for an unresolved stub
+/// path.
+pub const STUB_ERROR_CANDIDATES: &str = "stub-error-candidates";
+
+/// Option name used to set the edition the stub paths are resolved against.
+pub const STUB_PATH_EDITION: &str = "stub-path-edition";
+
+/// Option name used to print the `use` statements that the resolution of each stub path goes
+/// through.
+pub const TRACE_STUB_RESOLUTION: &str = "trace-stub-resolution";
+
+/// Option name used to resolve the stub paths that are exactly the `def_path_str` of a
+/// function/method directly.
+pub const STUB_EXACT_PATHS: &str = "stub-exact-paths";
+
+/// Option name used to emit the stub mapping of the current harness as a Rust source file.
+pub const EMIT_STUB_MAPPING: &str = "emit-stub-mapping";
+
+/// Option name used to print the final stub mapping of the current harness.
+pub const PRINT_STUB_MAPPING: &str = "print-stub-mapping";
+
+/// Option name used to emit the functions and methods that may be stubbed.
+pub const EMIT_STUB_CANDIDATES: &str = "emit-stub-candidates";
+
+/// Option name used to emit the number of calls to each stubbed function/method in each caller.
+pub const EMIT_STUB_CALLER_COUNTS: &str = "emit-stub-caller-counts";
+
+/// Option name used to include the stub candidates from other crates.
+pub const STUB_CANDIDATES_INCLUDE_FOREIGN: &str = "stub-candidates-include-foreign";
+
+/// Option name used to emit the metadata of each harness together with its resolved stubs.
+pub const EMIT_HARNESS_METADATA: &str = "emit-harness-metadata";
+
+/// Option name used to emit the verification plan of the crate.
+pub const EMIT_VERIFICATION_PLAN: &str = "emit-verification-plan";
+
+/// Option name used to emit the reachable functions that have no MIR.
+pub const EMIT_NO_MIR_REPORT: &str = "emit-no-mir-report";
+
+/// Option name used to emit the functions reachable from each proof harness.
+pub const EMIT_HARNESS_REACHABILITY: &str = "emit-harness-reachability";
+
+/// Option name used to set the directory where the reachability analysis is cached.
+pub const REACHABILITY_CACHE_DIR: &str = "reachability-cache-dir";
+
+/// Option name used to set the directory where the files emitted on request are written.
+pub const OUTPUT_DIR: &str = "output-dir";
+
+/// Option name used to validate a stub mapping instead of compiling the crate.
+pub const VALIDATE_STUB_MAPPING: &str = "validate-stub-mapping";
+
+/// Option name used to emit the call graph of the reachable items.
+pub const EMIT_CALL_GRAPH: &str = "emit-call-graph";
+
+/// Option name used to print the number of reachable functions of each crate.
+pub const REACHABLE_COUNTS: &str = "reachable-counts";
+
+/// Option name used to report the compiler-generated items omitted by the reachability analysis.
+pub const INCLUDE_SYNTHETIC_ITEMS: &str = "include-synthetic-items";
+
+/// Option name used to only run the identity pass instead of the Kani MIR passes.
+pub const IDENTITY_PASSES_ONLY: &str = "identity-passes-only";
+
+/// Option name used to emit the advisory messages produced by the compiler passes.
+pub const EMIT_ADVISORIES: &str = "emit-advisories";
+
+/// Option name used to emit the timings of the Kani MIR passes in the Chrome Trace Event format.
+pub const EMIT_PASS_TRACE: &str = "emit-pass-trace";
+
+/// Option name used to load the unwind bounds, stubs and reachability settings from a file.
+pub const VERIFICATION_CONFIG: &str = "verification-config";
+
 /// Configure command options for the Kani compiler.
 pub fn parser() -> Command {
     let app = command!()
@@ -137,6 +277,419 @@ pub fn parser() -> Command {
                 .help("Instruct the compiler to perform stubbing.")
                 .requires(HARNESS)
                 .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(ALLOW_STD_STUBBING)
+                .long(ALLOW_STD_STUBBING)
+                .help(
+                    "Do not warn about stubs that replace functions from `std`, `core` or `alloc`.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_HARNESS_BODY)
+                .long(STUB_HARNESS_BODY)
+                .help("Apply the stubs whose original function is the harness itself.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(VERIFY_STUB_TYPES)
+                .long(VERIFY_STUB_TYPES)
+                .help("Reject any stub whose compatibility with the original function cannot be verified.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(PRESERVE_STUB_SIGNATURE)
+                .long(PRESERVE_STUB_SIGNATURE)
+                .help("Only replace the body of stubbed functions, keeping their signature metadata.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_RESOLUTION_STATS)
+                .long(STUB_RESOLUTION_STATS)
+                .help("Print how long the resolution of stub paths took and how many paths were resolved.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_COLLECTION_STATS)
+                .long(STUB_COLLECTION_STATS)
+                .help(
+                    "Print how long the compilation that collects the stubs took and the peak \
+                    memory usage of the compiler at its end, where available.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_SUMMARY)
+                .long(STUB_SUMMARY)
+                .help("Print how many stubs of each harness were requested, resolved, applied and skipped. \
+                The summary is printed as json if `--pretty-json-files` is set.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_STUB_DIAGNOSTICS)
+                .long(EMIT_STUB_DIAGNOSTICS)
+                .help(
+                    "Emit the errors and warnings found while collecting the stubs as a json \
+                    array, in addition to the compiler diagnostics.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(DENY_CHAINED_STUBS)
+                .long(DENY_CHAINED_STUBS)
+                .help(
+                    "Report an error if the stub of a function/method is also stubbed by the \
+                    same harness.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(DENY_UNAPPLIED_STUBS)
+                .long(DENY_UNAPPLIED_STUBS)
+                .help(
+                    "Report an error for the stubs of the harness that were never applied, e.g., \
+                    because their original function/method is not reachable.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(CONTINUE_ON_STUB_ERRORS)
+                .long(CONTINUE_ON_STUB_ERRORS)
+                .help(
+                    "Report the stub errors of the harnesses other than the current one as \
+                    warnings and skip their stubs, instead of stopping the compilation.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(CHECK_STUB_CALL_SITES)
+                .long(CHECK_STUB_CALL_SITES)
+                .help(
+                    "Report the calls to a stubbed function/method that would not type check if \
+                    they called the stub, given their generic arguments.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_WITHOUT_KANI_CFG)
+                .long(STUB_WITHOUT_KANI_CFG)
+                .help(
+                    "Collect and apply the stubs even if the crate is not compiled with the \
+                    `kani` cfg. The stubs are ignored otherwise.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(WARN_MOCK_IMPORTS)
+                .long(WARN_MOCK_IMPORTS)
+                .help(
+                    "Warn about the harnesses that import a mock module (e.g., `mocks`) but have \
+                    no stubs.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_BODY_HASHES)
+                .long(STUB_BODY_HASHES)
+                .help(
+                    "Record the hash of the body of each stub in the given json file, and warn \
+                    about the stubs whose body no longer matches the recorded hash.",
+                )
+                .value_name("FILE")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(EMIT_STUBBED_MIR)
+                .long(EMIT_STUBBED_MIR)
+                .help(
+                    "Dump the MIR of the given harness and of the bodies reachable from it, once \
+                    the stubs are applied.",
+                )
+                .value_name("HARNESS")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(STUB_SOURCE_PRECEDENCE)
+                .long(STUB_SOURCE_PRECEDENCE)
+                .value_parser(PossibleValuesParser::new(StubSource::VARIANTS))
+                .value_delimiter(',')
+                .help(
+                    "The stub sources from the highest to the lowest precedence, separated by \
+                    commas. If two sources stub the same function differently, the stub from \
+                    the source with the highest precedence is used.",
+                )
+                .value_name("SOURCES")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new(ENABLE_EXPERIMENTAL_STUBS)
+                .long(ENABLE_EXPERIMENTAL_STUBS)
+                .help("Apply the stubs marked `kani::experimental_stub`, which are skipped otherwise.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(ALLOW_STUB_SHARED_REFS)
+                .long(ALLOW_STUB_SHARED_REFS)
+                .help(
+                    "Accept the stubs that take `&T` for a parameter of type `&mut T` of the \
+                    original function/method.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_REGEX_LIMIT)
+                .long(STUB_REGEX_LIMIT)
+                .help("Maximum number of functions a `kani::stub_regex` attribute may match.")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(STUB_COMPLEXITY_RATIO)
+                .long(STUB_COMPLEXITY_RATIO)
+                .help(
+                    "Report the stubs with more than N times as many basic blocks or statements \
+                    as the function they replace.",
+                )
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(STUB_RESOLUTION_DEPTH)
+                .long(STUB_RESOLUTION_DEPTH)
+                .help(
+                    "Maximum number of modules (including re-exports) the resolution of a stub \
+                    path may go through.",
+                )
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(STUB_ERROR_CANDIDATES)
+                .long(STUB_ERROR_CANDIDATES)
+                .help(
+                    "Maximum number of nearest functions/methods listed in the error for a stub \
+                    path that does not resolve. Use 0 to list none.",
+                )
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(STUB_PATH_EDITION)
+                .long(STUB_PATH_EDITION)
+                .value_parser(PossibleValuesParser::new(RustEdition::VARIANTS))
+                .help(
+                    "The edition the stub paths are resolved against. It is an error if the \
+                    crate declares a different edition; otherwise, the crate is compiled with it.",
+                )
+                .value_name("EDITION")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(TRACE_STUB_RESOLUTION)
+                .long(TRACE_STUB_RESOLUTION)
+                .help(
+                    "Print the chain of `use` statements that the resolution of each stub path \
+                    goes through.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_EXACT_PATHS)
+                .long(STUB_EXACT_PATHS)
+                .help(
+                    "Resolve the stub paths that are exactly the `def_path_str` of a \
+                    function/method to it, without the relative resolution.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_STUB_MAPPING)
+                .long(EMIT_STUB_MAPPING)
+                .help("Emit the stub mapping of the current harness as a Rust source file.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(PRINT_STUB_MAPPING)
+                .long(PRINT_STUB_MAPPING)
+                .help("Print the stub mapping of the current harness once the stubs of the \
+                attributes and of the verification config are merged. The mapping is printed \
+                as json if `--pretty-json-files` is set.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_STUB_CANDIDATES)
+                .long(EMIT_STUB_CANDIDATES)
+                .help("Emit the paths of the functions and methods that may be stubbed.")
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(STUB_CANDIDATES_INCLUDE_FOREIGN)
+                .long(STUB_CANDIDATES_INCLUDE_FOREIGN)
+                .help("Include the functions and methods from other crates in the stub candidates.")
+                .requires(EMIT_STUB_CANDIDATES)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_STUB_CALLER_COUNTS)
+                .long(EMIT_STUB_CALLER_COUNTS)
+                .help(
+                    "Emit the number of calls to each stubbed function/method in the body of \
+                    each caller.",
+                )
+                .requires(ENABLE_STUBBING)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_HARNESS_METADATA)
+                .long(EMIT_HARNESS_METADATA)
+                .help("Emit the metadata of each harness together with its resolved stubs.")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_VERIFICATION_PLAN)
+                .long(EMIT_VERIFICATION_PLAN)
+                .help(
+                    "Emit the harnesses, resolved stubs, reachability roots and options of the \
+                    crate in one file.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_NO_MIR_REPORT)
+                .long(EMIT_NO_MIR_REPORT)
+                .help(
+                    "Emit the reachable functions that have no MIR, such as foreign functions \
+                and intrinsics, together with the functions that call them.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_HARNESS_REACHABILITY)
+                .long(EMIT_HARNESS_REACHABILITY)
+                .help(
+                    "Emit the functions reachable from each proof harness on its own, keyed by \
+                    the name of the harness.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(REACHABILITY_CACHE_DIR)
+                .long(REACHABILITY_CACHE_DIR)
+                .help("Cache the result of the reachability analysis in the given directory.")
+                .value_name("DIR")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(OUTPUT_DIR)
+                .long(OUTPUT_DIR)
+                .help(
+                    "Write the files emitted on request (e.g., the call graph) in the given \
+                    directory instead of next to the other artifacts.",
+                )
+                .value_name("DIR")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(VALIDATE_STUB_MAPPING)
+                .long(VALIDATE_STUB_MAPPING)
+                .help(
+                    "Validate the stub mapping in the given json file (an object that maps each \
+                    original function/method path to its stub path), print a report, and stop.",
+                )
+                .value_name("FILE")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(EMIT_CALL_GRAPH)
+                .long(EMIT_CALL_GRAPH)
+                .value_parser(PossibleValuesParser::new(CallGraphFormat::VARIANTS))
+                .help("Emit the call graph of the reachable items in the given format.")
+                .value_name("FORMAT")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(REACHABLE_COUNTS)
+                .long(REACHABLE_COUNTS)
+                .value_parser(PossibleValuesParser::new(ReportFormat::VARIANTS))
+                .help("Print the number of reachable functions of each crate in the given format.")
+                .value_name("FORMAT")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(INCLUDE_SYNTHETIC_ITEMS)
+                .long(INCLUDE_SYNTHETIC_ITEMS)
+                .help(
+                    "Include the compiler-generated items, such as drop glue, in the reported \
+                    reachable items. This doesn't change which items are compiled.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(IDENTITY_PASSES_ONLY)
+                .long(IDENTITY_PASSES_ONLY)
+                .help(
+                    "Only run a pass that leaves the MIR unchanged instead of the Kani MIR passes \
+                    (e.g., stubbing). This is meant to benchmark the pass infrastructure.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_ADVISORIES)
+                .long(EMIT_ADVISORIES)
+                .help("Emit the advisory messages produced by the compiler passes as a json file.")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(EMIT_PASS_TRACE)
+                .long(EMIT_PASS_TRACE)
+                .help(
+                    "Emit the time each Kani MIR pass takes on each body as a json file in the \
+                    Chrome Trace Event format.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(VERIFICATION_CONFIG)
+                .long(VERIFICATION_CONFIG)
+                .help(
+                    "Load the unwind bounds, stub mappings and reachability settings of the \
+                    crate from the given json file.",
+                )
+                .value_name("FILE")
+                .action(ArgAction::Set),
         );
     #[cfg(feature = "unsound_experiments")]
     let app = crate::unsound_experiments::arg_parser::add_unsound_experiments_to_parser(app);
@@ -146,6 +699,10 @@ pub fn parser() -> Command {
 
 pub trait KaniCompilerParser {
     fn reachability_type(&self) -> ReachabilityType;
+    fn call_graph_format(&self) -> Option<CallGraphFormat>;
+    fn reachable_counts_format(&self) -> Option<ReportFormat>;
+    fn stub_source_precedence(&self) -> Option<Vec<StubSource>>;
+    fn stub_path_edition(&self) -> Option<RustEdition>;
 }
 
 impl KaniCompilerParser for ArgMatches {
@@ -153,6 +710,23 @@ fn reachability_type(&self) -> ReachabilityType {
         self.get_one::<String>(REACHABILITY)
             .map_or(ReachabilityType::None, |arg| ReachabilityType::from_str(arg).unwrap())
     }
+
+    fn call_graph_format(&self) -> Option<CallGraphFormat> {
+        self.get_one::<String>(EMIT_CALL_GRAPH).map(|arg| CallGraphFormat::from_str(arg).unwrap())
+    }
+
+    fn reachable_counts_format(&self) -> Option<ReportFormat> {
+        self.get_one::<String>(REACHABLE_COUNTS).map(|arg| ReportFormat::from_str(arg).unwrap())
+    }
+
+    fn stub_source_precedence(&self) -> Option<Vec<StubSource>> {
+        let sources = self.get_many::<String>(STUB_SOURCE_PRECEDENCE)?;
+        Some(sources.map(|arg| StubSource::from_str(arg).unwrap()).collect())
+    }
+
+    fn stub_path_edition(&self) -> Option<RustEdition> {
+        self.get_one::<String>(STUB_PATH_EDITION).map(|arg| RustEdition::from_str(arg).unwrap())
+    }
 }
 
 /// Return whether we should run our flavour of the compiler, and which arguments to pass to rustc.
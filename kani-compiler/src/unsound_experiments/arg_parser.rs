@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 #![cfg(feature = "unsound_experiments")]
+use std::fs;
+
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use kani_queries::{QueryDb, UserInput};
 /// Option used for zero initilizing variables.
 const ZERO_INIT_VARS: &str = "unsound-experiment-zero-init-vars";
+/// Option used for replacing every local function except the given ones.
+const STUB_ALL_EXCEPT: &str = "unsound-experiment-stub-all-except";
+/// Option used for recording the unsound experiments that are enabled.
+const DUMP_CONFIG: &str = "unsound-experiment-dump-config";
 
 pub fn add_unsound_experiments_to_parser(app: Command) -> Command {
     app.arg(
@@ -14,10 +20,89 @@ pub fn add_unsound_experiments_to_parser(app: Command) -> Command {
             .help("POTENTIALLY UNSOUND EXPERIMENTAL FEATURE. Zero initialize variables")
             .action(ArgAction::SetTrue),
     )
+    .arg(
+        Arg::new(STUB_ALL_EXCEPT)
+            .long(STUB_ALL_EXCEPT)
+            .help(
+                "POTENTIALLY UNSOUND EXPERIMENTAL FEATURE. Replace every local function other \
+                than the harnesses and the given comma-separated paths by a function that \
+                returns a nondeterministic value",
+            )
+            .value_delimiter(',')
+            .action(ArgAction::Append),
+    )
+    .arg(
+        Arg::new(DUMP_CONFIG)
+            .long(DUMP_CONFIG)
+            .help(
+                "Print the unsound experiments that are enabled as json to stderr, or write them \
+                to the given file, so unsound runs can be audited",
+            )
+            .value_name("FILE")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("-")
+            .action(ArgAction::Set),
+    )
 }
 
 pub fn add_unsound_experiment_args_to_queries(queries: &mut QueryDb, matches: &ArgMatches) {
     let mut experiments = queries.get_unsound_experiments();
     experiments.zero_init_vars = matches.get_flag(ZERO_INIT_VARS);
+    experiments.stub_all_except =
+        matches.get_many::<String>(STUB_ALL_EXCEPT).map(|paths| paths.cloned().collect());
     queries.set_unsound_experiments(experiments);
 }
+
+/// Print the unsound experiments that are enabled if `--unsound-experiment-dump-config` is set.
+/// They are written to the given file, or to stderr if the option has no value.
+pub fn dump_unsound_experiments(queries: &QueryDb, matches: &ArgMatches) -> Result<(), String> {
+    let Some(target) = matches.get_one::<String>(DUMP_CONFIG) else {
+        return Ok(());
+    };
+    let json = unsound_experiments_json(queries);
+    if target == "-" {
+        eprintln!("{json}");
+        Ok(())
+    } else {
+        fs::write(target, json + "\n")
+            .map_err(|err| format!("failed to write the unsound experiments to `{target}`: {err}"))
+    }
+}
+
+fn unsound_experiments_json(queries: &QueryDb) -> String {
+    serde_json::to_string_pretty(&queries.get_unsound_experiments()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn experiments_from(args: &[&str]) -> serde_json::Value {
+        let mut all_args = vec!["kani-compiler"];
+        all_args.extend(args);
+        let matches = parser::parser().get_matches_from(all_args);
+        let mut queries = QueryDb::default();
+        add_unsound_experiment_args_to_queries(&mut queries, &matches);
+        serde_json::from_str(&unsound_experiments_json(&queries)).unwrap()
+    }
+
+    #[test]
+    fn dump_default_experiments() {
+        let experiments = experiments_from(&[]);
+        assert_eq!(experiments["zero_init_vars"], false);
+        assert!(experiments["stub_all_except"].is_null());
+    }
+
+    #[test]
+    fn dump_set_experiments() {
+        let experiments = experiments_from(&[
+            "--unsound-experiment-zero-init-vars",
+            "--unsound-experiment-stub-all-except=checksum,Buffer::len",
+            "--unsound-experiment-dump-config",
+        ]);
+        assert_eq!(experiments["zero_init_vars"], true);
+        assert_eq!(experiments["stub_all_except"], serde_json::json!(["checksum", "Buffer::len"]));
+    }
+}
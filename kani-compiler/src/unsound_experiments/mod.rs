@@ -4,4 +4,5 @@
 #![cfg(feature = "unsound_experiments")]
 
 pub mod arg_parser;
+mod stub_all_except;
 mod zero_init;
@@ -0,0 +1,43 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "unsound_experiments")]
+use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::attributes::partition_kanitool_attributes;
+use kani_queries::UserInput;
+use rustc_hir::def::DefKind;
+use rustc_middle::mir::{SourceInfo, Terminator, TerminatorKind};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Codegens a body that only returns a nondeterministic value if the stub-all-except flag
+    /// is set, and the current function is a local function that is neither a harness nor one
+    /// of the functions to keep. Returns whether it did so.
+    /// The return variable is declared without an initializer, which in CBMC translates to a
+    /// NONDET value. This value may not be valid for its type (e.g., a `bool` other than 0 or 1).
+    pub fn codegen_abstracted_body(&mut self) -> bool {
+        let Some(keep) = self.queries.get_unsound_experiments().stub_all_except else {
+            return false;
+        };
+        let def_id = self.current_fn().instance().def_id();
+        if !def_id.is_local()
+            || !matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        {
+            return false;
+        }
+        let (proof, _) = partition_kanitool_attributes(self.tcx.get_attrs_unchecked(def_id));
+        let mir = self.current_fn().mir();
+        if !proof.is_empty()
+            || mir.return_ty().is_never()
+            || keep.contains(&self.tcx.def_path_str(def_id))
+        {
+            return false;
+        }
+        let ret = Terminator {
+            source_info: SourceInfo::outermost(mir.span),
+            kind: TerminatorKind::Return,
+        };
+        let stmt = self.codegen_terminator(&ret);
+        self.current_fn_mut().push_onto_block(stmt);
+        true
+    }
+}
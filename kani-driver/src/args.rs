@@ -237,6 +237,312 @@ pub struct KaniArgs {
     )]
     pub enable_stubbing: bool,
 
+    /// Allow stubs to replace functions from `std`, `core` and `alloc` without a warning.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub allow_std_stubbing: bool,
+
+    /// Apply the stubs whose original function is the harness itself, which replaces the body of
+    /// the harness. By default, the harness is verified as written.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_harness_body: bool,
+
+    /// Reject any stub whose compatibility with the original function cannot be fully verified,
+    /// which includes its qualifiers (e.g. `const` and `unsafe`) and trait bounds.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub verify_stub_types: bool,
+
+    /// Only replace the body of stubbed functions, so the generated code keeps the location,
+    /// parameter names and other signature metadata of the original function.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub preserve_stub_signature: bool,
+
+    /// Print how long Kani took to resolve the paths in `kani::stub` attributes, and how many
+    /// paths it resolved.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_resolution_stats: bool,
+
+    /// Print how long the compilation that collects the stubs took, and the peak memory usage of
+    /// the compiler at its end (only available on Linux). This compilation only runs when some
+    /// harness has stubs, and it comes on top of the one that applies them.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_collection_stats: bool,
+
+    /// Print a table with the number of stubs of each harness that were requested, resolved,
+    /// applied and skipped at the end of the compilation, followed by the stubbing warnings.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_summary: bool,
+
+    /// Emit the errors and warnings found while collecting the stubs in a JSON file
+    /// (`<crate>.stub_diagnostics.json`) next to the other artifacts, as an array of objects with
+    /// the severity, code, message, span and related paths of each diagnostic. They are still
+    /// reported as compiler diagnostics.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub emit_stub_diagnostics: bool,
+
+    /// Report an error if the stub of a function/method is itself stubbed by the same harness
+    /// (e.g., `#[kani::stub(a, b)]` together with `#[kani::stub(b, c)]`), so the harness states
+    /// which replacement it means.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub deny_chained_stubs: bool,
+
+    /// Report an error for the stubs of the harness that were never applied, e.g., because their
+    /// original function/method is not reachable from the harness, which usually means that the
+    /// stub targets the wrong function/method.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub deny_unapplied_stubs: bool,
+
+    /// Keep compiling when the stubs of some harness fail to resolve: the errors of the harnesses
+    /// other than the one being verified are reported as warnings and their stubs are skipped.
+    /// The errors of the harness being verified still stop the compilation. This is meant for IDE
+    /// and batch runs, where one harness may be broken while it is edited.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub continue_on_stub_errors: bool,
+
+    /// Check that each reachable call to a stubbed function/method would still type check if it
+    /// called the stub, given the generic arguments of the call, and report the call sites that
+    /// would not (e.g., a stub with a `T: Copy` bound that is called with `T = String`). This is
+    /// more precise than `--verify-stub-types`, which compares the signatures only.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub check_stub_call_sites: bool,
+
+    /// Warn about the harnesses that import a module named `mock`, `mocks`, `stub` or `stubs` but
+    /// have no stubs, which usually means that a stubbing attribute was forgotten.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub warn_mock_imports: bool,
+
+    /// Record the hash of the body of each stub in the given json file, and warn about the stubs
+    /// whose body changed since their hash was recorded. Remove the entry of a stub from the file
+    /// to accept its new body.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_body_hashes: Option<PathBuf>,
+
+    /// Dump the MIR of the given harness and of every body reachable from it, once the stubs are
+    /// applied, to `<crate>.stubbed.mir` (in the directory of `--output-dir`, if set). This shows
+    /// exactly what is verified, including the bodies of the stubs.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub emit_stubbed_mir: Option<String>,
+
+    /// The stub sources from the highest to the lowest precedence, separated by commas (the default
+    /// is `config,attribute`). If the stubbing attributes and the verification config of a harness
+    /// stub the same function differently, Kani warns and uses the stub from the source with the
+    /// highest precedence.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("enable_stubbing"),
+        value_enum,
+        value_delimiter = ','
+    )]
+    pub stub_source_precedence: Vec<StubSource>,
+
+    /// Apply the stubs marked `#[kani::experimental_stub]`. Without this option, these stubs are
+    /// skipped and Kani verifies the original functions instead.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub enable_experimental_stubs: bool,
+
+    /// Accept the stubs that take `&T` for a parameter of type `&mut T` of the original
+    /// function/method. Such a stub models an original that does not write through the reference.
+    /// The opposite (`&mut T` for `&T`) is always rejected.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub allow_stub_shared_refs: bool,
+
+    /// Maximum number of functions a single `kani::stub_regex` attribute may match. Kani rejects
+    /// patterns that match more functions, which usually indicates a pattern that is too broad.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_regex_limit: Option<usize>,
+
+    /// Ratio above which a stub with more basic blocks or statements than the function it
+    /// replaces is reported as an advisory, since a stub that is much more complex than the
+    /// original usually replaces the wrong function. The default is 4.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_complexity_ratio: Option<usize>,
+
+    /// Maximum number of modules (including re-exports) Kani may go through to resolve a path in
+    /// a stubbing attribute; the default is 128. Kani reports an error for paths that need more,
+    /// which usually indicates a re-export cycle.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_resolution_depth: Option<usize>,
+
+    /// Maximum number of functions and methods whose names are closest to a stub path that does
+    /// not resolve, which Kani lists in the error for the path; the default is 3. Use 0 to list
+    /// none.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_error_candidates: Option<usize>,
+
+    /// The edition of Rust the stub paths are resolved against, which changes how some paths are
+    /// resolved, e.g., those that start with `::`. Kani reports an error if a crate with stubs
+    /// declares a different edition, and compiles the crates that declare none with this one.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"), value_enum)]
+    pub stub_path_edition: Option<RustEdition>,
+
+    /// Print the chain of `use` statements that Kani goes through to resolve each stub path, e.g.,
+    /// "resolved `c::d` via `use a::b as c` -> `a::b::d`".
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub trace_stub_resolution: bool,
+
+    /// Resolve a stub path that is exactly the `def_path_str` of a function/method (e.g., as
+    /// printed by `--emit-stubbed-mir`) to it directly, instead of resolving it relative to the
+    /// harness through the `use` statements in scope. The crate name is omitted for the
+    /// functions/methods of the local crate.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub stub_exact_paths: bool,
+
+    /// Emit the stub mapping of the harness as a Rust source file (`<crate>.stubs.rs`) next to
+    /// the other artifacts, so it can be reviewed and committed.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub emit_stub_mapping: bool,
+
+    /// Print the stub mapping of the harness once the stubs of its attributes and of the
+    /// verification config are merged, i.e., exactly the stubs that Kani applies.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub print_stub_mapping: bool,
+
+    /// Emit the paths of the functions and methods that may be stubbed in a JSON file
+    /// (`<crate>.stub_candidates.json`) next to the other artifacts.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub emit_stub_candidates: bool,
+
+    /// Include the functions and methods from the dependencies in the stub candidates.
+    /// This option requires `--emit-stub-candidates` to be used
+    #[arg(long, hide_short_help = true, requires("emit_stub_candidates"))]
+    pub stub_candidates_include_foreign: bool,
+
+    /// Emit the number of calls to each stubbed function/method in the body of each of its callers
+    /// in a JSON file (`<crate>.stub_caller_counts.json`) next to the other artifacts. This shows
+    /// where a stub has the most influence.
+    /// This option requires `--enable-stubbing` to be used
+    #[arg(long, hide_short_help = true, requires("enable_stubbing"))]
+    pub emit_stub_caller_counts: bool,
+
+    /// Emit the name, location and unwind bound of each harness together with the stubs resolved
+    /// for it in a JSON file (`<crate>.harnesses.json`) next to the other artifacts. The stubs are
+    /// only resolved if `--enable-stubbing` is used.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_harness_metadata: bool,
+
+    /// Emit the verification plan of the crate in a JSON file (`<crate>.plan.json`) next to the
+    /// other artifacts. The plan combines the harness metadata of `--emit-harness-metadata` with
+    /// the roots of the reachability analysis, the goto model and the options that affect it, so
+    /// an external tool can drive and reproduce the verification of each harness.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_verification_plan: bool,
+
+    /// Emit the reachable functions that have no MIR, such as foreign functions and intrinsics,
+    /// together with the functions that call them in a JSON file (`<crate>.no_mir.json`) next to
+    /// the other artifacts. Kani cannot analyze the body of these functions.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_no_mir_report: bool,
+
+    /// Emit the functions reachable from each proof harness on its own in a JSON file
+    /// (`<crate>.harness_reachability.json`) keyed by the name of the harness, next to the other
+    /// artifacts. Only these functions are candidates for stubbing in the harness.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_harness_reachability: bool,
+
+    /// Cache the result of the reachability analysis in the given directory and reuse it while
+    /// the crate, its dependencies and the Kani options stay the same.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub reachability_cache_dir: Option<PathBuf>,
+
+    /// Write the files emitted on request (e.g., by `--emit-call-graph` or `--emit-advisories`)
+    /// in the given directory, which is created if needed, instead of next to the other artifacts.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub output_dir: Option<PathBuf>,
+
+    /// Read the unwind bounds, the stubs and the reachability settings from the given JSON file.
+    /// Bounds that conflict with the `kani::unwind` attributes are reported as errors, and the
+    /// command line options take precedence over the reachability settings. Files of an older
+    /// format version are migrated, and files of a newer version are rejected.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub verification_config: Option<PathBuf>,
+
+    /// Emit the call graph of the reachable items (`<crate>.call_graph.json` or
+    /// `<crate>.call_graph.dot`) next to the other compilation artifacts. In the JSON format,
+    /// each item is tagged with the crate it comes from.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("enable_unstable"),
+        ignore_case = true,
+        value_enum
+    )]
+    pub emit_call_graph: Option<CallGraphFormat>,
+
+    /// Print how many of the reachable functions come from the local crate and from each of its
+    /// dependencies, as a table (`text`) or as a JSON object (`json`).
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("enable_unstable"),
+        ignore_case = true,
+        value_enum
+    )]
+    pub reachable_counts: Option<ReportFormat>,
+
+    /// Include the compiler-generated items that the reachability analysis omits, such as the
+    /// drop glue of types that are never dropped, in the call graph and the reachable counts.
+    /// These items are only reported; they are not verified.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub include_synthetic_items: bool,
+
+    /// Only run a MIR pass that leaves every body unchanged instead of the Kani MIR passes, so
+    /// stubs are collected but never applied. This is meant to benchmark the cost of the pass
+    /// infrastructure separately from stubbing and code generation.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub identity_passes_only: bool,
+
+    /// Emit the advisory messages produced by the compiler passes as a json file next to the
+    /// other compilation artifacts.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_advisories: bool,
+
+    /// Emit the time each Kani MIR pass (e.g., stubbing) takes on each function as a json file in
+    /// the Chrome Trace Event format next to the other compilation artifacts, which can be loaded
+    /// in `chrome://tracing`.
+    /// This feature is unstable and it requires `--enable-unstable` to be used
+    #[arg(long, hide_short_help = true, requires("enable_unstable"))]
+    pub emit_pass_trace: bool,
+
     /// Arguments to pass down to Cargo
     #[command(flatten)]
     pub cargo: CargoArgs,
@@ -325,6 +631,34 @@ pub enum ConcretePlaybackMode {
     InPlace,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CallGraphFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StubSource {
+    Attribute,
+    Config,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RustEdition {
+    #[value(name = "2015")]
+    Edition2015,
+    #[value(name = "2018")]
+    Edition2018,
+    #[value(name = "2021")]
+    Edition2021,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Text,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
 pub enum OutputFormat {
     Regular,
@@ -802,6 +1136,454 @@ fn check_enable_stubbing() {
         assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
     }
 
+    #[test]
+    fn check_allow_std_stubbing() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --allow-std-stubbing",
+            allow_std_stubbing
+        );
+
+        // `--allow-std-stubbing` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--allow-std-stubbing").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_harness_body() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --stub-harness-body",
+            stub_harness_body
+        );
+
+        // `--stub-harness-body` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-harness-body").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_verify_stub_types() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --verify-stub-types",
+            verify_stub_types
+        );
+
+        // `--verify-stub-types` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--verify-stub-types").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_preserve_stub_signature() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --preserve-stub-signature",
+            preserve_stub_signature
+        );
+
+        // `--preserve-stub-signature` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--preserve-stub-signature").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_stub_diagnostics() {
+        check_unstable_flag!("--enable-stubbing --emit-stub-diagnostics", emit_stub_diagnostics);
+
+        // `--emit-stub-diagnostics` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--emit-stub-diagnostics").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_summary() {
+        check_unstable_flag!("--enable-stubbing --harness foo --stub-summary", stub_summary);
+
+        // `--stub-summary` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-summary").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_deny_chained_stubs() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --deny-chained-stubs",
+            deny_chained_stubs
+        );
+
+        // `--deny-chained-stubs` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--deny-chained-stubs").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_deny_unapplied_stubs() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --deny-unapplied-stubs",
+            deny_unapplied_stubs
+        );
+
+        // `--deny-unapplied-stubs` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--deny-unapplied-stubs").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_continue_on_stub_errors() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --continue-on-stub-errors",
+            continue_on_stub_errors
+        );
+
+        // `--continue-on-stub-errors` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--continue-on-stub-errors").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_check_stub_call_sites() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --check-stub-call-sites",
+            check_stub_call_sites
+        );
+
+        // `--check-stub-call-sites` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--check-stub-call-sites").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_warn_mock_imports() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --warn-mock-imports",
+            warn_mock_imports
+        );
+
+        // `--warn-mock-imports` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--warn-mock-imports").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_body_hashes() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-body-hashes stubs.json",
+            true,
+            stub_body_hashes,
+            Some(PathBuf::from("stubs.json"))
+        );
+
+        // `--stub-body-hashes` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-body-hashes stubs.json").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_stubbed_mir() {
+        check_opt!(
+            "--enable-stubbing --harness foo --emit-stubbed-mir foo",
+            true,
+            emit_stubbed_mir,
+            Some("foo".to_string())
+        );
+
+        // `--emit-stubbed-mir` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--emit-stubbed-mir foo").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_source_precedence() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-source-precedence attribute,config",
+            true,
+            stub_source_precedence,
+            vec![StubSource::Attribute, StubSource::Config]
+        );
+
+        // `--stub-source-precedence` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-source-precedence config").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_allow_stub_shared_refs() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --allow-stub-shared-refs",
+            allow_stub_shared_refs
+        );
+
+        // `--allow-stub-shared-refs` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--allow-stub-shared-refs").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_enable_experimental_stubs() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --enable-experimental-stubs",
+            enable_experimental_stubs
+        );
+
+        // `--enable-experimental-stubs` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--enable-experimental-stubs").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_collection_stats() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --stub-collection-stats",
+            stub_collection_stats
+        );
+
+        // `--stub-collection-stats` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-collection-stats").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_resolution_stats() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --stub-resolution-stats",
+            stub_resolution_stats
+        );
+
+        // `--stub-resolution-stats` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-resolution-stats").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_regex_limit() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-regex-limit 5",
+            true,
+            stub_regex_limit,
+            Some(5)
+        );
+
+        // `--stub-regex-limit` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-regex-limit 5").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_complexity_ratio() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-complexity-ratio 8",
+            true,
+            stub_complexity_ratio,
+            Some(8)
+        );
+
+        // `--stub-complexity-ratio` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-complexity-ratio 8").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_resolution_depth() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-resolution-depth 16",
+            true,
+            stub_resolution_depth,
+            Some(16)
+        );
+
+        // `--stub-resolution-depth` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-resolution-depth 16").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_error_candidates() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-error-candidates 0",
+            true,
+            stub_error_candidates,
+            Some(0)
+        );
+
+        // `--stub-error-candidates` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-error-candidates 0").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_path_edition() {
+        check_opt!(
+            "--enable-stubbing --harness foo --stub-path-edition 2015",
+            true,
+            stub_path_edition,
+            Some(RustEdition::Edition2015)
+        );
+
+        // `--stub-path-edition` only accepts the editions of Rust
+        let err =
+            parse_unstable_enabled("--enable-stubbing --harness foo --stub-path-edition 2020")
+                .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+
+        // `--stub-path-edition` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-path-edition 2015").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_trace_stub_resolution() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --trace-stub-resolution",
+            trace_stub_resolution
+        );
+
+        // `--trace-stub-resolution` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--trace-stub-resolution").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_stub_exact_paths() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --stub-exact-paths",
+            stub_exact_paths
+        );
+
+        // `--stub-exact-paths` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--stub-exact-paths").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_stub_mapping() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --emit-stub-mapping",
+            emit_stub_mapping
+        );
+
+        // `--emit-stub-mapping` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--emit-stub-mapping").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_print_stub_mapping() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --print-stub-mapping",
+            print_stub_mapping
+        );
+
+        // `--print-stub-mapping` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--print-stub-mapping").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_stub_candidates() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --emit-stub-candidates",
+            emit_stub_candidates
+        );
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --emit-stub-candidates --stub-candidates-include-foreign",
+            stub_candidates_include_foreign
+        );
+
+        // `--stub-candidates-include-foreign` cannot be called without `--emit-stub-candidates`
+        let err = parse_unstable_enabled(
+            "--enable-stubbing --harness foo --stub-candidates-include-foreign",
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_stub_caller_counts() {
+        check_unstable_flag!(
+            "--enable-stubbing --harness foo --emit-stub-caller-counts",
+            emit_stub_caller_counts
+        );
+
+        // `--emit-stub-caller-counts` cannot be called without `--enable-stubbing`
+        let err = parse_unstable_enabled("--emit-stub-caller-counts").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_emit_harness_metadata_unstable() {
+        check_unstable_flag!("--emit-harness-metadata", emit_harness_metadata);
+    }
+
+    #[test]
+    fn check_emit_verification_plan_unstable() {
+        check_unstable_flag!("--emit-verification-plan", emit_verification_plan);
+    }
+
+    #[test]
+    fn check_emit_no_mir_report_unstable() {
+        check_unstable_flag!("--emit-no-mir-report", emit_no_mir_report);
+    }
+
+    #[test]
+    fn check_emit_harness_reachability_unstable() {
+        check_unstable_flag!("--emit-harness-reachability", emit_harness_reachability);
+    }
+
+    #[test]
+    fn check_reachability_cache_dir_unstable() {
+        check_opt!(
+            "--reachability-cache-dir cache",
+            true,
+            reachability_cache_dir,
+            Some(PathBuf::from("cache"))
+        );
+    }
+
+    #[test]
+    fn check_output_dir_unstable() {
+        check_opt!("--output-dir reports", true, output_dir, Some(PathBuf::from("reports")));
+    }
+
+    #[test]
+    fn check_verification_config_unstable() {
+        check_opt!(
+            "--verification-config kani.json",
+            true,
+            verification_config,
+            Some(PathBuf::from("kani.json"))
+        );
+    }
+
+    #[test]
+    fn check_emit_call_graph_unstable() {
+        check_opt!("--emit-call-graph json", true, emit_call_graph, Some(CallGraphFormat::Json));
+        check_opt!("--emit-call-graph dot", true, emit_call_graph, Some(CallGraphFormat::Dot));
+    }
+
+    #[test]
+    fn check_reachable_counts_unstable() {
+        check_opt!("--reachable-counts text", true, reachable_counts, Some(ReportFormat::Text));
+        check_opt!("--reachable-counts json", true, reachable_counts, Some(ReportFormat::Json));
+    }
+
+    #[test]
+    fn check_include_synthetic_items_unstable() {
+        check_unstable_flag!("--include-synthetic-items", include_synthetic_items);
+    }
+
+    #[test]
+    fn check_identity_passes_only_unstable() {
+        check_unstable_flag!("--identity-passes-only", identity_passes_only);
+    }
+
+    #[test]
+    fn check_emit_advisories_unstable() {
+        check_unstable_flag!("--emit-advisories", emit_advisories);
+    }
+
+    #[test]
+    fn check_emit_pass_trace_unstable() {
+        check_unstable_flag!("--emit-pass-trace", emit_pass_trace);
+    }
+
     #[test]
     fn check_features_parsing() {
         fn parse(args: &[&str]) -> Vec<String> {
@@ -6,6 +6,7 @@
 use std::path::Path;
 use std::process::Command;
 
+use crate::args::{CallGraphFormat, ReportFormat, RustEdition, StubSource};
 use crate::session::{base_folder, lib_folder, KaniSession};
 
 impl KaniSession {
@@ -93,6 +94,163 @@ pub fn kani_compiler_flags(&self) -> Vec<String> {
         if let Some(harness) = &self.args.harness {
             flags.push(format!("--harness={harness}"));
         }
+        if self.args.allow_std_stubbing {
+            flags.push("--allow-std-stubbing".into());
+        }
+        if self.args.stub_harness_body {
+            flags.push("--stub-harness-body".into());
+        }
+        if self.args.verify_stub_types {
+            flags.push("--verify-stub-types".into());
+        }
+        if self.args.preserve_stub_signature {
+            flags.push("--preserve-stub-signature".into());
+        }
+        if self.args.stub_resolution_stats {
+            flags.push("--stub-resolution-stats".into());
+        }
+        if self.args.stub_collection_stats {
+            flags.push("--stub-collection-stats".into());
+        }
+        if self.args.stub_summary {
+            flags.push("--stub-summary".into());
+        }
+        if self.args.emit_stub_diagnostics {
+            flags.push("--emit-stub-diagnostics".into());
+        }
+        if self.args.deny_chained_stubs {
+            flags.push("--deny-chained-stubs".into());
+        }
+        if self.args.deny_unapplied_stubs {
+            flags.push("--deny-unapplied-stubs".into());
+        }
+        if self.args.continue_on_stub_errors {
+            flags.push("--continue-on-stub-errors".into());
+        }
+        if self.args.check_stub_call_sites {
+            flags.push("--check-stub-call-sites".into());
+        }
+        if self.args.warn_mock_imports {
+            flags.push("--warn-mock-imports".into());
+        }
+        if let Some(path) = &self.args.stub_body_hashes {
+            // The compiler may run from a different directory, e.g.: when invoked by cargo.
+            let path = std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or(path.clone());
+            flags.push(format!("--stub-body-hashes={}", path.display()));
+        }
+        if let Some(harness) = &self.args.emit_stubbed_mir {
+            flags.push(format!("--emit-stubbed-mir={harness}"));
+        }
+        if !self.args.stub_source_precedence.is_empty() {
+            let sources: Vec<_> = self
+                .args
+                .stub_source_precedence
+                .iter()
+                .map(|source| match source {
+                    StubSource::Attribute => "attribute",
+                    StubSource::Config => "config",
+                })
+                .collect();
+            flags.push(format!("--stub-source-precedence={}", sources.join(",")));
+        }
+        if self.args.enable_experimental_stubs {
+            flags.push("--enable-experimental-stubs".into());
+        }
+        if self.args.allow_stub_shared_refs {
+            flags.push("--allow-stub-shared-refs".into());
+        }
+        if let Some(limit) = self.args.stub_regex_limit {
+            flags.push(format!("--stub-regex-limit={limit}"));
+        }
+        if let Some(ratio) = self.args.stub_complexity_ratio {
+            flags.push(format!("--stub-complexity-ratio={ratio}"));
+        }
+        if let Some(depth) = self.args.stub_resolution_depth {
+            flags.push(format!("--stub-resolution-depth={depth}"));
+        }
+        if let Some(count) = self.args.stub_error_candidates {
+            flags.push(format!("--stub-error-candidates={count}"));
+        }
+        if let Some(edition) = self.args.stub_path_edition {
+            let edition = match edition {
+                RustEdition::Edition2015 => "2015",
+                RustEdition::Edition2018 => "2018",
+                RustEdition::Edition2021 => "2021",
+            };
+            flags.push(format!("--stub-path-edition={edition}"));
+        }
+        if self.args.trace_stub_resolution {
+            flags.push("--trace-stub-resolution".into());
+        }
+        if self.args.stub_exact_paths {
+            flags.push("--stub-exact-paths".into());
+        }
+        if self.args.emit_stub_mapping {
+            flags.push("--emit-stub-mapping".into());
+        }
+        if self.args.print_stub_mapping {
+            flags.push("--print-stub-mapping".into());
+        }
+        if self.args.emit_stub_caller_counts {
+            flags.push("--emit-stub-caller-counts".into());
+        }
+        if self.args.emit_stub_candidates {
+            flags.push("--emit-stub-candidates".into());
+        }
+        if self.args.stub_candidates_include_foreign {
+            flags.push("--stub-candidates-include-foreign".into());
+        }
+        if self.args.emit_harness_metadata {
+            flags.push("--emit-harness-metadata".into());
+        }
+        if self.args.emit_verification_plan {
+            flags.push("--emit-verification-plan".into());
+        }
+        if self.args.emit_no_mir_report {
+            flags.push("--emit-no-mir-report".into());
+        }
+        if self.args.emit_harness_reachability {
+            flags.push("--emit-harness-reachability".into());
+        }
+        if let Some(dir) = &self.args.reachability_cache_dir {
+            // The compiler may run from a different directory, e.g.: when invoked by cargo.
+            let dir = std::env::current_dir().map(|cwd| cwd.join(dir)).unwrap_or(dir.clone());
+            flags.push(format!("--reachability-cache-dir={}", dir.display()));
+        }
+        if let Some(dir) = &self.args.output_dir {
+            let dir = std::env::current_dir().map(|cwd| cwd.join(dir)).unwrap_or(dir.clone());
+            flags.push(format!("--output-dir={}", dir.display()));
+        }
+        if let Some(path) = &self.args.verification_config {
+            let path = std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or(path.clone());
+            flags.push(format!("--verification-config={}", path.display()));
+        }
+        if let Some(format) = self.args.emit_call_graph {
+            let format = match format {
+                CallGraphFormat::Dot => "dot",
+                CallGraphFormat::Json => "json",
+            };
+            flags.push(format!("--emit-call-graph={format}"));
+        }
+        if let Some(format) = self.args.reachable_counts {
+            let format = match format {
+                ReportFormat::Json => "json",
+                ReportFormat::Text => "text",
+            };
+            flags.push(format!("--reachable-counts={format}"));
+        }
+        if self.args.include_synthetic_items {
+            flags.push("--include-synthetic-items".into());
+        }
+        if self.args.identity_passes_only {
+            flags.push("--identity-passes-only".into());
+        }
+        if self.args.emit_advisories {
+            flags.push("--emit-advisories".into());
+        }
+        if self.args.emit_pass_trace {
+            flags.push("--emit-pass-trace".into());
+        }
 
         // This argument will select the Kani flavour of the compiler. It will be removed before
         // rustc driver is invoked.
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 #![cfg(feature = "unsound_experiments")]
+use std::path::PathBuf;
+
 use clap::Parser;
 #[derive(Debug, Parser)]
 pub struct UnsoundExperimentArgs {
@@ -12,6 +14,27 @@ pub struct UnsoundExperimentArgs {
     /// Marked as `unsound` to prevent use outside of experimental contexts.
     #[arg(long, hide_short_help = true, requires("enable_unstable"))]
     pub unsound_experiment_zero_init_vars: bool,
+    /// Replace the body of every local function other than the harnesses and the given ones
+    /// (comma-separated paths relative to the crate root) by one that returns a nondeterministic
+    /// value.
+    /// This is useful for experiments to see how verification scales in the most abstract
+    /// setting, where the function under test is isolated from everything it calls.
+    /// Unfortunatly, it is unsafe to use for production code, since the replaced functions may
+    /// return values that the original functions never return, and their side effects are lost.
+    /// Marked as `unsound` to prevent use outside of experimental contexts.
+    #[arg(long, hide_short_help = true, requires("enable_unstable"), value_delimiter = ',')]
+    pub unsound_experiment_stub_all_except: Option<Vec<String>>,
+    /// Print the unsound experiments that are enabled as json to stderr, or write them to the
+    /// given file if one is provided, so there is a record of the experiments of an unsound run.
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("enable_unstable"),
+        value_name = "FILE",
+        num_args(0..=1),
+        require_equals(true)
+    )]
+    pub unsound_experiment_dump_config: Option<Option<PathBuf>>,
 }
 
 impl UnsoundExperimentArgs {
@@ -21,6 +44,18 @@ pub fn process_args(&self) -> Vec<String> {
         if self.unsound_experiment_zero_init_vars {
             flags.push("--unsound-experiment-zero-init-vars".into());
         }
+        if let Some(paths) = &self.unsound_experiment_stub_all_except {
+            flags.push(format!("--unsound-experiment-stub-all-except={}", paths.join(",")));
+        }
+        match &self.unsound_experiment_dump_config {
+            Some(Some(path)) => {
+                let path =
+                    std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or(path.clone());
+                flags.push(format!("--unsound-experiment-dump-config={}", path.display()));
+            }
+            Some(None) => flags.push("--unsound-experiment-dump-config".into()),
+            None => {}
+        }
         flags
     }
 
@@ -30,5 +65,10 @@ pub fn print_warnings(&self) {
                 "Warning: using --unsound-experiment-zero-init-vars can lead to unsound results"
             );
         }
+        if self.unsound_experiment_stub_all_except.is_some() {
+            eprintln!(
+                "Warning: using --unsound-experiment-stub-all-except can lead to unsound results"
+            );
+        }
     }
 }
@@ -9,16 +9,38 @@
 /// Represent the type of an artifact generated by Kani and the corresponding extension.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum ArtifactType {
+    /// A `json` file with the advisory messages produced by the compiler passes.
+    Advisories,
+    /// A `json` file with the call graph of the reachable items.
+    CallGraph,
+    /// A Graphviz `dot` file with the call graph of the reachable items.
+    CallGraphDot,
     /// A complete goto model generated after linking.
     Goto,
+    /// A `json` file with the metadata of each harness together with its resolved stubs.
+    Harnesses,
+    /// A `json` file with the functions reachable from each harness.
+    HarnessReachability,
     /// The metadata generated by the compiler.
     Metadata,
+    /// A `json` file with the reachable functions that have no MIR.
+    NoMirReport,
+    /// A `json` file with the time each Kani MIR pass takes on each body, in the Chrome Trace
+    /// Event format.
+    PassTrace,
     /// The `json` file that represents the symbol table generated by the compiler.
     SymTab,
     /// The `goto` version of the symbol table before linking.
     SymTabGoto,
+    /// A `json` file with the number of calls to each stubbed function/method in each caller.
+    StubCallerCounts,
+    /// A `json` file with the paths of the functions and methods that may be stubbed.
+    StubCandidates,
     /// A `json` file that has a map of mangled name to pretty name for goto types.
     TypeMap,
+    /// A `json` file with the harnesses, stubs, reachability roots and options of a crate, which
+    /// is everything needed to drive its verification.
+    VerificationPlan,
     /// A `json` file that has information about the function pointer restrictions derived from
     /// vtable generation.
     VTableRestriction,
@@ -27,11 +49,21 @@ pub enum ArtifactType {
 impl ArtifactType {
     const fn extension(&self) -> &'static str {
         match self {
+            ArtifactType::Advisories => "advisories.json",
+            ArtifactType::CallGraph => "call_graph.json",
+            ArtifactType::CallGraphDot => "call_graph.dot",
             ArtifactType::Goto => "out",
+            ArtifactType::Harnesses => "harnesses.json",
+            ArtifactType::HarnessReachability => "harness_reachability.json",
             ArtifactType::Metadata => "kani-metadata.json",
+            ArtifactType::NoMirReport => "no_mir.json",
+            ArtifactType::PassTrace => "pass_trace.json",
             ArtifactType::SymTab => "symtab.json",
             ArtifactType::SymTabGoto => "symtab.out",
+            ArtifactType::StubCallerCounts => "stub_caller_counts.json",
+            ArtifactType::StubCandidates => "stub_candidates.json",
             ArtifactType::TypeMap => "type_map.json",
+            ArtifactType::VerificationPlan => "plan.json",
             ArtifactType::VTableRestriction => "restrictions.json",
         }
     }
@@ -55,10 +87,20 @@ pub fn convert_type(path: &Path, from: ArtifactType, to: ArtifactType) -> PathBu
             result.set_extension(&to);
         }
         // Artifact types that has two extensions.
-        ArtifactType::Metadata
+        ArtifactType::Advisories
+        | ArtifactType::CallGraph
+        | ArtifactType::CallGraphDot
+        | ArtifactType::Harnesses
+        | ArtifactType::HarnessReachability
+        | ArtifactType::Metadata
+        | ArtifactType::NoMirReport
+        | ArtifactType::PassTrace
         | ArtifactType::SymTab
         | ArtifactType::SymTabGoto
+        | ArtifactType::StubCallerCounts
+        | ArtifactType::StubCandidates
         | ArtifactType::TypeMap
+        | ArtifactType::VerificationPlan
         | ArtifactType::VTableRestriction => {
             result.set_extension("");
             result.set_extension(&to);
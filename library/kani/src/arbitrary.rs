@@ -7,6 +7,7 @@
 
 /// This trait should be used to generate symbolic variables that represent any valid value of
 /// its type.
+#[rustc_diagnostic_item = "KaniArbitrary"]
 pub trait Arbitrary {
     fn any() -> Self;
 }
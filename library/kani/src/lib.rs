@@ -105,6 +105,7 @@ pub fn cover(_cond: bool, _msg: &'static str) {}
 /// trait. The Arbitrary trait is used to build a symbolic value that represents all possible
 /// valid values for type `T`.
 #[inline(always)]
+#[rustc_diagnostic_item = "KaniAny"]
 pub fn any<T: Arbitrary>() -> T {
     T::any()
 }
@@ -171,6 +172,20 @@ fn any_raw_inner<T>() -> T {
     loop {}
 }
 
+/// The replacement of the functions and methods stubbed with `#[kani::stub_default]`. Instead of
+/// the body of this function, which is never executed, Kani uses a body that returns
+/// `Default::default()`.
+#[rustc_diagnostic_item = "KaniDefaultStub"]
+#[doc(hidden)]
+pub fn default_stub() {}
+
+/// The replacement of the functions and methods stubbed with `#[kani::stub_spec]`. Instead of the
+/// body of this function, which is never executed, Kani uses a body synthesized from the
+/// specification, e.g., one that returns `kani::any()` constrained by `kani::assume`.
+#[rustc_diagnostic_item = "KaniSpecStub"]
+#[doc(hidden)]
+pub fn spec_stub() {}
+
 /// Function used to generate panic with a static message as this is the only one currently
 /// supported by Kani display.
 ///
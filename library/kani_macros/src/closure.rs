@@ -0,0 +1,101 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module provides Kani's `hoist_closure!` macro, which turns a closure into a named
+//! function, so the closure can be stubbed like any other function.
+//!
+//! ```ignore
+//! kani::hoist_closure! {
+//!     fn parse_digit = |c: char| -> Option<u32> { c.to_digit(10) };
+//! }
+//! ```
+//! will generate the following function:
+//! ```ignore
+//! fn parse_digit(c: char) -> Option<u32> { c.to_digit(10) }
+//! ```
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Expr, ExprClosure, Ident, Pat, ReturnType, Token, Visibility,
+};
+
+/// The definition of one function: `#[attrs] vis fn name = closure;`.
+struct HoistedClosure {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    closure: ExprClosure,
+}
+
+impl Parse for HoistedClosure {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![fn]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let closure = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(HoistedClosure { attrs, vis, name, closure })
+    }
+}
+
+/// The definitions of all the functions of one invocation of the macro.
+struct HoistedClosures(Vec<HoistedClosure>);
+
+impl Parse for HoistedClosures {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut closures = Vec::new();
+        while !input.is_empty() {
+            closures.push(input.parse()?);
+        }
+        Ok(HoistedClosures(closures))
+    }
+}
+
+pub fn expand_hoist_closure(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let HoistedClosures(closures) = parse_macro_input!(item as HoistedClosures);
+    let functions = closures.into_iter().map(hoist);
+    proc_macro::TokenStream::from(quote! { #(#functions)* })
+}
+
+/// Generate the function with the parameters, return type and body of the closure.
+fn hoist(hoisted: HoistedClosure) -> TokenStream {
+    let HoistedClosure { attrs, vis, name, closure } = hoisted;
+    if let Some(token) =
+        closure.movability.map(|token| token.span()).or(closure.asyncness.map(|token| token.span()))
+    {
+        abort!(token, "Cannot hoist closure `{}`: only plain closures can be hoisted", name);
+    }
+    if let Some(token) = closure.capture {
+        abort!(token.span(), "Cannot hoist closure `{}`: it cannot capture any variable", name;
+            note = "the closure is turned into a function, which has no environment to capture"
+        );
+    }
+    let params = closure.inputs.iter().map(|input| match input {
+        Pat::Type(param) => param,
+        _ => abort!(input.span(), "Cannot hoist closure `{}`: missing parameter type", name;
+            note = "the type of each parameter must be annotated"
+        ),
+    });
+    let output = match &closure.output {
+        ReturnType::Type(..) => &closure.output,
+        ReturnType::Default => {
+            abort!(closure.span(), "Cannot hoist closure `{}`: its return type must be annotated",
+                name;
+                note = "write the closure as `|..| -> Type { .. }`"
+            )
+        }
+    };
+    // A closure with an annotated return type always has a block as its body.
+    let body = match &*closure.body {
+        Expr::Block(block) => block,
+        body => abort!(body.span(), "Cannot hoist closure `{}`: its body must be a block", name),
+    };
+    quote! {
+        #(#attrs)*
+        #vis fn #name(#(#params),*) #output #body
+    }
+}
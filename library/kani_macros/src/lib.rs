@@ -8,6 +8,7 @@
 // So we have to enable this on the commandline (see kani-rustc) with:
 //   RUSTFLAGS="-Zcrate-attr=feature(register_tool) -Zcrate-attr=register_tool(kanitool)"
 
+mod closure;
 mod derive;
 
 // proc_macro::quote is nightly-only, so we'll cobble things together instead
@@ -105,9 +106,12 @@ pub fn unwind(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
-/// Set Loop unwind limit for proof harnesses
-/// The attribute '#[kani::unwind(arg)]' can only be called alongside '#[kani::proof]'.
-/// arg - Takes in a integer value (u32) that represents the unwind value for the harness.
+/// Set Loop unwind limit for proof harnesses and functions
+/// When used alongside '#[kani::proof]', the attribute '#[kani::unwind(arg)]' sets the unwind
+/// value of the harness. On any other function, the value is collected for the backends but not
+/// applied yet, and Kani warns about it: the loops of the function are bounded by the unwind value
+/// of the harness.
+/// arg - Takes in a integer value (u32) that represents the unwind value.
 #[cfg(kani)]
 #[proc_macro_attribute]
 pub fn unwind(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -135,6 +139,15 @@ pub fn stub(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// # Arguments
 /// * `original` - The function or method to replace, specified as a path.
 /// * `replacement` - The function or method to use as a replacement, specified as a path.
+///
+/// The original function may also be specified by its symbol name, as a string with the prefix
+/// `symbol:` (e.g., `#[kani::stub("symbol:ffi_read", mock_read)]`). Only use this for functions
+/// that are best known by their linkage name, such as `#[no_mangle]` functions exposed through
+/// FFI. Kani reports an error if no non-generic function has this symbol name.
+///
+/// An `async` function or method can only be replaced by another `async` function or method whose
+/// future has the same output type; the future returned by the original then runs the body of
+/// the replacement.
 #[cfg(kani)]
 #[proc_macro_attribute]
 pub fn stub(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -148,6 +161,243 @@ pub fn stub(attr: TokenStream, item: TokenStream) -> TokenStream {
     result
 }
 
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn stub_regex(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Specify a set of function/method stub pairs to use for proof harness, using a regular
+/// expression.
+///
+/// The attribute `#[kani::stub_regex("pattern", "replacement")]` can only be used alongside
+/// `#[kani::proof]`. Every local function or method whose path matches the pattern is replaced
+/// by the function or method at the path obtained by substituting the matches in `replacement`.
+/// For example, `#[kani::stub_regex("logging::(.*)", "mock_logging::$1")]` replaces
+/// `logging::info` by `mock_logging::info`.
+///
+/// # Arguments
+/// * `pattern` - A regular expression that must match the whole path of the function or method.
+/// * `replacement` - The path of the replacement, which may refer to the capture groups of
+///   `pattern` (e.g., `$1`).
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn stub_regex(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::stub_regex(pattern, replacement)] to #[kanitool::stub_regex(pattern, replacement)]
+    let insert_string = "#[kanitool::stub_regex(".to_owned() + &attr.to_string() + ")]";
+    result.extend(insert_string.parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn stub_cfg(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Specify a function/method stub pair to use for proof harness, where the stub is selected
+/// among alternatives guarded by cfg predicates.
+///
+/// The attribute `#[kani::stub_cfg(original, cfg(predicate, replacement), ...)]` can only be
+/// used alongside `#[kani::proof]`. Exactly one predicate must hold in the current compilation;
+/// the original function or method is replaced by the corresponding replacement.
+/// For example, `#[kani::stub_cfg(read, cfg(feature = "fast_mock", fast_read),
+/// cfg(feature = "precise_mock", precise_read))]` replaces `read` by `fast_read` when the
+/// `fast_mock` feature is enabled.
+///
+/// # Arguments
+/// * `original` - The function or method to replace, specified as a path.
+/// * `cfg(predicate, replacement)` - An alternative, where `predicate` has the same syntax as in
+///   `#[cfg(predicate)]` and `replacement` is the path of the stub.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn stub_cfg(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::stub_cfg(original, alternatives...)] to #[kanitool::stub_cfg(original, alternatives...)]
+    let insert_string = "#[kanitool::stub_cfg(".to_owned() + &attr.to_string() + ")]";
+    result.extend(insert_string.parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn stub_default(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Specify a function/method to stub for proof harness with a body that returns
+/// `Default::default()`.
+///
+/// The attribute `#[kani::stub_default(original)]` can only be used alongside `#[kani::proof]`.
+/// The return type of the original function or method must implement `Default`.
+/// For example, `#[kani::stub_default(load_config)]` makes `load_config` return the default
+/// configuration.
+///
+/// # Arguments
+/// * `original` - The function or method to replace, specified as a path.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn stub_default(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::stub_default(original)] to #[kanitool::stub_default(original)]
+    let insert_string = "#[kanitool::stub_default(".to_owned() + &attr.to_string() + ")]";
+    result.extend(insert_string.parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn stub_spec(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Specify a function/method to stub for proof harness with a body that behaves as described by
+/// a specification.
+///
+/// The attribute `#[kani::stub_spec(original, "specification")]` can only be used alongside
+/// `#[kani::proof]`. The specifications supported so far are:
+/// * `returns in <range>`, where the range is written as in Rust with integer bounds (e.g.,
+///   `0..10`, `-5..=5` or `1..`), for a function or method with an integer return type. For
+///   example, `#[kani::stub_spec(read_sensor, "returns in 0..1024")]` makes `read_sensor` return
+///   any value between `0` and `1023`.
+/// * `returns where <predicate>`, where the predicate is the path of a function that takes a
+///   reference to the returned value and returns a `bool`, for a function or method whose return
+///   type implements `kani::Arbitrary`. The function is abstracted by this contract: for example,
+///   `#[kani::stub_spec(checksum, "returns where is_valid_checksum")]` makes `checksum` return any
+///   value for which `is_valid_checksum` returns `true`.
+/// * `table [(<input>, <output>), ..]`, optionally followed by `else <default>`, for a function or
+///   method with a single argument, where the inputs and outputs are integer or `bool` literals of
+///   the argument and return types. For example,
+///   `#[kani::stub_spec(is_odd, "table [(0, false), (1, true)]")]` makes `is_odd` return `false`
+///   for `0` and `true` for `1`. The replacement panics for the inputs that are not in the table
+///   unless a default is given.
+/// * `wrap requires <precondition> ensures <postcondition>`, where either condition may be
+///   omitted, for any function or method. The replacement runs the original body, but asserts that
+///   the precondition holds before and that the postcondition holds after. The precondition takes
+///   a reference to each argument and the postcondition a reference to the returned value, and
+///   both return a `bool`. For example,
+///   `#[kani::stub_spec(checked_div, "wrap requires nonzero_divisor ensures is_small")]` checks
+///   every call to `checked_div` without changing what it returns.
+///
+/// # Arguments
+/// * `original` - The function or method to replace, specified as a path.
+/// * `specification` - The behavior of the replacement, specified as a string.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn stub_spec(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::stub_spec(original, spec)] to #[kanitool::stub_spec(original, spec)]
+    let insert_string = "#[kanitool::stub_spec(".to_owned() + &attr.to_string() + ")]";
+    result.extend(insert_string.parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn stub_in(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Specify a function/method stub pair to use for proof harness, only for the calls made by
+/// one caller.
+///
+/// The attribute `#[kani::stub_in(caller, original, replacement)]` can only be used alongside
+/// `#[kani::proof]`. For example, `#[kani::stub_in(parse_config, read_file, mock_read_file)]`
+/// replaces the calls to `read_file` in `parse_config`, while the other functions keep calling
+/// `read_file`.
+///
+/// This operates at the level of the body of the caller: only the calls written in `caller`
+/// itself are replaced. The calls made by closures defined in `caller`, by the functions that
+/// `caller` calls, or through function pointers and trait objects are not.
+///
+/// # Arguments
+/// * `caller` - The function or method whose calls are replaced, specified as a path.
+/// * `original` - The function or method to replace, specified as a path.
+/// * `replacement` - The function or method to use as a replacement, specified as a path.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn stub_in(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::stub_in(caller, original, replacement)] to
+    // #[kanitool::stub_in(caller, original, replacement)]
+    let insert_string = "#[kanitool::stub_in(".to_owned() + &attr.to_string() + ")]";
+    result.extend(insert_string.parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn no_stub(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Prevent a function/method from being replaced by a stub.
+///
+/// The attribute `#[kani::no_stub]` can be used on any function or method that is not a proof
+/// harness. Kani reports an error if a `kani::stub`, `kani::stub_cfg`, `kani::stub_default`,
+/// `kani::stub_spec` or `kani::stub_in` attribute targets the function, and `kani::stub_regex`
+/// patterns never match it. Use this for functions whose real behavior must always be verified.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn no_stub(attr: TokenStream, item: TokenStream) -> TokenStream {
+    assert!(attr.is_empty(), "#[kani::no_stub] does not take any arguments");
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::no_stub] to #[kanitool::no_stub]
+    result.extend("#[kanitool::no_stub]".parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
+#[cfg(not(kani))]
+#[proc_macro_attribute]
+pub fn experimental_stub(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // When the config is not kani, we should leave the function alone
+    item
+}
+
+/// Mark a stub as experimental, so it is only applied with `--enable-experimental-stubs`.
+///
+/// The attribute `#[kani::experimental_stub]` can be used on any function or method that is used
+/// as a stub. Without the option, the stubbing attributes that use it are skipped, the original
+/// function is verified instead, and Kani records an advisory that lists the skipped stubs. This
+/// lets a crate ship draft mocks that its users opt into.
+#[cfg(kani)]
+#[proc_macro_attribute]
+pub fn experimental_stub(attr: TokenStream, item: TokenStream) -> TokenStream {
+    assert!(attr.is_empty(), "#[kani::experimental_stub] does not take any arguments");
+    let mut result = TokenStream::new();
+
+    // Translate #[kani::experimental_stub] to #[kanitool::experimental_stub]
+    result.extend("#[kanitool::experimental_stub]".parse::<TokenStream>().unwrap());
+
+    result.extend(item);
+    result
+}
+
 #[cfg(not(kani))]
 #[proc_macro_attribute]
 pub fn solver(_attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -177,3 +427,30 @@ pub fn solver(attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn derive_arbitrary(item: TokenStream) -> TokenStream {
     derive::expand_derive_arbitrary(item)
 }
+
+/// Define a named function from a closure, so it can be used as the original or the replacement
+/// of a stub (e.g., in `#[kani::stub(parse_digit, mock_parse_digit)]`), which only accept paths to
+/// functions and methods. Each definition has the form `fn name = closure;`, and one invocation
+/// may contain several of them:
+///
+/// ```ignore
+/// kani::hoist_closure! {
+///     /// Attributes and documentation are kept.
+///     pub fn parse_digit = |c: char| -> Option<u32> { c.to_digit(10) };
+/// }
+///
+/// fn sum_digits(input: &str) -> u32 {
+///     input.chars().filter_map(parse_digit).sum()
+/// }
+/// ```
+///
+/// The closure becomes the body of a function with the same parameters and return type, so the
+/// function can be passed wherever the closure was expected. Since functions have no environment,
+/// the closure cannot capture any variable, and it must be defined where items are allowed rather
+/// than inside the function that uses it. The type of each parameter and the return type must be
+/// annotated, and `move` and `async` closures are rejected.
+#[proc_macro_error]
+#[proc_macro]
+pub fn hoist_closure(item: TokenStream) -> TokenStream {
+    closure::expand_hoist_closure(item)
+}
@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This tests that `kani::stub_cfg` selects the stub whose cfg predicate holds,
+//! which is `fast_read_sensor` since the `fast_mock` feature is enabled.
+
+fn read_sensor() -> u32 {
+    unimplemented!("reads from the hardware")
+}
+
+fn fast_read_sensor() -> u32 {
+    10
+}
+
+fn precise_read_sensor() -> u32 {
+    let reading: u32 = kani::any();
+    kani::assume((10..=20).contains(&reading));
+    reading
+}
+
+#[kani::proof]
+#[kani::stub_cfg(
+    read_sensor,
+    cfg(feature = "fast_mock", fast_read_sensor),
+    cfg(feature = "precise_mock", precise_read_sensor)
+)]
+fn check_sensor() {
+    let reading = read_sensor();
+    assert_eq!(reading, 10);
+}
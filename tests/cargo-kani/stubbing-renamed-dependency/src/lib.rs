@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This tests that the paths in `kani::stub` attributes may name a dependency
+//! by the name it is given in `Cargo.toml` (`sensors`), which differs from the
+//! name of the crate (`other_crate`).
+
+#[kani::proof]
+#[kani::stub(zero, sensors::magic_number13)]
+#[kani::stub(one, sensors::inner_mod::magic_number42)]
+#[kani::stub(two, sensors::MyType::magic_number101)]
+fn harness() {
+    assert_eq!(zero(), sensors::magic_number13());
+    assert_eq!(one(), sensors::inner_mod::magic_number42());
+    assert_eq!(two(), sensors::MyType::magic_number101());
+}
+
+fn zero() -> u32 {
+    0
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn two() -> u32 {
+    2
+}
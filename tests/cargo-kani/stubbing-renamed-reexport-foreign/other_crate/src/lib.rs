@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The function `real_impl` is only reachable via the renamed re-export at the
+//! crate root, which also re-exports the module with the same name.
+
+mod internal {
+    pub fn real_impl() -> u32 {
+        0
+    }
+
+    pub mod real_impl {
+        pub fn helper() -> u32 {
+            1
+        }
+    }
+}
+
+pub use internal::real_impl as public_name;
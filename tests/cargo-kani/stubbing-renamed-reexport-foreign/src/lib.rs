@@ -0,0 +1,16 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This tests whether we resolve paths in `kani::stub` attributes that refer
+//! to a function through a renamed re-export at the root of another crate.
+
+fn magic_number42() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(other_crate::public_name, magic_number42)]
+fn harness() {
+    assert_eq!(other_crate::public_name(), 42);
+    assert_eq!(other_crate::public_name::helper(), 1);
+}
@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The functions of this crate are only reachable via the glob re-export in
+//! the `prelude` module.
+
+mod inner {
+    pub mod ops {
+        pub fn zero() -> u32 {
+            0
+        }
+
+        pub fn magic_number42() -> u32 {
+            42
+        }
+    }
+
+    pub use self::ops::*;
+}
+
+pub mod prelude {
+    pub use crate::inner::*;
+}
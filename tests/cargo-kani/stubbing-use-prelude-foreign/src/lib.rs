@@ -0,0 +1,11 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This tests whether we resolve paths in `kani::stub` attributes that refer
+//! to functions that are only exported via a `prelude` module glob re-export.
+
+#[kani::proof]
+#[kani::stub(other_crate::prelude::zero, other_crate::prelude::magic_number42)]
+fn harness() {
+    assert_eq!(other_crate::prelude::zero(), 42);
+}
@@ -0,0 +1,143 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! A simplified version of the Firecracker vsock example from the function stubbing RFC
+//! (`rfc/src/rfcs/0002-function-stubbing.md`). The deserialization of the request body is stubbed
+//! out with mocks that build a symbolic configuration.
+
+#[cfg(kani)]
+mod mocks;
+
+/// The vsock device configuration sent in a `PUT /vsock` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VsockDeviceConfig {
+    /// Deprecated identifier of the device.
+    pub vsock_id: Option<String>,
+    /// The context identifier of the guest.
+    pub guest_cid: u32,
+    /// Path to the unix domain socket on the host.
+    pub uds_path: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The request body could not be deserialized.
+    Deserialize,
+    /// The guest CID is one of the values reserved by the vsock specification.
+    ReservedCid(u32),
+    /// No device is listening on the unix domain socket.
+    NotFound,
+    /// A device is listening on the unix domain socket, but it has a different CID.
+    CidMismatch(u32),
+}
+
+/// Extra information collected while parsing a request.
+#[derive(Debug, Default)]
+pub struct ParsingInfo {
+    deprecation_message: Option<String>,
+}
+
+impl ParsingInfo {
+    pub fn append_deprecation_message(&mut self, message: &str) {
+        match self.deprecation_message.as_mut() {
+            None => self.deprecation_message = Some(message.to_owned()),
+            Some(s) => (*s).push_str(message),
+        }
+    }
+
+    pub fn take_deprecation_message(&mut self) -> Option<String> {
+        self.deprecation_message.take()
+    }
+}
+
+pub struct ParsedRequest {
+    config: VsockDeviceConfig,
+    parsing_info: ParsingInfo,
+}
+
+impl ParsedRequest {
+    pub fn into_parts(self) -> (VsockDeviceConfig, ParsingInfo) {
+        (self.config, self.parsing_info)
+    }
+}
+
+/// The lowest guest CID that is not reserved.
+pub const MIN_GUEST_CID: u32 = 3;
+
+/// Stands for `serde_json::from_slice`, which is too expensive to verify.
+pub fn deserialize(_body: &[u8]) -> Result<VsockDeviceConfig, Error> {
+    Err(Error::Deserialize)
+}
+
+/// Stands for a query to the device manager, which returns the CID of the device behind the
+/// given socket, if any.
+pub fn lookup_cid(_uds_path: &str) -> Option<u32> {
+    None
+}
+
+/// Returns the CID of an existing device with the same socket as the given configuration. The
+/// CID of the existing device has to match the requested one.
+pub fn find_existing_cid(config: &VsockDeviceConfig) -> Result<u32, Error> {
+    match lookup_cid(&config.uds_path) {
+        Some(cid) if cid == config.guest_cid => Ok(cid),
+        Some(cid) => Err(Error::CidMismatch(cid)),
+        None => Err(Error::NotFound),
+    }
+}
+
+pub fn parse_put_vsock(body: &[u8]) -> Result<ParsedRequest, Error> {
+    let config = deserialize(body)?;
+    if config.guest_cid < MIN_GUEST_CID {
+        return Err(Error::ReservedCid(config.guest_cid));
+    }
+
+    let mut parsing_info = ParsingInfo::default();
+    // Check for the presence of deprecated `vsock_id` field.
+    if config.vsock_id.is_some() {
+        parsing_info.append_deprecation_message("PUT /vsock: vsock_id field is deprecated.");
+    }
+    Ok(ParsedRequest { config, parsing_info })
+}
+
+/// The configuration has a vsock ID if and only if the parsing information includes a
+/// deprecation message. This requires every field of the configuration to be symbolic.
+#[cfg(kani)]
+#[kani::proof]
+#[kani::unwind(2)]
+#[kani::stub(deserialize, mocks::mock_deserialize)]
+fn check_deprecation_consistent() {
+    if let Ok(request) = parse_put_vsock(&[]) {
+        let (config, mut parsing_info) = request.into_parts();
+        assert_eq!(config.vsock_id.is_some(), parsing_info.take_deprecation_message().is_some());
+    }
+}
+
+/// Reserved CIDs are always rejected. Only the CID matters here, so the string fields can be
+/// concrete.
+#[cfg(kani)]
+#[kani::proof]
+#[kani::stub(deserialize, mocks::mock_deserialize_cid_only)]
+fn check_reserved_cid() {
+    match parse_put_vsock(&[]) {
+        Ok(request) => assert!(request.into_parts().0.guest_cid >= MIN_GUEST_CID),
+        Err(Error::ReservedCid(cid)) => assert!(cid < MIN_GUEST_CID),
+        Err(Error::Deserialize) => unreachable!("the mock never fails"),
+        Err(Error::NotFound | Error::CidMismatch(_)) => {
+            unreachable!("parsing does not look up devices")
+        }
+    }
+}
+
+/// The lookup is stubbed with a mock that may or may not find a device, so both outcomes are
+/// covered.
+#[cfg(kani)]
+#[kani::proof]
+#[kani::stub(lookup_cid, mocks::mock_lookup_cid)]
+fn check_existing_cid() {
+    let config = VsockDeviceConfig::any_cid();
+    let result = find_existing_cid(&config);
+    kani::cover!(result == Err(Error::NotFound), "no device found");
+    if let Ok(cid) = result {
+        assert_eq!(cid, config.guest_cid);
+    }
+}
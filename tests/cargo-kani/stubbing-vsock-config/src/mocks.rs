@@ -0,0 +1,48 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Support code shared by the harnesses: symbolic constructors and the stubs that use them.
+
+use crate::{Error, VsockDeviceConfig};
+
+/// Length of the symbolic strings. Keep it small since every byte adds to the state space.
+const STR_LEN: usize = 1;
+
+fn symbolic_string(len: usize) -> String {
+    let mut v: Vec<u8> = Vec::with_capacity(len);
+    for _ in 0..len {
+        v.push(kani::any());
+    }
+    unsafe { String::from_utf8_unchecked(v) }
+}
+
+impl VsockDeviceConfig {
+    /// Create a configuration where every field is symbolic, including the bounded strings.
+    pub fn any() -> Self {
+        let vsock_id = if kani::any() { None } else { Some(symbolic_string(STR_LEN)) };
+        VsockDeviceConfig { vsock_id, guest_cid: kani::any(), uds_path: symbolic_string(STR_LEN) }
+    }
+
+    /// Create a configuration where only `guest_cid` is symbolic. The string fields are fixed to
+    /// concrete values, which keeps the state space small for harnesses that only exercise the
+    /// CID logic.
+    pub fn any_cid() -> Self {
+        VsockDeviceConfig {
+            vsock_id: None,
+            guest_cid: kani::any(),
+            uds_path: String::from("/tmp/vsock.sock"),
+        }
+    }
+}
+
+pub fn mock_deserialize(_body: &[u8]) -> Result<VsockDeviceConfig, Error> {
+    Ok(VsockDeviceConfig::any())
+}
+
+pub fn mock_deserialize_cid_only(_body: &[u8]) -> Result<VsockDeviceConfig, Error> {
+    Ok(VsockDeviceConfig::any_cid())
+}
+
+pub fn mock_lookup_cid(_uds_path: &str) -> Option<u32> {
+    kani::any()
+}
@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! This tests that Kani reports an error if the edition of a crate with stubs is not the one
+//! given by `--stub-path-edition`.
+
+fn foo() -> u32 {
+    0
+}
+
+fn mock_foo() -> u32 {
+    1
+}
+
+#[kani::proof]
+#[kani::stub(foo, mock_foo)]
+fn harness() {
+    assert_eq!(foo(), 1);
+}
@@ -0,0 +1,25 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --enable-unstable --reachable-counts text
+//
+//! This tests that `--reachable-counts` reports how many of the reachable
+//! functions come from the local crate and from each of its dependencies.
+
+fn increment(x: u8) -> u8 {
+    x.wrapping_add(1)
+}
+
+fn is_changed(x: u8) -> bool {
+    increment(x) != x
+}
+
+fn unreachable_helper() -> u8 {
+    0
+}
+
+#[kani::proof]
+fn check_increment() {
+    let x: u8 = kani::any();
+    assert!(is_changed(x));
+}
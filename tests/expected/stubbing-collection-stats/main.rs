@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_stubs --enable-unstable --enable-stubbing --stub-collection-stats
+//
+//! This tests that `--stub-collection-stats` reports the cost of the
+//! compilation that collects the stubs.
+
+fn one() -> u32 {
+    1
+}
+
+fn stub_one() -> u32 {
+    10
+}
+
+#[kani::proof]
+#[kani::stub(one, stub_one)]
+fn check_stubs() {
+    assert_eq!(one(), 10);
+}
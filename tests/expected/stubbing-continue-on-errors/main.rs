@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness working --enable-unstable --enable-stubbing --continue-on-stub-errors
+//
+//! This tests that, with `--continue-on-stub-errors`, a stub that fails to
+//! resolve in one harness is reported as a warning and does not prevent the
+//! verification of another harness.
+
+fn magic_number() -> u32 {
+    13
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, does_not_exist)]
+fn broken() {
+    assert_eq!(magic_number(), 42);
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, forty_two)]
+fn working() {
+    assert_eq!(magic_number(), 42);
+}
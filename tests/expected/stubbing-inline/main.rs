@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_inline --enable-unstable --enable-stubbing
+//
+//! This tests that an `#[inline(always)]` function can be stubbed, and that
+//! Kani warns if the stub has an inline attribute that contradicts the one of
+//! the original function.
+
+#[inline(always)]
+fn fast_hash(value: u32) -> u32 {
+    value.wrapping_mul(0x9e37_79b9).rotate_left(5)
+}
+
+fn identity_hash(value: u32) -> u32 {
+    value
+}
+
+#[inline(always)]
+fn fast_len(values: &[u8]) -> usize {
+    values.len()
+}
+
+#[inline(never)]
+fn fixed_len(_values: &[u8]) -> usize {
+    3
+}
+
+#[kani::proof]
+#[kani::stub(fast_hash, identity_hash)]
+#[kani::stub(fast_len, fixed_len)]
+fn check_inline() {
+    let value: u32 = kani::any();
+    assert_eq!(fast_hash(value), value);
+    assert_eq!(fast_len(&[]), 3);
+}
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness main --enable-unstable --enable-stubbing
+//
+//! This tests that a path to a function re-exported by a `prelude` module
+//! resolves to the canonical definition of the function: stubbing both the
+//! re-export and the original function is a duplicate stub mapping.
+
+mod inner {
+    pub mod ops {
+        pub fn zero() -> u32 {
+            0
+        }
+    }
+
+    pub use self::ops::*;
+}
+
+mod prelude {
+    pub use crate::inner::*;
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn two() -> u32 {
+    2
+}
+
+#[kani::proof]
+#[kani::stub(prelude::zero, one)]
+#[kani::stub(inner::ops::zero, two)]
+fn main() {
+    assert_eq!(prelude::zero(), 1);
+}
@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_stubs --enable-unstable --enable-stubbing --stub-resolution-stats
+//
+//! This tests that `--stub-resolution-stats` reports the number of stub paths
+//! that were resolved.
+
+mod inner {
+    pub fn zero() -> u32 {
+        0
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn two() -> u32 {
+    2
+}
+
+fn stub_one() -> u32 {
+    10
+}
+
+#[kani::proof]
+#[kani::stub(one, stub_one)]
+#[kani::stub(two, inner::zero)]
+fn check_stubs() {
+    assert_eq!(one() + two(), 10);
+}
@@ -0,0 +1,17 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness main --enable-unstable --enable-stubbing
+//
+//! This tests that we warn the user when a stub replaces a function from the
+//! standard library and `--allow-std-stubbing` was not provided.
+
+fn mock_from_u32(_: u32) -> Option<char> {
+    None
+}
+
+#[kani::proof]
+#[kani::stub(std::char::from_u32, mock_from_u32)]
+fn main() {
+    assert!(std::char::from_u32(65).is_none());
+}
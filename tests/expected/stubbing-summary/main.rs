@@ -0,0 +1,56 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_mixed --enable-unstable --enable-stubbing --stub-summary
+//
+//! This tests that `--stub-summary` reports the number of stubs that were
+//! requested, resolved, applied and skipped for each harness, for stubs with
+//! different outcomes.
+
+mod ops {
+    pub fn get_one() -> u32 {
+        1
+    }
+
+    pub fn get_two() -> u32 {
+        2
+    }
+
+    pub fn unused() -> u32 {
+        3
+    }
+}
+
+fn zero() -> u32 {
+    0
+}
+
+#[track_caller]
+fn stub_with_location() -> u32 {
+    42
+}
+
+fn is_zero(value: u32) -> bool {
+    value == 0
+}
+
+/// One stub is not reachable, and the `kani::stub_regex` attribute resolves
+/// to two stubs.
+#[kani::proof]
+#[kani::stub_regex("ops::get_.*", "zero")]
+#[kani::stub(ops::unused, zero)]
+#[kani::stub(is_zero, stub_is_zero)]
+fn check_mixed() {
+    assert_eq!(ops::get_one() + ops::get_two(), 0);
+    assert!(is_zero(1));
+}
+
+fn stub_is_zero(_value: u32) -> bool {
+    true
+}
+
+#[kani::proof]
+#[kani::stub(ops::get_one, stub_with_location)]
+fn check_other() {
+    assert_eq!(ops::get_one(), 42);
+}
@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_track_caller --enable-unstable --enable-stubbing
+//
+//! This tests that Kani warns if a `#[track_caller]` function is stubbed by a
+//! function that is not `#[track_caller]`, and vice versa.
+
+#[track_caller]
+fn checked_get(values: &[u8], idx: usize) -> u8 {
+    values[idx]
+}
+
+fn clamped_get(values: &[u8], idx: usize) -> u8 {
+    values[idx.min(values.len() - 1)]
+}
+
+fn increment(value: u8) -> u8 {
+    value + 1
+}
+
+#[track_caller]
+fn saturating_increment(value: u8) -> u8 {
+    value.saturating_add(1)
+}
+
+#[kani::proof]
+#[kani::stub(checked_get, clamped_get)]
+#[kani::stub(increment, saturating_increment)]
+fn check_track_caller() {
+    let values = [1, 2, 3];
+    let idx: usize = kani::any();
+    assert_eq!(checked_get(&values, 2), 3);
+    assert!(checked_get(&values, idx) <= 3);
+    assert_eq!(increment(u8::MAX), u8::MAX);
+}
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_wrap --enable-unstable --enable-stubbing
+//
+//! This tests that the conditions of a wrapper are checked on every call of
+//! the wrapped function/method: the precondition holds for the calls the
+//! harness makes, but the postcondition does not hold for all of them.
+
+fn average(low: u8, high: u8) -> u8 {
+    low + (high - low) / 2
+}
+
+fn is_ordered(low: &u8, high: &u8) -> bool {
+    low <= high
+}
+
+fn is_small(value: &u8) -> bool {
+    *value < 200
+}
+
+#[kani::proof]
+#[kani::stub_spec(average, "wrap requires is_ordered ensures is_small")]
+fn check_wrap() {
+    let low: u8 = kani::any();
+    let high: u8 = kani::any();
+    kani::assume(low <= high);
+    average(low, high);
+}
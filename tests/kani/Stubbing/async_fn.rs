@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// compile-flags: --edition 2018
+// kani-flags: --harness check_async_stub --enable-unstable --enable-stubbing
+//
+//! This tests that an async function can be stubbed by another async function, in which case the
+//! future returned by the original runs the logic of the stub.
+
+async fn fetch(key: u8) -> u32 {
+    assert!(key != 0, "the original must not be called");
+    u32::MAX
+}
+
+async fn mock_fetch(key: u8) -> u32 {
+    key as u32 + 1
+}
+
+async fn sum_fetches() -> u32 {
+    fetch(1).await + fetch(2).await
+}
+
+#[kani::proof]
+#[kani::unwind(2)]
+#[kani::stub(fetch, mock_fetch)]
+fn check_async_stub() {
+    let sum = kani::block_on(sum_fetches());
+    assert_eq!(sum, 5);
+    assert_eq!(kani::block_on(fetch(0)), 1);
+}
@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a stub marked `kani::experimental_stub` is not applied
+//! without `--enable-experimental-stubs`, while the other stubs still are.
+
+fn magic_number() -> u32 {
+    42
+}
+
+fn other_number() -> u32 {
+    7
+}
+
+#[kani::experimental_stub]
+fn draft_magic_number() -> u32 {
+    0
+}
+
+fn mock_other_number() -> u32 {
+    8
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, draft_magic_number)]
+#[kani::stub(other_number, mock_other_number)]
+fn harness() {
+    assert_eq!(magic_number(), 42);
+    assert_eq!(other_number(), 8);
+}
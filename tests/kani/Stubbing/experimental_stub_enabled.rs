@@ -0,0 +1,22 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --enable-experimental-stubs
+//
+//! This tests that a stub marked `kani::experimental_stub` is applied with
+//! `--enable-experimental-stubs`.
+
+fn magic_number() -> u32 {
+    42
+}
+
+#[kani::experimental_stub]
+fn draft_magic_number() -> u32 {
+    0
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, draft_magic_number)]
+fn harness() {
+    assert_eq!(magic_number(), 0);
+}
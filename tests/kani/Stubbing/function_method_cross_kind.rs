@@ -0,0 +1,50 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a free function can be stubbed by an associated function or
+//! a method and vice versa, as long as the `self` receiver of the method has
+//! the type of the first parameter of the function.
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn new_zero() -> Counter {
+        Counter { count: 0 }
+    }
+
+    fn get(&self) -> u32 {
+        self.count
+    }
+
+    fn bump(&mut self) {
+        self.count += 1;
+    }
+}
+
+fn make_counter() -> Counter {
+    Counter { count: 10 }
+}
+
+fn count_of(counter: &Counter) -> u32 {
+    counter.count * 2
+}
+
+fn bump_by_ten(counter: &mut Counter) {
+    counter.count += 10;
+}
+
+#[kani::proof]
+#[kani::stub(make_counter, Counter::new_zero)]
+#[kani::stub(Counter::get, count_of)]
+#[kani::stub(Counter::bump, bump_by_ten)]
+fn harness() {
+    let mut counter = make_counter();
+    assert_eq!(counter.count, 0);
+    counter.bump();
+    assert_eq!(counter.count, 10);
+    assert_eq!(counter.get(), 20);
+}
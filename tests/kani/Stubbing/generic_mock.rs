@@ -0,0 +1,41 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_generic_mock --enable-unstable --enable-stubbing
+//
+//! This tests that one generic mock can replace several functions without generic parameters,
+//! each with its own signature, by instantiating the generic parameters of the mock for each of
+//! them.
+
+fn read_count() -> u32 {
+    7
+}
+
+fn read_flag() -> bool {
+    false
+}
+
+fn read_pair(_scale: u8) -> (i64, char) {
+    (0, 'a')
+}
+
+fn mock<T: kani::Arbitrary>() -> T {
+    kani::any()
+}
+
+fn mock_with_arg<A, T: kani::Arbitrary>(_arg: A) -> T {
+    kani::any()
+}
+
+#[kani::proof]
+#[kani::stub(read_count, mock)]
+#[kani::stub(read_flag, mock)]
+#[kani::stub(read_pair, mock_with_arg)]
+fn check_generic_mock() {
+    let count = read_count();
+    let flag = read_flag();
+    let (value, letter) = read_pair(2);
+    kani::cover!(count != 7);
+    kani::cover!(flag);
+    kani::cover!(value != 0 && letter != 'a');
+}
@@ -0,0 +1,15 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_input --enable-unstable --enable-stubbing --stub-harness-body
+//
+//! This tests that `--stub-harness-body` lets a stub replace the body of the
+//! harness itself.
+
+fn mock_input() {}
+
+#[kani::proof]
+#[kani::stub(check_input, mock_input)]
+fn check_input() {
+    panic!("the harness should be stubbed");
+}
@@ -0,0 +1,27 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_input --enable-unstable --enable-stubbing
+//
+//! This tests that a stub does not replace the body of the harness itself by
+//! default, even if the harness matches the same pattern as the functions it
+//! stubs or is explicitly stubbed.
+
+fn check_input_value() -> u32 {
+    0
+}
+
+fn mock_input_value() -> u32 {
+    42
+}
+
+fn mock_input() {
+    panic!("the harness should not be stubbed");
+}
+
+#[kani::proof]
+#[kani::stub_regex("check_(.*)", "mock_$1")]
+#[kani::stub(check_input, mock_input)]
+fn check_input() {
+    assert_eq!(check_input_value(), 42);
+}
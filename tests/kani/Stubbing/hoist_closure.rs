@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_mock_digits --enable-unstable --enable-stubbing
+//
+//! This tests the workflow to stub a closure: the closure is turned into a
+//! function with `kani::hoist_closure!`, which can then be stubbed by name.
+
+kani::hoist_closure! {
+    /// Parses one decimal digit.
+    fn parse_digit = |c: char| -> Option<u32> { c.to_digit(10) };
+
+    fn double = |(a, b): (u32, u32)| -> (u32, u32) { (2 * a, 2 * b) };
+}
+
+fn sum_digits(input: &str) -> u32 {
+    input.chars().filter_map(parse_digit).sum()
+}
+
+fn mock_parse_digit(_c: char) -> Option<u32> {
+    Some(7)
+}
+
+#[kani::proof]
+#[kani::stub(parse_digit, mock_parse_digit)]
+fn check_mock_digits() {
+    assert_eq!(sum_digits("ab"), 14);
+    assert_eq!(double((1, 2)), (2, 4));
+    let closure_result = [(3, 4)].map(double);
+    assert_eq!(closure_result, [(6, 8)]);
+}
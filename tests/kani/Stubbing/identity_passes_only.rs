@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --identity-passes-only
+//
+//! This tests that `--identity-passes-only` skips the stubbing pass, so the
+//! harness is verified against the original function.
+
+fn magic_number() -> u32 {
+    42
+}
+
+fn magic_number_stub() -> u32 {
+    1
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, magic_number_stub)]
+fn harness() {
+    assert_eq!(magic_number(), 42);
+}
@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_nested_mock --enable-unstable --enable-stubbing
+//
+//! This tests that a stub can be a function defined in the body of another
+//! function, which is named by the path of the enclosing function.
+
+fn read_sensor() -> u32 {
+    unimplemented!("no sensor attached")
+}
+
+fn mocks() {
+    #[allow(dead_code)]
+    fn mock_read_sensor() -> u32 {
+        42
+    }
+}
+
+#[kani::proof]
+#[kani::stub(read_sensor, mocks::mock_read_sensor)]
+fn check_nested_mock() {
+    assert_eq!(read_sensor(), 42);
+}
@@ -0,0 +1,35 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_sensors --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_regex` does not match functions marked
+//! `kani::no_stub`, while it still replaces the other matches.
+
+mod sensors {
+    pub fn read_temperature() -> i32 {
+        1000
+    }
+
+    #[kani::no_stub]
+    pub fn read_limit() -> i32 {
+        80
+    }
+}
+
+mod mock_sensors {
+    pub fn read_temperature() -> i32 {
+        25
+    }
+
+    pub fn read_limit() -> i32 {
+        0
+    }
+}
+
+#[kani::proof]
+#[kani::stub_regex("sensors::read_(.*)", "mock_sensors::read_$1")]
+fn check_sensors() {
+    assert_eq!(sensors::read_temperature(), 25);
+    assert_eq!(sensors::read_limit(), 80);
+}
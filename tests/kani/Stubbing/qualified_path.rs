@@ -0,0 +1,64 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that the original function/method may be given as a fully-qualified path of the
+//! form `<Type as Trait>::method`, which resolves to the method of the implementation of the
+//! trait for the type.
+
+mod shapes {
+    pub trait Area {
+        fn area(&self) -> u32;
+    }
+
+    pub trait Perimeter {
+        fn area(&self) -> u32;
+    }
+
+    pub struct Square(pub u32);
+
+    impl Area for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    impl Perimeter for Square {
+        fn area(&self) -> u32 {
+            4 * self.0
+        }
+    }
+
+    /// A value with the number of times it was cloned by a stub.
+    pub struct Wrapper<T>(pub T, pub u8);
+
+    impl<T: Clone> Clone for Wrapper<T> {
+        fn clone(&self) -> Self {
+            Wrapper(self.0.clone(), self.1)
+        }
+    }
+}
+
+use shapes::{Area, Perimeter, Square, Wrapper};
+
+fn mock_area(_: &Square) -> u32 {
+    42
+}
+
+fn mock_clone<T: Clone>(wrapper: &Wrapper<T>) -> Wrapper<T> {
+    Wrapper(wrapper.0.clone(), wrapper.1 + 1)
+}
+
+#[kani::proof]
+#[kani::stub("<shapes::Square as shapes::Area>::area", mock_area)]
+#[kani::stub("<Wrapper<u8> as Clone>::clone", mock_clone)]
+fn harness() {
+    let square = Square(2);
+    assert_eq!(Area::area(&square), 42);
+    assert_eq!(Perimeter::area(&square), 8);
+    let wrapper = Wrapper(kani::any::<u8>(), 0);
+    let cloned = wrapper.clone();
+    assert_eq!(cloned.0, wrapper.0);
+    assert_eq!(cloned.1, 1);
+}
@@ -0,0 +1,53 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --verify-stub-types
+//
+//! This tests that functions that take or return references can be stubbed by functions that
+//! use different lifetimes, since lifetimes are erased in MIR, while the referent types and the
+//! mutability of the references still have to match.
+
+struct Pair {
+    first: u32,
+    second: u32,
+}
+
+fn first(pair: &Pair) -> &u32 {
+    &pair.first
+}
+
+fn second<'p>(pair: &'p Pair) -> &'p u32 {
+    &pair.second
+}
+
+fn longest<'a>(x: &'a str, _y: &'a str) -> &'a str {
+    x
+}
+
+fn mock_longest<'x, 'y: 'x>(_x: &'x str, y: &'y str) -> &'x str {
+    y
+}
+
+fn first_mut<'a>(pair: &'a mut Pair) -> &'a mut u32 {
+    &mut pair.first
+}
+
+fn second_mut<'b, 'c>(pair: &'b mut Pair) -> &'b mut u32
+where
+    'b: 'c,
+{
+    &mut pair.second
+}
+
+#[kani::proof]
+#[kani::stub(first, second)]
+#[kani::stub(longest, mock_longest)]
+#[kani::stub(first_mut, second_mut)]
+fn harness() {
+    let mut pair = Pair { first: 1, second: 2 };
+    assert_eq!(*first(&pair), 2);
+    assert_eq!(longest("a", "b"), "b");
+    *first_mut(&mut pair) = 3;
+    assert_eq!(pair.first, 1);
+    assert_eq!(pair.second, 3);
+}
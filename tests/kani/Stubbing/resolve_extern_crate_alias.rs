@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness my_mod::harness --enable-unstable --enable-stubbing --allow-std-stubbing
+//
+//! This tests that we take into account the aliases of `extern crate XXX as
+//! YYY;` items at the root of the crate when resolving paths in `kani::stub`
+//! attributes, including paths used in other modules and paths that start with
+//! `::`.
+
+extern crate std as rust_std;
+
+fn mock_from_u32(_: u32) -> Option<char> {
+    None
+}
+
+fn mock_from_digit(_: u32, _: u32) -> Option<char> {
+    Some('x')
+}
+
+mod my_mod {
+    #[kani::proof]
+    #[kani::stub(rust_std::char::from_u32, super::mock_from_u32)]
+    #[kani::stub(::rust_std::char::from_digit, super::mock_from_digit)]
+    fn harness() {
+        assert!(std::char::from_u32(65).is_none());
+        assert_eq!(std::char::from_digit(1, 10), Some('x'));
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that the stubs of a function that contains closures replace the
+//! function itself, whether it is given by its path or by a source location
+//! within one of its closures: the closures of a function have paths nested
+//! under the path of the function, but they are never chosen as the original.
+
+fn transform(input: u8) -> u8 {
+    let double = |x: u8| x.wrapping_mul(2);
+    double(input)
+}
+
+fn scale(input: u8) -> u8 {
+    let add_one = |x: u8| {
+        x.wrapping_add(1)
+    };
+    add_one(input)
+}
+
+fn mock_transform(_input: u8) -> u8 {
+    7
+}
+
+fn mock_scale(_input: u8) -> u8 {
+    9
+}
+
+#[kani::proof]
+#[kani::stub(transform, mock_transform)]
+#[kani::stub("location:resolve_fn_with_closure.rs:18:9", mock_scale)]
+fn harness() {
+    assert_eq!(transform(kani::any()), 7);
+    assert_eq!(scale(kani::any()), 9);
+}
@@ -0,0 +1,43 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that paths in `kani::stub` attributes resolve to modules and
+//! functions that are generated by a function-like macro, using the path that
+//! is visible to the user.
+
+macro_rules! generate_module {
+    ($module:ident, $value:literal) => {
+        mod $module {
+            pub fn value() -> u32 {
+                $value
+            }
+
+            pub fn forty_two() -> u32 {
+                42
+            }
+        }
+    };
+}
+
+generate_module!(generated, 0);
+
+macro_rules! generate_fn {
+    ($name:ident, $value:literal) => {
+        fn $name() -> u32 {
+            $value
+        }
+    };
+}
+
+generate_fn!(one, 1);
+generate_fn!(thirteen, 13);
+
+#[kani::proof]
+#[kani::stub(generated::value, generated::forty_two)]
+#[kani::stub(one, thirteen)]
+fn harness() {
+    assert_eq!(generated::value(), 42);
+    assert_eq!(one(), 13);
+}
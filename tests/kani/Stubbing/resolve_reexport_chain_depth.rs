@@ -0,0 +1,60 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_chain --enable-unstable --enable-stubbing --stub-resolution-depth 11
+//
+//! This tests that we resolve paths through a chain of glob re-exports that
+//! goes through as many modules as `--stub-resolution-depth` allows: the crate
+//! root, `m0`, and the nine modules that `m0` re-exports transitively.
+
+mod m0 {
+    pub use super::m1::*;
+}
+
+mod m1 {
+    pub use super::m2::*;
+}
+
+mod m2 {
+    pub use super::m3::*;
+}
+
+mod m3 {
+    pub use super::m4::*;
+}
+
+mod m4 {
+    pub use super::m5::*;
+}
+
+mod m5 {
+    pub use super::m6::*;
+}
+
+mod m6 {
+    pub use super::m7::*;
+}
+
+mod m7 {
+    pub use super::m8::*;
+}
+
+mod m8 {
+    pub use super::m9::*;
+}
+
+mod m9 {
+    pub fn get() -> u32 {
+        0
+    }
+}
+
+fn stub_get() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(m0::get, stub_get)]
+fn check_chain() {
+    assert_eq!(m0::get(), 42);
+}
@@ -0,0 +1,38 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness my_mod::harness --enable-unstable --enable-stubbing
+//
+//! This tests whether we resolve paths in `kani::stub` attributes through a
+//! renamed re-export of a function at the crate root (`pub use XXX as YYY;`),
+//! even if the re-export also imports a module with the same name.
+
+mod internal {
+    pub fn real_impl() -> u32 {
+        0
+    }
+
+    /// A module with the same name as the function, which the re-export below
+    /// imports as well.
+    pub mod real_impl {
+        pub fn helper() -> u32 {
+            1
+        }
+    }
+}
+
+pub use internal::real_impl as public_name;
+
+mod my_mod {
+    pub fn magic_number13() -> u32 {
+        13
+    }
+
+    #[kani::proof]
+    #[kani::stub(crate::public_name, magic_number13)]
+    fn harness() {
+        assert_eq!(crate::public_name(), 13);
+        assert_eq!(crate::internal::real_impl(), 13);
+        assert_eq!(crate::public_name::helper(), 1);
+    }
+}
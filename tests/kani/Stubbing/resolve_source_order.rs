@@ -0,0 +1,75 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness my_mod::harness --enable-unstable --enable-stubbing
+//
+//! This tests that the paths in `kani::stub` attributes resolve regardless of
+//! whether the original functions and their stubs are defined before or after
+//! the harness, and in sibling or child modules. It also checks that a module
+//! defined before a function with the same name does not hide the function.
+
+fn zero_before() -> u32 {
+    0
+}
+
+mod sibling_before {
+    pub fn one() -> u32 {
+        1
+    }
+}
+
+mod my_mod {
+    fn two_before() -> u32 {
+        2
+    }
+
+    mod three {
+        pub fn three() -> u32 {
+            3
+        }
+    }
+
+    #[kani::proof]
+    #[kani::stub(super::zero_before, super::sibling_after::forty_two)]
+    #[kani::stub(super::sibling_before::one, child_after::nested::forty_two)]
+    #[kani::stub(two_before, forty_two_after)]
+    #[kani::stub(three, three::three)]
+    #[kani::stub(child_after::nested::four, super::forty_two_after_all)]
+    fn harness() {
+        assert_eq!(super::zero_before(), 42);
+        assert_eq!(super::sibling_before::one(), 42);
+        assert_eq!(two_before(), 42);
+        assert_eq!(three(), 3);
+        assert_eq!(child_after::nested::four(), 42);
+    }
+
+    fn three() -> u32 {
+        0
+    }
+
+    fn forty_two_after() -> u32 {
+        42
+    }
+
+    mod child_after {
+        pub mod nested {
+            pub fn forty_two() -> u32 {
+                42
+            }
+
+            pub fn four() -> u32 {
+                4
+            }
+        }
+    }
+}
+
+mod sibling_after {
+    pub fn forty_two() -> u32 {
+        42
+    }
+}
+
+fn forty_two_after_all() -> u32 {
+    42
+}
@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_symbol_stubs --enable-unstable --enable-stubbing
+//
+//! This tests whether we resolve the original function in `kani::stub`
+//! attributes by its symbol name, for functions exposed through FFI with
+//! `#[no_mangle]` or `#[export_name]`.
+
+mod ffi {
+    #[no_mangle]
+    pub extern "C" fn ffi_read() -> u32 {
+        0
+    }
+
+    #[export_name = "ffi_write_impl"]
+    pub extern "C" fn ffi_write(value: u32) -> u32 {
+        value
+    }
+}
+
+extern "C" fn mock_read() -> u32 {
+    42
+}
+
+extern "C" fn mock_write(_value: u32) -> u32 {
+    0
+}
+
+#[kani::proof]
+#[kani::stub("symbol:ffi_read", mock_read)]
+#[kani::stub("symbol:ffi_write_impl", mock_write)]
+fn check_symbol_stubs() {
+    assert_eq!(ffi::ffi_read(), 42);
+    assert_eq!(ffi::ffi_write(10), 0);
+}
@@ -0,0 +1,34 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that the whitespace around the segments of a path is ignored
+//! when resolving it, e.g., in a qualified path written as in formatted code.
+
+mod shapes {
+    pub trait Area {
+        fn area(&self) -> u32;
+    }
+
+    pub struct Square(pub u32);
+
+    impl Area for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+}
+
+use shapes::{Area, Square};
+
+fn mock_area(_: &Square) -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub("  < shapes :: Square   as\tshapes :: Area > :: area ", mock_area)]
+fn harness() {
+    let square = Square(2);
+    assert_eq!(square.area(), 42);
+}
@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_shared_ref_narrowing --enable-unstable --enable-stubbing --allow-stub-shared-refs
+//
+//! This tests that, with `--allow-stub-shared-refs`, a function that takes
+//! `&mut T` can be stubbed by a function that takes `&T`.
+
+struct Counter {
+    count: u32,
+}
+
+fn next_id(counter: &mut Counter) -> u32 {
+    counter.count += 1;
+    counter.count
+}
+
+fn peek_id(counter: &Counter) -> u32 {
+    counter.count + 1
+}
+
+#[kani::proof]
+#[kani::stub(next_id, peek_id)]
+fn check_shared_ref_narrowing() {
+    let mut counter = Counter { count: kani::any() };
+    kani::assume(counter.count < 100);
+    let before = counter.count;
+    assert_eq!(next_id(&mut counter), before + 1);
+    assert_eq!(counter.count, before);
+}
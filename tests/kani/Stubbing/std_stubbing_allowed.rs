@@ -0,0 +1,17 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --allow-std-stubbing
+//
+//! This tests that `--allow-std-stubbing` allows stubbing functions from the
+//! standard library.
+
+fn mock_from_u32(_: u32) -> Option<char> {
+    None
+}
+
+#[kani::proof]
+#[kani::stub(std::char::from_u32, mock_from_u32)]
+fn harness() {
+    assert!(std::char::from_u32(65).is_none());
+}
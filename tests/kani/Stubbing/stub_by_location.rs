@@ -0,0 +1,27 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that the original function/method may be given by a source
+//! location within its definition, which stubs the innermost function/method
+//! that contains it.
+
+mod generated {
+    pub fn parse(input: u8) -> u8 {
+        fn helper(input: u8) -> u8 {
+            input / 2
+        }
+        helper(input) + 1
+    }
+}
+
+fn mock_parse(_input: u8) -> u8 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub("location:stub_by_location.rs:15:9", mock_parse)]
+fn harness() {
+    assert_eq!(generated::parse(kani::any()), 42);
+}
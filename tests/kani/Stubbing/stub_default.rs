@@ -0,0 +1,43 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_default` replaces a function/method by one that
+//! returns `Default::default()`.
+
+#[derive(Default, PartialEq, Eq, Debug)]
+struct Config {
+    retries: u32,
+    verbose: bool,
+    name: Option<&'static str>,
+}
+
+fn load_config(_path: &str) -> Config {
+    panic!("the configuration cannot be read during verification")
+}
+
+struct Cache {
+    hits: u64,
+}
+
+impl Cache {
+    fn hits(&self) -> u64 {
+        self.hits
+    }
+}
+
+fn parse<T: Default + From<u8>>(byte: u8) -> T {
+    T::from(byte)
+}
+
+#[kani::proof]
+#[kani::stub_default(load_config)]
+#[kani::stub_default(Cache::hits)]
+#[kani::stub_default(parse)]
+fn harness() {
+    assert_eq!(load_config("/etc/app.toml"), Config { retries: 0, verbose: false, name: None });
+    let cache = Cache { hits: kani::any() };
+    assert_eq!(cache.hits(), 0);
+    assert_eq!(parse::<u32>(kani::any()), 0);
+}
@@ -0,0 +1,35 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harnesses::check_exact_paths --enable-unstable --enable-stubbing --stub-exact-paths
+//
+//! This tests that `--stub-exact-paths` resolves a stub path that is exactly
+//! the `def_path_str` of a function to it, even where a `use` statement in
+//! scope makes the relative path refer to another function.
+
+mod io {
+    pub fn read() -> u32 {
+        0
+    }
+}
+
+mod mocks {
+    pub fn read() -> u32 {
+        1
+    }
+
+    pub fn fake_read() -> u32 {
+        42
+    }
+}
+
+mod harnesses {
+    use super::mocks as io;
+
+    #[kani::proof]
+    #[kani::stub(io::read, mocks::fake_read)]
+    fn check_exact_paths() {
+        assert_eq!(crate::io::read(), 42);
+        assert_eq!(io::read(), 1);
+    }
+}
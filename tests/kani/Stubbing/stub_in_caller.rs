@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_stub_in_caller --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_in` only replaces the calls made by the given
+//! caller: `read_config` calls the stub, whereas `read_log` and the harness
+//! itself still call the original function.
+
+fn read_file() -> u32 {
+    1
+}
+
+fn mock_read_file() -> u32 {
+    42
+}
+
+fn read_config() -> u32 {
+    read_file()
+}
+
+fn read_log() -> u32 {
+    read_file()
+}
+
+#[kani::proof]
+#[kani::stub_in(read_config, read_file, mock_read_file)]
+fn check_stub_in_caller() {
+    assert_eq!(read_config(), 42);
+    assert_eq!(read_log(), 1);
+    assert_eq!(read_file(), 1);
+}
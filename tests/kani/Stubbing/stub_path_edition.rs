@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --stub-path-edition 2021 --allow-std-stubbing
+//
+//! This tests that `--stub-path-edition` resolves the stub paths against the given edition, where
+//! a path that starts with `::` refers to an external crate, and that the crate is compiled with
+//! that edition in every run of the compiler (`TryFrom` is only in the prelude of the 2021
+//! edition).
+
+fn mock_max<T: Ord>(a: T, _b: T) -> T {
+    a
+}
+
+#[kani::proof]
+#[kani::stub(::std::cmp::max, mock_max)]
+fn harness() {
+    assert_eq!(::std::cmp::max(1u32, 2u32), 1);
+    assert_eq!(u8::try_from(300u32).ok(), None);
+}
@@ -0,0 +1,38 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_regex` replaces every function whose path
+//! matches a prefix by the function at the corresponding replacement path.
+
+mod logging {
+    pub fn info(_msg: &str) -> u32 {
+        1
+    }
+
+    pub fn warn(_msg: &str) -> u32 {
+        2
+    }
+}
+
+mod mock_logging {
+    pub fn info(_msg: &str) -> u32 {
+        0
+    }
+
+    pub fn warn(_msg: &str) -> u32 {
+        0
+    }
+}
+
+fn unrelated() -> u32 {
+    3
+}
+
+#[kani::proof]
+#[kani::stub_regex("logging::(.*)", "mock_logging::$1")]
+fn harness() {
+    assert_eq!(logging::info("starting") + logging::warn("running"), 0);
+    assert_eq!(unrelated(), 3);
+}
@@ -0,0 +1,41 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_spec` replaces a function/method by one that
+//! returns any value that satisfies the specification.
+
+fn read_sensor() -> u16 {
+    panic!("the sensor cannot be read during verification")
+}
+
+fn temperature() -> i8 {
+    20
+}
+
+struct Dice;
+
+impl Dice {
+    fn roll(&self) -> u8 {
+        4
+    }
+}
+
+#[kani::proof]
+#[kani::stub_spec(read_sensor, "returns in 0..1024")]
+#[kani::stub_spec(temperature, "returns in -40..=85")]
+#[kani::stub_spec(Dice::roll, "returns in 1..=6")]
+fn harness() {
+    let reading = read_sensor();
+    assert!(reading < 1024);
+    kani::cover!(reading == 1023);
+
+    let temperature = temperature();
+    assert!(temperature >= -40 && temperature <= 85);
+    kani::cover!(temperature == -40);
+
+    let roll = Dice.roll();
+    assert!(roll >= 1 && roll <= 6);
+    kani::cover!(roll == 1);
+}
@@ -0,0 +1,52 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_spec` with a `returns where` specification
+//! abstracts a function/method by a contract on its return value: the stub
+//! returns any value for which the predicate holds.
+
+fn is_even(value: &u32) -> bool {
+    *value % 2 == 0
+}
+
+/// Too expensive to verify as written, so it is abstracted by its contract.
+fn next_even(start: u32) -> u32 {
+    let mut value = start;
+    while value % 2 != 0 {
+        value = value.wrapping_add(1);
+    }
+    value
+}
+
+struct Account {
+    balance: i64,
+}
+
+mod contracts {
+    pub fn is_solvent(balance: &i64) -> bool {
+        *balance >= 0
+    }
+}
+
+impl Account {
+    fn balance(&self) -> i64 {
+        self.balance
+    }
+}
+
+#[kani::proof]
+#[kani::stub_spec(next_even, "returns where is_even")]
+#[kani::stub_spec(Account::balance, "returns where contracts::is_solvent")]
+fn harness() {
+    let value = next_even(kani::any());
+    assert_eq!(value % 2, 0);
+    kani::cover!(value == 0);
+    kani::cover!(value > 1000);
+
+    let account = Account { balance: -10 };
+    let balance = account.balance();
+    assert!(balance >= 0);
+    kani::cover!(balance == 0);
+}
@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_spec` replaces a function/method by a lookup
+//! in a table, which falls back to the default for the inputs that are not in
+//! the table.
+
+fn is_odd(value: u8) -> bool {
+    unimplemented!("a slow computation that the table replaces for {value}")
+}
+
+fn gear_ratio(gear: i8) -> u32 {
+    unimplemented!("a lookup in a table that is not available for gear {gear}")
+}
+
+#[kani::proof]
+#[kani::stub_spec(is_odd, "table [(0, false), (1, true)]")]
+#[kani::stub_spec(gear_ratio, "table [(-1, 3), (1, 4)] else 0")]
+fn harness() {
+    let value: u8 = kani::any();
+    kani::assume(value < 2);
+    assert_eq!(is_odd(value), value == 1);
+
+    let gear: i8 = kani::any();
+    let ratio = gear_ratio(gear);
+    assert!(ratio == 0 || gear == -1 || gear == 1);
+    assert_eq!(gear_ratio(-1), 3);
+    kani::cover!(ratio == 0);
+}
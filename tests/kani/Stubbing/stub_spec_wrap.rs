@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::stub_spec` can wrap a function/method with a
+//! precondition on its arguments and a postcondition on its returned value,
+//! while still running the original body.
+
+fn average(low: u8, high: u8) -> u8 {
+    low + (high - low) / 2
+}
+
+fn is_ordered(low: &u8, high: &u8) -> bool {
+    low <= high
+}
+
+fn is_small(value: &u8) -> bool {
+    *value < 200
+}
+
+#[kani::proof]
+#[kani::stub_spec(average, "wrap requires is_ordered ensures is_small")]
+fn harness() {
+    let low: u8 = kani::any();
+    let high: u8 = kani::any();
+    kani::assume(low <= high && high < 200);
+    let mid = average(low, high);
+    assert!(low <= mid && mid <= high);
+    assert_eq!(average(10, 20), 15);
+}
@@ -0,0 +1,61 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a path of the form `Type::method` resolves to the method of
+//! the implementation of a trait for the type when the type has no inherent
+//! method with this name, and that inherent methods take precedence.
+
+mod counter {
+    pub struct Counter(pub u32);
+
+    impl Counter {
+        pub fn reset(&mut self) {
+            self.0 = 0;
+        }
+    }
+
+    impl Iterator for Counter {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            self.0 += 1;
+            Some(self.0)
+        }
+    }
+
+    pub trait Reset {
+        fn reset(&mut self);
+    }
+
+    impl Reset for Counter {
+        fn reset(&mut self) {
+            self.0 = 100;
+        }
+    }
+}
+
+use counter::{Counter, Reset};
+
+fn mock_next(counter: &mut Counter) -> Option<u32> {
+    counter.0 += 2;
+    None
+}
+
+fn mock_reset(counter: &mut Counter) {
+    counter.0 = 7;
+}
+
+#[kani::proof]
+#[kani::stub(counter::Counter::next, mock_next)]
+#[kani::stub(Counter::reset, mock_reset)]
+fn harness() {
+    let mut counter = Counter(0);
+    assert_eq!(counter.next(), None);
+    assert_eq!(counter.0, 2);
+    counter.reset();
+    assert_eq!(counter.0, 7);
+    Reset::reset(&mut counter);
+    assert_eq!(counter.0, 100);
+}
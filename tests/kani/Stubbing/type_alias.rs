@@ -0,0 +1,61 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_type_alias --enable-unstable --enable-stubbing
+//
+//! This tests that stub paths can refer to methods through type aliases.
+
+mod sensors {
+    pub struct Thermometer;
+
+    impl Thermometer {
+        pub fn read(&self) -> i32 {
+            -40
+        }
+    }
+
+    pub type Sensor = Thermometer;
+}
+
+type Probe = sensors::Sensor;
+
+use sensors::Sensor as ImportedSensor;
+
+fn read_stub(_sensor: &sensors::Thermometer) -> i32 {
+    21
+}
+
+struct Counter(u32);
+
+impl Counter {
+    fn next(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+type Ticker = Counter;
+
+fn next_stub(_counter: &mut Counter) -> u32 {
+    0
+}
+
+#[kani::proof]
+#[kani::stub(Probe::read, read_stub)]
+#[kani::stub(Ticker::next, next_stub)]
+fn check_type_alias() {
+    assert_eq!(sensors::Thermometer.read(), 21);
+    assert_eq!(Counter(5).next(), 0);
+}
+
+#[kani::proof]
+#[kani::stub(sensors::Sensor::read, read_stub)]
+fn check_alias_in_module() {
+    assert_eq!(sensors::Thermometer.read(), 21);
+}
+
+#[kani::proof]
+#[kani::stub(ImportedSensor::read, read_stub)]
+fn check_imported_alias() {
+    assert_eq!(sensors::Thermometer.read(), 21);
+}
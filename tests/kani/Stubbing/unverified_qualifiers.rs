@@ -0,0 +1,27 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that, without `--verify-stub-types`, stubs are applied even if
+//! their qualifiers differ from the original function.
+
+const fn zero() -> u32 {
+    0
+}
+
+unsafe fn one() -> u32 {
+    1
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(zero, forty_two)]
+#[kani::stub(one, forty_two)]
+fn harness() {
+    assert_eq!(zero(), 42);
+    assert_eq!(unsafe { one() }, 42);
+}
@@ -0,0 +1,39 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that unwind attributes on the functions that a harness calls are accepted. Their values
+//! are not applied yet, so the loops of these functions are bounded by the unwind value of the
+//! harness; this does not check per-function bounds.
+
+#[kani::unwind(3)]
+fn count_up(limit: u8) -> u8 {
+    let mut counter = 0;
+    while counter < limit {
+        counter += 1;
+    }
+    counter
+}
+
+struct Counter {
+    value: u8,
+}
+
+impl Counter {
+    #[kani::unwind(3)]
+    fn count_down(&mut self) {
+        while self.value > 0 {
+            self.value -= 1;
+        }
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn check_function_unwind() {
+    let limit: u8 = kani::any();
+    kani::assume(limit <= 2);
+    let mut counter = Counter { value: count_up(limit) };
+    assert_eq!(counter.value, limit);
+    counter.count_down();
+    assert_eq!(counter.value, 0);
+}
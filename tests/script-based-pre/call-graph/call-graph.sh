@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-call-graph` emits the edges between the reachable items in both the JSON
+# and the DOT formats, and that the JSON format tags each item with the crate it comes from.
+
+set -eu
+
+cd $(dirname $0)
+rm -f call_graph.call_graph.json call_graph.call_graph.dot
+
+run_kani() {
+    kani call_graph.rs --enable-unstable --only-codegen --emit-call-graph "$@" >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    rm -f kani.log
+}
+
+echo "== JSON"
+run_kani json
+python3 -c '
+import json
+graph = json.load(open("call_graph.call_graph.json"))
+edges = [(edge["caller"], edge["callee"]) for edge in graph["edges"]]
+nodes = {node["name"]: (node["crate"], node["local"]) for node in graph["nodes"]}
+print("check_helper -> helper:", ("check_helper", "helper") in edges)
+print("helper -> inner:", ("helper", "inner") in edges)
+print("unused listed:", "unused" in nodes)
+print("helper crate:", nodes["helper"])
+print("kani::any crate:", nodes["kani::any::<u8>"])
+'
+
+echo "== DOT"
+run_kani dot
+grep -F '"helper" -> "inner";' call_graph.call_graph.dot
+rm -f call_graph.call_graph.json call_graph.call_graph.dot
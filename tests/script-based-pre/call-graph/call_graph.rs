@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The harness calls `helper`, which calls `inner`, so the call graph must have the edges
+//! `check_helper -> helper` and `helper -> inner`.
+
+fn inner(x: u8) -> u8 {
+    x / 2
+}
+
+fn helper(x: u8) -> u8 {
+    inner(x)
+}
+
+fn unused() -> u8 {
+    0
+}
+
+#[kani::proof]
+fn check_helper() {
+    let x: u8 = kani::any();
+    assert!(helper(x) <= x);
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that an advisory produced by a compiler pass is emitted in the advisories json file when
+# `--emit-advisories` is used.
+
+set -eu
+
+cd $(dirname $0)
+rm -f ignored_stub.advisories.json
+
+kani ignored_stub.rs --enable-unstable --emit-advisories --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e ignored_stub.advisories.json ]
+then
+    echo "error: no advisories file generated. Expected: ignored_stub.advisories.json"
+    exit 1
+fi
+
+cat ignored_stub.advisories.json
+rm -f ignored_stub.advisories.json
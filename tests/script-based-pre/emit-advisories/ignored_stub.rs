@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Stubbing is not enabled, so the `kani::stub` attribute below is ignored and the codegen pass
+//! should record an advisory about it.
+
+fn foo() -> u32 {
+    0
+}
+
+fn bar() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(foo, bar)]
+fn harness() {
+    assert_eq!(foo(), 0);
+}
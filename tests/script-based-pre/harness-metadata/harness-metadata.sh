@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-harness-metadata` writes the name, location and unwind bound of each harness
+# of the crate together with the stubs resolved for it.
+
+set -eu
+
+cd $(dirname $0)
+rm -f harnesses.harnesses.json
+
+kani harnesses.rs --enable-unstable --enable-stubbing --harness check_stubbed \
+    --emit-harness-metadata --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e harnesses.harnesses.json ]
+then
+    echo "error: no harness metadata generated. Expected: harnesses.harnesses.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+metadata = json.load(open("harnesses.harnesses.json"))
+print("crate:", metadata["crate"])
+for harness in metadata["harnesses"]:
+    span = harness["span"]
+    print("harness:", harness["name"], "unwind:", harness["unwind"])
+    print("  span:", span["file"].endswith("harnesses.rs"), span["start_line"], span["end_line"])
+    for stub in harness["stubs"]:
+        print("  stub:", stub["original"], "->", stub["replacement"], "caller:", stub["caller"])
+'
+rm -f harnesses.harnesses.json
@@ -0,0 +1,34 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Two harnesses whose metadata is emitted with `--emit-harness-metadata`: one with an unwind
+//! bound and stubs, one without either.
+
+fn zero() -> u32 {
+    0
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+fn sum() -> u32 {
+    zero() + one()
+}
+
+#[kani::proof]
+#[kani::unwind(3)]
+#[kani::stub(zero, forty_two)]
+#[kani::stub_in(sum, one, forty_two)]
+fn check_stubbed() {
+    assert_eq!(sum(), 84);
+}
+
+#[kani::proof]
+fn check_plain() {
+    assert_eq!(sum(), 1);
+}
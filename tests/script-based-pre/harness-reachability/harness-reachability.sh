@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-harness-reachability` lists the functions reachable from each harness on
+# its own, for two harnesses with disjoint call trees.
+
+set -eu
+
+cd $(dirname $0)
+rm -f reachability.harness_reachability.json
+
+kani reachability.rs --enable-unstable --emit-harness-reachability --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e reachability.harness_reachability.json ]
+then
+    echo "error: no report generated. Expected: reachability.harness_reachability.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+report = json.load(open("reachability.harness_reachability.json"))["harnesses"]
+local = ["parse", "validate", "encode", "compress"]
+for harness in sorted(report):
+    functions = [function for function in report[harness] if function in local]
+    print(harness + ":", functions)
+'
+rm -f reachability.harness_reachability.json
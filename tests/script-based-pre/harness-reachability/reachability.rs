@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Two harnesses with disjoint call trees, so each one reaches its own functions only.
+
+fn parse(input: u8) -> u8 {
+    validate(input) + 1
+}
+
+fn validate(input: u8) -> u8 {
+    input / 2
+}
+
+fn encode(input: u16) -> u16 {
+    compress(input) * 2
+}
+
+fn compress(input: u16) -> u16 {
+    input / 4
+}
+
+#[kani::proof]
+fn check_parse() {
+    assert!(parse(kani::any()) > 0);
+}
+
+#[kani::proof]
+fn check_encode() {
+    assert!(encode(kani::any()) % 2 == 0);
+}
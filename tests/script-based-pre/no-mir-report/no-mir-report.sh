@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-no-mir-report` lists the reachable foreign functions together with the
+# functions that call them.
+
+set -eu
+
+cd $(dirname $0)
+rm -f no_mir.no_mir.json
+
+kani no_mir.rs --enable-unstable --emit-no-mir-report --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e no_mir.no_mir.json ]
+then
+    echo "error: no report generated. Expected: no_mir.no_mir.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+report = json.load(open("no_mir.no_mir.json"))
+functions = {function["name"]: function for function in report["functions"]}
+ext_abs = functions["ext_abs"]
+print("ext_abs:", ext_abs["kind"], ext_abs["callers"])
+print("checked_abs listed:", "checked_abs" in functions)
+'
+rm -f no_mir.no_mir.json
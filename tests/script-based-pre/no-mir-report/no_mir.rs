@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Calls a foreign function, which has no MIR, from a function reachable from the harness.
+
+extern "C" {
+    fn ext_abs(x: i32) -> i32;
+}
+
+fn checked_abs(x: i32) -> i32 {
+    unsafe { ext_abs(x) }
+}
+
+#[kani::proof]
+fn check_abs() {
+    let x: i32 = kani::any();
+    kani::assume(x > i32::MIN);
+    let _ = checked_abs(x);
+}
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--output-dir` writes the files emitted on request in the given directory, which is
+# created if needed, instead of next to the other artifacts.
+
+set -eu
+
+cd $(dirname $0)
+rm -rf reports output_dir.call_graph.json
+
+kani output_dir.rs --enable-unstable --only-codegen --emit-call-graph json \
+    --output-dir reports >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+echo "in output dir: $([ -e reports/output_dir.call_graph.json ] && echo yes || echo no)"
+echo "in current dir: $([ -e output_dir.call_graph.json ] && echo yes || echo no)"
+rm -rf reports output_dir.call_graph.json
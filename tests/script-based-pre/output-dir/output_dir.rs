@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! A crate whose call graph is emitted in the output directory.
+
+fn double(x: u8) -> u16 {
+    x as u16 * 2
+}
+
+#[kani::proof]
+fn check_double() {
+    let x: u8 = kani::any();
+    assert!(double(x) >= x as u16);
+}
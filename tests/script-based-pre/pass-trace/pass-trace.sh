@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-pass-trace` writes the timings of the identity and stubbing passes in the
+# Chrome Trace Event format, and that no trace is written without it.
+
+set -eu
+
+cd $(dirname $0)
+rm -f trace.pass_trace.json
+
+run_kani() {
+    kani trace.rs --enable-unstable --enable-stubbing --harness check_trace --only-codegen "$@" \
+        >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    rm -f kani.log
+}
+
+echo "== Without trace"
+run_kani
+[ -e trace.pass_trace.json ] && echo "trace emitted" || echo "no trace"
+
+echo "== With trace"
+run_kani --emit-pass-trace
+python3 -c '
+import json
+trace = json.load(open("trace.pass_trace.json"))
+events = trace["traceEvents"]
+print("passes:", sorted({event["name"] for event in events}))
+print("complete events:", all(event["ph"] == "X" and event["dur"] >= 0 for event in events))
+stubbed = [e for e in events if e["name"] == "stubbing" and e["args"]["item"] == "magic_number"]
+print("stubbing magic_number traced:", len(stubbed) > 0)
+'
+rm -f trace.pass_trace.json
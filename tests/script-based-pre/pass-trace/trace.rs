@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The harness applies one stub, so the MIR passes run on the bodies of the
+//! harness, of the original function and of the stub.
+
+fn magic_number() -> u32 {
+    42
+}
+
+fn magic_number_stub() -> u32 {
+    13
+}
+
+#[kani::proof]
+#[kani::stub(magic_number, magic_number_stub)]
+fn check_trace() {
+    assert_eq!(magic_number(), 13);
+}
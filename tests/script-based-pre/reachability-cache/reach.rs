@@ -0,0 +1,18 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+fn add(a: u8, b: u8) -> u8 {
+    a.wrapping_add(b)
+}
+
+#[kani::proof]
+fn check_add() {
+    let a: u8 = kani::any();
+    assert_eq!(add(a, 0), a);
+}
+
+#[kani::proof]
+fn check_vec() {
+    let v = vec![kani::any::<u8>()];
+    assert_eq!(v.len(), 1);
+}
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that the reachability analysis is loaded from the cache when Kani runs a second time with
+# the same inputs, and that the cache is not used once the inputs change.
+
+set -eu
+
+cd $(dirname $0)
+rm -rf cache
+
+export KANI_LOG=kani_compiler::kani_middle::reachability_cache=info
+
+run_kani() {
+    kani reach.rs --enable-unstable --reachability-cache-dir cache --only-codegen "$@" >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    if grep -q "Loaded reachable items from cache" kani.log
+    then
+        echo "cache hit"
+    else
+        echo "cache miss"
+    fi
+    rm -f kani.log
+}
+
+echo "== First run"
+run_kani
+echo "== Second run"
+run_kani
+echo "== Different options"
+run_kani --harness check_add
+
+rm -rf cache
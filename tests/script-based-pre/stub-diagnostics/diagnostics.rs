@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The stub of `read_sensor` names a function that does not exist.
+
+fn read_sensor() -> u32 {
+    unimplemented!("no sensor attached")
+}
+
+#[kani::proof]
+#[kani::stub(read_sensor, mock_read_sensor)]
+fn check_sensor() {
+    assert_eq!(read_sensor(), 42);
+}
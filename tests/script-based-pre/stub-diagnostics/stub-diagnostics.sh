@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-stub-diagnostics` emits the error for a stub path that does not resolve as a
+# json diagnostic, with its span and the path it refers to.
+
+set -eu
+
+cd $(dirname $0)
+rm -f diagnostics.stub_diagnostics.json
+
+# The compilation fails because of the unresolved path.
+kani diagnostics.rs --enable-unstable --enable-stubbing --harness check_sensor \
+    --emit-stub-diagnostics --only-codegen >& kani.log && \
+    { echo "== Kani should have failed"; cat kani.log; rm kani.log; exit 1; }
+grep -F "error: unable to resolve function/method: mock_read_sensor" kani.log
+rm -f kani.log
+
+if ! [ -e diagnostics.stub_diagnostics.json ]
+then
+    echo "error: no diagnostics generated. Expected: diagnostics.stub_diagnostics.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+for diagnostic in json.load(open("diagnostics.stub_diagnostics.json")):
+    span = diagnostic["span"]
+    print(diagnostic["severity"], diagnostic["code"], diagnostic["harness"])
+    print("message:", diagnostic["message"])
+    print("span:", span["file"], span["start_line"], span["end_line"])
+    print("paths:", diagnostic["paths"])
+'
+rm -f diagnostics.stub_diagnostics.json
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-stubbed-mir` dumps the MIR reachable from the given harness once the stubs are
+# applied, so the stubbed function has the body of its stub.
+
+set -eu
+
+cd $(dirname $0)
+rm -f stubbed_mir.stubbed.mir
+
+kani stubbed_mir.rs --enable-unstable --enable-stubbing --harness check_stubbed \
+    --emit-stubbed-mir check_stubbed --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e stubbed_mir.stubbed.mir ]
+then
+    echo "error: no MIR dump generated. Expected: stubbed_mir.stubbed.mir"
+    exit 1
+fi
+
+grep -q "check_stubbed" stubbed_mir.stubbed.mir && echo "Dump contains the harness"
+grep -q "const 42_u32" stubbed_mir.stubbed.mir && echo "Dump contains the body of the stub"
+grep -q "const 7_u32" stubbed_mir.stubbed.mir || echo "Dump does not contain the original body"
+grep -q "unrelated" stubbed_mir.stubbed.mir || echo "Dump does not contain the other harness"
+rm -f stubbed_mir.stubbed.mir
@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The MIR reachable from `check_stubbed` has the body of `mock_answer` instead of the body of
+//! `answer`, and does not include the items only reachable from the other harness.
+
+fn answer() -> u32 {
+    7
+}
+
+fn mock_answer() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(answer, mock_answer)]
+fn check_stubbed() {
+    assert_eq!(answer(), 42);
+}
+
+fn unrelated() -> u32 {
+    3
+}
+
+#[kani::proof]
+fn check_other() {
+    assert_eq!(unrelated(), 3);
+}
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--stub-body-hashes` records the hash of the body of each stub, stays quiet while
+# the bodies are unchanged, and warns only about the stub whose body was edited.
+
+set -eu
+
+cd $(dirname $0)
+rm -rf hashes.json changed
+
+run_kani() {
+    kani "$1" --enable-unstable --enable-stubbing --harness check_stubs \
+        --stub-body-hashes hashes.json --only-codegen >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    grep -o "warning: The body of stub .*" kani.log || echo "no warnings"
+    rm -f kani.log
+}
+
+echo "== First run"
+run_kani body_hashes.rs
+python3 -c 'import json; print("\n".join(json.load(open("hashes.json"))))'
+
+echo "== Unchanged stubs"
+run_kani body_hashes.rs
+
+echo "== Changed stub"
+mkdir changed
+sed 's/    100/    200/' body_hashes.rs > changed/body_hashes.rs
+run_kani changed/body_hashes.rs
+rm -rf hashes.json changed
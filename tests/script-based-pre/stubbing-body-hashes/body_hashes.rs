@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The harness replaces two functions whose stub bodies are hashed with
+//! `--stub-body-hashes`. The test script edits the body of `stub_zero` between
+//! runs.
+
+fn zero() -> u32 {
+    0
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn stub_zero() -> u32 {
+    100
+}
+
+fn stub_one() -> u32 {
+    101
+}
+
+#[kani::proof]
+#[kani::stub(zero, stub_zero)]
+#[kani::stub(one, stub_one)]
+fn check_stubs() {
+    assert!(zero() < one());
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-stub-caller-counts` counts the calls to each stubbed function in each of its
+# callers.
+
+set -eu
+
+cd $(dirname $0)
+rm -f counts.stub_caller_counts.json
+
+kani counts.rs --enable-unstable --enable-stubbing --harness check_counts \
+    --emit-stub-caller-counts --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e counts.stub_caller_counts.json ]
+then
+    echo "error: no stub caller counts generated. Expected: counts.stub_caller_counts.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+counts = json.load(open("counts.stub_caller_counts.json"))
+for stubbed, callers in sorted(counts.items()):
+    for caller, count in sorted(callers.items()):
+        print(f"{stubbed} in {caller}: {count}")
+'
+rm -f counts.stub_caller_counts.json
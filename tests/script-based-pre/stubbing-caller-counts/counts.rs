@@ -0,0 +1,39 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! `sensor::read` is stubbed everywhere and called from several functions,
+//! while `sensor::reset` is only stubbed in the calls made by `calibrate`.
+
+mod sensor {
+    pub fn read() -> u32 {
+        unimplemented!("reads the hardware")
+    }
+
+    pub fn reset() {
+        unimplemented!("resets the hardware")
+    }
+}
+
+fn fake_read() -> u32 {
+    7
+}
+
+fn fake_reset() {}
+
+fn average() -> u32 {
+    (sensor::read() + sensor::read() + sensor::read()) / 3
+}
+
+fn calibrate() -> u32 {
+    sensor::reset();
+    sensor::read()
+}
+
+#[kani::proof]
+#[kani::stub(sensor::read, fake_read)]
+#[kani::stub_in(calibrate, sensor::reset, fake_reset)]
+fn check_counts() {
+    assert_eq!(average(), 7);
+    assert_eq!(calibrate(), 7);
+    assert_eq!(sensor::read(), sensor::read());
+}
@@ -0,0 +1,40 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The functions and inherent methods of this crate are the stub candidates.
+//! Harnesses and trait methods are not candidates.
+
+mod ops {
+    pub fn zero() -> u32 {
+        0
+    }
+
+    pub fn one() -> u32 {
+        1
+    }
+}
+
+struct Counter(u32);
+
+impl Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Counter(ops::zero())
+    }
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(ops::zero, forty_two)]
+fn check_candidates() {
+    assert_eq!(Counter::default().get(), 42);
+    assert_eq!(ops::one(), 1);
+}
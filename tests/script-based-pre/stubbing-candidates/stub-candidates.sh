@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-stub-candidates` lists the functions and methods that may be stubbed, and
+# that the candidates from other crates are only included with `--stub-candidates-include-foreign`.
+
+set -eu
+
+cd $(dirname $0)
+rm -f candidates.stub_candidates.json
+
+run_kani() {
+    kani candidates.rs --enable-unstable --enable-stubbing --harness check_candidates \
+        --emit-stub-candidates --only-codegen "$@" >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    rm -f kani.log
+
+    if ! [ -e candidates.stub_candidates.json ]
+    then
+        echo "error: no stub candidates generated. Expected: candidates.stub_candidates.json"
+        exit 1
+    fi
+}
+
+echo "== Local candidates"
+run_kani
+python3 -c 'import json; print("\n".join(json.load(open("candidates.stub_candidates.json"))))'
+
+echo "== Foreign candidates"
+run_kani --stub-candidates-include-foreign
+python3 -c '
+import json
+candidates = json.load(open("candidates.stub_candidates.json"))
+print("local first:", candidates[:4] == ["Counter::get", "forty_two", "ops::one", "ops::zero"])
+for path in ["core::mem::swap", "kani::any", "std::process::exit"]:
+    print(path, "listed:", path in candidates)
+'
+rm -f candidates.stub_candidates.json
@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that the first replacement of a stub of the verification config that resolves is used,
+# and that every replacement is reported if none of them resolves.
+
+set -eu
+
+cd $(dirname $0)
+rm -f fallback.harnesses.json
+
+echo "== Fallback"
+kani fallback.rs --enable-unstable --enable-stubbing --harness check_fallback \
+    --verification-config kani.json --emit-harness-metadata --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+python3 -c '
+import json
+metadata = json.load(open("fallback.harnesses.json"))
+for harness in metadata["harnesses"]:
+    for stub in harness["stubs"]:
+        print("  stub:", stub["original"], "->", stub["replacement"])
+'
+rm -f fallback.harnesses.json
+
+echo "== Missing"
+if kani fallback.rs --enable-unstable --enable-stubbing --harness check_fallback \
+    --verification-config missing.json --only-codegen >& kani.log
+then
+    echo "== Kani should have failed"
+else
+    grep -o "unable to resolve function/method: .*" kani.log
+fi
+rm -f kani.log
@@ -0,0 +1,18 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! A harness whose stub in the verification config is a list of replacements, the first of which
+//! does not exist.
+
+fn zero() -> u32 {
+    0
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+#[kani::proof]
+fn check_fallback() {
+    assert_eq!(zero(), 42);
+}
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The stub path is ambiguous between several glob imports, and the error
+//! must list the possibilities in the same order every time.
+
+mod first {
+    pub fn answer() -> u32 {
+        1
+    }
+}
+
+mod second {
+    pub fn answer() -> u32 {
+        2
+    }
+}
+
+mod third {
+    pub fn answer() -> u32 {
+        3
+    }
+}
+
+mod ambiguous {
+    use super::third::*;
+    use super::first::*;
+    use super::second::*;
+
+    fn stub_answer() -> u32 {
+        42
+    }
+
+    #[kani::proof]
+    #[kani::stub(answer, stub_answer)]
+    fn check_ambiguous() {}
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Runs stub resolution twice on the same input and checks that the diagnostics are identical and
+# printed in a deterministic order.
+
+set -u
+
+cd $(dirname $0)
+
+diagnostics() {
+    kani "$1" --enable-unstable --enable-stubbing --harness "$2" --only-codegen 2>&1 | \
+        grep -E "^(error|warning: Stubbing)|::answer$"
+}
+
+for test in "ambiguous.rs check_ambiguous" "std_stubs.rs check_std"
+do
+    set -- $test
+    echo "== $1"
+    first=$(diagnostics $1 $2)
+    second=$(diagnostics $1 $2)
+    echo "$first"
+    if [ "$first" == "$second" ]
+    then
+        echo "identical output"
+    else
+        echo "different output:"
+        echo "$second"
+    fi
+done
@@ -0,0 +1,25 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Every stub replaces a function from the standard library, and the warnings
+//! must be printed in the same order every time.
+
+fn none_from_u32(_: u32) -> Option<char> {
+    None
+}
+
+fn none_from_digit(_: u32, _: u32) -> Option<char> {
+    None
+}
+
+fn no_spin() {}
+
+#[kani::proof]
+#[kani::stub(std::hint::spin_loop, no_spin)]
+#[kani::stub(std::char::from_u32, none_from_u32)]
+#[kani::stub(std::char::from_digit, none_from_digit)]
+fn check_std() {
+    std::hint::spin_loop();
+    assert!(std::char::from_u32(kani::any()).is_none());
+    assert!(std::char::from_digit(kani::any(), 10).is_none());
+}
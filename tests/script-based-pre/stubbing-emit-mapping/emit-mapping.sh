@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-stub-mapping` writes the stub mapping of the harness as a Rust source file
+# that lists every pair and compiles as a standalone library.
+
+set -eu
+
+cd $(dirname $0)
+rm -f emit_mapping.stubs.rs
+
+kani emit_mapping.rs --enable-unstable --enable-stubbing --harness check_mapping \
+    --emit-stub-mapping --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e emit_mapping.stubs.rs ]
+then
+    echo "error: no stub mapping generated. Expected: emit_mapping.stubs.rs"
+    exit 1
+fi
+
+cat emit_mapping.stubs.rs
+
+out_dir=$(mktemp -d)
+rustc emit_mapping.stubs.rs --crate-name=stub_mapping --crate-type=lib --emit=metadata --out-dir ${out_dir} && \
+    echo "Stub mapping compiles"
+rm -rf ${out_dir}
+rm -f emit_mapping.stubs.rs
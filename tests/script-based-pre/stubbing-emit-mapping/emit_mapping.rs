@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The stub mapping of the harness below is written to `emit_mapping.stubs.rs`.
+
+mod ops {
+    pub fn zero() -> u32 {
+        0
+    }
+
+    pub fn one() -> u32 {
+        1
+    }
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+fn answer() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(ops::zero, forty_two)]
+#[kani::stub(ops::one, answer)]
+fn check_mapping() {
+    assert_eq!(ops::zero(), 42);
+    assert_eq!(ops::one(), 42);
+}
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Both harnesses stub `get` with a different replacement. The assertions only hold if the stubs
+//! of the harness selected on the command line are the ones applied.
+
+fn get() -> u32 {
+    0
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn two() -> u32 {
+    2
+}
+
+#[kani::proof]
+#[kani::stub(get, one)]
+fn check_one() {
+    assert_eq!(get(), 1);
+}
+
+#[kani::proof]
+#[kani::stub(get, two)]
+fn check_two() {
+    assert_eq!(get(), 2);
+}
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Compiles the same crate twice with two different harness selections and checks that each run
+# only applies the stubs of the selected harness.
+
+set -eu
+
+cd $(dirname $0)
+
+for harness in check_one check_two
+do
+    echo "== ${harness}"
+    kani harnesses.rs --enable-unstable --enable-stubbing --harness ${harness} | grep "VERIFICATION"
+done
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The stubs of `check_merged` come from its attributes and from the verification config
+//! `kani.json`, which also repeats one of the attribute stubs.
+
+fn read() -> u32 {
+    0
+}
+
+fn write(_value: u32) {}
+
+fn flush() {
+    write(0);
+}
+
+fn mock_read() -> u32 {
+    42
+}
+
+fn mock_write(_value: u32) {}
+
+#[kani::proof]
+#[kani::stub(read, mock_read)]
+#[kani::stub_in(flush, write, mock_write)]
+fn check_merged() {
+    write(read());
+    flush();
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--print-stub-mapping` prints the stubs of a harness once the stubs of its
+# attributes and of the verification config are merged, with a single entry for the stub that
+# both sources request.
+
+set -eu
+
+cd $(dirname $0)
+
+kani merged.rs --enable-unstable --enable-stubbing --harness check_merged \
+    --verification-config kani.json --print-stub-mapping --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+# Only keep the mapping, which is followed by the rest of the output of Kani.
+awk '/^Stub mapping/ { mapping = 1; print; next } mapping && /^  / { print; next } { mapping = 0 }' \
+    kani.log
+rm -f kani.log
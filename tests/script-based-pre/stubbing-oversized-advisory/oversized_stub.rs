@@ -0,0 +1,40 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! A stub that is much larger than the function it replaces is reported as an advisory, since it
+//! was meant for another function. The stub that is about as large as its original is not.
+
+fn is_even(value: u32) -> bool {
+    value % 2 == 0
+}
+
+fn parity(value: u32) -> u32 {
+    value & 1
+}
+
+/// The mock of a checksum, which was meant to replace another function.
+fn mock_checksum(value: u32) -> bool {
+    let mut sum = value;
+    for shift in [8, 16, 24] {
+        if (value >> shift) & 1 == 1 {
+            sum = sum.wrapping_add(value >> shift);
+        } else {
+            sum = sum.wrapping_mul(3);
+        }
+    }
+    let folded = (sum ^ (sum >> 16)).wrapping_mul(31);
+    folded % 2 == 0
+}
+
+fn mock_parity(value: u32) -> u32 {
+    value % 2
+}
+
+#[kani::proof]
+#[kani::stub(is_even, mock_checksum)]
+#[kani::stub(parity, mock_parity)]
+fn check_parity() {
+    let value = kani::any();
+    let even = is_even(value);
+    assert!(even || parity(value) <= 1);
+}
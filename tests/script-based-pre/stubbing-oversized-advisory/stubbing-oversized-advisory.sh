@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that a stub that is much more complex than the function it replaces is reported in the
+# advisories json file.
+
+set -eu
+
+cd $(dirname $0)
+rm -f oversized_stub.advisories.json
+
+kani oversized_stub.rs --enable-unstable --enable-stubbing --harness check_parity \
+    --emit-advisories --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e oversized_stub.advisories.json ]
+then
+    echo "error: no advisories file generated. Expected: oversized_stub.advisories.json"
+    exit 1
+fi
+
+cat oversized_stub.advisories.json
+rm -f oversized_stub.advisories.json
@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--preserve-stub-signature` keeps the location and parameter names of the original
+# function in the generated symbol table, while the body still comes from the stub.
+
+set -eu
+
+cd $(dirname $0)
+rm -rf out
+
+show_scale() {
+    kani preserve_signature.rs --enable-unstable --enable-stubbing --harness harness \
+        --target-dir out --keep-temps --only-codegen "$@" >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    python3 - out/preserve_signature.symtab.json <<'PYTHON'
+import json, sys
+symbols = json.load(open(sys.argv[1]))["symbolTable"].values()
+function = next(s for s in symbols if s["prettyName"] == "scale")
+params = [s["baseName"] for s in symbols
+          if s["isParameter"] and s["name"].startswith(function["name"] + "::")]
+line = function["location"]["namedSub"]["line"]["id"]
+print(f"scale: line {line}, parameters: {', '.join(params)}")
+PYTHON
+    rm -rf out kani.log
+}
+
+echo "== Replace the whole function"
+show_scale
+echo "== Preserve the signature"
+show_scale --preserve-stub-signature
+kani preserve_signature.rs --enable-unstable --enable-stubbing --harness harness \
+    --preserve-stub-signature | grep "VERIFICATION"
+rm -rf out
@@ -0,0 +1,16 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+fn scale(value: u32) -> u32 {
+    value * 100
+}
+
+fn stub_scale(_x: u32) -> u32 {
+    7
+}
+
+#[kani::proof]
+#[kani::stub(scale, stub_scale)]
+fn harness() {
+    assert_eq!(scale(kani::any()), 7);
+}
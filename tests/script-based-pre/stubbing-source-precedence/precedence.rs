@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The attribute of the harness and the verification config stub `zero` with
+//! different functions, while `one` is only stubbed by the attribute.
+
+fn zero() -> u32 {
+    0
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn from_attribute() -> u32 {
+    13
+}
+
+fn from_config() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(zero, from_attribute)]
+#[kani::stub(one, from_attribute)]
+fn check_precedence() {
+    assert!(zero() > 0);
+    assert_eq!(one(), 13);
+}
@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--stub-source-precedence` decides whether the stub from the verification config or
+# from the attribute is used when both stub the same function, and that the warning names the
+# source whose stub is used.
+
+set -eu
+
+cd $(dirname $0)
+rm -f precedence.harnesses.json
+
+run_kani() {
+    kani precedence.rs --enable-unstable --enable-stubbing --harness check_precedence \
+        --verification-config kani.json --emit-harness-metadata --only-codegen "$@" \
+        >& kani.log || \
+        { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    grep -o "warning: Harness .*" kani.log
+    rm -f kani.log
+    python3 -c '
+import json
+metadata = json.load(open("precedence.harnesses.json"))
+for harness in metadata["harnesses"]:
+    for stub in harness["stubs"]:
+        print("  stub:", stub["original"], "->", stub["replacement"])
+'
+    rm -f precedence.harnesses.json
+}
+
+echo "== Default precedence"
+run_kani
+
+echo "== Config first"
+run_kani --stub-source-precedence config,attribute
+
+echo "== Attribute first"
+run_kani --stub-source-precedence attribute,config
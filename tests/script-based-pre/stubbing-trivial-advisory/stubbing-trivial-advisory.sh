@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that a stub that does nothing, while the original function has substantial logic, is
+# reported in the advisories json file.
+
+set -eu
+
+cd $(dirname $0)
+rm -f trivial_stub.advisories.json
+
+kani trivial_stub.rs --enable-unstable --enable-stubbing --harness check_checksum \
+    --emit-advisories --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e trivial_stub.advisories.json ]
+then
+    echo "error: no advisories file generated. Expected: trivial_stub.advisories.json"
+    exit 1
+fi
+
+cat trivial_stub.advisories.json
+rm -f trivial_stub.advisories.json
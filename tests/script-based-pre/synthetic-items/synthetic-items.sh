@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--include-synthetic-items` adds the drop glue of a type that is never dropped, and
+# thus its `Drop` implementation, to the call graph of the reachable items.
+
+set -eu
+
+cd $(dirname $0)
+rm -f synthetic_items.call_graph.json
+
+run_kani() {
+    kani synthetic_items.rs --enable-unstable --only-codegen --emit-call-graph json "$@" \
+        >& kani.log || { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+    rm -f kani.log
+}
+
+check_drop() {
+    python3 -c '
+import json
+graph = json.load(open("synthetic_items.call_graph.json"))
+print("Guard::drop listed:", "<Guard as std::ops::Drop>::drop" in [node["name"] for node in graph["nodes"]])
+'
+    rm -f synthetic_items.call_graph.json
+}
+
+echo "== Default"
+run_kani
+check_drop
+
+echo "== Synthetic items"
+run_kani --include-synthetic-items
+check_drop
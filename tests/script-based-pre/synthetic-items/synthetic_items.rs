@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The harness never drops its `Guard`, so the `Drop` implementation of `Guard` is only part of
+//! the reported reachable items if the compiler-generated items are included.
+
+struct Guard(u8);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        assert!(self.0 > 0);
+    }
+}
+
+#[kani::proof]
+fn check_forget() {
+    let guard = Guard(kani::any());
+    std::mem::forget(guard);
+}
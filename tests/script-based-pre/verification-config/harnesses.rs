@@ -0,0 +1,33 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Harnesses whose unwind bound and stubs come from the verification config `kani.json` instead
+//! of attributes.
+
+fn zero() -> u32 {
+    0
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+fn count(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+#[kani::proof]
+fn check_config() {
+    assert_eq!(zero(), 42);
+    assert_eq!(count(3), 3);
+}
+
+#[kani::proof]
+#[kani::unwind(2)]
+fn check_attribute() {
+    assert_eq!(count(1), 1);
+}
@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--verification-config` applies the unwind bounds and stubs of the config to the
+# harnesses, as reported by `--emit-harness-metadata`, and that a malformed config is reported
+# with the location of the problem.
+
+set -eu
+
+cd $(dirname $0)
+rm -f harnesses.harnesses.json
+
+echo "== Well-formed config"
+kani harnesses.rs --enable-unstable --enable-stubbing --harness check_config \
+    --verification-config kani.json --emit-harness-metadata --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+python3 -c '
+import json
+metadata = json.load(open("harnesses.harnesses.json"))
+for harness in metadata["harnesses"]:
+    print("harness:", harness["name"], "unwind:", harness["unwind"])
+    for stub in harness["stubs"]:
+        print("  stub:", stub["original"], "->", stub["replacement"])
+'
+rm -f harnesses.harnesses.json
+
+echo "== Malformed config"
+if kani harnesses.rs --enable-unstable --verification-config malformed.json --only-codegen \
+    >& kani.log
+then
+    echo "== Kani should have failed"
+else
+    grep -o "unknown field .*" kani.log
+fi
+rm -f kani.log
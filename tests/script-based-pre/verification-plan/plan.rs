@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! A crate whose verification plan is emitted with `--emit-verification-plan`.
+
+fn zero() -> u32 {
+    0
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::unwind(2)]
+#[kani::stub(zero, forty_two)]
+fn check_stubbed() {
+    assert_eq!(zero(), 42);
+}
+
+#[kani::proof]
+fn check_plain() {
+    assert_eq!(zero(), 0);
+}
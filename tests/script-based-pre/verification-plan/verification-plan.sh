@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--emit-verification-plan` writes the harnesses of the crate with their stubs, the
+# reachability roots, the goto model and the options in one file.
+
+set -eu
+
+cd $(dirname $0)
+rm -f plan.plan.json
+
+kani plan.rs --enable-unstable --enable-stubbing --harness check_stubbed \
+    --emit-verification-plan --only-codegen >& kani.log || \
+    { ret=$?; echo "== Failed to run Kani"; cat kani.log; rm kani.log; exit 1; }
+rm -f kani.log
+
+if ! [ -e plan.plan.json ]
+then
+    echo "error: no verification plan generated. Expected: plan.plan.json"
+    exit 1
+fi
+
+python3 -c '
+import json
+plan = json.load(open("plan.plan.json"))
+print("keys:", ", ".join(sorted(plan)))
+print("crate:", plan["crate"])
+print("model:", plan["model"].endswith(".symtab.out"))
+print("reachability:", plan["reachability"]["mode"])
+for harness in plan["harnesses"]:
+    is_root = any(root.endswith(harness["name"]) for root in plan["reachability"]["roots"])
+    print("harness:", harness["name"], "unwind:", harness["unwind"], "root:", is_root)
+    for stub in harness["stubs"]:
+        print("  stub:", stub["original"], "->", stub["replacement"])
+options = plan["options"]
+print("current harness:", options["current_harness"])
+print("stubbing:", options["enable_stubbing"])
+'
+rm -f plan.plan.json
@@ -0,0 +1,22 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that `kani::hoist_closure!` rejects the closures that cannot be
+//! turned into functions.
+
+kani::hoist_closure! {
+    fn untyped = |x| -> u32 { x };
+}
+
+kani::hoist_closure! {
+    fn no_return_type = |x: u32| x + 1;
+}
+
+kani::hoist_closure! {
+    fn captured = move |x: u32| -> u32 { x };
+}
+
+#[kani::proof]
+fn harness() {}
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// compile-flags: --edition 2018
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that an async function/method can only be stubbed by another async
+//! function/method, and that the outputs of their futures must match.
+
+async fn f1() -> u32 {
+    0
+}
+
+fn f2() -> u32 {
+    1
+}
+
+async fn g1() -> u32 {
+    0
+}
+
+async fn g2() -> bool {
+    true
+}
+
+#[kani::proof]
+#[kani::stub(f1, f2)]
+#[kani::stub(g1, g2)]
+fn harness() {}
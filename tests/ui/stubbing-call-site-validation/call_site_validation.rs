@@ -0,0 +1,35 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --check-stub-call-sites
+//
+//! This tests that `--check-stub-call-sites` reports the calls that would not
+//! type check with the stub: the caller uses the value returned by `fresh` as
+//! a `Vec<u8>`, which the original allows, but the stub requires the returned
+//! type to be `Copy`. The stub never relies on this bound, so nothing else
+//! reports it.
+
+fn fresh<T: Default>() -> T {
+    T::default()
+}
+
+fn fresh_copy<T: Default + Copy>() -> T {
+    T::default()
+}
+
+fn counter() -> u32 {
+    fresh()
+}
+
+fn buffer() -> Vec<u8> {
+    let mut buffer: Vec<u8> = fresh();
+    buffer.push(1);
+    buffer
+}
+
+#[kani::proof]
+#[kani::stub(fresh, fresh_copy)]
+fn harness() {
+    assert_eq!(counter(), 0);
+    assert_eq!(buffer().len(), 1);
+}
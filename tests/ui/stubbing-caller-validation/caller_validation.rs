@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports malformed `kani::stub_in` attributes.
+
+fn original() -> u32 {
+    0
+}
+
+fn caller() -> u32 {
+    original()
+}
+
+fn stub() -> u32 {
+    42
+}
+
+fn other_stub() -> u32 {
+    1
+}
+
+#[kani::proof]
+#[kani::stub_in(caller, original)]
+#[kani::stub_in(caller, original, stub)]
+#[kani::stub_in(caller, original, other_stub)]
+fn harness() {
+    assert_eq!(caller(), 42);
+}
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a stub path that only differs from an existing path in the case of some
+//! segments is reported with the correctly-cased path.
+
+mod sensors {
+    pub struct Reader;
+
+    impl Reader {
+        pub fn read(&self) -> u32 {
+            0
+        }
+    }
+
+    pub fn mock_read(_reader: &Reader) -> u32 {
+        42
+    }
+}
+
+#[kani::proof]
+#[kani::stub(sensors::Reader::Read, sensors::mock_read)]
+#[kani::stub(sensors::reader::read, Sensors::Mock_Read)]
+#[kani::stub(sensors::Reader::write, sensors::mock_read)]
+fn harness() {
+    assert_eq!(sensors::Reader.read(), 42);
+}
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports malformed `kani::stub_cfg` attributes and
+//! attributes without any active alternative.
+
+fn read_sensor() -> u32 {
+    0
+}
+
+fn fast_read_sensor() -> u32 {
+    10
+}
+
+fn precise_read_sensor() -> u32 {
+    20
+}
+
+#[kani::proof]
+#[kani::stub_cfg(
+    read_sensor,
+    cfg(feature = "fast_mock", fast_read_sensor),
+    cfg(feature = "precise_mock", precise_read_sensor)
+)]
+fn harness() {
+    assert_eq!(read_sensor(), 10);
+}
+
+#[kani::proof]
+#[kani::stub_cfg(read_sensor, fast_read_sensor)]
+#[kani::stub_cfg(read_sensor)]
+#[kani::stub_cfg(read_sensor, cfg(kani))]
+fn malformed() {
+    assert_eq!(read_sensor(), 10);
+}
@@ -0,0 +1,26 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --deny-chained-stubs
+//
+//! This tests that `--deny-chained-stubs` rejects a stub that is itself stubbed by the same
+//! harness.
+
+fn read() -> u32 {
+    0
+}
+
+fn mock_read() -> u32 {
+    1
+}
+
+fn mock_mock_read() -> u32 {
+    2
+}
+
+#[kani::proof]
+#[kani::stub(read, mock_read)]
+#[kani::stub(mock_read, mock_mock_read)]
+fn harness() {
+    assert_eq!(read(), 2);
+}
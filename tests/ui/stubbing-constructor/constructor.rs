@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports an error for stubbing attributes whose paths
+//! refer to the constructor of a tuple struct or enum variant.
+
+pub struct Celsius(i32);
+
+pub enum Reading {
+    Valid(i32),
+    Invalid,
+}
+
+fn valid(value: i32) -> Reading {
+    Reading::Valid(value)
+}
+
+fn celsius(value: i32) -> Celsius {
+    Celsius(value)
+}
+
+fn read(value: i32) -> Reading {
+    if value > 100 { Reading::Invalid } else { valid(value) }
+}
+
+#[kani::proof]
+#[kani::stub(Reading::Valid, valid)]
+#[kani::stub(celsius, Celsius)]
+#[kani::stub_in(read, Reading::Invalid, valid)]
+#[kani::stub(std::option::Option::Some, celsius)]
+fn harness() {
+    let Reading::Valid(value) = read(10) else { panic!() };
+    assert_eq!(celsius(value).0, 10);
+}
@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports malformed `kani::stub_default` attributes and
+//! functions whose return type does not implement `Default`.
+
+struct Handle(u32);
+
+fn open() -> Handle {
+    Handle(1)
+}
+
+fn parse<T: From<u8>>(byte: u8) -> T {
+    T::from(byte)
+}
+
+fn zero() -> u32 {
+    0
+}
+
+#[kani::proof]
+#[kani::stub_default(open)]
+#[kani::stub_default(parse)]
+#[kani::stub_default(zero, zero)]
+#[kani::stub_default]
+fn harness() {
+    assert_eq!(open().0, 1);
+    assert_eq!(parse::<u32>(1), 1);
+}
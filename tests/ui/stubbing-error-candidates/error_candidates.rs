@@ -0,0 +1,25 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --stub-error-candidates 1
+//
+//! This tests that the error for a path that does not resolve lists at most as many of the
+//! closest functions/methods as `--stub-error-candidates` allows.
+
+mod io {
+    pub fn read() -> u32 {
+        0
+    }
+}
+
+mod mocks {
+    pub fn read() -> u32 {
+        1
+    }
+}
+
+#[kani::proof]
+#[kani::stub(io::reed, mocks::read)]
+fn harness() {
+    assert_eq!(io::read(), 1);
+}
@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a generic stub instantiated for a function without generic parameters must
+//! satisfy its trait bounds, even if the trait method that requires them has no receiver.
+
+struct NoDefault;
+
+fn make() -> NoDefault {
+    NoDefault
+}
+
+fn mock<T: Default>() -> T {
+    T::default()
+}
+
+#[kani::proof]
+#[kani::stub(make, mock)]
+fn harness() {
+    let _ = make();
+}
@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a generic parameter of a stub that does not appear in its signature cannot be
+//! inferred when the stub replaces a function without generic parameters.
+
+fn count() -> u32 {
+    0
+}
+
+fn unrelated<T>() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(count, unrelated)]
+fn harness() {
+    assert_eq!(count(), 42);
+}
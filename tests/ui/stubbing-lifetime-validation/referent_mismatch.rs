@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that stubs that use different lifetimes than the original function are still
+//! rejected if the referent types or the mutability of the references differ.
+
+fn get<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn get_u64<'b>(x: &'b u64) -> &'b u64 {
+    x
+}
+
+fn update<'a>(x: &'a mut u32) -> &'a mut u32 {
+    x
+}
+
+fn update_shared<'c>(x: &'c u32) -> &'c u32 {
+    x
+}
+
+#[kani::proof]
+#[kani::stub(get, get_u64)]
+#[kani::stub(update, update_shared)]
+fn harness() {
+    assert_eq!(*get(&1), 1);
+    assert_eq!(*update(&mut 2), 2);
+}
@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a source location that is malformed, or that does not fall
+//! within a function/method, is reported as an error.
+
+const LIMIT: u32 = 10;
+
+fn mock() -> u32 {
+    LIMIT
+}
+
+#[kani::proof]
+#[kani::stub("location:location_validation.rs:9:1", mock)]
+#[kani::stub("location:location_validation.rs:9", mock)]
+fn harness() {
+    assert_eq!(mock(), LIMIT);
+}
@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_read --enable-unstable --enable-stubbing
+//
+//! This tests that the stubbing attributes of items that are not harnesses are reported in a
+//! single warning, since they are ignored.
+
+fn read() -> u32 {
+    0
+}
+
+fn mock_read() -> u32 {
+    42
+}
+
+#[kani::stub(read, mock_read)]
+fn setup() {}
+
+struct Reader;
+
+impl Reader {
+    #[kani::stub(read, mock_read)]
+    #[kani::stub_default(read)]
+    fn check_reader() {}
+}
+
+#[kani::proof]
+fn check_read() {
+    assert_eq!(read(), 0);
+}
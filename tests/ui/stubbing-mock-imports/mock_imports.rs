@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness check_read --enable-unstable --enable-stubbing --warn-mock-imports
+//
+//! This tests that `--warn-mock-imports` warns about a harness that imports a mock module but
+//! has no stubs, since a stubbing attribute was likely forgotten.
+
+fn read() -> u32 {
+    0
+}
+
+mod mocks {
+    pub fn mock_read() -> u32 {
+        42
+    }
+}
+
+#[kani::proof]
+fn check_read() {
+    use mocks::mock_read;
+    assert_eq!(read(), 0);
+    assert_eq!(mock_read(), 42);
+}
+
+#[kani::proof]
+#[kani::stub(read, mocks::mock_read)]
+fn check_stubbed_read() {
+    use mocks::mock_read;
+    assert_eq!(read(), mock_read());
+}
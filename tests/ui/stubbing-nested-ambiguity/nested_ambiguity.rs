@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests the error for a path to a nested function that is defined in
+//! several blocks of the enclosing function.
+
+fn read_sensor() -> u32 {
+    unimplemented!("no sensor attached")
+}
+
+#[allow(dead_code)]
+fn mocks(calibrated: bool) {
+    if calibrated {
+        fn mock_read_sensor() -> u32 {
+            42
+        }
+    } else {
+        fn mock_read_sensor() -> u32 {
+            0
+        }
+    }
+}
+
+#[kani::proof]
+#[kani::stub(read_sensor, mocks::mock_read_sensor)]
+fn harness() {}
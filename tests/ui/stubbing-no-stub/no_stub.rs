@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports an error for every stub that targets a function
+//! marked `kani::no_stub`.
+
+#[kani::no_stub]
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |acc, byte| acc.wrapping_add(*byte))
+}
+
+#[kani::no_stub]
+fn validate(data: &[u8]) -> bool {
+    checksum(data) == 0
+}
+
+fn process(data: &[u8]) -> bool {
+    validate(data)
+}
+
+fn mock_checksum(_data: &[u8]) -> u8 {
+    0
+}
+
+fn mock_validate(_data: &[u8]) -> bool {
+    true
+}
+
+#[kani::proof]
+#[kani::stub(checksum, mock_checksum)]
+#[kani::stub_default(checksum)]
+#[kani::stub_in(process, validate, mock_validate)]
+fn harness() {
+    assert!(process(&[1, 255]));
+}
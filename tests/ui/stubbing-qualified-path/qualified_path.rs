@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests the errors for fully-qualified paths that do not resolve to a single method.
+
+struct Celsius(f32);
+
+impl From<u8> for Celsius {
+    fn from(value: u8) -> Self {
+        Celsius(value as f32)
+    }
+}
+
+impl From<i8> for Celsius {
+    fn from(value: i8) -> Self {
+        Celsius(value as f32)
+    }
+}
+
+fn mock_from(_: u8) -> Celsius {
+    Celsius(0.0)
+}
+
+#[kani::proof]
+#[kani::stub("<Celsius as From<u8>>::from", mock_from)]
+#[kani::stub("<Celsius as Default>::default", mock_from)]
+fn harness() {}
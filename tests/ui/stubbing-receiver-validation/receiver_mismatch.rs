@@ -0,0 +1,44 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that stubbing a method by a function (or vice versa) is rejected
+//! if the `self` receiver does not have the type of the first parameter, or if
+//! the function has no parameter for the receiver.
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn get(&self) -> u32 {
+        self.count
+    }
+
+    fn take(self) -> u32 {
+        self.count
+    }
+
+    fn zero() -> u32 {
+        0
+    }
+}
+
+fn get_mut(counter: &mut Counter) -> u32 {
+    counter.count
+}
+
+fn take_ref(counter: &Counter) -> u32 {
+    counter.count
+}
+
+#[kani::proof]
+#[kani::stub(Counter::get, get_mut)]
+#[kani::stub(take_ref, Counter::take)]
+#[kani::stub(Counter::zero, Counter::get)]
+fn harness() {
+    let counter = Counter { count: 0 };
+    assert_eq!(counter.get(), take_ref(&counter));
+    assert_eq!(Counter::zero(), counter.take());
+}
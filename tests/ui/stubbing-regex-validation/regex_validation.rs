@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --stub-regex-limit 2
+//
+//! This tests that we report `kani::stub_regex` attributes that are malformed,
+//! that do not match anything, or that match more functions than allowed by
+//! `--stub-regex-limit`.
+
+mod logging {
+    pub fn info() {}
+
+    pub fn warn() {}
+
+    pub fn error() {}
+}
+
+mod mock_logging {
+    pub fn info() {}
+}
+
+#[kani::proof]
+#[kani::stub_regex("logging::(.*)", "mock_logging::$1")]
+#[kani::stub_regex("logging::(info|warn)", "mock_logging::$1")]
+#[kani::stub_regex("tracing::.*", "mock_logging::info")]
+#[kani::stub_regex("logging::(", "mock_logging::info")]
+#[kani::stub_regex(logging::info, mock_logging::info)]
+fn harness() {
+    logging::info();
+    logging::warn();
+    logging::error();
+}
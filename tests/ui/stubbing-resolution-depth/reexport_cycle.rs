@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports paths that cannot be resolved because of a
+//! cycle of glob re-exports, instead of recursing forever.
+
+mod a {
+    pub use super::b::*;
+    pub use super::c::*;
+}
+
+mod b {
+    pub use super::a::*;
+    pub use super::c::*;
+}
+
+mod c {
+    pub use super::a::*;
+    pub use super::b::*;
+}
+
+fn stub() {}
+
+#[kani::proof]
+#[kani::stub(a::missing, stub)]
+fn harness() {}
@@ -0,0 +1,39 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --trace-stub-resolution
+//
+//! This tests that `--trace-stub-resolution` prints the `use` statements that
+//! the resolution of a stub path goes through, including an aliased import and
+//! a glob import.
+
+mod a {
+    pub mod b {
+        pub fn d() -> u32 {
+            0
+        }
+
+        pub fn e() -> u32 {
+            1
+        }
+    }
+}
+
+mod reexports {
+    pub use crate::a::b::*;
+}
+
+use a::b as c;
+use reexports as r;
+
+fn mock() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(c::d, mock)]
+#[kani::stub(r::e, mock)]
+fn harness() {
+    assert_eq!(a::b::d(), 42);
+    assert_eq!(a::b::e(), 42);
+}
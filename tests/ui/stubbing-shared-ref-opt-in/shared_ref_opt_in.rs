@@ -0,0 +1,34 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --allow-stub-shared-refs
+//
+//! This tests that `--allow-stub-shared-refs` accepts a stub that takes `&T`
+//! for `&mut T`, but still rejects a stub that takes `&mut T` for `&T`.
+
+fn increment(value: &mut u32) -> u32 {
+    *value += 1;
+    *value
+}
+
+fn peek(value: &u32) -> u32 {
+    *value + 1
+}
+
+fn read(value: &u32) -> u32 {
+    *value
+}
+
+fn reset(value: &mut u32) -> u32 {
+    *value = 0;
+    0
+}
+
+#[kani::proof]
+#[kani::stub(increment, peek)]
+#[kani::stub(read, reset)]
+fn harness() {
+    let mut value = 1;
+    increment(&mut value);
+    read(&value);
+}
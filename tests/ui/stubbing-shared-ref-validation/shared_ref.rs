@@ -0,0 +1,34 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that a stub whose parameter only differs from the original in
+//! the mutability of a reference is rejected in both directions by default.
+
+fn increment(value: &mut u32) -> u32 {
+    *value += 1;
+    *value
+}
+
+fn peek(value: &u32) -> u32 {
+    *value + 1
+}
+
+fn read(value: &u32) -> u32 {
+    *value
+}
+
+fn reset(value: &mut u32) -> u32 {
+    *value = 0;
+    0
+}
+
+#[kani::proof]
+#[kani::stub(increment, peek)]
+#[kani::stub(read, reset)]
+fn harness() {
+    let mut value = 1;
+    increment(&mut value);
+    read(&value);
+}
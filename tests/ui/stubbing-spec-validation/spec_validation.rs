@@ -0,0 +1,48 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports malformed `kani::stub_spec` attributes and
+//! specifications that do not match the return type of the function, including
+//! predicates that cannot be called on the returned value.
+
+fn ready() -> bool {
+    true
+}
+
+fn level() -> u8 {
+    1
+}
+
+fn offset() -> i32 {
+    0
+}
+
+fn count() -> usize {
+    0
+}
+
+fn checksum() -> u32 {
+    0
+}
+
+fn is_even(value: u32) -> bool {
+    value % 2 == 0
+}
+
+#[kani::proof]
+#[kani::stub_spec(ready, "returns in 0..2")]
+#[kani::stub_spec(level, "returns in 0..300")]
+#[kani::stub_spec(offset, "returns 0..10")]
+#[kani::stub_spec(count, "returns in 10..10")]
+#[kani::stub_spec(count)]
+#[kani::stub_spec(checksum, "returns where is_even")]
+#[kani::stub_spec(level, "returns where missing")]
+fn harness() {
+    assert!(ready());
+    assert_eq!(level(), 1);
+    assert_eq!(offset(), 0);
+    assert_eq!(count(), 0);
+    assert_eq!(checksum(), 0);
+}
@@ -0,0 +1,64 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --verify-stub-types
+//
+//! This tests that `--verify-stub-types` rejects stubs whose compatibility
+//! with the original function/method cannot be fully verified.
+
+const fn const1() -> u32 {
+    1
+}
+
+fn const2() -> u32 {
+    2
+}
+
+unsafe fn unsafe1() -> u32 {
+    1
+}
+
+fn unsafe2() -> u32 {
+    2
+}
+
+extern "C" fn abi1() -> u32 {
+    1
+}
+
+fn abi2() -> u32 {
+    2
+}
+
+fn kind1<const N: usize>() -> u32 {
+    1
+}
+
+fn kind2<T>() -> u32 {
+    2
+}
+
+fn const_ty1<const N: usize>() -> u32 {
+    1
+}
+
+fn const_ty2<const N: u8>() -> u32 {
+    2
+}
+
+fn bound1<T: Copy>(_x: T) -> u32 {
+    1
+}
+
+fn bound2<T: Copy + Default>(_x: T) -> u32 {
+    2
+}
+
+#[kani::proof]
+#[kani::stub(const1, const2)]
+#[kani::stub(unsafe1, unsafe2)]
+#[kani::stub(abi1, abi2)]
+#[kani::stub(kind1, kind2)]
+#[kani::stub(const_ty1, const_ty2)]
+#[kani::stub(bound1, bound2)]
+fn harness() {}
@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports the symbol names in `kani::stub` attributes
+//! that do not belong to a non-generic function.
+
+#[no_mangle]
+pub extern "C" fn ffi_read() -> u32 {
+    0
+}
+
+pub fn generic<T>(value: T) -> T {
+    value
+}
+
+fn mock_read() -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub("symbol:ffi_missing", mock_read)]
+#[kani::stub("ffi_read", mock_read)]
+#[kani::stub("symbol:generic", generic)]
+fn harness() {
+    assert_eq!(ffi_read(), 0);
+    assert_eq!(generic(1), 1);
+}
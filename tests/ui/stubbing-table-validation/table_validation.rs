@@ -0,0 +1,25 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports tables whose inputs or outputs do not match
+//! the signature of the function they replace.
+
+fn is_odd(value: u8) -> bool {
+    value % 2 == 1
+}
+
+fn add(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+#[kani::proof]
+#[kani::stub_spec(is_odd, "table [(0, 0), (1, 1)]")]
+#[kani::stub_spec(is_odd, "table [(-1, false)]")]
+#[kani::stub_spec(is_odd, "table [(0, false), (0, true)]")]
+#[kani::stub_spec(add, "table [(0, 0)]")]
+fn harness() {
+    assert!(is_odd(1));
+    assert_eq!(add(1, 2), 3);
+}
@@ -0,0 +1,39 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests the error for a path of the form `Type::method` where several
+//! traits implemented for the type have the method.
+
+trait Area {
+    fn size(&self) -> u32;
+}
+
+trait Perimeter {
+    fn size(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Area for Square {
+    fn size(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl Perimeter for Square {
+    fn size(&self) -> u32 {
+        4 * self.0
+    }
+}
+
+fn mock_size(_: &Square) -> u32 {
+    42
+}
+
+#[kani::proof]
+#[kani::stub(Square::size, mock_size)]
+fn harness() {
+    assert_eq!(Area::size(&Square(2)), 42);
+}
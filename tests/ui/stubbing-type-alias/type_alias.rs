@@ -0,0 +1,30 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing
+//
+//! This tests that Kani reports stub paths through type aliases that do not
+//! refer to a method of the aliased type.
+
+struct Bar;
+
+impl Bar {
+    fn m(&self) -> u32 {
+        1
+    }
+}
+
+type Foo = Bar;
+
+type Number = u32;
+
+fn stub(_bar: &Bar) -> u32 {
+    2
+}
+
+#[kani::proof]
+#[kani::stub(Foo::missing, stub)]
+#[kani::stub(Number::m, stub)]
+fn harness() {
+    assert_eq!(Bar.m(), 2);
+}
@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --deny-unapplied-stubs
+//
+//! This tests that `--deny-unapplied-stubs` does not report an experimental stub that is
+//! skipped because `--enable-experimental-stubs` is not set, while it still reports a stub
+//! whose original function is not reachable from the harness.
+
+fn read() -> u32 {
+    0
+}
+
+#[kani::experimental_stub]
+fn draft_read() -> u32 {
+    1
+}
+
+fn write(_value: u32) {}
+
+fn mock_write(_value: u32) {}
+
+#[kani::proof]
+#[kani::stub(read, draft_read)]
+#[kani::stub(write, mock_write)]
+fn harness() {
+    assert_eq!(read(), 0);
+}
@@ -0,0 +1,26 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --harness harness --enable-unstable --enable-stubbing --deny-unapplied-stubs
+//
+//! This tests that `--deny-unapplied-stubs` reports a stub whose original function is not
+//! reachable from the harness, while the stub that is applied is accepted.
+
+fn read() -> u32 {
+    0
+}
+
+fn mock_read() -> u32 {
+    1
+}
+
+fn write(_value: u32) {}
+
+fn mock_write(_value: u32) {}
+
+#[kani::proof]
+#[kani::stub(read, mock_read)]
+#[kani::stub(write, mock_write)]
+fn harness() {
+    assert_eq!(read(), 1);
+}
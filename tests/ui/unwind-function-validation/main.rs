@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// kani-flags: --no-unwinding-checks
+
+// This test is to check Kani's error handling for unwind attributes on functions that are not
+// harnesses: the values must be valid and a function cannot have conflicting values.
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn main() {
+    conflicting();
+    too_many_arguments();
+}
+
+#[kani::unwind(7)]
+#[kani::unwind(8)]
+pub fn conflicting() {
+    let mut counter = 0;
+    while counter < 5 {
+        counter += 1;
+    }
+}
+
+#[kani::unwind(7, 8)]
+pub fn too_many_arguments() {
+    let mut counter = 0;
+    while counter < 5 {
+        counter += 1;
+    }
+}
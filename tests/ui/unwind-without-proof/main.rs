@@ -3,20 +3,20 @@
 
 // kani-flags: --no-unwinding-checks
 
-// This test is to check Kani's error handling for harnesses that have unwind attributes
-// without '#[kani::proof]' attribute declared
+// This test is to check that Kani warns about unwind attributes on functions that are not
+// harnesses, since their values are not applied yet.
 
 #[kani::proof]
+#[kani::unwind(10)]
 fn main() {
-    assert!(1 == 2);
-    harness();
+    assert_eq!(helper(), 5);
 }
 
 #[kani::unwind(7)]
-pub fn harness() {
+pub fn helper() -> u32 {
     let mut counter = 0;
-    loop {
+    while counter < 5 {
         counter += 1;
-        assert!(counter < 8);
     }
+    counter
 }
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --enable-unstable --unsound-experiment-stub-all-except=checksum,Buffer::len
+
+// Checks that every local function other than the harness and the given ones
+// is replaced by a function that returns a nondeterministic value.
+
+struct Buffer {
+    data: [u8; 4],
+}
+
+impl Buffer {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn first(&self) -> u8 {
+        panic!("the body of `Buffer::first` should be replaced")
+    }
+}
+
+fn checksum(buffer: &Buffer) -> u8 {
+    buffer.data.iter().fold(0, |acc, byte| acc.wrapping_add(*byte))
+}
+
+fn validate(_buffer: &Buffer) -> bool {
+    panic!("the body of `validate` should be replaced")
+}
+
+#[kani::proof]
+fn check_whitelist() {
+    let buffer = Buffer { data: [1, 2, 3, 4] };
+    assert_eq!(checksum(&buffer), 10);
+    assert_eq!(buffer.len(), 4);
+    let _ = buffer.first();
+    let _ = validate(&buffer);
+}